@@ -0,0 +1,648 @@
+use anyhow::{anyhow, bail, ensure, Result};
+use mysql::PooledConn;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::artifact::{ArtifactStage, ArtifactType};
+use crate::converter::model::event::group::WhirlpoolEventGroup;
+use crate::converter::model::long_key::KeyFormat;
+use crate::converter::model::ohlcv::{interval::OhlcvInterval, period::OhlcvPeriod};
+use crate::converter::model::parquet::ParquetTable;
+use crate::storage::{self, ArchiveStorage, ObjectSummary};
+use crate::{converter, date, io, manifest};
+
+/*
+
+Archiving a date
+
+Each artifact of the date goes through the following stages.
+The stage is recorded in admArchiverStage as soon as it is completed,
+so a restarted archiver resumes at the first incomplete stage instead of redoing the whole date.
+
+- token, state, transaction: exported -> uploaded -> verified
+- event, event group files, ohlcv-daily, ohlcv-minutely, ohlcv-pair, ohlcv-token, ohlcv-stats, ohlcv-adaptive-fee, ohlcv interval files, ohlcv-weekly, ohlcv-monthly, position, position-ledger, position-valuation, position-range, parquet files: derived -> uploaded -> verified
+
+event & ohlcv are derived after token, state and transaction have been verified.
+event group files (e.g. event-trade) are filtered from the event file.
+position is decoded from the state file of the date (Position accounts at the end of the date).
+position-ledger is derived from the event file and the position-ledger of the previous date (downloaded from the storage).
+The ledger starts over on the configured ledger start date (ArchiveOptions::position_ledger_start_date)
+and on the first date archived with the ledger (no position-ledger has been archived before the date, e.g. the first live date).
+On the other dates a missing position-ledger of the previous date is an error (positions open on the missing date would be lost).
+ohlcv-pair, ohlcv-token, ohlcv-stats, ohlcv-adaptive-fee and ohlcv interval files (e.g. ohlcv-5m) are derived together with ohlcv-daily and ohlcv-minutely.
+position-valuation and position-range are derived together from the position-ledger of the date
+and the prices of the day (rebuilt from the previous state, token and event, ohlcv does not depend on the ledger).
+ohlcv-weekly and ohlcv-monthly are derived on the last date of the week (sunday) and the month
+from the ohlcv-daily files of the period (the previous dates are downloaded from the storage).
+The dates of the period before the first archived ohlcv-daily file are skipped,
+but a missing ohlcv-daily file after it fails the rollup (it is retried once the date has been archived).
+Whether an earlier ohlcv-daily file exists is found by listing the archive month by month backwards from the missing date.
+parquet files (optional) are converted from the verified transaction, event and ohlcv files.
+If the tmp file of a verified artifact is lost (e.g. the working directory was cleaned),
+it is downloaded from the storage instead of being exported or derived again.
+
+The manifest is uploaded after all artifacts have been verified (it is cheap, so it is not tracked).
+
+Backfill archives the dates in two passes (ArchivePass).
+The artifacts chained across dates (position-ledger, position-valuation and position-range derived from it, ohlcv-weekly and ohlcv-monthly)
+and the manifest are skipped by the first pass which runs the dates in parallel,
+then the second pass resumes each date in date order, so the previous date is complete before the date is chained to it.
+
+*/
+
+// files archived in addition to ArtifactType::ALL (configured per profile)
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveOptions {
+    // group-filtered event files (e.g. trade, liquidity)
+    pub event_groups: Vec<WhirlpoolEventGroup>,
+    // ohlcv files of the candle intervals (e.g. 5m, 1h)
+    pub ohlcv_intervals: Vec<OhlcvInterval>,
+    // parquet copies of transaction, event and ohlcv files
+    pub parquet: bool,
+    // USD prices of ohlcv files are derived from the pools paired with these mints
+    pub anchor_mints: Vec<String>,
+    // the position ledger starts over on this date (yyyymmdd) even if older ledgers are archived
+    // (it also starts over if no ledger has been archived before the date), the ledger of the previous date is required on the other dates
+    pub position_ledger_start_date: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchivePass {
+    // all artifacts of the date and the manifest
+    All,
+    // artifacts derived from the date alone (the dates can be archived in any order)
+    Independent,
+}
+
+pub fn archive_date(
+    profile: &String,
+    yyyymmdd_date: u32,
+    pass: ArchivePass,
+    tmpdir: &String,
+    options: &ArchiveOptions,
+    storage: &dyn ArchiveStorage,
+    conn: &mut PooledConn,
+) -> Result<()> {
+    let stages = io::fetch_archiver_stages(profile, yyyymmdd_date, yyyymmdd_date, conn)?
+        .into_iter()
+        .map(|stage| (stage.artifact, (stage.stage, stage.summary)))
+        .collect::<BTreeMap<_, _>>();
+
+    for (artifact, (stage, _)) in stages.iter() {
+        println!("resuming {} from {} stage ...", artifact.name(), stage.name());
+    }
+
+    let mut archiver = DateArchiver {
+        profile,
+        yyyymmdd_date,
+        tmpdir,
+        artifacts: ArtifactType::of_profile(yyyymmdd_date, &options.event_groups, &options.ohlcv_intervals, options.parquet),
+        storage,
+        conn,
+        stages,
+        intact: HashSet::new(),
+        previous_state_exported: false,
+    };
+
+    // export & upload token & state & transaction
+    archiver.produce(&[ArtifactType::Token], |archiver| {
+        println!("exporting token to tmp file ...");
+        io::export_token(archiver.yyyymmdd_date, &archiver.tmpfile(ArtifactType::Token), archiver.conn);
+        Ok(())
+    })?;
+    archiver.publish(ArtifactType::Token)?;
+
+    archiver.produce(&[ArtifactType::State], |archiver| {
+        println!("exporting state to tmp file ...");
+        io::export_state(archiver.yyyymmdd_date, &archiver.tmpfile(ArtifactType::State), archiver.conn);
+        Ok(())
+    })?;
+    archiver.publish(ArtifactType::State)?;
+
+    archiver.produce(&[ArtifactType::Transaction], |archiver| {
+        println!("exporting transaction to tmp file ...");
+        io::export_transaction(archiver.yyyymmdd_date, &archiver.tmpfile(ArtifactType::Transaction), archiver.conn);
+        Ok(())
+    })?;
+    archiver.publish(ArtifactType::Transaction)?;
+
+    // token & state & transaction upload completed
+    // now we need to generate event & ohlcv
+
+    archiver.produce(&[ArtifactType::Event], |archiver| {
+        archiver.ensure_tmpfile(ArtifactType::Token)?;
+        archiver.ensure_tmpfile(ArtifactType::Transaction)?;
+        let previous_state_file_tmpfile = archiver.export_previous_state()?;
+
+        println!("processing event to tmp file ...");
+        converter::process::event::process(
+            previous_state_file_tmpfile,
+            archiver.tmpfile(ArtifactType::Token),
+            archiver.tmpfile(ArtifactType::Transaction),
+            archiver.tmpfile(ArtifactType::Event),
+            KeyFormat::Short,
+        )
+    })?;
+    archiver.publish(ArtifactType::Event)?;
+
+    for group in options.event_groups.iter() {
+        let artifact = ArtifactType::EventGroup(*group);
+        archiver.produce(&[artifact], |archiver| {
+            archiver.ensure_tmpfile(ArtifactType::Event)?;
+
+            println!("processing {} to tmp file ...", artifact.name());
+            converter::process::event_group::process(
+                archiver.tmpfile(ArtifactType::Event),
+                *group,
+                archiver.tmpfile(artifact),
+                KeyFormat::Short,
+            )
+        })?;
+        archiver.publish(artifact)?;
+    }
+
+    archiver.produce(&[ArtifactType::Position], |archiver| {
+        archiver.ensure_tmpfile(ArtifactType::State)?;
+
+        println!("processing position to tmp file ...");
+        converter::process::position::snapshot::process(
+            archiver.tmpfile(ArtifactType::State),
+            archiver.tmpfile(ArtifactType::Position),
+            KeyFormat::Short,
+        )
+    })?;
+    archiver.publish(ArtifactType::Position)?;
+
+    if pass == ArchivePass::All {
+        archiver.produce(&[ArtifactType::PositionLedger], |archiver| {
+            archiver.ensure_tmpfile(ArtifactType::Event)?;
+            let previous_position_ledger_tmpfile = archiver.ensure_previous_position_ledger_tmpfile(options)?;
+
+            println!("processing position-ledger to tmp file ...");
+            converter::process::position::ledger::process(
+                previous_position_ledger_tmpfile,
+                archiver.tmpfile(ArtifactType::Event),
+                archiver.tmpfile(ArtifactType::PositionLedger),
+                KeyFormat::Short,
+            )
+        })?;
+        archiver.publish(ArtifactType::PositionLedger)?;
+    }
+
+    let mut ohlcv_artifacts = vec![ArtifactType::OhlcvDaily, ArtifactType::OhlcvMinutely, ArtifactType::OhlcvPair, ArtifactType::OhlcvToken, ArtifactType::OhlcvStats, ArtifactType::OhlcvAdaptiveFee];
+    ohlcv_artifacts.extend(options.ohlcv_intervals.iter().map(|interval| ArtifactType::OhlcvInterval(*interval)));
+    archiver.produce(&ohlcv_artifacts, |archiver| {
+        archiver.ensure_tmpfile(ArtifactType::Token)?;
+        archiver.ensure_tmpfile(ArtifactType::Event)?;
+        let previous_state_file_tmpfile = archiver.export_previous_state()?;
+        let interval_tmpfiles = options.ohlcv_intervals
+            .iter()
+            .map(|interval| (*interval, archiver.tmpfile(ArtifactType::OhlcvInterval(*interval))))
+            .collect::<Vec<_>>();
+
+        println!("processing ohlcv to tmp file ...");
+        converter::process::ohlcv::process(
+            previous_state_file_tmpfile,
+            archiver.tmpfile(ArtifactType::Token),
+            archiver.tmpfile(ArtifactType::Event),
+            archiver.tmpfile(ArtifactType::OhlcvDaily),
+            archiver.tmpfile(ArtifactType::OhlcvMinutely),
+            &interval_tmpfiles,
+            archiver.tmpfile(ArtifactType::OhlcvPair),
+            archiver.tmpfile(ArtifactType::OhlcvToken),
+            archiver.tmpfile(ArtifactType::OhlcvStats),
+            archiver.tmpfile(ArtifactType::OhlcvAdaptiveFee),
+            // archived minutely file is sparse
+            &converter::process::ohlcv::OhlcvOptions {
+                anchor_mints: options.anchor_mints.clone(),
+                dense_minutely: false,
+                top_traders: converter::process::ohlcv::DEFAULT_TOP_TRADERS,
+            },
+            KeyFormat::Short,
+        )
+    })?;
+    for artifact in ohlcv_artifacts {
+        archiver.publish(artifact)?;
+    }
+
+    if pass == ArchivePass::All {
+        let valuation_artifacts = [ArtifactType::PositionValuation, ArtifactType::PositionRange];
+        archiver.produce(&valuation_artifacts, |archiver| {
+            archiver.ensure_tmpfile(ArtifactType::Token)?;
+            archiver.ensure_tmpfile(ArtifactType::Event)?;
+            archiver.ensure_tmpfile(ArtifactType::PositionLedger)?;
+            let previous_state_file_tmpfile = archiver.export_previous_state()?;
+
+            println!("processing position-valuation to tmp file ...");
+            converter::process::position::valuation::process(
+                previous_state_file_tmpfile,
+                archiver.tmpfile(ArtifactType::Token),
+                archiver.tmpfile(ArtifactType::Event),
+                archiver.tmpfile(ArtifactType::PositionLedger),
+                archiver.tmpfile(ArtifactType::PositionValuation),
+                archiver.tmpfile(ArtifactType::PositionRange),
+                KeyFormat::Short,
+            )
+        })?;
+        for artifact in valuation_artifacts {
+            archiver.publish(artifact)?;
+        }
+
+        for period in OhlcvPeriod::closing_on(yyyymmdd_date) {
+            let artifact = ArtifactType::OhlcvRollup(period);
+            archiver.produce(&[artifact], |archiver| {
+                let daily_tmpfiles = archiver.ensure_period_daily_tmpfiles(period)?;
+
+                println!("processing {} to tmp file ...", artifact.name());
+                converter::process::ohlcv::rollup::process(
+                    period,
+                    &daily_tmpfiles,
+                    archiver.tmpfile(artifact),
+                    KeyFormat::Short,
+                )?;
+                archiver.remove_period_daily_tmpfiles(period)
+            })?;
+            archiver.publish(artifact)?;
+        }
+    }
+
+    if options.parquet {
+        let transaction_tables = [
+            ArtifactType::Parquet(ParquetTable::TransactionInstruction),
+            ArtifactType::Parquet(ParquetTable::TransactionBalance),
+        ];
+        archiver.produce(&transaction_tables, |archiver| {
+            archiver.ensure_tmpfile(ArtifactType::Transaction)?;
+
+            println!("processing transaction parquet to tmp files ...");
+            converter::process::parquet::process_transaction(
+                archiver.tmpfile(ArtifactType::Transaction),
+                archiver.tmpfile(transaction_tables[0]),
+                archiver.tmpfile(transaction_tables[1]),
+            )
+        })?;
+
+        let event_tables = ParquetTable::event_tables().into_iter().map(ArtifactType::Parquet).collect::<Vec<_>>();
+        archiver.produce(&event_tables, |archiver| {
+            archiver.ensure_tmpfile(ArtifactType::Event)?;
+
+            println!("processing event parquet to tmp files ...");
+            converter::process::parquet::process_event(
+                archiver.tmpfile(ArtifactType::Event),
+                |table| archiver.tmpfile(ArtifactType::Parquet(table)),
+            )
+        })?;
+
+        let ohlcv_daily_table = ArtifactType::Parquet(ParquetTable::OhlcvDaily);
+        archiver.produce(&[ohlcv_daily_table], |archiver| {
+            archiver.ensure_tmpfile(ArtifactType::OhlcvDaily)?;
+
+            println!("processing ohlcv-daily parquet to tmp file ...");
+            converter::process::parquet::process_ohlcv_daily(
+                archiver.tmpfile(ArtifactType::OhlcvDaily),
+                archiver.tmpfile(ohlcv_daily_table),
+            )
+        })?;
+
+        let ohlcv_minutely_table = ArtifactType::Parquet(ParquetTable::OhlcvMinutely);
+        archiver.produce(&[ohlcv_minutely_table], |archiver| {
+            archiver.ensure_tmpfile(ArtifactType::OhlcvMinutely)?;
+
+            println!("processing ohlcv-minutely parquet to tmp file ...");
+            converter::process::parquet::process_ohlcv_minutely(
+                archiver.tmpfile(ArtifactType::OhlcvMinutely),
+                archiver.tmpfile(ohlcv_minutely_table),
+            )
+        })?;
+
+        for table in ParquetTable::all() {
+            archiver.publish(ArtifactType::Parquet(table))?;
+        }
+    }
+
+    // upload manifest (last, the date is complete if the manifest exists)
+    if pass == ArchivePass::All {
+        archiver.publish_manifest()?;
+    }
+
+    // remove tmp files
+    archiver.remove_tmpfiles()?;
+
+    Ok(())
+}
+
+// date of the position-ledger carried over to the ledger of the date (None if the ledger starts over on the date)
+pub fn previous_position_ledger_date(yyyymmdd_date: u32, options: &ArchiveOptions, storage: &dyn ArchiveStorage) -> Result<Option<u32>> {
+    if options.position_ledger_start_date == Some(yyyymmdd_date) {
+        return Ok(None);
+    }
+    let previous_yyyymmdd_date = date::prev_yyyymmdd_date(yyyymmdd_date);
+    let dest = ArtifactType::PositionLedger.key(previous_yyyymmdd_date);
+    if storage.head(&dest)?.is_some() {
+        return Ok(Some(previous_yyyymmdd_date));
+    }
+    // nothing to chain to (the first date archived with the ledger)
+    let Some(last) = last_archived_yyyymmdd_date_before(ArtifactType::PositionLedger, yyyymmdd_date, storage)? else {
+        return Ok(None);
+    };
+    bail!(
+        "{} is not archived (the last archived position-ledger is {}), the position ledger of {} cannot be chained to it (archive the missing dates first, or configure {} as the ledger start date)",
+        dest, last, yyyymmdd_date, yyyymmdd_date
+    )
+}
+
+// latest date before the date whose file of the artifact is archived (None if nothing has been archived before the date)
+// the keys are listed month by month (yyyy/mm prefix) backwards, a month without any object is taken as the start of the archive
+pub fn last_archived_yyyymmdd_date_before(artifact: ArtifactType, yyyymmdd_date: u32, storage: &dyn ArchiveStorage) -> Result<Option<u32>> {
+    let mut first_yyyymmdd_date_of_month = date::first_yyyymmdd_date_of_month(yyyymmdd_date);
+    loop {
+        let prefix = format!("{:04}/{:02}", first_yyyymmdd_date_of_month / 10000, first_yyyymmdd_date_of_month % 10000 / 100);
+        let objects = storage.list(&prefix)?;
+        if objects.is_empty() {
+            return Ok(None);
+        }
+        let last = objects
+            .iter()
+            .filter_map(|object| {
+                // yyyy/mmdd/...
+                let archived_yyyymmdd_date = format!("{}{}", object.key.get(0..4)?, object.key.get(5..9)?).parse::<u32>().ok()?;
+                (archived_yyyymmdd_date < yyyymmdd_date && object.key == artifact.key(archived_yyyymmdd_date)).then_some(archived_yyyymmdd_date)
+            })
+            .max();
+        if last.is_some() {
+            return Ok(last);
+        }
+        first_yyyymmdd_date_of_month = date::first_yyyymmdd_date_of_month(date::prev_yyyymmdd_date(first_yyyymmdd_date_of_month));
+    }
+}
+
+struct DateArchiver<'a> {
+    profile: &'a String,
+    yyyymmdd_date: u32,
+    tmpdir: &'a String,
+    // artifacts archived for the profile
+    artifacts: Vec<ArtifactType>,
+    storage: &'a dyn ArchiveStorage,
+    conn: &'a mut PooledConn,
+    // recorded stage & summary of each artifact
+    stages: BTreeMap<ArtifactType, (ArtifactStage, ObjectSummary)>,
+    // artifacts whose tmp file is known to match the recorded summary
+    intact: HashSet<ArtifactType>,
+    previous_state_exported: bool,
+}
+
+impl DateArchiver<'_> {
+    fn tmpfile(&self, artifact: ArtifactType) -> String {
+        format!("{}/{}.{}.tmp", self.tmpdir, self.profile, artifact.name())
+    }
+
+    fn verifyfile(&self, artifact: ArtifactType) -> String {
+        format!("{}/{}.{}.verify", self.tmpdir, self.profile, artifact.name())
+    }
+
+    fn previous_state_tmpfile(&self) -> String {
+        format!("{}/{}.previous-state.tmp", self.tmpdir, self.profile)
+    }
+
+    // ohlcv-daily file of the previous date of the period
+    fn period_daily_tmpfile(&self, yyyymmdd_date: u32) -> String {
+        format!("{}/{}.{}.{}.tmp", self.tmpdir, self.profile, ArtifactType::OhlcvDaily.name(), yyyymmdd_date)
+    }
+
+    fn previous_position_ledger_tmpfile(&self) -> String {
+        format!("{}/{}.previous-{}.tmp", self.tmpdir, self.profile, ArtifactType::PositionLedger.name())
+    }
+
+    fn manifest_tmpfile(&self) -> String {
+        format!("{}/{}.manifest.tmp", self.tmpdir, self.profile)
+    }
+
+    fn manifest_verifyfile(&self) -> String {
+        format!("{}/{}.manifest.verify", self.tmpdir, self.profile)
+    }
+
+    fn stage(&self, artifact: ArtifactType) -> Option<ArtifactStage> {
+        self.stages.get(&artifact).map(|(stage, _)| *stage)
+    }
+
+    fn recorded_summary(&self, artifact: ArtifactType) -> Result<ObjectSummary> {
+        self.stages
+            .get(&artifact)
+            .map(|(_, summary)| summary.clone())
+            .ok_or_else(|| anyhow!("{} has not been produced", artifact.name()))
+    }
+
+    fn record(&mut self, artifact: ArtifactType, stage: ArtifactStage, summary: ObjectSummary) -> Result<()> {
+        io::update_archiver_stage(self.profile, self.yyyymmdd_date, artifact, stage, &summary, self.conn)?;
+        self.stages.insert(artifact, (stage, summary));
+        Ok(())
+    }
+
+    fn is_tmpfile_intact(&mut self, artifact: ArtifactType) -> Result<bool> {
+        if self.intact.contains(&artifact) {
+            return Ok(true);
+        }
+        let Some((_, recorded)) = self.stages.get(&artifact) else {
+            return Ok(false);
+        };
+        let intact = storage::local_file_summary(&self.tmpfile(artifact))?.as_ref() == Some(recorded);
+        if intact {
+            self.intact.insert(artifact);
+        }
+        Ok(intact)
+    }
+
+    fn is_produced(&mut self, artifact: ArtifactType) -> Result<bool> {
+        let recorded = self.stage(artifact);
+        // exported or derived, but the tmp file is needed to upload it
+        let tmpfile_intact = match recorded {
+            Some(stage) if stage < ArtifactStage::Uploaded => self.is_tmpfile_intact(artifact)?,
+            _ => false,
+        };
+        Ok(!artifact.remaining_stages(recorded, tmpfile_intact).contains(&artifact.produced_stage()))
+    }
+
+    // export or derive the artifacts (all of them are produced by one process)
+    fn produce(&mut self, artifacts: &[ArtifactType], process: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        let mut produced = true;
+        for artifact in artifacts {
+            produced &= self.is_produced(*artifact)?;
+        }
+        if produced {
+            return Ok(());
+        }
+
+        process(self)?;
+
+        for artifact in artifacts {
+            let summary = storage::local_file_summary(&self.tmpfile(*artifact))?
+                .ok_or_else(|| anyhow!("{} was not produced", self.tmpfile(*artifact)))?;
+            println!("{}_hash = {}", artifact.name(), summary.sha256);
+            self.record(*artifact, artifact.produced_stage(), summary)?;
+            self.intact.insert(*artifact);
+        }
+        Ok(())
+    }
+
+    // upload & verify the artifact
+    fn publish(&mut self, artifact: ArtifactType) -> Result<()> {
+        let dest = artifact.key(self.yyyymmdd_date);
+        let produced = self.recorded_summary(artifact)?;
+        // the tmp file has been produced (or verified to be intact) just before
+        let remaining = artifact.remaining_stages(self.stage(artifact), true);
+
+        if remaining.contains(&ArtifactStage::Uploaded) {
+            // sha256 is calculated while uploading
+            let tmpfile = self.tmpfile(artifact);
+            println!("uploading {} to {} ...", tmpfile, dest);
+            let uploaded = self.storage.put(&tmpfile, &dest)?;
+            ensure!(uploaded == produced, "{} uploaded {:?} != produced {:?}", artifact.name(), uploaded, produced);
+            self.record(artifact, ArtifactStage::Uploaded, uploaded)?;
+        }
+
+        if remaining.contains(&ArtifactStage::Verified) {
+            // sha256 is calculated while downloading
+            let verifyfile = self.verifyfile(artifact);
+            println!("downloading {} to {} ...", dest, verifyfile);
+            let downloaded = self.storage.get(&dest, &verifyfile)?;
+            std::fs::remove_file(&verifyfile)?;
+
+            println!("verifying {} ...", artifact.name());
+            ensure!(downloaded == produced, "{} downloaded {:?} != uploaded {:?}", artifact.name(), downloaded, produced);
+            self.record(artifact, ArtifactStage::Verified, downloaded)?;
+        }
+
+        Ok(())
+    }
+
+    // tmp file of the verified artifact is used to derive other artifacts and to build the manifest
+    fn ensure_tmpfile(&mut self, artifact: ArtifactType) -> Result<()> {
+        if self.is_tmpfile_intact(artifact)? {
+            return Ok(());
+        }
+
+        ensure!(self.stage(artifact) == Some(ArtifactStage::Verified), "{} has not been verified", artifact.name());
+        let verified = self.recorded_summary(artifact)?;
+        let dest = artifact.key(self.yyyymmdd_date);
+        let tmpfile = self.tmpfile(artifact);
+        println!("downloading {} to {} ...", dest, tmpfile);
+        let downloaded = self.storage.get(&dest, &tmpfile)?;
+        ensure!(downloaded == verified, "{} downloaded {:?} != verified {:?}", artifact.name(), downloaded, verified);
+        self.intact.insert(artifact);
+        Ok(())
+    }
+
+    // previous state is not archived for this date, so it is exported on every run
+    fn export_previous_state(&mut self) -> Result<String> {
+        let previous_state_file_tmpfile = self.previous_state_tmpfile();
+        if !self.previous_state_exported {
+            let previous_yyyymmdd_date = date::prev_yyyymmdd_date(self.yyyymmdd_date);
+            println!("exporting previous state to tmp file ...");
+            io::export_state(previous_yyyymmdd_date, &previous_state_file_tmpfile, self.conn);
+            self.previous_state_exported = true;
+        }
+        Ok(previous_state_file_tmpfile)
+    }
+
+    // ohlcv-daily files of the period in date order (dates before the first archived date are skipped, other missing dates are an error)
+    fn ensure_period_daily_tmpfiles(&mut self, period: OhlcvPeriod) -> Result<Vec<String>> {
+        let mut tmpfiles = Vec::new();
+        // a missing date is skipped only if nothing has been archived before it (it is known for the following missing dates until a date is downloaded)
+        let mut nothing_archived_before = false;
+        for yyyymmdd_date in period.yyyymmdd_dates(self.yyyymmdd_date) {
+            if yyyymmdd_date == self.yyyymmdd_date {
+                self.ensure_tmpfile(ArtifactType::OhlcvDaily)?;
+                tmpfiles.push(self.tmpfile(ArtifactType::OhlcvDaily));
+                continue;
+            }
+
+            let dest = ArtifactType::OhlcvDaily.key(yyyymmdd_date);
+            if self.storage.head(&dest)?.is_none() {
+                if !nothing_archived_before {
+                    if let Some(last) = last_archived_yyyymmdd_date_before(ArtifactType::OhlcvDaily, yyyymmdd_date, self.storage)? {
+                        bail!("{} is not archived (the archive has {}), {} is retried once it has been archived", dest, last, period.artifact_name());
+                    }
+                    nothing_archived_before = true;
+                }
+                println!("{} is not archived yet (before the first archived date), skipped", dest);
+                continue;
+            }
+            nothing_archived_before = false;
+            let tmpfile = self.period_daily_tmpfile(yyyymmdd_date);
+            println!("downloading {} to {} ...", dest, tmpfile);
+            self.storage.get(&dest, &tmpfile)?;
+            tmpfiles.push(tmpfile);
+        }
+        Ok(tmpfiles)
+    }
+
+    // position-ledger file of the previous date (None on the ledger start date)
+    fn ensure_previous_position_ledger_tmpfile(&self, options: &ArchiveOptions) -> Result<Option<String>> {
+        let Some(previous_yyyymmdd_date) = previous_position_ledger_date(self.yyyymmdd_date, options, self.storage)? else {
+            println!("{} is the ledger start date (configured, or no position-ledger has been archived before it), the ledger starts over", self.yyyymmdd_date);
+            return Ok(None);
+        };
+        let dest = ArtifactType::PositionLedger.key(previous_yyyymmdd_date);
+        let tmpfile = self.previous_position_ledger_tmpfile();
+        println!("downloading {} to {} ...", dest, tmpfile);
+        self.storage.get(&dest, &tmpfile)?;
+        Ok(Some(tmpfile))
+    }
+
+    fn remove_period_daily_tmpfiles(&self, period: OhlcvPeriod) -> Result<()> {
+        for yyyymmdd_date in period.yyyymmdd_dates(self.yyyymmdd_date) {
+            let tmpfile = self.period_daily_tmpfile(yyyymmdd_date);
+            if Path::new(&tmpfile).exists() {
+                std::fs::remove_file(&tmpfile)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn publish_manifest(&mut self) -> Result<()> {
+        let mut artifacts = Vec::new();
+        for artifact in self.artifacts.clone() {
+            self.ensure_tmpfile(artifact)?;
+            artifacts.push((artifact, self.tmpfile(artifact), self.recorded_summary(artifact)?));
+        }
+
+        println!("building manifest ...");
+        let artifacts = artifacts
+            .iter()
+            .map(|(artifact, tmpfile, summary)| (*artifact, tmpfile.as_str(), summary))
+            .collect::<Vec<_>>();
+        let manifest = manifest::build_manifest(self.yyyymmdd_date, &artifacts)?;
+        let manifest_file_tmpfile = self.manifest_tmpfile();
+        manifest::save_to_whirlpool_manifest_file(&manifest_file_tmpfile, &manifest)?;
+
+        let manifest_file_dest = manifest::manifest_key(self.yyyymmdd_date);
+        println!("uploading {} to {} ...", manifest_file_tmpfile, manifest_file_dest);
+        let manifest_hash = self.storage.put(&manifest_file_tmpfile, &manifest_file_dest)?.sha256;
+
+        let manifest_file_verify = self.manifest_verifyfile();
+        println!("downloading {} to {} ...", manifest_file_dest, manifest_file_verify);
+        let manifest_verify_hash = self.storage.get(&manifest_file_dest, &manifest_file_verify)?.sha256;
+
+        println!("verifying manifest ...");
+        ensure!(manifest_hash == manifest_verify_hash, "manifest_hash != manifest_verify_hash");
+        Ok(())
+    }
+
+    fn remove_tmpfiles(&self) -> Result<()> {
+        let mut files = self.artifacts.iter().map(|artifact| self.tmpfile(*artifact)).collect::<Vec<_>>();
+        files.push(self.previous_state_tmpfile());
+        files.push(self.previous_position_ledger_tmpfile());
+        files.push(self.manifest_tmpfile());
+        files.push(self.manifest_verifyfile());
+
+        for file in files {
+            if Path::new(&file).exists() {
+                std::fs::remove_file(&file)?;
+            }
+        }
+        for period in OhlcvPeriod::closing_on(self.yyyymmdd_date) {
+            self.remove_period_daily_tmpfiles(period)?;
+        }
+        Ok(())
+    }
+}
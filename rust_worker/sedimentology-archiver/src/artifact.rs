@@ -0,0 +1,205 @@
+use crate::converter::model::{event::{group::{WhirlpoolEventGroup, WHIRLPOOL_EVENT_GROUP_FORMAT_VERSION}, WHIRLPOOL_EVENT_FORMAT_VERSION}, ohlcv::{interval::OhlcvInterval, period::OhlcvPeriod, WHIRLPOOL_OHLCV_ADAPTIVE_FEE_FORMAT_VERSION, WHIRLPOOL_OHLCV_DAILY_FORMAT_VERSION, WHIRLPOOL_OHLCV_INTERVAL_FORMAT_VERSION, WHIRLPOOL_OHLCV_MINUTELY_FORMAT_VERSION, WHIRLPOOL_OHLCV_PAIR_FORMAT_VERSION, WHIRLPOOL_OHLCV_ROLLUP_FORMAT_VERSION, WHIRLPOOL_OHLCV_STATS_FORMAT_VERSION, WHIRLPOOL_OHLCV_TOKEN_FORMAT_VERSION}};
+use crate::converter::model::position::{WHIRLPOOL_POSITION_FORMAT_VERSION, WHIRLPOOL_POSITION_LEDGER_FORMAT_VERSION, WHIRLPOOL_POSITION_RANGE_FORMAT_VERSION, WHIRLPOOL_POSITION_VALUATION_FORMAT_VERSION};
+use crate::converter::model::parquet::{ParquetTable, WHIRLPOOL_PARQUET_FORMAT_VERSION};
+use crate::schema::{WHIRLPOOL_STATE_FORMAT_VERSION, WHIRLPOOL_TOKEN_FORMAT_VERSION, WHIRLPOOL_TRANSACTION_FORMAT_VERSION};
+use crate::storage;
+
+// files archived for each date (manifest file is not included, it is uploaded last)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ArtifactType {
+    Token,
+    State,
+    Transaction,
+    Event,
+    // group-filtered event file (e.g. whirlpool-event-trade-yyyymmdd.jsonl.gz), configured per profile
+    EventGroup(WhirlpoolEventGroup),
+    OhlcvDaily,
+    OhlcvMinutely,
+    // daily aggregates across pools of each mint pair and each mint
+    OhlcvPair,
+    OhlcvToken,
+    // daily volatility and price impact of each pool
+    OhlcvStats,
+    // daily and minutely oracle activity of each adaptive fee pool
+    OhlcvAdaptiveFee,
+    // decoded Position accounts of the state file of the date
+    Position,
+    // lifecycle of each position carried over from the previous date
+    PositionLedger,
+    // daily value and impermanent loss of each open position at the close price of its pool
+    PositionValuation,
+    // daily minutes the pool price sat inside the range of each position
+    PositionRange,
+    // ohlcv file of the candle interval (e.g. whirlpool-ohlcv-5m-yyyymmdd.jsonl.gz), configured per profile
+    OhlcvInterval(OhlcvInterval),
+    // ohlcv file of the week or month (e.g. whirlpool-ohlcv-weekly-yyyymmdd.jsonl.gz), archived on the last date of the period
+    OhlcvRollup(OhlcvPeriod),
+    // parquet copy of transaction, event or ohlcv file (e.g. whirlpool-event-traded-yyyymmdd.parquet), configured per profile
+    Parquet(ParquetTable),
+}
+
+impl ArtifactType {
+    // archived for every profile
+    pub const ALL: [ArtifactType; 14] = [
+        ArtifactType::Token,
+        ArtifactType::State,
+        ArtifactType::Transaction,
+        ArtifactType::Event,
+        ArtifactType::OhlcvDaily,
+        ArtifactType::OhlcvMinutely,
+        ArtifactType::OhlcvPair,
+        ArtifactType::OhlcvToken,
+        ArtifactType::OhlcvStats,
+        ArtifactType::OhlcvAdaptiveFee,
+        ArtifactType::Position,
+        ArtifactType::PositionLedger,
+        ArtifactType::PositionValuation,
+        ArtifactType::PositionRange,
+    ];
+
+    pub fn from_name(name: &str) -> Option<ArtifactType> {
+        if let Some(group) = name.strip_prefix("event-").and_then(WhirlpoolEventGroup::from_name) {
+            return Some(ArtifactType::EventGroup(group));
+        }
+        if let Some(interval) = name.strip_prefix("ohlcv-").and_then(OhlcvInterval::from_name) {
+            return Some(ArtifactType::OhlcvInterval(interval));
+        }
+        if let Some(period) = name.strip_prefix("ohlcv-").and_then(OhlcvPeriod::from_name) {
+            return Some(ArtifactType::OhlcvRollup(period));
+        }
+        if let Some(table) = name.strip_prefix("parquet-").and_then(ParquetTable::from_name) {
+            return Some(ArtifactType::Parquet(table));
+        }
+        ArtifactType::ALL.into_iter().find(|artifact| artifact.name() == name)
+    }
+
+    // ALL and the event group & ohlcv interval & parquet files of the profile, and the ohlcv rollup files of the periods closing on the date
+    pub fn of_profile(yyyymmdd_date: u32, event_groups: &[WhirlpoolEventGroup], ohlcv_intervals: &[OhlcvInterval], parquet: bool) -> Vec<ArtifactType> {
+        let mut artifacts = ArtifactType::ALL.to_vec();
+        artifacts.extend(OhlcvPeriod::closing_on(yyyymmdd_date).into_iter().map(ArtifactType::OhlcvRollup));
+        artifacts.extend(event_groups.iter().map(|group| ArtifactType::EventGroup(*group)));
+        artifacts.extend(ohlcv_intervals.iter().map(|interval| ArtifactType::OhlcvInterval(*interval)));
+        if parquet {
+            artifacts.extend(ParquetTable::all().into_iter().map(ArtifactType::Parquet));
+        }
+        artifacts.sort();
+        artifacts.dedup();
+        artifacts
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ArtifactType::Token => "token",
+            ArtifactType::State => "state",
+            ArtifactType::Transaction => "transaction",
+            ArtifactType::Event => "event",
+            ArtifactType::EventGroup(WhirlpoolEventGroup::Trade) => "event-trade",
+            ArtifactType::EventGroup(WhirlpoolEventGroup::Liquidity) => "event-liquidity",
+            ArtifactType::EventGroup(WhirlpoolEventGroup::All) => "event-all",
+            ArtifactType::OhlcvDaily => "ohlcv-daily",
+            ArtifactType::OhlcvMinutely => "ohlcv-minutely",
+            ArtifactType::OhlcvPair => "ohlcv-pair",
+            ArtifactType::OhlcvToken => "ohlcv-token",
+            ArtifactType::OhlcvStats => "ohlcv-stats",
+            ArtifactType::OhlcvAdaptiveFee => "ohlcv-adaptive-fee",
+            ArtifactType::Position => "position",
+            ArtifactType::PositionLedger => "position-ledger",
+            ArtifactType::PositionValuation => "position-valuation",
+            ArtifactType::PositionRange => "position-range",
+            ArtifactType::OhlcvInterval(interval) => interval.artifact_name(),
+            ArtifactType::OhlcvRollup(period) => period.artifact_name(),
+            ArtifactType::Parquet(table) => table.artifact_name(),
+        }
+    }
+
+    pub fn format_version(&self) -> u32 {
+        match self {
+            ArtifactType::Token => WHIRLPOOL_TOKEN_FORMAT_VERSION,
+            ArtifactType::State => WHIRLPOOL_STATE_FORMAT_VERSION,
+            ArtifactType::Transaction => WHIRLPOOL_TRANSACTION_FORMAT_VERSION,
+            ArtifactType::Event => WHIRLPOOL_EVENT_FORMAT_VERSION,
+            ArtifactType::EventGroup(_) => WHIRLPOOL_EVENT_GROUP_FORMAT_VERSION,
+            ArtifactType::OhlcvDaily => WHIRLPOOL_OHLCV_DAILY_FORMAT_VERSION,
+            ArtifactType::OhlcvMinutely => WHIRLPOOL_OHLCV_MINUTELY_FORMAT_VERSION,
+            ArtifactType::OhlcvPair => WHIRLPOOL_OHLCV_PAIR_FORMAT_VERSION,
+            ArtifactType::OhlcvToken => WHIRLPOOL_OHLCV_TOKEN_FORMAT_VERSION,
+            ArtifactType::OhlcvStats => WHIRLPOOL_OHLCV_STATS_FORMAT_VERSION,
+            ArtifactType::OhlcvAdaptiveFee => WHIRLPOOL_OHLCV_ADAPTIVE_FEE_FORMAT_VERSION,
+            ArtifactType::Position => WHIRLPOOL_POSITION_FORMAT_VERSION,
+            ArtifactType::PositionLedger => WHIRLPOOL_POSITION_LEDGER_FORMAT_VERSION,
+            ArtifactType::PositionValuation => WHIRLPOOL_POSITION_VALUATION_FORMAT_VERSION,
+            ArtifactType::PositionRange => WHIRLPOOL_POSITION_RANGE_FORMAT_VERSION,
+            ArtifactType::OhlcvInterval(_) => WHIRLPOOL_OHLCV_INTERVAL_FORMAT_VERSION,
+            ArtifactType::OhlcvRollup(_) => WHIRLPOOL_OHLCV_ROLLUP_FORMAT_VERSION,
+            ArtifactType::Parquet(_) => WHIRLPOOL_PARQUET_FORMAT_VERSION,
+        }
+    }
+
+    // event & ohlcv & position & parquet are derived from token, state and transaction (not exported from the database)
+    pub fn produced_stage(&self) -> ArtifactStage {
+        match self {
+            ArtifactType::Token | ArtifactType::State | ArtifactType::Transaction => ArtifactStage::Exported,
+            _ => ArtifactStage::Derived,
+        }
+    }
+
+    // stages left to run when resuming from the recorded stage (None if nothing has been recorded)
+    // the exported or derived stage is run again if its tmp file was lost before the upload
+    pub fn remaining_stages(&self, recorded: Option<ArtifactStage>, tmpfile_intact: bool) -> Vec<ArtifactStage> {
+        let redo = match recorded {
+            None => true,
+            Some(stage) => stage < ArtifactStage::Uploaded && !tmpfile_intact,
+        };
+        [self.produced_stage(), ArtifactStage::Uploaded, ArtifactStage::Verified]
+            .into_iter()
+            .filter(|stage| redo || Some(*stage) > recorded)
+            .collect()
+    }
+
+    // e.g. whirlpool-transaction-20240101.jsonl.gz
+    pub fn file_name(&self, yyyymmdd_date: u32) -> String {
+        match self {
+            ArtifactType::Token | ArtifactType::State => format!("whirlpool-{}-{}.json.gz", self.name(), yyyymmdd_date),
+            ArtifactType::Parquet(table) => format!("whirlpool-{}-{}.parquet", table.name(), yyyymmdd_date),
+            _ => format!("whirlpool-{}-{}.jsonl.gz", self.name(), yyyymmdd_date),
+        }
+    }
+
+    // e.g. 2024/0101/whirlpool-transaction-20240101.jsonl.gz
+    pub fn key(&self, yyyymmdd_date: u32) -> String {
+        storage::date_key(yyyymmdd_date, &self.file_name(yyyymmdd_date))
+    }
+}
+
+// archiving stage of each artifact (recorded in admArchiverStage)
+// - token, state, transaction: exported -> uploaded -> verified
+// - event, ohlcv-daily, ohlcv-minutely, ohlcv-pair, ohlcv-token, ohlcv-stats, ohlcv-adaptive-fee, ohlcv-<interval>, ohlcv-weekly, ohlcv-monthly, position, position-ledger, position-valuation, position-range, parquet-*: derived -> uploaded -> verified
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ArtifactStage {
+    Exported,
+    Derived,
+    Uploaded,
+    Verified,
+}
+
+impl ArtifactStage {
+    pub const ALL: [ArtifactStage; 4] = [
+        ArtifactStage::Exported,
+        ArtifactStage::Derived,
+        ArtifactStage::Uploaded,
+        ArtifactStage::Verified,
+    ];
+
+    pub fn from_name(name: &str) -> Option<ArtifactStage> {
+        ArtifactStage::ALL.into_iter().find(|stage| stage.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ArtifactStage::Exported => "exported",
+            ArtifactStage::Derived => "derived",
+            ArtifactStage::Uploaded => "uploaded",
+            ArtifactStage::Verified => "verified",
+        }
+    }
+}
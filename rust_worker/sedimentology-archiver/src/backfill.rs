@@ -0,0 +1,110 @@
+use anyhow::{anyhow, Result};
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::date;
+
+/*
+
+Backfill
+
+The dates in [from, to] are archived by the workers in parallel (each worker takes the next date not taken yet),
+then the dates before the first failed (or not attempted) date are resumed in date order (see ArchivePass).
+A failed or panicked date is recorded and the worker moves on to the next date.
+
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParallelPassResult {
+    // failed dates (sorted)
+    pub failed: Vec<u32>,
+    // number of dates taken by the workers (dates after them were not attempted because of the shutdown)
+    pub attempted: usize,
+    // number of dates which can be resumed in date order (the dates before the first failed or not attempted date)
+    pub ready: usize,
+}
+
+// dates in [from, to]
+pub fn dates(from_yyyymmdd_date: u32, to_yyyymmdd_date: u32) -> Vec<u32> {
+    let mut dates = vec![from_yyyymmdd_date];
+    while *dates.last().unwrap() < to_yyyymmdd_date {
+        dates.push(date::next_yyyymmdd_date(*dates.last().unwrap()));
+    }
+    dates
+}
+
+// connect(worker) opens the connection of the worker, archive(worker, conn, date) archives the date
+pub fn run_parallel_pass<C>(
+    dates: &[u32],
+    workers: usize,
+    shutdown: &AtomicBool,
+    connect: impl Fn(usize) -> Result<C> + Sync,
+    archive: impl Fn(usize, &mut C, u32) -> Result<()> + Sync,
+) -> ParallelPassResult {
+    let next = AtomicUsize::new(0);
+    let failed = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for worker in 0..workers.min(dates.len()) {
+            let (next, failed, connect, archive) = (&next, &failed, &connect, &archive);
+            scope.spawn(move || {
+                let run = || -> Result<()> {
+                    let mut conn = connect(worker)?;
+                    loop {
+                        if shutdown.load(Ordering::SeqCst) {
+                            println!("[worker {}] shutting down ...", worker);
+                            return Ok(());
+                        }
+
+                        let Some(&yyyymmdd_date) = dates.get(next.fetch_add(1, Ordering::SeqCst)) else {
+                            return Ok(());
+                        };
+
+                        println!("[worker {}] backfilling {} ...", worker, yyyymmdd_date);
+                        let archived = catch_panic(&mut conn, || connect(worker), |conn| archive(worker, conn, yyyymmdd_date))?;
+                        match archived {
+                            Ok(()) => println!("[worker {}] backfilled {}", worker, yyyymmdd_date),
+                            Err(err) => {
+                                println!("[worker {}] failed to backfill {}: {:?}", worker, yyyymmdd_date, err);
+                                failed.lock().unwrap().push(yyyymmdd_date);
+                            }
+                        }
+                    }
+                };
+                if let Err(err) = run() {
+                    println!("[worker {}] stopped: {:?}", worker, err);
+                }
+            });
+        }
+    });
+
+    let mut failed = failed.into_inner().unwrap();
+    failed.sort();
+    // dates before the first failed (or not attempted) date can be completed
+    let attempted = next.into_inner().min(dates.len());
+    let ready = dates[..attempted].iter().take_while(|yyyymmdd_date| !failed.contains(yyyymmdd_date)).count();
+    ParallelPassResult { failed, attempted, ready }
+}
+
+// a panic (e.g. unwrap in export) fails the date, not the whole backfill
+pub fn catch_panic<C>(conn: &mut C, reconnect: impl FnOnce() -> Result<C>, archive: impl FnOnce(&mut C) -> Result<()>) -> Result<Result<()>> {
+    match catch_unwind(AssertUnwindSafe(|| archive(conn))) {
+        Ok(archived) => Ok(archived),
+        Err(panic) => {
+            // the connection may be left in the middle of a transaction
+            *conn = reconnect()?;
+            Ok(Err(anyhow!("panicked: {}", panic_message(panic.as_ref()))))
+        }
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
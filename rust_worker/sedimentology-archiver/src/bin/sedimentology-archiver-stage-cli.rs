@@ -0,0 +1,91 @@
+use mysql::*;
+use clap::Parser;
+use std::collections::{BTreeMap, BTreeSet};
+
+use sedimentology_archiver::artifact::ArtifactType;
+use sedimentology_archiver::io;
+
+// show the archiving stage of each artifact for each date
+#[derive(Parser, Debug)]
+struct Args {
+    #[clap(long, id = "profile")]
+    profile: String,
+
+    #[clap(long, id = "mariadb-host", default_value = "localhost")]
+    mariadb_host: Option<String>,
+
+    #[clap(long, id = "mariadb-port", default_value = "3306")]
+    mariadb_port: Option<u16>,
+
+    #[clap(long, id = "mariadb-user", default_value = "root")]
+    mariadb_user: Option<String>,
+
+    #[clap(long, id = "mariadb-password", default_value = "password")]
+    mariadb_password: Option<String>,
+
+    #[clap(long, id = "mariadb-database", default_value = "whirlpool")]
+    mariadb_database: Option<String>,
+
+    // yyyymmdd (default: the date after the latest archived date)
+    #[clap(long, id = "from")]
+    from: Option<u32>,
+
+    // yyyymmdd (default: no limit)
+    #[clap(long, id = "to")]
+    to: Option<u32>,
+}
+
+fn main() {
+    // connect to mariadb
+    let args = Args::parse();
+    let mariadb_url = format!("mysql://{}:{}@{}:{}/{}",
+                      args.mariadb_user.unwrap(),
+                      args.mariadb_password.unwrap(),
+                      args.mariadb_host.unwrap(),
+                      args.mariadb_port.unwrap(),
+                      args.mariadb_database.unwrap());
+    let pool = Pool::new(mariadb_url.as_str()).unwrap();
+    let mut conn = pool.get_conn().unwrap();
+
+    let profile = args.profile;
+    let latest_archived_yyyymmdd_date = io::fetch_latest_archived_date(&profile, &mut conn);
+    let from_yyyymmdd_date = args.from.unwrap_or(latest_archived_yyyymmdd_date + 1);
+    let to_yyyymmdd_date = args.to.unwrap_or(u32::MAX);
+
+    let stages = io::fetch_archiver_stages(&profile, from_yyyymmdd_date, to_yyyymmdd_date, &mut conn).unwrap();
+
+    let mut dates: BTreeMap<u32, Vec<_>> = BTreeMap::new();
+    for stage in stages {
+        dates.entry(stage.date).or_default().push(stage);
+    }
+
+    println!("profile: {}", profile);
+    println!("latest archived date: {}", latest_archived_yyyymmdd_date);
+    if dates.is_empty() {
+        println!("no stage recorded since {}", from_yyyymmdd_date);
+        return;
+    }
+
+    // event group and parquet files are configured per profile
+    let mut artifacts: BTreeSet<ArtifactType> = ArtifactType::ALL.into_iter().collect();
+    artifacts.extend(dates.values().flatten().map(|stage| stage.artifact));
+
+    // header
+    print!("{:<8}  {:<8}", "date", "status");
+    for artifact in artifacts.iter() {
+        print!("  {:<14}", artifact.name());
+    }
+    println!("  updated at");
+
+    for (date, stages) in dates {
+        // the date is complete when the latest archived date has been advanced (manifest uploaded)
+        let status = if date <= latest_archived_yyyymmdd_date { "complete" } else { "pending" };
+        print!("{:<8}  {:<8}", date, status);
+        for artifact in artifacts.iter() {
+            let stage = stages.iter().find(|stage| stage.artifact == *artifact);
+            print!("  {:<14}", stage.map(|stage| stage.stage.name()).unwrap_or("-"));
+        }
+        let updated_at = stages.iter().map(|stage| stage.updated_at.as_str()).max().unwrap_or("-");
+        println!("  {}", updated_at);
+    }
+}
@@ -0,0 +1,692 @@
+use anyhow::{bail, ensure, Result};
+use clap::{Args, Parser, Subcommand};
+use std::collections::HashMap;
+use std::path::Path;
+
+use sedimentology_archiver::artifact::ArtifactType;
+use sedimentology_archiver::converter;
+use sedimentology_archiver::converter::model::account::WhirlpoolAccountType;
+use sedimentology_archiver::converter::model::event::group::WhirlpoolEventGroup;
+use sedimentology_archiver::converter::model::long_key::KeyFormat;
+use sedimentology_archiver::converter::model::ohlcv::{interval::OhlcvInterval, period::OhlcvPeriod};
+use sedimentology_archiver::converter::model::parquet::ParquetTable;
+use sedimentology_archiver::{date, storage};
+
+/*
+
+Offline converter (no database is needed)
+
+Derived files can be built from the archived files in two ways.
+
+- explicit paths:
+  sedimentology-convert event --state whirlpool-state-20240101.json.gz --token whirlpool-token-20240102.json.gz \
+    --transaction whirlpool-transaction-20240102.jsonl.gz --output whirlpool-event-20240102.jsonl.gz
+
+- archive directory (<dir>/<yyyy>/<mmdd>/<filename>, the same layout as the archive) and date range:
+  sedimentology-convert event --archive-dir ./archive --from 20240101 --to 20240131
+  sedimentology-convert event-group --group trade --archive-dir ./archive --from 20240101 --to 20240131
+  sedimentology-convert ohlcv --archive-dir ./archive --from 20240101 --to 20240131
+  sedimentology-convert ohlcv --intervals 5m,15m,1h,4h --archive-dir ./archive --from 20240101 --to 20240131
+  sedimentology-convert ohlcv --dense --archive-dir ./archive --output-dir ./dense --from 20240101 --to 20240131
+  sedimentology-convert rollup --periods weekly,monthly --archive-dir ./archive --from 20240101 --to 20240331
+  sedimentology-convert position --archive-dir ./archive --from 20240101 --to 20240131
+  sedimentology-convert position-ledger --start-date 20240101 --archive-dir ./archive --from 20240101 --to 20240131
+  sedimentology-convert position-valuation --archive-dir ./archive --from 20240101 --to 20240131
+
+- decoded accounts of the state file (one file per account type, e.g. whirlpool-account-whirlpool-20240101.jsonl.gz):
+  sedimentology-convert decode-state --archive-dir ./archive --output-dir ./decoded --from 20240101 --to 20240131
+  sedimentology-convert decode-state --types whirlpool,oracle --state whirlpool-state-20240101.json.gz --token whirlpool-token-20240101.json.gz \
+    --outputs whirlpool.jsonl.gz,oracle.jsonl.gz
+
+- long key (human-readable) output:
+  sedimentology-convert ohlcv --key-format long --archive-dir ./archive --output-dir ./long --from 20240101 --to 20240131
+  sedimentology-convert long-key --artifact event-trade --archive-dir ./archive --output-dir ./long --from 20240101 --to 20240131
+  sedimentology-convert long-key --artifact ohlcv-daily --input whirlpool-ohlcv-daily-20240102.jsonl.gz --output long.jsonl.gz
+
+- parquet output (written next to the JSON lines files, archive directory only):
+  sedimentology-convert parquet --artifact transaction,event,ohlcv-daily,ohlcv-minutely --archive-dir ./archive --from 20240101 --to 20240131
+
+Note: the state file of the previous date is used as the initial state of the date (position decodes the state file of the date itself).
+Note: rollup files are written for the weeks and months whose last date is in the range (missing daily files are skipped).
+Note: position ledger of the previous date is read from the output directory (archive directory for long keys), it is required except on --start-date.
+Note: position-valuation reads the position ledger of the date from the archive directory (run position-ledger first).
+Note: long key files are not written into the archive directory (the archive uses short keys).
+
+*/
+
+#[derive(Parser, Debug)]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
+enum Command {
+    // build whirlpool-event-yyyymmdd.jsonl.gz from state (previous date), token and transaction
+    Event(EventArgs),
+    // build whirlpool-event-<group>-yyyymmdd.jsonl.gz from event
+    EventGroup(EventGroupArgs),
+    // build whirlpool-ohlcv-daily-yyyymmdd.jsonl.gz, whirlpool-ohlcv-minutely-yyyymmdd.jsonl.gz, whirlpool-ohlcv-pair-yyyymmdd.jsonl.gz, whirlpool-ohlcv-token-yyyymmdd.jsonl.gz, whirlpool-ohlcv-stats-yyyymmdd.jsonl.gz and whirlpool-ohlcv-adaptive-fee-yyyymmdd.jsonl.gz (and whirlpool-ohlcv-<interval>-yyyymmdd.jsonl.gz) from state (previous date), token and event
+    Ohlcv(OhlcvArgs),
+    // build whirlpool-ohlcv-weekly-yyyymmdd.jsonl.gz and whirlpool-ohlcv-monthly-yyyymmdd.jsonl.gz from the daily files of the period
+    Rollup(RollupArgs),
+    // build whirlpool-position-yyyymmdd.jsonl.gz from state (the date)
+    Position(PositionArgs),
+    // build whirlpool-position-ledger-yyyymmdd.jsonl.gz from the position ledger of the previous date and event
+    PositionLedger(PositionLedgerArgs),
+    // build whirlpool-position-valuation-yyyymmdd.jsonl.gz and whirlpool-position-range-yyyymmdd.jsonl.gz from state (previous date), token, event and position ledger
+    PositionValuation(PositionValuationArgs),
+    // build whirlpool-account-<type>-yyyymmdd.jsonl.gz from state (the date) and token
+    DecodeState(DecodeStateArgs),
+    // rewrite existing event, event group or ohlcv files with long keys
+    LongKey(LongKeyArgs),
+    // build whirlpool-<table>-yyyymmdd.parquet from transaction, event or ohlcv files
+    Parquet(ParquetArgs),
+}
+
+#[derive(Args, Debug)]
+struct DateRangeArgs {
+    // read input files from <archive-dir>/<yyyy>/<mmdd>/
+    #[clap(long, id = "archive-dir", requires_all = ["from", "to"])]
+    archive_dir: Option<String>,
+
+    // write output files to <output-dir>/<yyyy>/<mmdd>/ (default: archive-dir)
+    #[clap(long, id = "output-dir", requires = "archive-dir")]
+    output_dir: Option<String>,
+
+    // yyyymmdd
+    #[clap(long, id = "from", requires = "archive-dir")]
+    from: Option<u32>,
+
+    // yyyymmdd
+    #[clap(long, id = "to", requires = "archive-dir")]
+    to: Option<u32>,
+}
+
+#[derive(Args, Debug)]
+struct EventArgs {
+    #[clap(long, id = "state", conflicts_with = "archive-dir", requires_all = ["token", "transaction", "output"])]
+    state: Option<String>,
+
+    #[clap(long, id = "token", conflicts_with = "archive-dir")]
+    token: Option<String>,
+
+    #[clap(long, id = "transaction", conflicts_with = "archive-dir")]
+    transaction: Option<String>,
+
+    #[clap(long, id = "output", conflicts_with = "archive-dir")]
+    output: Option<String>,
+
+    // short (same as the archive) | long (human-readable)
+    #[clap(long, id = "key-format", default_value = "short", value_parser = parse_key_format)]
+    key_format: KeyFormat,
+
+    #[clap(flatten)]
+    range: DateRangeArgs,
+}
+
+#[derive(Args, Debug)]
+struct EventGroupArgs {
+    // trade | liquidity
+    #[clap(long, id = "group", value_parser = parse_event_group)]
+    group: WhirlpoolEventGroup,
+
+    #[clap(long, id = "event", conflicts_with = "archive-dir", requires = "output")]
+    event: Option<String>,
+
+    #[clap(long, id = "output", conflicts_with = "archive-dir")]
+    output: Option<String>,
+
+    // short (same as the archive) | long (human-readable)
+    #[clap(long, id = "key-format", default_value = "short", value_parser = parse_key_format)]
+    key_format: KeyFormat,
+
+    #[clap(flatten)]
+    range: DateRangeArgs,
+}
+
+#[derive(Args, Debug)]
+struct OhlcvArgs {
+    #[clap(long, id = "state", conflicts_with = "archive-dir", requires_all = ["token", "event", "output-daily", "output-minutely", "output-pair", "output-token", "output-stats", "output-adaptive-fee"])]
+    state: Option<String>,
+
+    #[clap(long, id = "token", conflicts_with = "archive-dir")]
+    token: Option<String>,
+
+    #[clap(long, id = "event", conflicts_with = "archive-dir")]
+    event: Option<String>,
+
+    #[clap(long, id = "output-daily", conflicts_with = "archive-dir")]
+    output_daily: Option<String>,
+
+    #[clap(long, id = "output-minutely", conflicts_with = "archive-dir")]
+    output_minutely: Option<String>,
+
+    // daily aggregates across pools of each mint pair
+    #[clap(long, id = "output-pair", conflicts_with = "archive-dir")]
+    output_pair: Option<String>,
+
+    // daily aggregates across pools of each mint
+    #[clap(long, id = "output-token", conflicts_with = "archive-dir")]
+    output_token: Option<String>,
+
+    // daily volatility and price impact of each pool
+    #[clap(long, id = "output-stats", conflicts_with = "archive-dir")]
+    output_stats: Option<String>,
+
+    // daily and minutely oracle activity of each adaptive fee pool
+    #[clap(long, id = "output-adaptive-fee", conflicts_with = "archive-dir")]
+    output_adaptive_fee: Option<String>,
+
+    // candle intervals written in addition to daily and minutely (e.g. 5m,15m,1h,4h)
+    #[clap(long, id = "intervals", value_delimiter = ',', value_parser = parse_ohlcv_interval)]
+    intervals: Vec<OhlcvInterval>,
+
+    // output paths of the intervals (in the same order as --intervals)
+    #[clap(long, id = "output-intervals", value_delimiter = ',', conflicts_with = "archive-dir")]
+    output_intervals: Vec<String>,
+
+    // mints priced at 1 USD to derive USD prices (default: USDC, USDT)
+    #[clap(long, id = "anchor-mints", value_delimiter = ',')]
+    anchor_mints: Vec<String>,
+
+    // forward-fill minutes with no trades (1440 minutes for each traded pool, empty minutes are marked as synthetic)
+    #[clap(long, id = "dense")]
+    dense: bool,
+
+    // number of traders by volume in topTraders of the daily file
+    #[clap(long, id = "top-traders", default_value_t = converter::process::ohlcv::DEFAULT_TOP_TRADERS)]
+    top_traders: usize,
+
+    // short (same as the archive) | long (human-readable)
+    #[clap(long, id = "key-format", default_value = "short", value_parser = parse_key_format)]
+    key_format: KeyFormat,
+
+    #[clap(flatten)]
+    range: DateRangeArgs,
+}
+
+#[derive(Args, Debug)]
+struct RollupArgs {
+    // weekly | monthly (a single period for explicit paths)
+    #[clap(long, id = "periods", value_delimiter = ',', value_parser = parse_ohlcv_period, default_value = "weekly,monthly")]
+    periods: Vec<OhlcvPeriod>,
+
+    // daily files of the period in date order
+    #[clap(long, id = "daily", value_delimiter = ',', conflicts_with = "archive-dir", requires = "output")]
+    daily: Vec<String>,
+
+    #[clap(long, id = "output", conflicts_with = "archive-dir")]
+    output: Option<String>,
+
+    // short (same as the archive) | long (human-readable)
+    #[clap(long, id = "key-format", default_value = "short", value_parser = parse_key_format)]
+    key_format: KeyFormat,
+
+    #[clap(flatten)]
+    range: DateRangeArgs,
+}
+
+#[derive(Args, Debug)]
+struct PositionArgs {
+    // state file of the date (not the previous date)
+    #[clap(long, id = "state", conflicts_with = "archive-dir", requires = "output")]
+    state: Option<String>,
+
+    #[clap(long, id = "output", conflicts_with = "archive-dir")]
+    output: Option<String>,
+
+    // short (same as the archive) | long (human-readable)
+    #[clap(long, id = "key-format", default_value = "short", value_parser = parse_key_format)]
+    key_format: KeyFormat,
+
+    #[clap(flatten)]
+    range: DateRangeArgs,
+}
+
+#[derive(Args, Debug)]
+struct PositionLedgerArgs {
+    // position ledger of the previous date (omitted for the first date)
+    #[clap(long, id = "previous", conflicts_with = "archive-dir")]
+    previous: Option<String>,
+
+    // the ledger starts over on this date (yyyymmdd), the ledger of the previous date is required on the other dates
+    #[clap(long, id = "start-date", requires = "archive-dir")]
+    start_date: Option<u32>,
+
+    #[clap(long, id = "event", conflicts_with = "archive-dir", requires = "output")]
+    event: Option<String>,
+
+    #[clap(long, id = "output", conflicts_with = "archive-dir")]
+    output: Option<String>,
+
+    // short (same as the archive) | long (human-readable)
+    #[clap(long, id = "key-format", default_value = "short", value_parser = parse_key_format)]
+    key_format: KeyFormat,
+
+    #[clap(flatten)]
+    range: DateRangeArgs,
+}
+
+#[derive(Args, Debug)]
+struct PositionValuationArgs {
+    #[clap(long, id = "state", conflicts_with = "archive-dir", requires_all = ["token", "event", "position-ledger", "output-valuation", "output-range"])]
+    state: Option<String>,
+
+    #[clap(long, id = "token", conflicts_with = "archive-dir")]
+    token: Option<String>,
+
+    #[clap(long, id = "event", conflicts_with = "archive-dir")]
+    event: Option<String>,
+
+    // position ledger of the date
+    #[clap(long, id = "position-ledger", conflicts_with = "archive-dir")]
+    position_ledger: Option<String>,
+
+    // daily value and impermanent loss of each open position
+    #[clap(long, id = "output-valuation", conflicts_with = "archive-dir")]
+    output_valuation: Option<String>,
+
+    // daily minutes in range of each position
+    #[clap(long, id = "output-range", conflicts_with = "archive-dir")]
+    output_range: Option<String>,
+
+    // short (same as the archive) | long (human-readable)
+    #[clap(long, id = "key-format", default_value = "short", value_parser = parse_key_format)]
+    key_format: KeyFormat,
+
+    #[clap(flatten)]
+    range: DateRangeArgs,
+}
+
+#[derive(Args, Debug)]
+struct DecodeStateArgs {
+    // whirlpool | whirlpools-config | whirlpools-config-extension | fee-tier | adaptive-fee-tier | oracle | token-badge | lock-config (default: all)
+    #[clap(long, id = "types", value_delimiter = ',', value_parser = parse_account_type)]
+    types: Vec<WhirlpoolAccountType>,
+
+    // state file of the date (not the previous date)
+    #[clap(long, id = "state", conflicts_with = "archive-dir", requires_all = ["token", "outputs"])]
+    state: Option<String>,
+
+    // token file of the date (decimals of the prices)
+    #[clap(long, id = "token", conflicts_with = "archive-dir")]
+    token: Option<String>,
+
+    // output paths of the account types (in the same order as --types)
+    #[clap(long, id = "outputs", value_delimiter = ',', conflicts_with = "archive-dir")]
+    outputs: Vec<String>,
+
+    // short (same as the archive) | long (human-readable)
+    #[clap(long, id = "key-format", default_value = "short", value_parser = parse_key_format)]
+    key_format: KeyFormat,
+
+    #[clap(flatten)]
+    range: DateRangeArgs,
+}
+
+#[derive(Args, Debug)]
+struct LongKeyArgs {
+    // event | event-<group> | ohlcv-daily | ohlcv-minutely | ohlcv-pair | ohlcv-token | ohlcv-stats | ohlcv-adaptive-fee | ohlcv-<interval> | ohlcv-weekly | ohlcv-monthly | position | position-ledger | position-valuation | position-range
+    #[clap(long, id = "artifact", value_parser = parse_long_key_artifact)]
+    artifact: ArtifactType,
+
+    #[clap(long, id = "input", conflicts_with = "archive-dir", requires = "output")]
+    input: Option<String>,
+
+    #[clap(long, id = "output", conflicts_with = "archive-dir")]
+    output: Option<String>,
+
+    #[clap(flatten)]
+    range: DateRangeArgs,
+}
+
+#[derive(Args, Debug)]
+struct ParquetArgs {
+    // transaction | event | ohlcv-daily | ohlcv-minutely
+    #[clap(long, id = "artifact", value_delimiter = ',', value_parser = parse_parquet_artifact, default_value = "transaction,event,ohlcv-daily,ohlcv-minutely")]
+    artifacts: Vec<ArtifactType>,
+
+    #[clap(flatten)]
+    range: DateRangeArgs,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Event(args) => {
+            if let (Some(state), Some(token), Some(transaction), Some(output)) = (args.state, args.token, args.transaction, args.output) {
+                println!("processing event to {} ...", output);
+                return converter::process::event::process(state, token, transaction, output, args.key_format);
+            }
+
+            for (yyyymmdd_date, archive_dir, output_dir) in dates(&args.range, args.key_format)? {
+                println!("processing event of {} ...", yyyymmdd_date);
+                let state = input_path(&archive_dir, ArtifactType::State, date::prev_yyyymmdd_date(yyyymmdd_date))?;
+                let token = input_path(&archive_dir, ArtifactType::Token, yyyymmdd_date)?;
+                let transaction = input_path(&archive_dir, ArtifactType::Transaction, yyyymmdd_date)?;
+                let output = output_path(&output_dir, ArtifactType::Event, yyyymmdd_date)?;
+                converter::process::event::process(state, token, transaction, output.clone(), args.key_format)?;
+                println!("processed event to {}", output);
+            }
+        }
+        Command::EventGroup(args) => {
+            let artifact = ArtifactType::EventGroup(args.group);
+            if let (Some(event), Some(output)) = (args.event, args.output) {
+                println!("processing {} to {} ...", artifact.name(), output);
+                return converter::process::event_group::process(event, args.group, output, args.key_format);
+            }
+
+            for (yyyymmdd_date, archive_dir, output_dir) in dates(&args.range, args.key_format)? {
+                println!("processing {} of {} ...", artifact.name(), yyyymmdd_date);
+                let event = input_path(&archive_dir, ArtifactType::Event, yyyymmdd_date)?;
+                let output = output_path(&output_dir, artifact, yyyymmdd_date)?;
+                converter::process::event_group::process(event, args.group, output.clone(), args.key_format)?;
+                println!("processed {} to {}", artifact.name(), output);
+            }
+        }
+        Command::Ohlcv(args) => {
+            let anchor_mints = match args.anchor_mints.is_empty() {
+                true => converter::process::ohlcv::pricing::DEFAULT_ANCHOR_MINTS.iter().map(|mint| mint.to_string()).collect(),
+                false => args.anchor_mints,
+            };
+            let options = converter::process::ohlcv::OhlcvOptions { anchor_mints, dense_minutely: args.dense, top_traders: args.top_traders };
+            if let (Some(state), Some(token), Some(event), Some(output_daily), Some(output_minutely), Some(output_pair), Some(output_token), Some(output_stats), Some(output_adaptive_fee)) = (args.state, args.token, args.event, args.output_daily, args.output_minutely, args.output_pair, args.output_token, args.output_stats, args.output_adaptive_fee) {
+                ensure!(args.intervals.len() == args.output_intervals.len(), "--output-intervals must have a path for each of --intervals");
+                let output_intervals = args.intervals.into_iter().zip(args.output_intervals).collect::<Vec<_>>();
+                println!("processing ohlcv to {} and {} ...", output_daily, output_minutely);
+                return converter::process::ohlcv::process(state, token, event, output_daily, output_minutely, &output_intervals, output_pair, output_token, output_stats, output_adaptive_fee, &options, args.key_format);
+            }
+
+            for (yyyymmdd_date, archive_dir, output_dir) in dates(&args.range, args.key_format)? {
+                // dense files must not replace the archived (sparse) files
+                ensure!(!args.dense || Path::new(&output_dir) != Path::new(&archive_dir), "--output-dir other than --archive-dir is required for dense files");
+                println!("processing ohlcv of {} ...", yyyymmdd_date);
+                let state = input_path(&archive_dir, ArtifactType::State, date::prev_yyyymmdd_date(yyyymmdd_date))?;
+                let token = input_path(&archive_dir, ArtifactType::Token, yyyymmdd_date)?;
+                let event = input_path(&archive_dir, ArtifactType::Event, yyyymmdd_date)?;
+                let output_daily = output_path(&output_dir, ArtifactType::OhlcvDaily, yyyymmdd_date)?;
+                let output_minutely = output_path(&output_dir, ArtifactType::OhlcvMinutely, yyyymmdd_date)?;
+                let output_pair = output_path(&output_dir, ArtifactType::OhlcvPair, yyyymmdd_date)?;
+                let output_token = output_path(&output_dir, ArtifactType::OhlcvToken, yyyymmdd_date)?;
+                let output_stats = output_path(&output_dir, ArtifactType::OhlcvStats, yyyymmdd_date)?;
+                let output_adaptive_fee = output_path(&output_dir, ArtifactType::OhlcvAdaptiveFee, yyyymmdd_date)?;
+                let mut output_intervals = vec![];
+                for interval in args.intervals.iter() {
+                    output_intervals.push((*interval, output_path(&output_dir, ArtifactType::OhlcvInterval(*interval), yyyymmdd_date)?));
+                }
+                converter::process::ohlcv::process(state, token, event, output_daily.clone(), output_minutely.clone(), &output_intervals, output_pair, output_token, output_stats, output_adaptive_fee, &options, args.key_format)?;
+                println!("processed ohlcv to {} and {}", output_daily, output_minutely);
+            }
+        }
+        Command::Rollup(args) => {
+            if let Some(output) = args.output {
+                ensure!(args.periods.len() == 1, "--periods must be a single period for explicit paths");
+                let period = args.periods[0];
+                println!("processing ohlcv {} to {} ...", period.name(), output);
+                return converter::process::ohlcv::rollup::process(period, &args.daily, output, args.key_format);
+            }
+
+            for (yyyymmdd_date, archive_dir, output_dir) in dates(&args.range, args.key_format)? {
+                for period in OhlcvPeriod::closing_on(yyyymmdd_date).into_iter().filter(|period| args.periods.contains(period)) {
+                    let artifact = ArtifactType::OhlcvRollup(period);
+                    println!("processing {} of {} ...", artifact.name(), yyyymmdd_date);
+                    let mut daily = vec![];
+                    for daily_yyyymmdd_date in period.yyyymmdd_dates(yyyymmdd_date) {
+                        match input_path(&archive_dir, ArtifactType::OhlcvDaily, daily_yyyymmdd_date) {
+                            Ok(path) => daily.push(path),
+                            Err(err) => println!("{}, skipped", err),
+                        }
+                    }
+                    let output = output_path(&output_dir, artifact, yyyymmdd_date)?;
+                    converter::process::ohlcv::rollup::process(period, &daily, output.clone(), args.key_format)?;
+                    println!("processed {} to {}", artifact.name(), output);
+                }
+            }
+        }
+        Command::Position(args) => {
+            let artifact = ArtifactType::Position;
+            if let (Some(state), Some(output)) = (args.state, args.output) {
+                println!("processing {} to {} ...", artifact.name(), output);
+                return converter::process::position::snapshot::process(state, output, args.key_format);
+            }
+
+            for (yyyymmdd_date, archive_dir, output_dir) in dates(&args.range, args.key_format)? {
+                println!("processing {} of {} ...", artifact.name(), yyyymmdd_date);
+                let state = input_path(&archive_dir, ArtifactType::State, yyyymmdd_date)?;
+                let output = output_path(&output_dir, artifact, yyyymmdd_date)?;
+                converter::process::position::snapshot::process(state, output.clone(), args.key_format)?;
+                println!("processed {} to {}", artifact.name(), output);
+            }
+        }
+        Command::PositionLedger(args) => {
+            let artifact = ArtifactType::PositionLedger;
+            if let (Some(event), Some(output)) = (args.event, args.output) {
+                println!("processing {} to {} ...", artifact.name(), output);
+                return converter::process::position::ledger::process(args.previous, event, output, args.key_format);
+            }
+
+            for (yyyymmdd_date, archive_dir, output_dir) in dates(&args.range, args.key_format)? {
+                println!("processing {} of {} ...", artifact.name(), yyyymmdd_date);
+                // the ledger of the previous date is the output of the previous date in the range (short keys only)
+                let previous_dir = match args.key_format {
+                    KeyFormat::Short => &output_dir,
+                    KeyFormat::Long => &archive_dir,
+                };
+                let previous = match args.start_date == Some(yyyymmdd_date) {
+                    true => {
+                        println!("{} is the ledger start date, the ledger starts over", yyyymmdd_date);
+                        None
+                    }
+                    false => Some(input_path(previous_dir, artifact, date::prev_yyyymmdd_date(yyyymmdd_date))?),
+                };
+                let event = input_path(&archive_dir, ArtifactType::Event, yyyymmdd_date)?;
+                let output = output_path(&output_dir, artifact, yyyymmdd_date)?;
+                converter::process::position::ledger::process(previous, event, output.clone(), args.key_format)?;
+                println!("processed {} to {}", artifact.name(), output);
+            }
+        }
+        Command::PositionValuation(args) => {
+            let artifact = ArtifactType::PositionValuation;
+            if let (Some(state), Some(token), Some(event), Some(position_ledger), Some(output_valuation), Some(output_range)) = (args.state, args.token, args.event, args.position_ledger, args.output_valuation, args.output_range) {
+                println!("processing {} to {} and {} ...", artifact.name(), output_valuation, output_range);
+                return converter::process::position::valuation::process(state, token, event, position_ledger, output_valuation, output_range, args.key_format);
+            }
+
+            for (yyyymmdd_date, archive_dir, output_dir) in dates(&args.range, args.key_format)? {
+                println!("processing {} of {} ...", artifact.name(), yyyymmdd_date);
+                let state = input_path(&archive_dir, ArtifactType::State, date::prev_yyyymmdd_date(yyyymmdd_date))?;
+                let token = input_path(&archive_dir, ArtifactType::Token, yyyymmdd_date)?;
+                let event = input_path(&archive_dir, ArtifactType::Event, yyyymmdd_date)?;
+                let position_ledger = input_path(&archive_dir, ArtifactType::PositionLedger, yyyymmdd_date)?;
+                let output_valuation = output_path(&output_dir, ArtifactType::PositionValuation, yyyymmdd_date)?;
+                let output_range = output_path(&output_dir, ArtifactType::PositionRange, yyyymmdd_date)?;
+                converter::process::position::valuation::process(state, token, event, position_ledger, output_valuation.clone(), output_range.clone(), args.key_format)?;
+                println!("processed {} to {} and {}", artifact.name(), output_valuation, output_range);
+            }
+        }
+        Command::DecodeState(args) => {
+            let types = match args.types.is_empty() {
+                true => WhirlpoolAccountType::ALL.to_vec(),
+                false => args.types,
+            };
+            if let (Some(state), Some(token)) = (args.state, args.token) {
+                ensure!(types.len() == args.outputs.len(), "--outputs must have a path for each of --types");
+                let outputs = types.into_iter().zip(args.outputs).collect::<Vec<_>>();
+                println!("decoding state {} ...", state);
+                return converter::process::account::process(state, token, &outputs, args.key_format);
+            }
+
+            for (yyyymmdd_date, archive_dir, output_dir) in dates(&args.range, args.key_format)? {
+                println!("decoding state of {} ...", yyyymmdd_date);
+                let state = input_path(&archive_dir, ArtifactType::State, yyyymmdd_date)?;
+                let token = input_path(&archive_dir, ArtifactType::Token, yyyymmdd_date)?;
+                let mut outputs = vec![];
+                for account_type in types.iter() {
+                    outputs.push((*account_type, account_output_path(&output_dir, *account_type, yyyymmdd_date)?));
+                }
+                converter::process::account::process(state, token, &outputs, args.key_format)?;
+                println!("decoded state of {} to {}", yyyymmdd_date, output_dir);
+            }
+        }
+        Command::LongKey(args) => {
+            if let (Some(input), Some(output)) = (args.input, args.output) {
+                println!("processing long key {} to {} ...", args.artifact.name(), output);
+                return process_long_key(args.artifact, input, output);
+            }
+
+            for (yyyymmdd_date, archive_dir, output_dir) in dates(&args.range, KeyFormat::Long)? {
+                println!("processing long key {} of {} ...", args.artifact.name(), yyyymmdd_date);
+                let input = input_path(&archive_dir, args.artifact, yyyymmdd_date)?;
+                let output = output_path(&output_dir, args.artifact, yyyymmdd_date)?;
+                process_long_key(args.artifact, input, output.clone())?;
+                println!("processed long key {} to {}", args.artifact.name(), output);
+            }
+        }
+        Command::Parquet(args) => {
+            for (yyyymmdd_date, archive_dir, output_dir) in dates(&args.range, KeyFormat::Short)? {
+                for artifact in args.artifacts.iter() {
+                    println!("processing parquet of {} of {} ...", artifact.name(), yyyymmdd_date);
+                    let input = input_path(&archive_dir, *artifact, yyyymmdd_date)?;
+                    let output = |table: ParquetTable| output_path(&output_dir, ArtifactType::Parquet(table), yyyymmdd_date);
+                    match artifact {
+                        ArtifactType::Transaction => converter::process::parquet::process_transaction(
+                            input,
+                            output(ParquetTable::TransactionInstruction)?,
+                            output(ParquetTable::TransactionBalance)?,
+                        )?,
+                        ArtifactType::Event => {
+                            let mut outputs = HashMap::new();
+                            for table in ParquetTable::event_tables() {
+                                outputs.insert(table, output(table)?);
+                            }
+                            converter::process::parquet::process_event(input, |table| outputs[&table].clone())?
+                        }
+                        ArtifactType::OhlcvDaily => converter::process::parquet::process_ohlcv_daily(input, output(ParquetTable::OhlcvDaily)?)?,
+                        ArtifactType::OhlcvMinutely => converter::process::parquet::process_ohlcv_minutely(input, output(ParquetTable::OhlcvMinutely)?)?,
+                        _ => bail!("unsupported artifact: {}", artifact.name()),
+                    }
+                    println!("processed parquet of {}", artifact.name());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_event_group(name: &str) -> Result<WhirlpoolEventGroup> {
+    match WhirlpoolEventGroup::from_name(name) {
+        // all is the event file itself
+        Some(WhirlpoolEventGroup::All) | None => bail!("unknown event group: {} (trade or liquidity)", name),
+        Some(group) => Ok(group),
+    }
+}
+
+fn parse_ohlcv_interval(name: &str) -> Result<OhlcvInterval> {
+    match OhlcvInterval::from_name(name) {
+        Some(interval) => Ok(interval),
+        None => bail!("unknown ohlcv interval: {} (5m, 15m, 30m, 1h, 4h or 12h)", name),
+    }
+}
+
+fn parse_ohlcv_period(name: &str) -> Result<OhlcvPeriod> {
+    match OhlcvPeriod::from_name(name) {
+        Some(period) => Ok(period),
+        None => bail!("unknown ohlcv period: {} (weekly or monthly)", name),
+    }
+}
+
+fn parse_key_format(name: &str) -> Result<KeyFormat> {
+    match KeyFormat::from_name(name) {
+        Some(key_format) => Ok(key_format),
+        None => bail!("unknown key format: {} (short or long)", name),
+    }
+}
+
+fn parse_long_key_artifact(name: &str) -> Result<ArtifactType> {
+    match ArtifactType::from_name(name) {
+        Some(artifact @ (ArtifactType::Event | ArtifactType::EventGroup(_) | ArtifactType::OhlcvDaily | ArtifactType::OhlcvMinutely | ArtifactType::OhlcvPair | ArtifactType::OhlcvToken | ArtifactType::OhlcvStats | ArtifactType::OhlcvAdaptiveFee | ArtifactType::OhlcvInterval(_) | ArtifactType::OhlcvRollup(_) | ArtifactType::Position | ArtifactType::PositionLedger | ArtifactType::PositionValuation | ArtifactType::PositionRange)) => Ok(artifact),
+        _ => bail!("unsupported artifact: {} (event, event-<group>, ohlcv-daily, ohlcv-minutely, ohlcv-pair, ohlcv-token, ohlcv-stats, ohlcv-adaptive-fee, ohlcv-<interval>, ohlcv-weekly, ohlcv-monthly, position, position-ledger, position-valuation or position-range)", name),
+    }
+}
+
+fn parse_account_type(name: &str) -> Result<WhirlpoolAccountType> {
+    match WhirlpoolAccountType::from_name(name) {
+        Some(account_type) => Ok(account_type),
+        None => bail!("unknown account type: {} (whirlpool, whirlpools-config, whirlpools-config-extension, fee-tier, adaptive-fee-tier, oracle, token-badge or lock-config)", name),
+    }
+}
+
+fn parse_parquet_artifact(name: &str) -> Result<ArtifactType> {
+    match ArtifactType::from_name(name) {
+        Some(artifact @ (ArtifactType::Transaction | ArtifactType::Event | ArtifactType::OhlcvDaily | ArtifactType::OhlcvMinutely)) => Ok(artifact),
+        _ => bail!("unsupported artifact: {} (transaction, event, ohlcv-daily or ohlcv-minutely)", name),
+    }
+}
+
+fn process_long_key(artifact: ArtifactType, input: String, output: String) -> Result<()> {
+    match artifact {
+        ArtifactType::Event | ArtifactType::EventGroup(_) => converter::process::long_key::process_event(input, output),
+        ArtifactType::OhlcvDaily => converter::process::long_key::process_ohlcv_daily(input, output),
+        ArtifactType::OhlcvMinutely => converter::process::long_key::process_ohlcv_minutely(input, output),
+        ArtifactType::OhlcvPair => converter::process::long_key::process_ohlcv_pair(input, output),
+        ArtifactType::OhlcvToken => converter::process::long_key::process_ohlcv_token(input, output),
+        ArtifactType::OhlcvStats => converter::process::long_key::process_ohlcv_stats(input, output),
+        ArtifactType::OhlcvAdaptiveFee => converter::process::long_key::process_ohlcv_adaptive_fee(input, output),
+        ArtifactType::OhlcvInterval(_) => converter::process::long_key::process_ohlcv_interval(input, output),
+        ArtifactType::OhlcvRollup(_) => converter::process::long_key::process_ohlcv_rollup(input, output),
+        ArtifactType::Position => converter::process::long_key::process_position(input, output),
+        ArtifactType::PositionLedger => converter::process::long_key::process_position_ledger(input, output),
+        ArtifactType::PositionValuation => converter::process::long_key::process_position_valuation(input, output),
+        ArtifactType::PositionRange => converter::process::long_key::process_position_range(input, output),
+        _ => bail!("unsupported artifact: {}", artifact.name()),
+    }
+}
+
+// (yyyymmdd_date, archive_dir, output_dir) for each date in the range
+fn dates(range: &DateRangeArgs, key_format: KeyFormat) -> Result<Vec<(u32, String, String)>> {
+    let (Some(archive_dir), Some(from), Some(to)) = (range.archive_dir.clone(), range.from, range.to) else {
+        bail!("either input/output paths or --archive-dir with --from and --to are required");
+    };
+    ensure!(from <= to, "--from must be less than or equal to --to");
+    let output_dir = range.output_dir.clone().unwrap_or(archive_dir.clone());
+    // long key files must not replace the archived (short key) files
+    ensure!(
+        key_format == KeyFormat::Short || Path::new(&output_dir) != Path::new(&archive_dir),
+        "--output-dir other than --archive-dir is required for long key files"
+    );
+
+    let mut dates = vec![];
+    let mut yyyymmdd_date = from;
+    while yyyymmdd_date <= to {
+        dates.push((yyyymmdd_date, archive_dir.clone(), output_dir.clone()));
+        yyyymmdd_date = date::next_yyyymmdd_date(yyyymmdd_date);
+    }
+    Ok(dates)
+}
+
+fn input_path(archive_dir: &str, artifact: ArtifactType, yyyymmdd_date: u32) -> Result<String> {
+    let path = Path::new(archive_dir).join(artifact.key(yyyymmdd_date));
+    ensure!(path.is_file(), "{} not found", path.display());
+    Ok(path.to_string_lossy().to_string())
+}
+
+fn output_path(output_dir: &str, artifact: ArtifactType, yyyymmdd_date: u32) -> Result<String> {
+    let path = Path::new(output_dir).join(artifact.key(yyyymmdd_date));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(path.to_string_lossy().to_string())
+}
+
+// decoded account files are not archived, but they use the same layout as the archive
+fn account_output_path(output_dir: &str, account_type: WhirlpoolAccountType, yyyymmdd_date: u32) -> Result<String> {
+    let path = Path::new(output_dir).join(storage::date_key(yyyymmdd_date, &account_type.file_name(yyyymmdd_date)));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(path.to_string_lossy().to_string())
+}
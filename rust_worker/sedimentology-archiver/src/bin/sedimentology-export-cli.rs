@@ -2,6 +2,9 @@ use mysql::*;
 use clap::Parser;
 use std::str::FromStr;
 
+use sedimentology_archiver::artifact::ArtifactType;
+use sedimentology_archiver::converter;
+use sedimentology_archiver::converter::model::parquet::ParquetTable;
 use sedimentology_archiver::io;
 
 #[derive(Parser, Debug)]
@@ -27,6 +30,9 @@ struct Args {
     token: bool,
     #[clap(long, id = "transaction")]
     transaction: bool,
+    // also write parquet files of the transaction file (transaction-instruction and transaction-balance)
+    #[clap(long, id = "parquet", requires = "transaction")]
+    parquet: bool,
 
     #[clap(id = "yyyymmdd")]
     yyyymmdd: String,
@@ -69,5 +75,13 @@ fn main() {
         println!("exporting transaction ...");
         io::export_transaction(exporting_yyyymmdd_date, &transaction_file_expfile, &mut conn);
         println!("exported transaction to {}.", transaction_file_expfile);
+
+        if args.parquet {
+            let instruction_file_expfile = ArtifactType::Parquet(ParquetTable::TransactionInstruction).file_name(exporting_yyyymmdd_date);
+            let balance_file_expfile = ArtifactType::Parquet(ParquetTable::TransactionBalance).file_name(exporting_yyyymmdd_date);
+            println!("converting transaction to parquet ...");
+            converter::process::parquet::process_transaction(transaction_file_expfile, instruction_file_expfile.clone(), balance_file_expfile.clone()).unwrap();
+            println!("converted transaction to {} and {}.", instruction_file_expfile, balance_file_expfile);
+        }
     }
 }
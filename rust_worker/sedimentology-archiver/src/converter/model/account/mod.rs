@@ -0,0 +1,346 @@
+use serde_derive::{Serialize, Deserialize};
+use super::event::definition::{AdaptiveFeeConstants, AdaptiveFeeVariables, PositionLockType};
+use super::ohlcv::{DecimalPrice, PubkeyString, TokenData};
+use super::serde::{string_decimal_price, string_option_decimal_price, string_u128, string_u64};
+
+// format version of decoded account files
+pub const WHIRLPOOL_ACCOUNT_FORMAT_VERSION: u32 = 1;
+
+/*
+
+Whirlpool Decoded Account JSON Lines Format (whirlpool-account-<type>-yyyymmdd.jsonl.gz)
+
+Accounts in the state file of the date (whirlpool-state-yyyymmdd.json.gz) decoded with Anchor,
+so consumers do not need to decode the raw account data.
+The account type is detected by the discriminator of the account data, one file per account type:
+
+- whirlpool: Whirlpool
+- whirlpools-config: WhirlpoolsConfig
+- whirlpools-config-extension: WhirlpoolsConfigExtension
+- fee-tier: FeeTier
+- adaptive-fee-tier: AdaptiveFeeTier
+- oracle: Oracle
+- token-badge: TokenBadge
+- lock-config: LockConfig
+
+One line per account (sorted by account).
+Prices are adjusted with the decimals of the token file of the date (whirlpool-token-yyyymmdd.json.gz).
+
+To reduce data size, we use short field names.
+Long key format (long_key::KeyFormat::Long) uses the names in front of the parentheses.
+
+whirlpool:
+{
+  account(a): String(base58 encoding),
+  whirlpoolsConfig(wc): String(base58 encoding),
+  tickSpacing(ts): u16,
+  feeTierIndex(fti): u16 (same as tickSpacing unless the pool is initialized with an adaptive fee tier),
+  adaptiveFeeEnabled(afe): bool,
+  tokenA(ta): { mint(m): String(base58 encoding), decimals(d): u8 },
+  tokenB(tb): { mint(m): String(base58 encoding), decimals(d): u8 },
+  tokenVaultA(tva): String(base58 encoding),
+  tokenVaultB(tvb): String(base58 encoding),
+  feeRate(fr): u16 (hundredths of a basis point),
+  protocolFeeRate(pfr): u16 (basis points of the fee),
+  liquidity(l): String,
+  sqrtPrice(sp): String (X64 fixed point),
+  decimalPrice(dp): String (price of token A in token B, adjusted with the decimals),
+  currentTickIndex(cti): i32,
+  protocolFeeOwedA(pfoa): String,
+  protocolFeeOwedB(pfob): String,
+  feeGrowthGlobalA(fgga): String (X64 fixed point),
+  feeGrowthGlobalB(fggb): String (X64 fixed point),
+  rewardLastUpdatedTimestamp(rlut): String,
+  rewards(r): [
+    {
+      index(i): u8,
+      mint(m): String(base58 encoding),
+      vault(v): String(base58 encoding),
+      authority(au): String(base58 encoding),
+      emissionsPerSecondX64(eps): String (X64 fixed point),
+      emissionsPerDay(epd): String (adjusted with the decimals of the reward mint, omitted if the decimals are unknown),
+      growthGlobalX64(gg): String (X64 fixed point),
+    },
+    ... (initialized reward slots only)
+  ],
+}
+
+whirlpools-config:
+{
+  account(a): String(base58 encoding),
+  feeAuthority(fa): String(base58 encoding),
+  collectProtocolFeesAuthority(cpfa): String(base58 encoding),
+  rewardEmissionsSuperAuthority(resa): String(base58 encoding),
+  defaultProtocolFeeRate(dpfr): u16,
+  featureFlags(ff): u16,
+}
+
+whirlpools-config-extension:
+{
+  account(a): String(base58 encoding),
+  whirlpoolsConfig(wc): String(base58 encoding),
+  configExtensionAuthority(cea): String(base58 encoding),
+  tokenBadgeAuthority(tba): String(base58 encoding),
+}
+
+fee-tier:
+{
+  account(a): String(base58 encoding),
+  whirlpoolsConfig(wc): String(base58 encoding),
+  tickSpacing(ts): u16,
+  defaultFeeRate(dfr): u16,
+}
+
+adaptive-fee-tier:
+{
+  account(a): String(base58 encoding),
+  whirlpoolsConfig(wc): String(base58 encoding),
+  feeTierIndex(fti): u16,
+  tickSpacing(ts): u16,
+  initializePoolAuthority(ipa): String(base58 encoding),
+  delegatedFeeAuthority(dfa): String(base58 encoding),
+  defaultBaseFeeRate(dbfr): u16,
+  adaptiveFeeConstants(afc): AdaptiveFeeConstants (same as the event file),
+}
+
+oracle:
+{
+  account(a): String(base58 encoding),
+  whirlpool(w): String(base58 encoding),
+  tradeEnableTimestamp(tet): String,
+  adaptiveFeeConstants(afc): AdaptiveFeeConstants (same as the event file),
+  adaptiveFeeVariables(afv): AdaptiveFeeVariables (same as the event file),
+}
+
+token-badge:
+{
+  account(a): String(base58 encoding),
+  whirlpoolsConfig(wc): String(base58 encoding),
+  tokenMint(tm): String(base58 encoding),
+  attributeRequireNonTransferablePosition(arntp): bool,
+}
+
+lock-config:
+{
+  account(a): String(base58 encoding),
+  position(p): String(base58 encoding),
+  positionOwner(po): String(base58 encoding),
+  whirlpool(w): String(base58 encoding),
+  lockedTimestamp(lts): String,
+  lockType(lt): PositionLockType (same as the event file),
+}
+
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum WhirlpoolAccountType {
+  Whirlpool,
+  WhirlpoolsConfig,
+  WhirlpoolsConfigExtension,
+  FeeTier,
+  AdaptiveFeeTier,
+  Oracle,
+  TokenBadge,
+  LockConfig,
+}
+
+impl WhirlpoolAccountType {
+  pub const ALL: [WhirlpoolAccountType; 8] = [
+    WhirlpoolAccountType::Whirlpool,
+    WhirlpoolAccountType::WhirlpoolsConfig,
+    WhirlpoolAccountType::WhirlpoolsConfigExtension,
+    WhirlpoolAccountType::FeeTier,
+    WhirlpoolAccountType::AdaptiveFeeTier,
+    WhirlpoolAccountType::Oracle,
+    WhirlpoolAccountType::TokenBadge,
+    WhirlpoolAccountType::LockConfig,
+  ];
+
+  pub fn from_name(name: &str) -> Option<WhirlpoolAccountType> {
+    WhirlpoolAccountType::ALL.into_iter().find(|account_type| account_type.name() == name)
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      WhirlpoolAccountType::Whirlpool => "whirlpool",
+      WhirlpoolAccountType::WhirlpoolsConfig => "whirlpools-config",
+      WhirlpoolAccountType::WhirlpoolsConfigExtension => "whirlpools-config-extension",
+      WhirlpoolAccountType::FeeTier => "fee-tier",
+      WhirlpoolAccountType::AdaptiveFeeTier => "adaptive-fee-tier",
+      WhirlpoolAccountType::Oracle => "oracle",
+      WhirlpoolAccountType::TokenBadge => "token-badge",
+      WhirlpoolAccountType::LockConfig => "lock-config",
+    }
+  }
+
+  // e.g. whirlpool-account-fee-tier-20240101.jsonl.gz
+  pub fn file_name(&self, yyyymmdd_date: u32) -> String {
+    format!("whirlpool-account-{}-{}.jsonl.gz", self.name(), yyyymmdd_date)
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DecodedWhirlpoolData {
+  #[serde(rename = "a")]
+  pub account: PubkeyString,
+  #[serde(rename = "wc")]
+  pub whirlpools_config: PubkeyString,
+  #[serde(rename = "ts")]
+  pub tick_spacing: u16,
+  #[serde(rename = "fti")]
+  pub fee_tier_index: u16,
+  #[serde(rename = "afe")]
+  pub adaptive_fee_enabled: bool,
+  #[serde(rename = "ta")]
+  pub token_a: TokenData,
+  #[serde(rename = "tb")]
+  pub token_b: TokenData,
+  #[serde(rename = "tva")]
+  pub token_vault_a: PubkeyString,
+  #[serde(rename = "tvb")]
+  pub token_vault_b: PubkeyString,
+  #[serde(rename = "fr")]
+  pub fee_rate: u16,
+  #[serde(rename = "pfr")]
+  pub protocol_fee_rate: u16,
+  #[serde(rename = "l", with = "string_u128")]
+  pub liquidity: u128,
+  #[serde(rename = "sp", with = "string_u128")]
+  pub sqrt_price: u128,
+  #[serde(rename = "dp", with = "string_decimal_price")]
+  pub decimal_price: DecimalPrice,
+  #[serde(rename = "cti")]
+  pub current_tick_index: i32,
+  #[serde(rename = "pfoa", with = "string_u64")]
+  pub protocol_fee_owed_a: u64,
+  #[serde(rename = "pfob", with = "string_u64")]
+  pub protocol_fee_owed_b: u64,
+  #[serde(rename = "fgga", with = "string_u128")]
+  pub fee_growth_global_a: u128,
+  #[serde(rename = "fggb", with = "string_u128")]
+  pub fee_growth_global_b: u128,
+  #[serde(rename = "rlut", with = "string_u64")]
+  pub reward_last_updated_timestamp: u64,
+  #[serde(rename = "r")]
+  pub rewards: Vec<DecodedRewardData>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DecodedRewardData {
+  #[serde(rename = "i")]
+  pub index: u8,
+  #[serde(rename = "m")]
+  pub mint: PubkeyString,
+  #[serde(rename = "v")]
+  pub vault: PubkeyString,
+  #[serde(rename = "au")]
+  pub authority: PubkeyString,
+  #[serde(rename = "eps", with = "string_u128")]
+  pub emissions_per_second_x64: u128,
+  #[serde(rename = "epd", default, skip_serializing_if = "Option::is_none", with = "string_option_decimal_price")]
+  pub emissions_per_day: Option<DecimalPrice>,
+  #[serde(rename = "gg", with = "string_u128")]
+  pub growth_global_x64: u128,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DecodedWhirlpoolsConfigData {
+  #[serde(rename = "a")]
+  pub account: PubkeyString,
+  #[serde(rename = "fa")]
+  pub fee_authority: PubkeyString,
+  #[serde(rename = "cpfa")]
+  pub collect_protocol_fees_authority: PubkeyString,
+  #[serde(rename = "resa")]
+  pub reward_emissions_super_authority: PubkeyString,
+  #[serde(rename = "dpfr")]
+  pub default_protocol_fee_rate: u16,
+  #[serde(rename = "ff")]
+  pub feature_flags: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DecodedWhirlpoolsConfigExtensionData {
+  #[serde(rename = "a")]
+  pub account: PubkeyString,
+  #[serde(rename = "wc")]
+  pub whirlpools_config: PubkeyString,
+  #[serde(rename = "cea")]
+  pub config_extension_authority: PubkeyString,
+  #[serde(rename = "tba")]
+  pub token_badge_authority: PubkeyString,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DecodedFeeTierData {
+  #[serde(rename = "a")]
+  pub account: PubkeyString,
+  #[serde(rename = "wc")]
+  pub whirlpools_config: PubkeyString,
+  #[serde(rename = "ts")]
+  pub tick_spacing: u16,
+  #[serde(rename = "dfr")]
+  pub default_fee_rate: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DecodedAdaptiveFeeTierData {
+  #[serde(rename = "a")]
+  pub account: PubkeyString,
+  #[serde(rename = "wc")]
+  pub whirlpools_config: PubkeyString,
+  #[serde(rename = "fti")]
+  pub fee_tier_index: u16,
+  #[serde(rename = "ts")]
+  pub tick_spacing: u16,
+  #[serde(rename = "ipa")]
+  pub initialize_pool_authority: PubkeyString,
+  #[serde(rename = "dfa")]
+  pub delegated_fee_authority: PubkeyString,
+  #[serde(rename = "dbfr")]
+  pub default_base_fee_rate: u16,
+  #[serde(rename = "afc")]
+  pub adaptive_fee_constants: AdaptiveFeeConstants,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DecodedOracleData {
+  #[serde(rename = "a")]
+  pub account: PubkeyString,
+  #[serde(rename = "w")]
+  pub whirlpool: PubkeyString,
+  #[serde(rename = "tet", with = "string_u64")]
+  pub trade_enable_timestamp: u64,
+  #[serde(rename = "afc")]
+  pub adaptive_fee_constants: AdaptiveFeeConstants,
+  #[serde(rename = "afv")]
+  pub adaptive_fee_variables: AdaptiveFeeVariables,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DecodedTokenBadgeData {
+  #[serde(rename = "a")]
+  pub account: PubkeyString,
+  #[serde(rename = "wc")]
+  pub whirlpools_config: PubkeyString,
+  #[serde(rename = "tm")]
+  pub token_mint: PubkeyString,
+  #[serde(rename = "arntp")]
+  pub attribute_require_non_transferable_position: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DecodedLockConfigData {
+  #[serde(rename = "a")]
+  pub account: PubkeyString,
+  #[serde(rename = "p")]
+  pub position: PubkeyString,
+  #[serde(rename = "po")]
+  pub position_owner: PubkeyString,
+  #[serde(rename = "w")]
+  pub whirlpool: PubkeyString,
+  #[serde(rename = "lts", with = "string_u64")]
+  pub locked_timestamp: u64,
+  #[serde(rename = "lt")]
+  pub lock_type: PositionLockType,
+}
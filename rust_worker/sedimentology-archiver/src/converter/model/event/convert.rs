@@ -1,7 +1,7 @@
 use core::panic;
 use std::collections::HashMap;
 
-use bigdecimal::BigDecimal;
+use bigdecimal::{num_bigint::BigInt, BigDecimal, ToPrimitive};
 use replay_engine::{
     account_data_store::AccountDataStore,
     decoded_instructions::TransferAmountWithTransferFeeConfig, types::WritableAccountSnapshot,
@@ -82,6 +82,11 @@ pub fn build_whirlpool_events(
                 transfer_out: from_v1_transfer(params.transfer_amount_1, mint_out, decimals),
                 old_adaptive_fee_variables: from_option_oracle(&old_oracle),
                 new_adaptive_fee_variables: from_option_oracle(&new_oracle),
+                lp_fee: lp_fee(&old_whirlpool, &new_whirlpool, &old_oracle, params.data_a_to_b),
+                protocol_fee: Some(protocol_fee(&old_whirlpool, &new_whirlpool, params.data_a_to_b)),
+                old_liquidity: Some(old_whirlpool.liquidity),
+                new_liquidity: Some(new_whirlpool.liquidity),
+                crossed_initialized_tick: Some(crossed_initialized_tick(accounts, writable_account_snapshot, &params.key_whirlpool, &old_whirlpool, &new_whirlpool)),
             }));
         }
         DecodedWhirlpoolInstruction::SwapV2(params) => {
@@ -139,6 +144,11 @@ pub fn build_whirlpool_events(
                 transfer_out: from_v2_transfer(&params.transfer_1, mint_out, decimals),
                 old_adaptive_fee_variables: from_option_oracle(&old_oracle),
                 new_adaptive_fee_variables: from_option_oracle(&new_oracle),
+                lp_fee: lp_fee(&old_whirlpool, &new_whirlpool, &old_oracle, params.data_a_to_b),
+                protocol_fee: Some(protocol_fee(&old_whirlpool, &new_whirlpool, params.data_a_to_b)),
+                old_liquidity: Some(old_whirlpool.liquidity),
+                new_liquidity: Some(new_whirlpool.liquidity),
+                crossed_initialized_tick: Some(crossed_initialized_tick(accounts, writable_account_snapshot, &params.key_whirlpool, &old_whirlpool, &new_whirlpool)),
             }));
         }
         DecodedWhirlpoolInstruction::TwoHopSwap(params) => {
@@ -203,6 +213,11 @@ pub fn build_whirlpool_events(
                 transfer_out: from_v1_transfer(params.transfer_amount_1, mint_out_one, decimals),
                 old_adaptive_fee_variables: from_option_oracle(&old_oracle_one),
                 new_adaptive_fee_variables: from_option_oracle(&new_oracle_one),
+                lp_fee: lp_fee(&old_whirlpool_one, &new_whirlpool_one, &old_oracle_one, params.data_a_to_b_one),
+                protocol_fee: Some(protocol_fee(&old_whirlpool_one, &new_whirlpool_one, params.data_a_to_b_one)),
+                old_liquidity: Some(old_whirlpool_one.liquidity),
+                new_liquidity: Some(new_whirlpool_one.liquidity),
+                crossed_initialized_tick: Some(crossed_initialized_tick(accounts, writable_account_snapshot, &params.key_whirlpool_one, &old_whirlpool_one, &new_whirlpool_one)),
             }));
 
             let old_whirlpool_two =
@@ -266,6 +281,11 @@ pub fn build_whirlpool_events(
                 transfer_out: from_v1_transfer(params.transfer_amount_3, mint_out_two, decimals),
                 old_adaptive_fee_variables: from_option_oracle(&old_oracle_two),
                 new_adaptive_fee_variables: from_option_oracle(&new_oracle_two),
+                lp_fee: lp_fee(&old_whirlpool_two, &new_whirlpool_two, &old_oracle_two, params.data_a_to_b_two),
+                protocol_fee: Some(protocol_fee(&old_whirlpool_two, &new_whirlpool_two, params.data_a_to_b_two)),
+                old_liquidity: Some(old_whirlpool_two.liquidity),
+                new_liquidity: Some(new_whirlpool_two.liquidity),
+                crossed_initialized_tick: Some(crossed_initialized_tick(accounts, writable_account_snapshot, &params.key_whirlpool_two, &old_whirlpool_two, &new_whirlpool_two)),
             }));
         }
         DecodedWhirlpoolInstruction::TwoHopSwapV2(params) => {
@@ -330,6 +350,11 @@ pub fn build_whirlpool_events(
                 transfer_out: from_v2_transfer(&params.transfer_1, mint_out_one, decimals),
                 old_adaptive_fee_variables: from_option_oracle(&old_oracle_one),
                 new_adaptive_fee_variables: from_option_oracle(&new_oracle_one),
+                lp_fee: lp_fee(&old_whirlpool_one, &new_whirlpool_one, &old_oracle_one, params.data_a_to_b_one),
+                protocol_fee: Some(protocol_fee(&old_whirlpool_one, &new_whirlpool_one, params.data_a_to_b_one)),
+                old_liquidity: Some(old_whirlpool_one.liquidity),
+                new_liquidity: Some(new_whirlpool_one.liquidity),
+                crossed_initialized_tick: Some(crossed_initialized_tick(accounts, writable_account_snapshot, &params.key_whirlpool_one, &old_whirlpool_one, &new_whirlpool_one)),
             }));
 
             let old_whirlpool_two =
@@ -393,6 +418,11 @@ pub fn build_whirlpool_events(
                 transfer_out: from_v2_transfer(&params.transfer_2, mint_out_two, decimals),
                 old_adaptive_fee_variables: from_option_oracle(&old_oracle_two),
                 new_adaptive_fee_variables: from_option_oracle(&new_oracle_two),
+                lp_fee: lp_fee(&old_whirlpool_two, &new_whirlpool_two, &old_oracle_two, params.data_a_to_b_two),
+                protocol_fee: Some(protocol_fee(&old_whirlpool_two, &new_whirlpool_two, params.data_a_to_b_two)),
+                old_liquidity: Some(old_whirlpool_two.liquidity),
+                new_liquidity: Some(new_whirlpool_two.liquidity),
+                crossed_initialized_tick: Some(crossed_initialized_tick(accounts, writable_account_snapshot, &params.key_whirlpool_two, &old_whirlpool_two, &new_whirlpool_two)),
             }));
         }
         ////////////////////////////////////////////////////////////////////////////////
@@ -2192,6 +2222,95 @@ fn get_new_whirlpool(accounts: &AccountDataStore, pubkey: &PubkeyString) -> Whir
     Whirlpool::try_deserialize(&mut post_data.as_slice()).unwrap()
 }
 
+// LP fee of a swap in the input token
+// fee growth is incremented by floor(fee * 2^64 / liquidity) at each step of the swap, so the fee is restored with the liquidity,
+// but each step loses less than liquidity / 2^64 token units to the rounding of fee growth.
+// it is exact only if the swap does not change the active liquidity (no initialized tick with non-zero liquidity net is crossed)
+// and the rounding of all steps is less than 1 token unit (steps * liquidity is at most 2^64).
+// None if it is not exact, the fee is estimated from the fee rate instead.
+fn lp_fee(old_whirlpool: &Whirlpool, new_whirlpool: &Whirlpool, old_oracle: &Option<Oracle>, a_to_b: bool) -> Option<u64> {
+    let liquidity = old_whirlpool.liquidity;
+    if liquidity != new_whirlpool.liquidity {
+        return None;
+    }
+    let max_steps = max_swap_steps(old_whirlpool, new_whirlpool, old_oracle);
+    if !(max_steps as u128).checked_mul(liquidity).is_some_and(|rounding| rounding <= 1u128 << 64) {
+        return None;
+    }
+
+    let (old_fee_growth, new_fee_growth) = if a_to_b {
+        (old_whirlpool.fee_growth_global_a, new_whirlpool.fee_growth_global_a)
+    } else {
+        (old_whirlpool.fee_growth_global_b, new_whirlpool.fee_growth_global_b)
+    };
+    let fee_growth = new_fee_growth.wrapping_sub(old_fee_growth);
+
+    // ceil(fee_growth * liquidity / 2^64) (the product may exceed u128)
+    let x64_mask = BigInt::from(u64::MAX);
+    let fee: BigInt = (BigInt::from(fee_growth) * BigInt::from(liquidity) + x64_mask) >> 64;
+    fee.to_u64()
+}
+
+// upper bound of the steps of a swap
+// a step ends at the next initialized tick, the end of the tick array or the end of the tick group (adaptive fee pools),
+// they are all multiples of the tick spacing or the tick group size, so the steps are bounded by the ticks the price moved.
+fn max_swap_steps(old_whirlpool: &Whirlpool, new_whirlpool: &Whirlpool, old_oracle: &Option<Oracle>) -> u64 {
+    let mut boundary_ticks = old_whirlpool.tick_spacing as u64;
+    if let Some(oracle) = old_oracle {
+        boundary_ticks = boundary_ticks.min(oracle.adaptive_fee_constants.tick_group_size as u64);
+    }
+    // the current tick index is the floor of the price, so the price may have moved 1 tick more
+    let ticks = (new_whirlpool.tick_current_index as i64 - old_whirlpool.tick_current_index as i64).unsigned_abs() + 1;
+    // boundaries crossed and the last step
+    ticks / boundary_ticks.max(1) + 2
+}
+
+// whether a swap crossed initialized ticks of the whirlpool
+// crossing an initialized tick flips its fee and reward growths outside, so a tick array of the whirlpool is written only if the swap crossed its ticks.
+// the flip leaves a tick unchanged if its growths outside are half of the global ones (e.g. no fees have been accrued yet),
+// so a swap changing the active liquidity is taken as crossed regardless of the tick arrays.
+fn crossed_initialized_tick(
+    accounts: &AccountDataStore,
+    writable_account_snapshot: &WritableAccountSnapshot,
+    whirlpool: &PubkeyString,
+    old_whirlpool: &Whirlpool,
+    new_whirlpool: &Whirlpool,
+) -> bool {
+    if old_whirlpool.liquidity != new_whirlpool.liquidity {
+        return true;
+    }
+    // the tick arrays passed to the swap (including supplemental tick arrays) are writable
+    writable_account_snapshot.pre_snapshot.iter().any(|(pubkey, pre_data)| {
+        tick_array_whirlpool(pre_data).as_ref() == Some(whirlpool)
+            && accounts.get(pubkey).unwrap().is_some_and(|post_data| post_data.as_slice() != pre_data.as_slice())
+    })
+}
+
+// sha256("account:TickArray")[..8] and sha256("account:DynamicTickArray")[..8]
+const FIXED_TICK_ARRAY_DISCRIMINATOR: [u8; 8] = [69, 97, 189, 190, 110, 7, 66, 187];
+const DYNAMIC_TICK_ARRAY_DISCRIMINATOR: [u8; 8] = [17, 216, 246, 142, 225, 199, 218, 56];
+
+// whirlpool of a tick array account (None for other accounts)
+fn tick_array_whirlpool(data: &[u8]) -> Option<PubkeyString> {
+    let whirlpool = match data.get(0..8)? {
+        // discriminator, start_tick_index: i32, ticks: [Tick(113 bytes); 88], whirlpool
+        discriminator if discriminator == FIXED_TICK_ARRAY_DISCRIMINATOR => data.get(9956..9988)?,
+        // discriminator, start_tick_index: i32, whirlpool, tick_bitmap: u128, ticks (variable length)
+        discriminator if discriminator == DYNAMIC_TICK_ARRAY_DISCRIMINATOR => data.get(12..44)?,
+        _ => return None,
+    };
+    Some(bs58::encode(whirlpool).into_string())
+}
+
+// protocol fee of a swap in the input token
+fn protocol_fee(old_whirlpool: &Whirlpool, new_whirlpool: &Whirlpool, a_to_b: bool) -> u64 {
+    if a_to_b {
+        new_whirlpool.protocol_fee_owed_a.wrapping_sub(old_whirlpool.protocol_fee_owed_a)
+    } else {
+        new_whirlpool.protocol_fee_owed_b.wrapping_sub(old_whirlpool.protocol_fee_owed_b)
+    }
+}
+
 fn get_old_position(
     writable_account_snapshot: &WritableAccountSnapshot,
     pubkey: &PubkeyString,
@@ -1,4 +1,4 @@
-use super::super::super::serde::{string_decimal_price, string_u128};
+use super::super::super::serde::{string_decimal_price, string_option_u64, string_option_u128, string_u128};
 use super::{AdaptiveFeeVariables, DecimalPrice, PubkeyString, TransferInfo};
 use serde_derive::{Serialize, Deserialize};
 
@@ -47,6 +47,45 @@ pub struct TradedEventPayload {
     pub old_adaptive_fee_variables: Option<AdaptiveFeeVariables>,
     #[serde(rename = "nafv", skip_serializing_if = "Option::is_none")]
     pub new_adaptive_fee_variables: Option<AdaptiveFeeVariables>,
+
+    // fees charged in the input token (derived from fee growth and protocol fee owed of the pool)
+    // missing in the files written before event format version 3
+    // lp_fee is also missing if it cannot be restored exactly (the trade changed the active liquidity, or the rounding of fee growth may reach 1 token unit)
+    #[serde(
+        rename = "lpf",
+        skip_serializing_if = "Option::is_none",
+        default = "Option::default",
+        with = "string_option_u64"
+    )]
+    pub lp_fee: Option<u64>,
+    #[serde(
+        rename = "pf",
+        skip_serializing_if = "Option::is_none",
+        default = "Option::default",
+        with = "string_option_u64"
+    )]
+    pub protocol_fee: Option<u64>,
+
+    // active liquidity of the pool before and after the trade (differs if the trade crossed initialized ticks)
+    // missing in the files written before event format version 4
+    #[serde(
+        rename = "ol",
+        skip_serializing_if = "Option::is_none",
+        default = "Option::default",
+        with = "string_option_u128"
+    )]
+    pub old_liquidity: Option<u128>,
+    #[serde(
+        rename = "nl",
+        skip_serializing_if = "Option::is_none",
+        default = "Option::default",
+        with = "string_option_u128"
+    )]
+    pub new_liquidity: Option<u128>,
+    // whether the trade crossed at least one initialized tick (true even if the net liquidity of the crossed ticks cancels out)
+    // missing in the files written before event format version 4
+    #[serde(rename = "cit", skip_serializing_if = "Option::is_none", default = "Option::default")]
+    pub crossed_initialized_tick: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
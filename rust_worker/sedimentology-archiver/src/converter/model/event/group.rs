@@ -1,14 +1,59 @@
-use super::WhirlpoolEvent;
+use super::{WhirlpoolEvent, WhirlpoolEventBlock, WhirlpoolEventTransaction};
 
-#[derive(Debug)]
+// format version of group-filtered event files (recorded in whirlpool-manifest-yyyymmdd.json)
+// the format of the blocks is the same as the event file (recorded in each block), this version tracks the events of each group
+// version 2: LiquidityRepositioned is included in the liquidity group
+pub const WHIRLPOOL_EVENT_GROUP_FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum WhirlpoolEventGroup {
     Trade,
     Liquidity,
-    #[allow(dead_code)]
     All,
 }
 
 impl WhirlpoolEventGroup {
+    pub fn from_name(name: &str) -> Option<WhirlpoolEventGroup> {
+        match name {
+            "trade" => Some(WhirlpoolEventGroup::Trade),
+            "liquidity" => Some(WhirlpoolEventGroup::Liquidity),
+            "all" => Some(WhirlpoolEventGroup::All),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WhirlpoolEventGroup::Trade => "trade",
+            WhirlpoolEventGroup::Liquidity => "liquidity",
+            WhirlpoolEventGroup::All => "all",
+        }
+    }
+
+    // events of the group only, transactions and blocks without events are dropped
+    pub fn filter(&self, block: WhirlpoolEventBlock) -> Option<WhirlpoolEventBlock> {
+        let transactions: Vec<WhirlpoolEventTransaction> = block
+            .transactions
+            .into_iter()
+            .filter_map(|transaction| {
+                let events: Vec<WhirlpoolEvent> = transaction
+                    .events
+                    .into_iter()
+                    .filter(|event| self.contains(event))
+                    .collect();
+                if events.is_empty() {
+                    return None;
+                }
+                Some(WhirlpoolEventTransaction { events, ..transaction })
+            })
+            .collect();
+
+        if transactions.is_empty() {
+            return None;
+        }
+        Some(WhirlpoolEventBlock { transactions, ..block })
+    }
+
     pub fn contains(&self, event: &WhirlpoolEvent) -> bool {
         match self {
             WhirlpoolEventGroup::Trade => matches!(event, WhirlpoolEvent::Traded(_)),
@@ -17,6 +62,7 @@ impl WhirlpoolEventGroup {
                 WhirlpoolEvent::Traded(_)
                     | WhirlpoolEvent::LiquidityDeposited(_)
                     | WhirlpoolEvent::LiquidityWithdrawn(_)
+                    | WhirlpoolEvent::LiquidityRepositioned(_)
                     | WhirlpoolEvent::LiquidityPatched(_)
                     | WhirlpoolEvent::PoolInitialized(_)
                     | WhirlpoolEvent::PoolFeeRateUpdated(_)
@@ -1,18 +1,26 @@
 pub mod convert;
 pub mod definition;
 pub mod group;
+pub mod reader;
 
 use definition::*;
 use serde_derive::{Serialize, Deserialize};
 
+// format version of event file (stored in each block and recorded in whirlpool-manifest-yyyymmdd.json)
+// increment it when the layout is changed, and teach reader.rs how to upgrade the previous one
+pub const WHIRLPOOL_EVENT_FORMAT_VERSION: u32 = 4;
+
 /*
 
 Whirlpool Event JSON Lines Format
 
 To reduce data size, we use short field names.
+Long key format (long_key::KeyFormat::Long) uses the names in front of the parentheses,
+and the event names and enum values are expanded to the variant names (e.g. "T" to "Traded", "ab" to "AtoB").
 Each line is a JSON object with the following schema:
 
 {
+  formatVersion(v): u32 (missing in the files written before version 2),
   slot(s): u64,
   blockHeight(h): u64,
   blockTime(t): i64,
@@ -30,10 +38,14 @@ Each line is a JSON object with the following schema:
   ]
 }
 
+Use reader::WhirlpoolEventReader to read files of any format version.
+
 */
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct WhirlpoolEventBlock {
+    #[serde(rename = "v", default = "reader::legacy_format_version")]
+    pub format_version: u32,
     #[serde(rename = "s")]
     pub slot: u64,
     #[serde(rename = "h")]
@@ -0,0 +1,142 @@
+use super::{WhirlpoolEventBlock, WHIRLPOOL_EVENT_FORMAT_VERSION};
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use serde_json::{Map, Value};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Lines},
+};
+
+/*
+
+Backward-compatible reader for whirlpool event files
+
+Every historical layout is deserialized into the current WhirlpoolEvent types.
+Each line is parsed as a JSON value, upgraded to the current layout, then deserialized.
+
+Format versions:
+
+- 1: files written before the format version was introduced (no "v" field in blocks)
+     They span several layouts, so the upgrade only fills the fields missing in older ones:
+     - PoolInitialized (PI) without token programs (tpa, tpb): pools before Token-2022 support use Token program
+     - RewardInitialized (RI) without reward token program (rtp): same as above
+     Fields added as optional (e.g. oafv/nafv of Traded, tfb/tfm of TransferInfo) and
+     new enum values (e.g. ilbtav2 origin) need no upgrade.
+- 2: "v" field is stored in each block
+- 3: exact LP fee and protocol fee (lpf, pf) are added to Traded
+     They are optional and cannot be restored from older files, so the upgrade does nothing.
+- 4: active liquidity before and after the trade (ol, nl) and whether the trade crossed initialized ticks (cit) are added to Traded
+     Same as version 3, they are optional and the upgrade does nothing.
+
+*/
+
+pub const WHIRLPOOL_EVENT_LEGACY_FORMAT_VERSION: u32 = 1;
+
+// used by serde to deserialize blocks without "v" field
+pub fn legacy_format_version() -> u32 {
+    WHIRLPOOL_EVENT_LEGACY_FORMAT_VERSION
+}
+
+pub struct WhirlpoolEventReader<R: BufRead> {
+    lines: Lines<R>,
+    line_number: usize,
+}
+
+impl WhirlpoolEventReader<BufReader<GzDecoder<File>>> {
+    // whirlpool-event-yyyymmdd.jsonl.gz (or group-filtered event file)
+    pub fn open(whirlpool_event_file_path: &str) -> Result<Self> {
+        let file = File::open(whirlpool_event_file_path)
+            .with_context(|| format!("failed to open {}", whirlpool_event_file_path))?;
+        Ok(WhirlpoolEventReader::new(BufReader::new(GzDecoder::new(file))))
+    }
+}
+
+impl<R: BufRead> WhirlpoolEventReader<R> {
+    // uncompressed JSON lines
+    pub fn new(reader: R) -> Self {
+        WhirlpoolEventReader { lines: reader.lines(), line_number: 0 }
+    }
+}
+
+impl<R: BufRead> Iterator for WhirlpoolEventReader<R> {
+    type Item = Result<WhirlpoolEventBlock>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(err) => return Some(Err(err.into())),
+            };
+            self.line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line_number = self.line_number;
+            return Some(parse_whirlpool_event_block(&line).with_context(|| format!("line {}", line_number)));
+        }
+    }
+}
+
+// parse one line of event file in any format version
+pub fn parse_whirlpool_event_block(jsonl: &str) -> Result<WhirlpoolEventBlock> {
+    let mut block: Value = serde_json::from_str(jsonl)?;
+
+    let format_version = match block.get("v") {
+        None => WHIRLPOOL_EVENT_LEGACY_FORMAT_VERSION,
+        Some(v) => match v.as_u64().and_then(|v| u32::try_from(v).ok()) {
+            Some(v) => v,
+            None => bail!("invalid format version: {}", v),
+        },
+    };
+    if format_version > WHIRLPOOL_EVENT_FORMAT_VERSION {
+        bail!("unsupported format version: {} (supported up to {})", format_version, WHIRLPOOL_EVENT_FORMAT_VERSION);
+    }
+
+    if format_version < 2 {
+        upgrade_from_v1(&mut block);
+    }
+
+    let mut block: WhirlpoolEventBlock = serde_json::from_value(block)?;
+    // the block is in the current layout now
+    block.format_version = WHIRLPOOL_EVENT_FORMAT_VERSION;
+    Ok(block)
+}
+
+fn upgrade_from_v1(block: &mut Value) {
+    for_each_event_payload(block, |name, payload| match name {
+        "PI" => {
+            insert_if_missing(payload, "tpa", Value::from("t"));
+            insert_if_missing(payload, "tpb", Value::from("t"));
+        }
+        "RI" => {
+            insert_if_missing(payload, "rtp", Value::from("t"));
+        }
+        _ => {}
+    });
+}
+
+fn for_each_event_payload(block: &mut Value, mut f: impl FnMut(&str, &mut Map<String, Value>)) {
+    let Some(transactions) = block.get_mut("x").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for transaction in transactions {
+        let Some(events) = transaction.get_mut("e").and_then(Value::as_array_mut) else {
+            continue;
+        };
+        for event in events {
+            let Some(event) = event.as_object_mut() else {
+                continue;
+            };
+            let name = event.get("n").and_then(Value::as_str).unwrap_or_default().to_string();
+            if let Some(payload) = event.get_mut("p").and_then(Value::as_object_mut) {
+                f(&name, payload);
+            }
+        }
+    }
+}
+
+fn insert_if_missing(payload: &mut Map<String, Value>, key: &str, value: Value) {
+    if !payload.contains_key(key) {
+        payload.insert(key.to_string(), value);
+    }
+}
@@ -0,0 +1,370 @@
+use serde::ser::{self, Serialize, Serializer};
+
+mod names;
+
+pub use names::{long_field_name, long_variant_name};
+
+/*
+
+Human-readable JSON output
+
+The archived files use short keys to reduce data size.
+Long key format writes the same data with full camelCase keys and expanded enum values.
+
+  short: { "n": "T", "p": { "o": "s", "td": "ab", ... } }
+  long:  { "name": "Traded", "payload": { "origin": "Swap", "tradeDirection": "AtoB", ... } }
+
+Values (numbers, strings of u64/u128/decimal price) are not changed.
+Any type with short-key Serialize implementation can be written in long key format,
+keys are looked up in names.rs by the name given by serde.
+
+Long key files are for people and external tools, archived files are always written with short keys
+and the readers in this crate (e.g. WhirlpoolEventReader) accept short keys only.
+
+*/
+
+// pseudo name for the keys serialized as a map (struct with flattened fields)
+const FLATTENED: &str = "";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    Short,
+    Long,
+}
+
+impl KeyFormat {
+    pub fn from_name(name: &str) -> Option<KeyFormat> {
+        match name {
+            "short" => Some(KeyFormat::Short),
+            "long" => Some(KeyFormat::Long),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyFormat::Short => "short",
+            KeyFormat::Long => "long",
+        }
+    }
+}
+
+// serialize a line of JSON lines file in the given key format
+pub fn to_string<T: Serialize + ?Sized>(value: &T, key_format: KeyFormat) -> serde_json::Result<String> {
+    match key_format {
+        KeyFormat::Short => serde_json::to_string(value),
+        KeyFormat::Long => serde_json::to_string(&LongKey::new(value)),
+    }
+}
+
+// wrapper to serialize the value with long keys
+pub struct LongKey<'a, T: ?Sized> {
+    value: &'a T,
+    // name of internally tagged enum (its tag value is serialized as a string)
+    tag_of: Option<&'static str>,
+}
+
+impl<'a, T: ?Sized> LongKey<'a, T> {
+    pub fn new(value: &'a T) -> Self {
+        LongKey { value, tag_of: None }
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for LongKey<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(LongKeySerializer { inner: serializer, tag_of: self.tag_of })
+    }
+}
+
+struct LongKeySerializer<S> {
+    inner: S,
+    tag_of: Option<&'static str>,
+}
+
+impl<S: Serializer> Serializer for LongKeySerializer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Compound<S::SerializeSeq>;
+    type SerializeTuple = Compound<S::SerializeTuple>;
+    type SerializeTupleStruct = Compound<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Compound<S::SerializeTupleVariant>;
+    type SerializeMap = Compound<S::SerializeMap>;
+    type SerializeStruct = Compound<S::SerializeStruct>;
+    type SerializeStructVariant = Compound<S::SerializeStructVariant>;
+
+    fn serialize_bool(self, v: bool) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_bool(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_i8(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_i16(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_i64(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_i128(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_u8(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_u16(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_u32(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_u64(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_f32(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<S::Ok, S::Error> {
+        match self.tag_of.and_then(|name| long_variant_name(name, v)) {
+            Some(long) => self.inner.serialize_str(long),
+            None => self.inner.serialize_str(v),
+        }
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_some(&LongKey::new(value))
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, variant_index: u32, variant: &'static str) -> Result<S::Ok, S::Error> {
+        let variant = long_variant_name(name, variant).unwrap_or(variant);
+        self.inner.serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, name: &'static str, value: &T) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_newtype_struct(name, &LongKey::new(value))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let long_variant = long_variant_name(name, variant).unwrap_or(variant);
+        self.inner.serialize_newtype_variant(name, variant_index, long_variant, &LongKey::new(value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        Ok(Compound::new(self.inner.serialize_seq(len)?, FLATTENED))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        Ok(Compound::new(self.inner.serialize_tuple(len)?, FLATTENED))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, S::Error> {
+        Ok(Compound::new(self.inner.serialize_tuple_struct(name, len)?, name))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        let long_variant = long_variant_name(name, variant).unwrap_or(variant);
+        Ok(Compound::new(self.inner.serialize_tuple_variant(name, variant_index, long_variant, len)?, variant))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        Ok(Compound::new(self.inner.serialize_map(len)?, FLATTENED))
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct, S::Error> {
+        Ok(Compound::new(self.inner.serialize_struct(name, len)?, name))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        // fields of struct variant are looked up by the variant name
+        let long_variant = long_variant_name(name, variant).unwrap_or(variant);
+        Ok(Compound::new(self.inner.serialize_struct_variant(name, variant_index, long_variant, len)?, variant))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+struct Compound<C> {
+    inner: C,
+    name: &'static str,
+}
+
+impl<C> Compound<C> {
+    fn new(inner: C, name: &'static str) -> Self {
+        Compound { inner, name }
+    }
+
+    fn long_field_name(&self, key: &'static str) -> &'static str {
+        long_field_name(self.name, key).unwrap_or(key)
+    }
+}
+
+impl<C: ser::SerializeSeq> ser::SerializeSeq for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.inner.serialize_element(&LongKey::new(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTuple> ser::SerializeTuple for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.inner.serialize_element(&LongKey::new(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTupleStruct> ser::SerializeTupleStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.inner.serialize_field(&LongKey::new(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTupleVariant> ser::SerializeTupleVariant for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.inner.serialize_field(&LongKey::new(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeMap> ser::SerializeMap for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), C::Error> {
+        // keys of flattened structs are strings
+        let key = match serde_json::to_value(key) {
+            Ok(serde_json::Value::String(key)) => key,
+            Ok(_) => return self.inner.serialize_key(key),
+            Err(err) => return Err(ser::Error::custom(err)),
+        };
+        let long = long_field_name(self.name, &key).map(str::to_string).unwrap_or(key);
+        self.inner.serialize_key(&long)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.inner.serialize_value(&LongKey::new(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeStruct> ser::SerializeStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), C::Error> {
+        let value = LongKey { value, tag_of: Some(self.name) };
+        self.inner.serialize_field(self.long_field_name(key), &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.inner.skip_field(self.long_field_name(key))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeStructVariant> ser::SerializeStructVariant for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), C::Error> {
+        self.inner.serialize_field(self.long_field_name(key), &LongKey::new(value))
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.inner.skip_field(self.long_field_name(key))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
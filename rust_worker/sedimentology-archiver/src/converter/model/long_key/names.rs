@@ -0,0 +1,1051 @@
+// long keys of the short keys (update when a field or an enum value is added to the event, ohlcv, position or decoded account files)
+//
+// key is the name given by serde: struct name, enum name, or variant name for struct variants
+// unknown keys and values are written as they are
+
+use super::FLATTENED;
+
+pub fn long_field_name(name: &str, key: &str) -> Option<&'static str> {
+    let long = match (name, key) {
+        // WhirlpoolEventBlock
+        ("WhirlpoolEventBlock", "v") => "formatVersion",
+        ("WhirlpoolEventBlock", "s") => "slot",
+        ("WhirlpoolEventBlock", "h") => "blockHeight",
+        ("WhirlpoolEventBlock", "t") => "blockTime",
+        ("WhirlpoolEventBlock", "x") => "transactions",
+
+        // WhirlpoolEventTransaction
+        ("WhirlpoolEventTransaction", "s") => "signature",
+        ("WhirlpoolEventTransaction", "p") => "payer",
+        ("WhirlpoolEventTransaction", "e") => "events",
+
+        // WhirlpoolEvent
+        ("WhirlpoolEvent", "n") => "name",
+        ("WhirlpoolEvent", "p") => "payload",
+
+        // AdaptiveFeeTierInitializedEventPayload
+        ("AdaptiveFeeTierInitializedEventPayload", "o") => "origin",
+        ("AdaptiveFeeTierInitializedEventPayload", "c") => "config",
+        ("AdaptiveFeeTierInitializedEventPayload", "aft") => "adaptiveFeeTier",
+        ("AdaptiveFeeTierInitializedEventPayload", "fti") => "feeTierIndex",
+        ("AdaptiveFeeTierInitializedEventPayload", "ts") => "tickSpacing",
+        ("AdaptiveFeeTierInitializedEventPayload", "ipa") => "initializePoolAuthority",
+        ("AdaptiveFeeTierInitializedEventPayload", "dfa") => "delegatedFeeAuthority",
+        ("AdaptiveFeeTierInitializedEventPayload", "dbfr") => "defaultBaseFeeRate",
+        ("AdaptiveFeeTierInitializedEventPayload", "afc") => "adaptiveFeeConstants",
+
+        // AdaptiveFeeTierUpdatedEventPayload
+        ("AdaptiveFeeTierUpdatedEventPayload", "o") => "origin",
+        ("AdaptiveFeeTierUpdatedEventPayload", "c") => "config",
+        ("AdaptiveFeeTierUpdatedEventPayload", "aft") => "adaptiveFeeTier",
+        ("AdaptiveFeeTierUpdatedEventPayload", "fti") => "feeTierIndex",
+        ("AdaptiveFeeTierUpdatedEventPayload", "ts") => "tickSpacing",
+        ("AdaptiveFeeTierUpdatedEventPayload", "oipa") => "oldInitializePoolAuthority",
+        ("AdaptiveFeeTierUpdatedEventPayload", "nipa") => "newInitializePoolAuthority",
+        ("AdaptiveFeeTierUpdatedEventPayload", "odfa") => "oldDelegatedFeeAuthority",
+        ("AdaptiveFeeTierUpdatedEventPayload", "ndfa") => "newDelegatedFeeAuthority",
+        ("AdaptiveFeeTierUpdatedEventPayload", "odbfr") => "oldDefaultBaseFeeRate",
+        ("AdaptiveFeeTierUpdatedEventPayload", "ndbfr") => "newDefaultBaseFeeRate",
+        ("AdaptiveFeeTierUpdatedEventPayload", "oafc") => "oldAdaptiveFeeConstants",
+        ("AdaptiveFeeTierUpdatedEventPayload", "nafc") => "newAdaptiveFeeConstants",
+
+        // ConfigExtensionInitializedEventPayload
+        ("ConfigExtensionInitializedEventPayload", "o") => "origin",
+        ("ConfigExtensionInitializedEventPayload", "c") => "config",
+        ("ConfigExtensionInitializedEventPayload", "ce") => "configExtension",
+        ("ConfigExtensionInitializedEventPayload", "cea") => "configExtensionAuthority",
+        ("ConfigExtensionInitializedEventPayload", "tba") => "tokenBadgeAuthority",
+
+        // ConfigExtensionUpdatedEventPayload
+        ("ConfigExtensionUpdatedEventPayload", "o") => "origin",
+        ("ConfigExtensionUpdatedEventPayload", "c") => "config",
+        ("ConfigExtensionUpdatedEventPayload", "ce") => "configExtension",
+        ("ConfigExtensionUpdatedEventPayload", "ocea") => "oldConfigExtensionAuthority",
+        ("ConfigExtensionUpdatedEventPayload", "ncea") => "newConfigExtensionAuthority",
+        ("ConfigExtensionUpdatedEventPayload", "otba") => "oldTokenBadgeAuthority",
+        ("ConfigExtensionUpdatedEventPayload", "ntba") => "newTokenBadgeAuthority",
+
+        // ConfigInitializedEventPayload
+        ("ConfigInitializedEventPayload", "o") => "origin",
+        ("ConfigInitializedEventPayload", "c") => "config",
+        ("ConfigInitializedEventPayload", "fa") => "feeAuthority",
+        ("ConfigInitializedEventPayload", "cpfa") => "collectProtocolFeesAuthority",
+        ("ConfigInitializedEventPayload", "resa") => "rewardEmissionsSuperAuthority",
+        ("ConfigInitializedEventPayload", "dpfr") => "defaultProtocolFeeRate",
+
+        // ConfigUpdatedEventPayload
+        ("ConfigUpdatedEventPayload", "o") => "origin",
+        ("ConfigUpdatedEventPayload", "c") => "config",
+        ("ConfigUpdatedEventPayload", "ofa") => "oldFeeAuthority",
+        ("ConfigUpdatedEventPayload", "nfa") => "newFeeAuthority",
+        ("ConfigUpdatedEventPayload", "ocpfa") => "oldCollectProtocolFeesAuthority",
+        ("ConfigUpdatedEventPayload", "ncpfa") => "newCollectProtocolFeesAuthority",
+        ("ConfigUpdatedEventPayload", "oresa") => "oldRewardEmissionsSuperAuthority",
+        ("ConfigUpdatedEventPayload", "nresa") => "newRewardEmissionsSuperAuthority",
+        ("ConfigUpdatedEventPayload", "odpfr") => "oldDefaultProtocolFeeRate",
+        ("ConfigUpdatedEventPayload", "ndpfr") => "newDefaultProtocolFeeRate",
+        ("ConfigUpdatedEventPayload", "off") => "oldFeatureFlags",
+        ("ConfigUpdatedEventPayload", "nof") => "newFeatureFlags",
+
+        // FeeTierInitializedEventPayload
+        ("FeeTierInitializedEventPayload", "o") => "origin",
+        ("FeeTierInitializedEventPayload", "c") => "config",
+        ("FeeTierInitializedEventPayload", "ft") => "feeTier",
+        ("FeeTierInitializedEventPayload", "ts") => "tickSpacing",
+        ("FeeTierInitializedEventPayload", "dfr") => "defaultFeeRate",
+
+        // FeeTierUpdatedEventPayload
+        ("FeeTierUpdatedEventPayload", "o") => "origin",
+        ("FeeTierUpdatedEventPayload", "c") => "config",
+        ("FeeTierUpdatedEventPayload", "ft") => "feeTier",
+        ("FeeTierUpdatedEventPayload", "ts") => "tickSpacing",
+        ("FeeTierUpdatedEventPayload", "odfr") => "oldDefaultFeeRate",
+        ("FeeTierUpdatedEventPayload", "ndfr") => "newDefaultFeeRate",
+
+        // LiquidityDepositedEventPayload
+        ("LiquidityDepositedEventPayload", "o") => "origin",
+        ("LiquidityDepositedEventPayload", "w") => "whirlpool",
+        ("LiquidityDepositedEventPayload", "pa") => "positionAuthority",
+        ("LiquidityDepositedEventPayload", "p") => "position",
+        ("LiquidityDepositedEventPayload", "lta") => "lowerTickArray",
+        ("LiquidityDepositedEventPayload", "uta") => "upperTickArray",
+        ("LiquidityDepositedEventPayload", "ld") => "liquidityDelta",
+        ("LiquidityDepositedEventPayload", "ta") => "transferA",
+        ("LiquidityDepositedEventPayload", "tb") => "transferB",
+        ("LiquidityDepositedEventPayload", "lti") => "lowerTickIndex",
+        ("LiquidityDepositedEventPayload", "uti") => "upperTickIndex",
+        ("LiquidityDepositedEventPayload", "ldp") => "lowerDecimalPrice",
+        ("LiquidityDepositedEventPayload", "udp") => "upperDecimalPrice",
+        ("LiquidityDepositedEventPayload", "opl") => "oldPositionLiquidity",
+        ("LiquidityDepositedEventPayload", "npl") => "newPositionLiquidity",
+        ("LiquidityDepositedEventPayload", "owl") => "oldWhirlpoolLiquidity",
+        ("LiquidityDepositedEventPayload", "nwl") => "newWhirlpoolLiquidity",
+        ("LiquidityDepositedEventPayload", "wsp") => "whirlpoolSqrtPrice",
+        ("LiquidityDepositedEventPayload", "wcti") => "whirlpoolCurrentTickIndex",
+        ("LiquidityDepositedEventPayload", "wdp") => "whirlpoolDecimalPrice",
+
+        // LiquidityPatchedEventPayload
+        ("LiquidityPatchedEventPayload", "o") => "origin",
+        ("LiquidityPatchedEventPayload", "w") => "whirlpool",
+        ("LiquidityPatchedEventPayload", "ld") => "liquidityDelta",
+        ("LiquidityPatchedEventPayload", "owl") => "oldWhirlpoolLiquidity",
+        ("LiquidityPatchedEventPayload", "nwl") => "newWhirlpoolLiquidity",
+
+        // LiquidityRepositionedEventPayload
+        ("LiquidityRepositionedEventPayload", "o") => "origin",
+        ("LiquidityRepositionedEventPayload", "w") => "whirlpool",
+        ("LiquidityRepositionedEventPayload", "pa") => "positionAuthority",
+        ("LiquidityRepositionedEventPayload", "p") => "position",
+        ("LiquidityRepositionedEventPayload", "olta") => "oldLowerTickArray",
+        ("LiquidityRepositionedEventPayload", "outa") => "oldUpperTickArray",
+        ("LiquidityRepositionedEventPayload", "nlta") => "newLowerTickArray",
+        ("LiquidityRepositionedEventPayload", "nuta") => "newUpperTickArray",
+        ("LiquidityRepositionedEventPayload", "ta") => "transferA",
+        ("LiquidityRepositionedEventPayload", "tfoa") => "transferFromOwnerA",
+        ("LiquidityRepositionedEventPayload", "tb") => "transferB",
+        ("LiquidityRepositionedEventPayload", "tfob") => "transferFromOwnerB",
+        ("LiquidityRepositionedEventPayload", "olti") => "oldLowerTickIndex",
+        ("LiquidityRepositionedEventPayload", "outi") => "oldUpperTickIndex",
+        ("LiquidityRepositionedEventPayload", "oldp") => "oldLowerDecimalPrice",
+        ("LiquidityRepositionedEventPayload", "oudp") => "oldUpperDecimalPrice",
+        ("LiquidityRepositionedEventPayload", "nlti") => "newLowerTickIndex",
+        ("LiquidityRepositionedEventPayload", "nuti") => "newUpperTickIndex",
+        ("LiquidityRepositionedEventPayload", "nldp") => "newLowerDecimalPrice",
+        ("LiquidityRepositionedEventPayload", "nudp") => "newUpperDecimalPrice",
+        ("LiquidityRepositionedEventPayload", "opl") => "oldPositionLiquidity",
+        ("LiquidityRepositionedEventPayload", "npl") => "newPositionLiquidity",
+        ("LiquidityRepositionedEventPayload", "owl") => "oldWhirlpoolLiquidity",
+        ("LiquidityRepositionedEventPayload", "nwl") => "newWhirlpoolLiquidity",
+        ("LiquidityRepositionedEventPayload", "wsp") => "whirlpoolSqrtPrice",
+        ("LiquidityRepositionedEventPayload", "wcti") => "whirlpoolCurrentTickIndex",
+        ("LiquidityRepositionedEventPayload", "wdp") => "whirlpoolDecimalPrice",
+
+        // LiquidityWithdrawnEventPayload
+        ("LiquidityWithdrawnEventPayload", "o") => "origin",
+        ("LiquidityWithdrawnEventPayload", "w") => "whirlpool",
+        ("LiquidityWithdrawnEventPayload", "pa") => "positionAuthority",
+        ("LiquidityWithdrawnEventPayload", "p") => "position",
+        ("LiquidityWithdrawnEventPayload", "lta") => "lowerTickArray",
+        ("LiquidityWithdrawnEventPayload", "uta") => "upperTickArray",
+        ("LiquidityWithdrawnEventPayload", "ld") => "liquidityDelta",
+        ("LiquidityWithdrawnEventPayload", "ta") => "transferA",
+        ("LiquidityWithdrawnEventPayload", "tb") => "transferB",
+        ("LiquidityWithdrawnEventPayload", "lti") => "lowerTickIndex",
+        ("LiquidityWithdrawnEventPayload", "uti") => "upperTickIndex",
+        ("LiquidityWithdrawnEventPayload", "ldp") => "lowerDecimalPrice",
+        ("LiquidityWithdrawnEventPayload", "udp") => "upperDecimalPrice",
+        ("LiquidityWithdrawnEventPayload", "opl") => "oldPositionLiquidity",
+        ("LiquidityWithdrawnEventPayload", "npl") => "newPositionLiquidity",
+        ("LiquidityWithdrawnEventPayload", "owl") => "oldWhirlpoolLiquidity",
+        ("LiquidityWithdrawnEventPayload", "nwl") => "newWhirlpoolLiquidity",
+        ("LiquidityWithdrawnEventPayload", "wsp") => "whirlpoolSqrtPrice",
+        ("LiquidityWithdrawnEventPayload", "wcti") => "whirlpoolCurrentTickIndex",
+        ("LiquidityWithdrawnEventPayload", "wdp") => "whirlpoolDecimalPrice",
+
+        // TransferInfo
+        ("TransferInfo", "m") => "mint",
+        ("TransferInfo", "a") => "amount",
+        ("TransferInfo", "d") => "decimals",
+        ("TransferInfo", "tfb") => "transferFeeBps",
+        ("TransferInfo", "tfm") => "transferFeeMax",
+
+        // AdaptiveFeeConstants
+        ("AdaptiveFeeConstants", "fp") => "filterPeriod",
+        ("AdaptiveFeeConstants", "dp") => "decayPeriod",
+        ("AdaptiveFeeConstants", "rf") => "reductionFactor",
+        ("AdaptiveFeeConstants", "afcf") => "adaptiveFeeControlFactor",
+        ("AdaptiveFeeConstants", "mva") => "maxVolatilityAccumulator",
+        ("AdaptiveFeeConstants", "tgs") => "tickGroupSize",
+        ("AdaptiveFeeConstants", "mstt") => "majorSwapThresholdTicks",
+
+        // AdaptiveFeeVariables
+        ("AdaptiveFeeVariables", "lrut") => "lastReferenceUpdateTimestamp",
+        ("AdaptiveFeeVariables", "lmst") => "lastMajorSwapTimestamp",
+        ("AdaptiveFeeVariables", "vr") => "volatilityReference",
+        ("AdaptiveFeeVariables", "tgir") => "tickGroupIndexReference",
+        ("AdaptiveFeeVariables", "va") => "volatilityAccumulator",
+
+        // PoolAdaptiveFeeConstantsUpdatedEventPayload
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "o") => "origin",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "c") => "config",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "w") => "whirlpool",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "ofp") => "oldFilterPeriod",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "nfp") => "newFilterPeriod",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "odp") => "oldDecayPeriod",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "ndp") => "newDecayPeriod",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "orf") => "oldReductionFactor",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "nrf") => "newReductionFactor",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "oafcf") => "oldAdaptiveFeeControlFactor",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "nafcf") => "newAdaptiveFeeControlFactor",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "omva") => "oldMaxVolatilityAccumulator",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "nmva") => "newMaxVolatilityAccumulator",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "otgs") => "oldTickGroupSize",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "ntgs") => "newTickGroupSize",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "omstt") => "oldMajorSwapThresholdTicks",
+        ("PoolAdaptiveFeeConstantsUpdatedEventPayload", "nmstt") => "newMajorSwapThresholdTicks",
+
+        // PoolFeeRateUpdatedEventPayload
+        ("PoolFeeRateUpdatedEventPayload", "o") => "origin",
+        ("PoolFeeRateUpdatedEventPayload", "c") => "config",
+        ("PoolFeeRateUpdatedEventPayload", "w") => "whirlpool",
+        ("PoolFeeRateUpdatedEventPayload", "ofr") => "oldFeeRate",
+        ("PoolFeeRateUpdatedEventPayload", "nfr") => "newFeeRate",
+
+        // PoolInitializedEventPayload
+        ("PoolInitializedEventPayload", "o") => "origin",
+        ("PoolInitializedEventPayload", "ts") => "tickSpacing",
+        ("PoolInitializedEventPayload", "sp") => "sqrtPrice",
+        ("PoolInitializedEventPayload", "dp") => "decimalPrice",
+        ("PoolInitializedEventPayload", "c") => "config",
+        ("PoolInitializedEventPayload", "tma") => "tokenMintA",
+        ("PoolInitializedEventPayload", "tmb") => "tokenMintB",
+        ("PoolInitializedEventPayload", "f") => "funder",
+        ("PoolInitializedEventPayload", "w") => "whirlpool",
+        ("PoolInitializedEventPayload", "ft") => "feeTier",
+        ("PoolInitializedEventPayload", "tpa") => "tokenProgramA",
+        ("PoolInitializedEventPayload", "tpb") => "tokenProgramB",
+        ("PoolInitializedEventPayload", "tda") => "tokenDecimalsA",
+        ("PoolInitializedEventPayload", "tdb") => "tokenDecimalsB",
+        ("PoolInitializedEventPayload", "cti") => "currentTickIndex",
+        ("PoolInitializedEventPayload", "fr") => "feeRate",
+        ("PoolInitializedEventPayload", "pfr") => "protocolFeeRate",
+        ("PoolInitializedEventPayload", "fti") => "feeTierIndex",
+        ("PoolInitializedEventPayload", "tet") => "tradeEnableTimestamp",
+        ("PoolInitializedEventPayload", "afc") => "adaptiveFeeConstants",
+
+        // PoolMigratedEventPayload
+        ("PoolMigratedEventPayload", "o") => "origin",
+        ("PoolMigratedEventPayload", "w") => "whirlpool",
+
+        // PoolProtocolFeeRateUpdatedEventPayload
+        ("PoolProtocolFeeRateUpdatedEventPayload", "o") => "origin",
+        ("PoolProtocolFeeRateUpdatedEventPayload", "c") => "config",
+        ("PoolProtocolFeeRateUpdatedEventPayload", "w") => "whirlpool",
+        ("PoolProtocolFeeRateUpdatedEventPayload", "opfr") => "oldProtocolFeeRate",
+        ("PoolProtocolFeeRateUpdatedEventPayload", "npfr") => "newProtocolFeeRate",
+
+        // PositionBundleDeletedEventPayload
+        ("PositionBundleDeletedEventPayload", "o") => "origin",
+        ("PositionBundleDeletedEventPayload", "pb") => "positionBundle",
+        ("PositionBundleDeletedEventPayload", "pbm") => "positionBundleMint",
+        ("PositionBundleDeletedEventPayload", "pbo") => "positionBundleOwner",
+
+        // PositionBundleInitializedEventPayload
+        ("PositionBundleInitializedEventPayload", "o") => "origin",
+        ("PositionBundleInitializedEventPayload", "pb") => "positionBundle",
+        ("PositionBundleInitializedEventPayload", "pbm") => "positionBundleMint",
+        ("PositionBundleInitializedEventPayload", "pbo") => "positionBundleOwner",
+
+        // PositionClosedEventPayload
+        ("PositionClosedEventPayload", "o") => "origin",
+        ("PositionClosedEventPayload", "w") => "whirlpool",
+        ("PositionClosedEventPayload", "p") => "position",
+        ("PositionClosedEventPayload", "lti") => "lowerTickIndex",
+        ("PositionClosedEventPayload", "uti") => "upperTickIndex",
+        ("PositionClosedEventPayload", "ldp") => "lowerDecimalPrice",
+        ("PositionClosedEventPayload", "udp") => "upperDecimalPrice",
+        ("PositionClosedEventPayload", "pa") => "positionAuthority",
+        ("PositionClosedEventPayload", "pt") => "positionType",
+        ("PositionClosedEventPayload", "pm") => "positionMint",
+        ("PositionClosedEventPayload", "pbm") => "positionBundleMint",
+        ("PositionClosedEventPayload", "pb") => "positionBundle",
+        ("PositionClosedEventPayload", "pbi") => "positionBundleIndex",
+
+        // PositionFeesHarvestedEventPayload
+        ("PositionFeesHarvestedEventPayload", "o") => "origin",
+        ("PositionFeesHarvestedEventPayload", "w") => "whirlpool",
+        ("PositionFeesHarvestedEventPayload", "pa") => "positionAuthority",
+        ("PositionFeesHarvestedEventPayload", "p") => "position",
+        ("PositionFeesHarvestedEventPayload", "ta") => "transferA",
+        ("PositionFeesHarvestedEventPayload", "tb") => "transferB",
+
+        // PositionHarvestUpdatedEventPayload
+        ("PositionHarvestUpdatedEventPayload", "o") => "origin",
+        ("PositionHarvestUpdatedEventPayload", "w") => "whirlpool",
+        ("PositionHarvestUpdatedEventPayload", "p") => "position",
+
+        // PositionLockedEventPayload
+        ("PositionLockedEventPayload", "o") => "origin",
+        ("PositionLockedEventPayload", "w") => "whirlpool",
+        ("PositionLockedEventPayload", "p") => "position",
+        ("PositionLockedEventPayload", "lt") => "lockType",
+        ("PositionLockedEventPayload", "lc") => "lockConfig",
+        ("PositionLockedEventPayload", "lti") => "lowerTickIndex",
+        ("PositionLockedEventPayload", "uti") => "upperTickIndex",
+        ("PositionLockedEventPayload", "ldp") => "lowerDecimalPrice",
+        ("PositionLockedEventPayload", "udp") => "upperDecimalPrice",
+        ("PositionLockedEventPayload", "ll") => "lockedLiquidity",
+        ("PositionLockedEventPayload", "po") => "positionOwner",
+        ("PositionLockedEventPayload", "pm") => "positionMint",
+
+        // PositionLockType
+        ("PositionLockType", "n") => "name",
+
+        // PositionLockedTransferredEventPayload
+        ("PositionLockedTransferredEventPayload", "o") => "origin",
+        ("PositionLockedTransferredEventPayload", "w") => "whirlpool",
+        ("PositionLockedTransferredEventPayload", "p") => "position",
+        ("PositionLockedTransferredEventPayload", "lt") => "lockType",
+        ("PositionLockedTransferredEventPayload", "lc") => "lockConfig",
+        ("PositionLockedTransferredEventPayload", "lti") => "lowerTickIndex",
+        ("PositionLockedTransferredEventPayload", "uti") => "upperTickIndex",
+        ("PositionLockedTransferredEventPayload", "ldp") => "lowerDecimalPrice",
+        ("PositionLockedTransferredEventPayload", "udp") => "upperDecimalPrice",
+        ("PositionLockedTransferredEventPayload", "ll") => "lockedLiquidity",
+        ("PositionLockedTransferredEventPayload", "opo") => "oldPositionOwner",
+        ("PositionLockedTransferredEventPayload", "npo") => "newPositionOwner",
+        ("PositionLockedTransferredEventPayload", "pm") => "positionMint",
+
+        // PositionOpenedEventPayload
+        ("PositionOpenedEventPayload", "o") => "origin",
+        ("PositionOpenedEventPayload", "w") => "whirlpool",
+        ("PositionOpenedEventPayload", "p") => "position",
+        ("PositionOpenedEventPayload", "lti") => "lowerTickIndex",
+        ("PositionOpenedEventPayload", "uti") => "upperTickIndex",
+        ("PositionOpenedEventPayload", "ldp") => "lowerDecimalPrice",
+        ("PositionOpenedEventPayload", "udp") => "upperDecimalPrice",
+        ("PositionOpenedEventPayload", "pa") => "positionAuthority",
+        ("PositionOpenedEventPayload", "pt") => "positionType",
+        ("PositionOpenedEventPayload", "pm") => "positionMint",
+        ("PositionOpenedEventPayload", "pbm") => "positionBundleMint",
+        ("PositionOpenedEventPayload", "pb") => "positionBundle",
+        ("PositionOpenedEventPayload", "pbi") => "positionBundleIndex",
+
+        // PositionRangeResetEventPayload
+        ("PositionRangeResetEventPayload", "o") => "origin",
+        ("PositionRangeResetEventPayload", "w") => "whirlpool",
+        ("PositionRangeResetEventPayload", "p") => "position",
+        ("PositionRangeResetEventPayload", "olti") => "oldLowerTickIndex",
+        ("PositionRangeResetEventPayload", "outi") => "oldUpperTickIndex",
+        ("PositionRangeResetEventPayload", "oldp") => "oldLowerDecimalPrice",
+        ("PositionRangeResetEventPayload", "oudp") => "oldUpperDecimalPrice",
+        ("PositionRangeResetEventPayload", "nlti") => "newLowerTickIndex",
+        ("PositionRangeResetEventPayload", "nuti") => "newUpperTickIndex",
+        ("PositionRangeResetEventPayload", "nldp") => "newLowerDecimalPrice",
+        ("PositionRangeResetEventPayload", "nudp") => "newUpperDecimalPrice",
+        ("PositionRangeResetEventPayload", "pa") => "positionAuthority",
+
+        // PositionRewardHarvestedEventPayload
+        ("PositionRewardHarvestedEventPayload", "o") => "origin",
+        ("PositionRewardHarvestedEventPayload", "w") => "whirlpool",
+        ("PositionRewardHarvestedEventPayload", "pa") => "positionAuthority",
+        ("PositionRewardHarvestedEventPayload", "p") => "position",
+        ("PositionRewardHarvestedEventPayload", "ri") => "rewardIndex",
+        ("PositionRewardHarvestedEventPayload", "tr") => "transferReward",
+
+        // ProtocolFeesCollectedEventPayload
+        ("ProtocolFeesCollectedEventPayload", "o") => "origin",
+        ("ProtocolFeesCollectedEventPayload", "c") => "config",
+        ("ProtocolFeesCollectedEventPayload", "w") => "whirlpool",
+        ("ProtocolFeesCollectedEventPayload", "cpfa") => "collectProtocolFeesAuthority",
+        ("ProtocolFeesCollectedEventPayload", "ta") => "transferA",
+        ("ProtocolFeesCollectedEventPayload", "tb") => "transferB",
+
+        // RewardAuthorityUpdatedEventPayload
+        ("RewardAuthorityUpdatedEventPayload", "o") => "origin",
+        ("RewardAuthorityUpdatedEventPayload", "w") => "whirlpool",
+        ("RewardAuthorityUpdatedEventPayload", "ri") => "rewardIndex",
+        ("RewardAuthorityUpdatedEventPayload", "ora") => "oldRewardAuthority",
+        ("RewardAuthorityUpdatedEventPayload", "nra") => "newRewardAuthority",
+
+        // RewardEmissionsUpdatedEventPayload
+        ("RewardEmissionsUpdatedEventPayload", "o") => "origin",
+        ("RewardEmissionsUpdatedEventPayload", "w") => "whirlpool",
+        ("RewardEmissionsUpdatedEventPayload", "ri") => "rewardIndex",
+        ("RewardEmissionsUpdatedEventPayload", "rm") => "rewardMint",
+        ("RewardEmissionsUpdatedEventPayload", "rd") => "rewardDecimals",
+        ("RewardEmissionsUpdatedEventPayload", "oepsx64") => "oldEmissionsPerSecondX64",
+        ("RewardEmissionsUpdatedEventPayload", "nepsx64") => "newEmissionsPerSecondX64",
+
+        // RewardInitializedEventPayload
+        ("RewardInitializedEventPayload", "o") => "origin",
+        ("RewardInitializedEventPayload", "w") => "whirlpool",
+        ("RewardInitializedEventPayload", "ri") => "rewardIndex",
+        ("RewardInitializedEventPayload", "rm") => "rewardMint",
+        ("RewardInitializedEventPayload", "rtp") => "rewardTokenProgram",
+        ("RewardInitializedEventPayload", "rd") => "rewardDecimal",
+
+        // TickArrayInitializedEventPayload
+        ("TickArrayInitializedEventPayload", "o") => "origin",
+        ("TickArrayInitializedEventPayload", "w") => "whirlpool",
+        ("TickArrayInitializedEventPayload", "sti") => "startTickIndex",
+        ("TickArrayInitializedEventPayload", "ta") => "tickArray",
+
+        // TokenBadgeDeletedEventPayload
+        ("TokenBadgeDeletedEventPayload", "o") => "origin",
+        ("TokenBadgeDeletedEventPayload", "c") => "config",
+        ("TokenBadgeDeletedEventPayload", "ce") => "configExtension",
+        ("TokenBadgeDeletedEventPayload", "tm") => "tokenMint",
+        ("TokenBadgeDeletedEventPayload", "tb") => "tokenBadge",
+
+        // TokenBadgeInitializedEventPayload
+        ("TokenBadgeInitializedEventPayload", "o") => "origin",
+        ("TokenBadgeInitializedEventPayload", "c") => "config",
+        ("TokenBadgeInitializedEventPayload", "ce") => "configExtension",
+        ("TokenBadgeInitializedEventPayload", "tm") => "tokenMint",
+        ("TokenBadgeInitializedEventPayload", "tb") => "tokenBadge",
+
+        // TokenBadgeUpdatedEventPayload
+        ("TokenBadgeUpdatedEventPayload", "o") => "origin",
+        ("TokenBadgeUpdatedEventPayload", "c") => "config",
+        ("TokenBadgeUpdatedEventPayload", "tm") => "tokenMint",
+        ("TokenBadgeUpdatedEventPayload", "tb") => "tokenBadge",
+        ("TokenBadgeUpdatedEventPayload", "oarntp") => "oldAttributeRequireNonTransferablePosition",
+        ("TokenBadgeUpdatedEventPayload", "narntp") => "newAttributeRequireNonTransferablePosition",
+
+        // TradedEventPayload
+        ("TradedEventPayload", "o") => "origin",
+        ("TradedEventPayload", "w") => "whirlpool",
+        ("TradedEventPayload", "ta") => "tokenAuthority",
+        ("TradedEventPayload", "tm") => "tradeMode",
+        ("TradedEventPayload", "td") => "tradeDirection",
+        ("TradedEventPayload", "ti") => "transferIn",
+        ("TradedEventPayload", "to") => "transferOut",
+        ("TradedEventPayload", "osp") => "oldSqrtPrice",
+        ("TradedEventPayload", "nsp") => "newSqrtPrice",
+        ("TradedEventPayload", "octi") => "oldCurrentTickIndex",
+        ("TradedEventPayload", "ncti") => "newCurrentTickIndex",
+        ("TradedEventPayload", "odp") => "oldDecimalPrice",
+        ("TradedEventPayload", "ndp") => "newDecimalPrice",
+        ("TradedEventPayload", "fr") => "feeRate",
+        ("TradedEventPayload", "pfr") => "protocolFeeRate",
+        ("TradedEventPayload", "oafv") => "oldAdaptiveFeeVariables",
+        ("TradedEventPayload", "nafv") => "newAdaptiveFeeVariables",
+        ("TradedEventPayload", "lpf") => "lpFee",
+        ("TradedEventPayload", "pf") => "protocolFee",
+        ("TradedEventPayload", "ol") => "oldLiquidity",
+        ("TradedEventPayload", "nl") => "newLiquidity",
+        ("TradedEventPayload", "cit") => "crossedInitializedTick",
+
+        // TokenData
+        ("TokenData", "m") => "mint",
+        ("TokenData", "d") => "decimals",
+
+        // InitialState
+        ("InitialState", "t") => "type",
+        ("InitialState", "p") => "payload",
+
+        // InitialState::Existing (struct variant is serialized with the variant name)
+        ("e", "pcsp") => "previousCloseSqrtPrice",
+        ("e", "pcdp") => "previousCloseDecimalPrice",
+
+        // InitialState::New
+        ("n", "isp") => "initialSqrtPrice",
+        ("n", "idp") => "initialDecimalPrice",
+        ("n", "is") => "initializedSlot",
+        ("n", "ibt") => "initializedBlockTime",
+
+        // EstimatedFees
+        ("EstimatedFees", "lpfa") => "liquidityProviderFeeA",
+        ("EstimatedFees", "lpfb") => "liquidityProviderFeeB",
+        ("EstimatedFees", "pfa") => "protocolFeeA",
+        ("EstimatedFees", "pfb") => "protocolFeeB",
+
+        // LiquidityData
+        ("LiquidityData", "p") => "ohlc",
+        ("LiquidityData", "dc") => "depositCount",
+        ("LiquidityData", "wc") => "withdrawalCount",
+        ("LiquidityData", "rc") => "repositionCount",
+        ("LiquidityData", "aa") => "addedA",
+        ("LiquidityData", "ab") => "addedB",
+        ("LiquidityData", "ra") => "removedA",
+        ("LiquidityData", "rb") => "removedB",
+        ("LiquidityData", "pc") => "positionCount",
+
+        // LiquidityOhlcData
+        ("LiquidityOhlcData", "o") => "open",
+        ("LiquidityOhlcData", "h") => "high",
+        ("LiquidityOhlcData", "l") => "low",
+        ("LiquidityOhlcData", "c") => "close",
+
+        // WhirlpoolOhlcvDataUnit
+        ("WhirlpoolOhlcvDataUnit", "t") => "timestamp",
+        ("WhirlpoolOhlcvDataUnit", "p") => "ohlc",
+        ("WhirlpoolOhlcvDataUnit", "v") => "volume",
+        ("WhirlpoolOhlcvDataUnit", "vw") => "vwap",
+        ("WhirlpoolOhlcvDataUnit", "tw") => "twap",
+        ("WhirlpoolOhlcvDataUnit", "u") => "usd",
+        ("WhirlpoolOhlcvDataUnit", "f") => "fees",
+        ("WhirlpoolOhlcvDataUnit", "tr") => "traders",
+        ("WhirlpoolOhlcvDataUnit", "s") => "synthetic",
+
+        // WhirlpoolOhlcvData
+        ("WhirlpoolOhlcvData", "sp") => "sqrtPrice",
+        ("WhirlpoolOhlcvData", "dp") => "decimalPrice",
+
+        // SqrtPriceData
+        ("SqrtPriceData", "o") => "open",
+        ("SqrtPriceData", "h") => "high",
+        ("SqrtPriceData", "l") => "low",
+        ("SqrtPriceData", "c") => "close",
+
+        // DecimalPriceData
+        ("DecimalPriceData", "o") => "open",
+        ("DecimalPriceData", "h") => "high",
+        ("DecimalPriceData", "l") => "low",
+        ("DecimalPriceData", "c") => "close",
+
+        // VolumeData
+        ("VolumeData", "ab") => "aToB",
+        ("VolumeData", "ba") => "bToA",
+
+        // VolumeDirectionData
+        ("VolumeDirectionData", "ti") => "totalIn",
+        ("VolumeDirectionData", "to") => "totalOut",
+        ("VolumeDirectionData", "c") => "count",
+
+        // AveragePriceData
+        ("AveragePriceData", "sp") => "sqrtPrice",
+        ("AveragePriceData", "dp") => "decimalPrice",
+
+        // UsdData
+        ("UsdData", "ca") => "closeA",
+        ("UsdData", "cb") => "closeB",
+        ("UsdData", "v") => "volume",
+
+        // FeeData
+        ("FeeData", "lpfa") => "liquidityProviderFeeA",
+        ("FeeData", "lpfb") => "liquidityProviderFeeB",
+        ("FeeData", "pfa") => "protocolFeeA",
+        ("FeeData", "pfb") => "protocolFeeB",
+        ("FeeData", "mnfr") => "minFeeRate",
+        ("FeeData", "mxfr") => "maxFeeRate",
+        ("FeeData", "vwfr") => "vwapFeeRate",
+        ("FeeData", "etc") => "estimatedTradeCount",
+
+        // TraderData
+        ("TraderData", "tac") => "tokenAuthorityCount",
+        ("TraderData", "pc") => "payerCount",
+
+        // TopTraderData
+        ("TopTraderData", "ta") => "tokenAuthority",
+        ("TopTraderData", "c") => "tradeCount",
+        ("TopTraderData", "va") => "volumeA",
+        ("TopTraderData", "vb") => "volumeB",
+
+        // WhirlpoolOhlcvPairData
+        ("WhirlpoolOhlcvPairData", "ta") => "tokenA",
+        ("WhirlpoolOhlcvPairData", "tb") => "tokenB",
+        ("WhirlpoolOhlcvPairData", "t") => "timestamp",
+        ("WhirlpoolOhlcvPairData", "w") => "whirlpools",
+        ("WhirlpoolOhlcvPairData", "v") => "volume",
+        ("WhirlpoolOhlcvPairData", "vw") => "vwap",
+        ("WhirlpoolOhlcvPairData", "f") => "fees",
+
+        // WhirlpoolOhlcvTokenData
+        ("WhirlpoolOhlcvTokenData", "m") => "mint",
+        ("WhirlpoolOhlcvTokenData", "d") => "decimals",
+        ("WhirlpoolOhlcvTokenData", "t") => "timestamp",
+        ("WhirlpoolOhlcvTokenData", "pc") => "poolCount",
+        ("WhirlpoolOhlcvTokenData", "c") => "tradeCount",
+        ("WhirlpoolOhlcvTokenData", "vi") => "volumeIn",
+        ("WhirlpoolOhlcvTokenData", "vo") => "volumeOut",
+        ("WhirlpoolOhlcvTokenData", "lpf") => "liquidityProviderFee",
+        ("WhirlpoolOhlcvTokenData", "pf") => "protocolFee",
+
+        // StatsData
+        ("StatsData", "t") => "timestamp",
+        ("StatsData", "rv") => "realizedVolatility",
+        ("StatsData", "rc") => "returnCount",
+        ("StatsData", "c") => "tradeCount",
+        ("StatsData", "pi") => "priceImpact",
+        ("StatsData", "itc") => "initializedTickCrossingTrades",
+
+        // PriceImpactData
+        ("PriceImpactData", "a") => "average",
+        ("PriceImpactData", "m") => "max",
+
+        // TickCrossingTradeData
+        ("TickCrossingTradeData", "c") => "count",
+        ("TickCrossingTradeData", "s") => "share",
+
+        // AdaptiveFeeDataUnit
+        ("AdaptiveFeeDataUnit", "t") => "timestamp",
+        ("AdaptiveFeeDataUnit", "c") => "tradeCount",
+        ("AdaptiveFeeDataUnit", "va") => "volatilityAccumulator",
+        ("AdaptiveFeeDataUnit", "msc") => "majorSwapCount",
+        ("AdaptiveFeeDataUnit", "rrc") => "referenceResetCount",
+        ("AdaptiveFeeDataUnit", "fr") => "feeRate",
+
+        // VolatilityAccumulatorData
+        ("VolatilityAccumulatorData", "o") => "open",
+        ("VolatilityAccumulatorData", "h") => "high",
+        ("VolatilityAccumulatorData", "l") => "low",
+        ("VolatilityAccumulatorData", "c") => "close",
+
+        // FeeRateDistributionData
+        ("FeeRateDistributionData", "mn") => "min",
+        ("FeeRateDistributionData", "p25") => "percentile25",
+        ("FeeRateDistributionData", "p50") => "median",
+        ("FeeRateDistributionData", "p75") => "percentile75",
+        ("FeeRateDistributionData", "p90") => "percentile90",
+        ("FeeRateDistributionData", "mx") => "max",
+
+        // WhirlpoolPositionLedgerData
+        ("WhirlpoolPositionLedgerData", "p") => "position",
+        ("WhirlpoolPositionLedgerData", "w") => "whirlpool",
+        ("WhirlpoolPositionLedgerData", "ta") => "tokenA",
+        ("WhirlpoolPositionLedgerData", "tb") => "tokenB",
+        ("WhirlpoolPositionLedgerData", "o") => "opened",
+        ("WhirlpoolPositionLedgerData", "cl") => "closed",
+        ("WhirlpoolPositionLedgerData", "lti") => "lowerTickIndex",
+        ("WhirlpoolPositionLedgerData", "uti") => "upperTickIndex",
+        ("WhirlpoolPositionLedgerData", "l") => "liquidity",
+        ("WhirlpoolPositionLedgerData", "dp") => "decimalPrice",
+        ("WhirlpoolPositionLedgerData", "d") => "deposited",
+        ("WhirlpoolPositionLedgerData", "wd") => "withdrawn",
+        ("WhirlpoolPositionLedgerData", "f") => "fees",
+        ("WhirlpoolPositionLedgerData", "r") => "rewards",
+        ("WhirlpoolPositionLedgerData", "rp") => "realizedPnl",
+        ("WhirlpoolPositionLedgerData", "e") => "entries",
+
+        // LedgerBlockData
+        ("LedgerBlockData", "s") => "slot",
+        ("LedgerBlockData", "bt") => "blockTime",
+
+        // LedgerAmountData
+        ("LedgerAmountData", "a") => "amountA",
+        ("LedgerAmountData", "b") => "amountB",
+
+        // LedgerRewardData
+        ("LedgerRewardData", "i") => "index",
+        ("LedgerRewardData", "m") => "mint",
+        ("LedgerRewardData", "a") => "amount",
+
+        // RealizedPnlData
+        ("RealizedPnlData", "a") => "amountA",
+        ("RealizedPnlData", "b") => "amountB",
+        ("RealizedPnlData", "v") => "value",
+
+        // LedgerEntryData
+        ("LedgerEntryData", "s") => "slot",
+        ("LedgerEntryData", "bt") => "blockTime",
+        ("LedgerEntryData", "sg") => "signature",
+        ("LedgerEntryData", "t") => "type",
+        ("LedgerEntryData", "l") => "liquidity",
+        ("LedgerEntryData", "lti") => "lowerTickIndex",
+        ("LedgerEntryData", "uti") => "upperTickIndex",
+        ("LedgerEntryData", "d") => "deposited",
+        ("LedgerEntryData", "wd") => "withdrawn",
+        ("LedgerEntryData", "f") => "fees",
+        ("LedgerEntryData", "r") => "reward",
+
+        // WhirlpoolPositionValuationData
+        ("WhirlpoolPositionValuationData", "p") => "position",
+        ("WhirlpoolPositionValuationData", "w") => "whirlpool",
+        ("WhirlpoolPositionValuationData", "t") => "timestamp",
+        ("WhirlpoolPositionValuationData", "ta") => "tokenA",
+        ("WhirlpoolPositionValuationData", "tb") => "tokenB",
+        ("WhirlpoolPositionValuationData", "lti") => "lowerTickIndex",
+        ("WhirlpoolPositionValuationData", "uti") => "upperTickIndex",
+        ("WhirlpoolPositionValuationData", "l") => "liquidity",
+        ("WhirlpoolPositionValuationData", "sp") => "sqrtPrice",
+        ("WhirlpoolPositionValuationData", "dp") => "decimalPrice",
+        ("WhirlpoolPositionValuationData", "ti") => "tickIndex",
+        ("WhirlpoolPositionValuationData", "ir") => "inRange",
+        ("WhirlpoolPositionValuationData", "a") => "amounts",
+        ("WhirlpoolPositionValuationData", "v") => "value",
+        ("WhirlpoolPositionValuationData", "nd") => "netDeposited",
+        ("WhirlpoolPositionValuationData", "hv") => "holdValue",
+        ("WhirlpoolPositionValuationData", "il") => "impermanentLoss",
+        ("WhirlpoolPositionValuationData", "ilr") => "impermanentLossRatio",
+
+        // NetAmountData
+        ("NetAmountData", "a") => "amountA",
+        ("NetAmountData", "b") => "amountB",
+
+        // WhirlpoolPositionRangeData
+        ("WhirlpoolPositionRangeData", "p") => "position",
+        ("WhirlpoolPositionRangeData", "w") => "whirlpool",
+        ("WhirlpoolPositionRangeData", "t") => "timestamp",
+        ("WhirlpoolPositionRangeData", "st") => "startTime",
+        ("WhirlpoolPositionRangeData", "et") => "endTime",
+        ("WhirlpoolPositionRangeData", "lti") => "lowerTickIndex",
+        ("WhirlpoolPositionRangeData", "uti") => "upperTickIndex",
+        ("WhirlpoolPositionRangeData", "tm") => "trackedMinutes",
+        ("WhirlpoolPositionRangeData", "irm") => "inRangeMinutes",
+
+        // WhirlpoolPositionData
+        ("WhirlpoolPositionData", "p") => "position",
+        ("WhirlpoolPositionData", "w") => "whirlpool",
+        ("WhirlpoolPositionData", "pm") => "positionMint",
+        ("WhirlpoolPositionData", "l") => "liquidity",
+        ("WhirlpoolPositionData", "lti") => "lowerTickIndex",
+        ("WhirlpoolPositionData", "uti") => "upperTickIndex",
+        ("WhirlpoolPositionData", "fgca") => "feeGrowthCheckpointA",
+        ("WhirlpoolPositionData", "foa") => "feeOwedA",
+        ("WhirlpoolPositionData", "fgcb") => "feeGrowthCheckpointB",
+        ("WhirlpoolPositionData", "fob") => "feeOwedB",
+        ("WhirlpoolPositionData", "r") => "rewards",
+        ("WhirlpoolPositionData", "pb") => "positionBundle",
+        ("WhirlpoolPositionData", "pbm") => "positionBundleMint",
+        ("WhirlpoolPositionData", "pbi") => "positionBundleIndex",
+
+        // PositionRewardData
+        ("PositionRewardData", "gic") => "growthInsideCheckpoint",
+        ("PositionRewardData", "ao") => "amountOwed",
+
+        // DecodedWhirlpoolData
+        ("DecodedWhirlpoolData", "a") => "account",
+        ("DecodedWhirlpoolData", "wc") => "whirlpoolsConfig",
+        ("DecodedWhirlpoolData", "ts") => "tickSpacing",
+        ("DecodedWhirlpoolData", "fti") => "feeTierIndex",
+        ("DecodedWhirlpoolData", "afe") => "adaptiveFeeEnabled",
+        ("DecodedWhirlpoolData", "ta") => "tokenA",
+        ("DecodedWhirlpoolData", "tb") => "tokenB",
+        ("DecodedWhirlpoolData", "tva") => "tokenVaultA",
+        ("DecodedWhirlpoolData", "tvb") => "tokenVaultB",
+        ("DecodedWhirlpoolData", "fr") => "feeRate",
+        ("DecodedWhirlpoolData", "pfr") => "protocolFeeRate",
+        ("DecodedWhirlpoolData", "l") => "liquidity",
+        ("DecodedWhirlpoolData", "sp") => "sqrtPrice",
+        ("DecodedWhirlpoolData", "dp") => "decimalPrice",
+        ("DecodedWhirlpoolData", "cti") => "currentTickIndex",
+        ("DecodedWhirlpoolData", "pfoa") => "protocolFeeOwedA",
+        ("DecodedWhirlpoolData", "pfob") => "protocolFeeOwedB",
+        ("DecodedWhirlpoolData", "fgga") => "feeGrowthGlobalA",
+        ("DecodedWhirlpoolData", "fggb") => "feeGrowthGlobalB",
+        ("DecodedWhirlpoolData", "rlut") => "rewardLastUpdatedTimestamp",
+        ("DecodedWhirlpoolData", "r") => "rewards",
+
+        // DecodedRewardData
+        ("DecodedRewardData", "i") => "index",
+        ("DecodedRewardData", "m") => "mint",
+        ("DecodedRewardData", "v") => "vault",
+        ("DecodedRewardData", "au") => "authority",
+        ("DecodedRewardData", "eps") => "emissionsPerSecondX64",
+        ("DecodedRewardData", "epd") => "emissionsPerDay",
+        ("DecodedRewardData", "gg") => "growthGlobalX64",
+
+        // DecodedWhirlpoolsConfigData
+        ("DecodedWhirlpoolsConfigData", "a") => "account",
+        ("DecodedWhirlpoolsConfigData", "fa") => "feeAuthority",
+        ("DecodedWhirlpoolsConfigData", "cpfa") => "collectProtocolFeesAuthority",
+        ("DecodedWhirlpoolsConfigData", "resa") => "rewardEmissionsSuperAuthority",
+        ("DecodedWhirlpoolsConfigData", "dpfr") => "defaultProtocolFeeRate",
+        ("DecodedWhirlpoolsConfigData", "ff") => "featureFlags",
+
+        // DecodedWhirlpoolsConfigExtensionData
+        ("DecodedWhirlpoolsConfigExtensionData", "a") => "account",
+        ("DecodedWhirlpoolsConfigExtensionData", "wc") => "whirlpoolsConfig",
+        ("DecodedWhirlpoolsConfigExtensionData", "cea") => "configExtensionAuthority",
+        ("DecodedWhirlpoolsConfigExtensionData", "tba") => "tokenBadgeAuthority",
+
+        // DecodedFeeTierData
+        ("DecodedFeeTierData", "a") => "account",
+        ("DecodedFeeTierData", "wc") => "whirlpoolsConfig",
+        ("DecodedFeeTierData", "ts") => "tickSpacing",
+        ("DecodedFeeTierData", "dfr") => "defaultFeeRate",
+
+        // DecodedAdaptiveFeeTierData
+        ("DecodedAdaptiveFeeTierData", "a") => "account",
+        ("DecodedAdaptiveFeeTierData", "wc") => "whirlpoolsConfig",
+        ("DecodedAdaptiveFeeTierData", "fti") => "feeTierIndex",
+        ("DecodedAdaptiveFeeTierData", "ts") => "tickSpacing",
+        ("DecodedAdaptiveFeeTierData", "ipa") => "initializePoolAuthority",
+        ("DecodedAdaptiveFeeTierData", "dfa") => "delegatedFeeAuthority",
+        ("DecodedAdaptiveFeeTierData", "dbfr") => "defaultBaseFeeRate",
+        ("DecodedAdaptiveFeeTierData", "afc") => "adaptiveFeeConstants",
+
+        // DecodedOracleData
+        ("DecodedOracleData", "a") => "account",
+        ("DecodedOracleData", "w") => "whirlpool",
+        ("DecodedOracleData", "tet") => "tradeEnableTimestamp",
+        ("DecodedOracleData", "afc") => "adaptiveFeeConstants",
+        ("DecodedOracleData", "afv") => "adaptiveFeeVariables",
+
+        // DecodedTokenBadgeData
+        ("DecodedTokenBadgeData", "a") => "account",
+        ("DecodedTokenBadgeData", "wc") => "whirlpoolsConfig",
+        ("DecodedTokenBadgeData", "tm") => "tokenMint",
+        ("DecodedTokenBadgeData", "arntp") => "attributeRequireNonTransferablePosition",
+
+        // DecodedLockConfigData
+        ("DecodedLockConfigData", "a") => "account",
+        ("DecodedLockConfigData", "p") => "position",
+        ("DecodedLockConfigData", "po") => "positionOwner",
+        ("DecodedLockConfigData", "w") => "whirlpool",
+        ("DecodedLockConfigData", "lts") => "lockedTimestamp",
+        ("DecodedLockConfigData", "lt") => "lockType",
+
+        // WhirlpoolOhlcvDailyData, WhirlpoolOhlcvMinutelyData, WhirlpoolOhlcvIntervalData, WhirlpoolOhlcvRollupData, WhirlpoolOhlcvStatsData and WhirlpoolOhlcvAdaptiveFeeData (serialized as a map because of the flattened metadata)
+        (FLATTENED, "w") => "whirlpool",
+        (FLATTENED, "wc") => "whirlpoolsConfig",
+        (FLATTENED, "ta") => "tokenA",
+        (FLATTENED, "tb") => "tokenB",
+        (FLATTENED, "ts") => "tickSpacing",
+        (FLATTENED, "is") => "initialState",
+        (FLATTENED, "ef") => "estimatedFees",
+        (FLATTENED, "l") => "liquidity",
+        (FLATTENED, "d") => "daily",
+        (FLATTENED, "m") => "minutely",
+        (FLATTENED, "i") => "interval",
+        (FLATTENED, "c") => "candles",
+        (FLATTENED, "tt") => "topTraders",
+        (FLATTENED, "p") => "period",
+        (FLATTENED, "dc") => "dayCount",
+        (FLATTENED, "s") => "stats",
+        (FLATTENED, "afc") => "adaptiveFeeConstants",
+        (FLATTENED, "cuc") => "constantsUpdateCount",
+        _ => return None,
+    };
+    Some(long)
+}
+
+pub fn long_variant_name(name: &str, variant: &str) -> Option<&'static str> {
+    let long = match (name, variant) {
+        // WhirlpoolEvent
+        ("WhirlpoolEvent", "PD") => "ProgramDeployed",
+        ("WhirlpoolEvent", "T") => "Traded",
+        ("WhirlpoolEvent", "LD") => "LiquidityDeposited",
+        ("WhirlpoolEvent", "LW") => "LiquidityWithdrawn",
+        ("WhirlpoolEvent", "LR") => "LiquidityRepositioned",
+        ("WhirlpoolEvent", "PI") => "PoolInitialized",
+        ("WhirlpoolEvent", "RI") => "RewardInitialized",
+        ("WhirlpoolEvent", "REU") => "RewardEmissionsUpdated",
+        ("WhirlpoolEvent", "RAU") => "RewardAuthorityUpdated",
+        ("WhirlpoolEvent", "PHU") => "PositionHarvestUpdated",
+        ("WhirlpoolEvent", "PFH") => "PositionFeesHarvested",
+        ("WhirlpoolEvent", "PRH") => "PositionRewardHarvested",
+        ("WhirlpoolEvent", "PFC") => "ProtocolFeesCollected",
+        ("WhirlpoolEvent", "PO") => "PositionOpened",
+        ("WhirlpoolEvent", "PC") => "PositionClosed",
+        ("WhirlpoolEvent", "PRR") => "PositionRangeReset",
+        ("WhirlpoolEvent", "PL") => "PositionLocked",
+        ("WhirlpoolEvent", "PLT") => "PositionLockedTransferred",
+        ("WhirlpoolEvent", "PBI") => "PositionBundleInitialized",
+        ("WhirlpoolEvent", "PBD") => "PositionBundleDeleted",
+        ("WhirlpoolEvent", "PFRU") => "PoolFeeRateUpdated",
+        ("WhirlpoolEvent", "PPFRU") => "PoolProtocolFeeRateUpdated",
+        ("WhirlpoolEvent", "PAFCU") => "PoolAdaptiveFeeConstantsUpdated",
+        ("WhirlpoolEvent", "TAI") => "TickArrayInitialized",
+        ("WhirlpoolEvent", "CI") => "ConfigInitialized",
+        ("WhirlpoolEvent", "CU") => "ConfigUpdated",
+        ("WhirlpoolEvent", "FTI") => "FeeTierInitialized",
+        ("WhirlpoolEvent", "FTU") => "FeeTierUpdated",
+        ("WhirlpoolEvent", "AFTI") => "AdaptiveFeeTierInitialized",
+        ("WhirlpoolEvent", "AFTU") => "AdaptiveFeeTierUpdated",
+        ("WhirlpoolEvent", "CEI") => "ConfigExtensionInitialized",
+        ("WhirlpoolEvent", "CEU") => "ConfigExtensionUpdated",
+        ("WhirlpoolEvent", "TBI") => "TokenBadgeInitialized",
+        ("WhirlpoolEvent", "TBD") => "TokenBadgeDeleted",
+        ("WhirlpoolEvent", "TBU") => "TokenBadgeUpdated",
+        ("WhirlpoolEvent", "LP") => "LiquidityPatched",
+        ("WhirlpoolEvent", "PM") => "PoolMigrated",
+
+        // AdaptiveFeeTierInitializedEventOrigin
+        ("AdaptiveFeeTierInitializedEventOrigin", "iaft") => "InitializeAdaptiveFeeTier",
+
+        // AdaptiveFeeTierUpdatedEventOrigin
+        ("AdaptiveFeeTierUpdatedEventOrigin", "sipa") => "SetInitializePoolAuthority",
+        ("AdaptiveFeeTierUpdatedEventOrigin", "sdfa") => "SetDelegatedFeeAuthority",
+        ("AdaptiveFeeTierUpdatedEventOrigin", "sdbfr") => "SetDefaultBaseFeeRate",
+        ("AdaptiveFeeTierUpdatedEventOrigin", "spafc") => "SetPresetAdaptiveFeeConstants",
+
+        // ConfigExtensionInitializedEventOrigin
+        ("ConfigExtensionInitializedEventOrigin", "ice") => "InitializeConfigExtension",
+
+        // ConfigExtensionUpdatedEventOrigin
+        ("ConfigExtensionUpdatedEventOrigin", "scea") => "SetConfigExtensionAuthority",
+        ("ConfigExtensionUpdatedEventOrigin", "stba") => "SetTokenBadgeAuthority",
+
+        // ConfigInitializedEventOrigin
+        ("ConfigInitializedEventOrigin", "ic") => "InitializeConfig",
+
+        // ConfigUpdatedEventOrigin
+        ("ConfigUpdatedEventOrigin", "sfa") => "SetFeeAuthority",
+        ("ConfigUpdatedEventOrigin", "scpfa") => "SetCollectProtocolFeesAuthority",
+        ("ConfigUpdatedEventOrigin", "sresa") => "SetRewardEmissionsSuperAuthority",
+        ("ConfigUpdatedEventOrigin", "sdpfr") => "SetDefaultProtocolFeeRate",
+        ("ConfigUpdatedEventOrigin", "scff") => "SetConfigFeatureFlag",
+
+        // FeeTierInitializedEventOrigin
+        ("FeeTierInitializedEventOrigin", "ift") => "InitializeFeeTier",
+
+        // FeeTierUpdatedEventOrigin
+        ("FeeTierUpdatedEventOrigin", "sdfr") => "SetDefaultFeeRate",
+
+        // LiquidityDepositedEventOrigin
+        ("LiquidityDepositedEventOrigin", "il") => "IncreaseLiquidity",
+        ("LiquidityDepositedEventOrigin", "ilv2") => "IncreaseLiquidityV2",
+        ("LiquidityDepositedEventOrigin", "ilbtav2") => "IncreaseLiquidityByTokenAmountsV2",
+
+        // LiquidityPatchedEventOrigin
+        ("LiquidityPatchedEventOrigin", "ail") => "AdminIncreaseLiquidity",
+
+        // LiquidityRepositionedEventOrigin
+        ("LiquidityRepositionedEventOrigin", "rlv2") => "RepositionLiquidityV2",
+
+        // LiquidityWithdrawnEventOrigin
+        ("LiquidityWithdrawnEventOrigin", "dl") => "DecreaseLiquidity",
+        ("LiquidityWithdrawnEventOrigin", "dlv2") => "DecreaseLiquidityV2",
+
+        // TokenProgram
+        ("TokenProgram", "t") => "Token",
+        ("TokenProgram", "t2") => "Token2022",
+
+        // PoolAdaptiveFeeConstantsUpdatedEventOrigin
+        ("PoolAdaptiveFeeConstantsUpdatedEventOrigin", "safc") => "SetAdaptiveFeeConstants",
+
+        // PoolFeeRateUpdatedEventOrigin
+        ("PoolFeeRateUpdatedEventOrigin", "sfr") => "SetFeeRate",
+        ("PoolFeeRateUpdatedEventOrigin", "sfrbdfa") => "SetFeeRateByDelegatedFeeAuthority",
+
+        // PoolInitializedEventOrigin
+        ("PoolInitializedEventOrigin", "ip") => "InitializePool",
+        ("PoolInitializedEventOrigin", "ipv2") => "InitializePoolV2",
+        ("PoolInitializedEventOrigin", "ipwaf") => "InitializePoolWithAdaptiveFee",
+
+        // PoolMigratedEventOrigin
+        ("PoolMigratedEventOrigin", "mrras") => "MigrateRepurposeRewardAuthoritySpace",
+
+        // PoolProtocolFeeRateUpdatedEventOrigin
+        ("PoolProtocolFeeRateUpdatedEventOrigin", "spfr") => "SetProtocolFeeRate",
+
+        // PositionBundleDeletedEventOrigin
+        ("PositionBundleDeletedEventOrigin", "dpb") => "DeletePositionBundle",
+
+        // PositionBundleInitializedEventOrigin
+        ("PositionBundleInitializedEventOrigin", "ipb") => "InitializePositionBundle",
+        ("PositionBundleInitializedEventOrigin", "ipbwm") => "InitializePositionBundleWithMetadata",
+
+        // PositionClosedEventOrigin
+        ("PositionClosedEventOrigin", "cp") => "ClosePosition",
+        ("PositionClosedEventOrigin", "cbp") => "CloseBundledPosition",
+        ("PositionClosedEventOrigin", "cpwte") => "ClosePositionWithTokenExtensions",
+
+        // PositionFeesHarvestedEventOrigin
+        ("PositionFeesHarvestedEventOrigin", "cf") => "CollectFees",
+        ("PositionFeesHarvestedEventOrigin", "cfv2") => "CollectFeesV2",
+
+        // PositionHarvestUpdatedEventOrigin
+        ("PositionHarvestUpdatedEventOrigin", "ufar") => "UpdateFeesAndRewards",
+
+        // PositionLockedEventOrigin
+        ("PositionLockedEventOrigin", "lp") => "LockPosition",
+
+        // PositionLockType
+        ("PositionLockType", "p") => "Permanent",
+
+        // PositionLockedTransferredEventOrigin
+        ("PositionLockedTransferredEventOrigin", "tlp") => "TransferLockedPosition",
+
+        // PositionOpenedEventOrigin
+        ("PositionOpenedEventOrigin", "op") => "OpenPosition",
+        ("PositionOpenedEventOrigin", "opwm") => "OpenPositionWithMetadata",
+        ("PositionOpenedEventOrigin", "obp") => "OpenBundledPosition",
+        ("PositionOpenedEventOrigin", "opwte") => "OpenPositionWithTokenExtensions",
+
+        // PositionType
+        ("PositionType", "p") => "Position",
+        ("PositionType", "bp") => "BundledPosition",
+
+        // PositionRangeResetEventOrigin
+        ("PositionRangeResetEventOrigin", "rpr") => "ResetPositionRange",
+
+        // PositionRewardHarvestedEventOrigin
+        ("PositionRewardHarvestedEventOrigin", "cr") => "CollectReward",
+        ("PositionRewardHarvestedEventOrigin", "crv2") => "CollectRewardV2",
+
+        // ProtocolFeesCollectedEventOrigin
+        ("ProtocolFeesCollectedEventOrigin", "cpf") => "CollectProtocolFees",
+        ("ProtocolFeesCollectedEventOrigin", "cpfv2") => "CollectProtocolFeesV2",
+
+        // RewardAuthorityUpdatedEventOrigin
+        ("RewardAuthorityUpdatedEventOrigin", "sra") => "SetRewardAuthority",
+        ("RewardAuthorityUpdatedEventOrigin", "srabsa") => "SetRewardAuthorityBySuperAuthority",
+
+        // RewardEmissionsUpdatedEventOrigin
+        ("RewardEmissionsUpdatedEventOrigin", "sre") => "SetRewardEmissions",
+        ("RewardEmissionsUpdatedEventOrigin", "srev2") => "SetRewardEmissionsV2",
+
+        // RewardInitializedEventOrigin
+        ("RewardInitializedEventOrigin", "ir") => "InitializeReward",
+        ("RewardInitializedEventOrigin", "irv2") => "InitializeRewardV2",
+
+        // TickArrayInitializedEventOrigin
+        ("TickArrayInitializedEventOrigin", "ita") => "InitializeTickArray",
+        ("TickArrayInitializedEventOrigin", "idta") => "InitializeDynamicTickArray",
+
+        // TokenBadgeDeletedEventOrigin
+        ("TokenBadgeDeletedEventOrigin", "dtb") => "DeleteTokenBadge",
+
+        // TokenBadgeInitializedEventOrigin
+        ("TokenBadgeInitializedEventOrigin", "itb") => "InitializeTokenBadge",
+
+        // TokenBadgeUpdatedEventOrigin
+        ("TokenBadgeUpdatedEventOrigin", "stba") => "SetTokenBadgeAttribute",
+
+        // TradedEventOrigin
+        ("TradedEventOrigin", "s") => "Swap",
+        ("TradedEventOrigin", "sv2") => "SwapV2",
+        ("TradedEventOrigin", "thso") => "TwoHopSwapOne",
+        ("TradedEventOrigin", "thst") => "TwoHopSwapTwo",
+        ("TradedEventOrigin", "thsv2o") => "TwoHopSwapV2One",
+        ("TradedEventOrigin", "thsv2t") => "TwoHopSwapV2Two",
+
+        // TradeMode
+        ("TradeMode", "ei") => "ExactInput",
+        ("TradeMode", "eo") => "ExactOutput",
+
+        // TradeDirection
+        ("TradeDirection", "ab") => "AtoB",
+        ("TradeDirection", "ba") => "BtoA",
+
+        // InitialState
+        ("InitialState", "e") => "Existing",
+        ("InitialState", "n") => "New",
+
+        // LedgerEntryType
+        ("LedgerEntryType", "o") => "Opened",
+        ("LedgerEntryType", "d") => "Deposited",
+        ("LedgerEntryType", "w") => "Withdrawn",
+        ("LedgerEntryType", "rp") => "Repositioned",
+        ("LedgerEntryType", "fh") => "FeesHarvested",
+        ("LedgerEntryType", "rh") => "RewardHarvested",
+        ("LedgerEntryType", "rr") => "RangeReset",
+        ("LedgerEntryType", "c") => "Closed",
+        _ => return None,
+    };
+    Some(long)
+}
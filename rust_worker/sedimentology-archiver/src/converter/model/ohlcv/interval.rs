@@ -0,0 +1,60 @@
+// candle interval of whirlpool-ohlcv-<interval>-yyyymmdd.jsonl.gz (daily and minutely files are always archived)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum OhlcvInterval {
+    Minutes5,
+    Minutes15,
+    Minutes30,
+    Hours1,
+    Hours4,
+    Hours12,
+}
+
+impl OhlcvInterval {
+    pub const ALL: [OhlcvInterval; 6] = [
+        OhlcvInterval::Minutes5,
+        OhlcvInterval::Minutes15,
+        OhlcvInterval::Minutes30,
+        OhlcvInterval::Hours1,
+        OhlcvInterval::Hours4,
+        OhlcvInterval::Hours12,
+    ];
+
+    pub fn from_name(name: &str) -> Option<OhlcvInterval> {
+        OhlcvInterval::ALL.into_iter().find(|interval| interval.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OhlcvInterval::Minutes5 => "5m",
+            OhlcvInterval::Minutes15 => "15m",
+            OhlcvInterval::Minutes30 => "30m",
+            OhlcvInterval::Hours1 => "1h",
+            OhlcvInterval::Hours4 => "4h",
+            OhlcvInterval::Hours12 => "12h",
+        }
+    }
+
+    // name of ArtifactType::OhlcvInterval
+    pub fn artifact_name(&self) -> &'static str {
+        match self {
+            OhlcvInterval::Minutes5 => "ohlcv-5m",
+            OhlcvInterval::Minutes15 => "ohlcv-15m",
+            OhlcvInterval::Minutes30 => "ohlcv-30m",
+            OhlcvInterval::Hours1 => "ohlcv-1h",
+            OhlcvInterval::Hours4 => "ohlcv-4h",
+            OhlcvInterval::Hours12 => "ohlcv-12h",
+        }
+    }
+
+    // length of a candle (a day is divisible by every interval, so candles never cross the date boundary)
+    pub fn seconds(&self) -> u32 {
+        match self {
+            OhlcvInterval::Minutes5 => 5 * 60,
+            OhlcvInterval::Minutes15 => 15 * 60,
+            OhlcvInterval::Minutes30 => 30 * 60,
+            OhlcvInterval::Hours1 => 60 * 60,
+            OhlcvInterval::Hours4 => 4 * 60 * 60,
+            OhlcvInterval::Hours12 => 12 * 60 * 60,
+        }
+    }
+}
@@ -1,11 +1,33 @@
-use serde_derive::Serialize;
-use super::serde::{string_decimal_price, string_u128, string_u64};
+use serde_derive::{Serialize, Deserialize};
+use super::event::definition::AdaptiveFeeConstants;
+use super::serde::{string_decimal_price, string_option_decimal_price, string_u128, string_u64};
+
+pub mod interval;
+pub mod period;
+
+// format version of ohlcv files (recorded in whirlpool-manifest-yyyymmdd.json)
+// version 2: usd(u) is added to daily and minutely data
+// version 3: vwap(vw) and twap(tw) are added to daily and minutely data (version 2 of interval data)
+// version 4: fees(f) are added to daily and minutely data, estimatedFees(ef) are exact if recorded in events (version 3 of interval data)
+// version 5: liquidity(l) is added (version 4 of interval data)
+// version 6: traders(tr) are added to daily data and topTraders(tt) to daily file (version 5 of interval data)
+// version 7: estimatedTradeCount(etc) is added to fees(f) (version 6 of interval data, version 2 of rollup data)
+// rollup version 3: positionCount(pc) is omitted from liquidity(l)
+pub const WHIRLPOOL_OHLCV_DAILY_FORMAT_VERSION: u32 = 7;
+pub const WHIRLPOOL_OHLCV_MINUTELY_FORMAT_VERSION: u32 = 7;
+pub const WHIRLPOOL_OHLCV_INTERVAL_FORMAT_VERSION: u32 = 6;
+pub const WHIRLPOOL_OHLCV_PAIR_FORMAT_VERSION: u32 = 1;
+pub const WHIRLPOOL_OHLCV_TOKEN_FORMAT_VERSION: u32 = 1;
+pub const WHIRLPOOL_OHLCV_ROLLUP_FORMAT_VERSION: u32 = 3;
+pub const WHIRLPOOL_OHLCV_STATS_FORMAT_VERSION: u32 = 1;
+pub const WHIRLPOOL_OHLCV_ADAPTIVE_FEE_FORMAT_VERSION: u32 = 1;
 
 /*
 
 Whirlpool OHLCV Daily JSON Lines Format
 
 To reduce data size, we use short field names.
+Long key format (long_key::KeyFormat::Long) uses the names in front of the parentheses.
 
 Each line is a JSON object with the following schema:
 
@@ -16,28 +38,49 @@ Each line is a JSON object with the following schema:
   tokenB(tb): { mint(m): String(base58 encoding), decimals(d): u8 },
   tickSpacing(ts): u16,
   initialState(is):
-    { t: "existing(e)", p: { previousCloseSqrtPrice(pcsp): String, previousCloseDecimalPrice(pcdp): String } } |
-    { t: "new(n)", p: { initialSqrtPrice(isp): String, initialDecimalPrice(idp): String, initializedSlot: u64, initializedBlockTime(ibt): i64 } },
+    { type(t): "existing(e)", payload(p): { previousCloseSqrtPrice(pcsp): String, previousCloseDecimalPrice(pcdp): String } } |
+    { type(t): "new(n)", payload(p): { initialSqrtPrice(isp): String, initialDecimalPrice(idp): String, initializedSlot(is): u64, initializedBlockTime(ibt): i64 } },
   estimatedFees(ef): {
     liquidityProviderFeeA(lpfa): u64,
     liquidityProviderFeeB(lpfb): u64,
     protocolFeeA(pfa): u64,
     protocolFeeB(pfb): u64,
   },
+  liquidity(l): {
+    ohlc(p): { open(o): String, high(h): String, low(l): String, close(c): String },
+    depositCount(dc): u64,
+    withdrawalCount(wc): u64,
+    repositionCount(rc): u64,
+    addedA(aa): String,
+    addedB(ab): String,
+    removedA(ra): String,
+    removedB(rb): String,
+    positionCount(pc): u64,
+  },
   daily(d): {
     timestamp(t): i64(UTC, UNIX timestamp in seconds, first second of the day),
     ohlc(p): { sqrtPrice(sp): { open(o): String, high(h): String, low(l): String, close(c): String }, decimalPrice(dp): { open(o): String, high(h): String, low(l): String, close(c): String } },
     volume(v): {
-      ab: { totalIn(ti): String, totalOut(to): String, count(c): u64 },
-      ba: { totalIn(ti): String, totalOut(to): String, count(c): u64 },
+      aToB(ab): { totalIn(ti): String, totalOut(to): String, count(c): u64 },
+      bToA(ba): { totalIn(ti): String, totalOut(to): String, count(c): u64 },
     },
+    vwap(vw): { sqrtPrice(sp): String, decimalPrice(dp): String } (omitted if no trades),
+    twap(tw): { sqrtPrice(sp): String, decimalPrice(dp): String },
+    usd(u): { closeA(ca): String, closeB(cb): String, volume(v): String } (omitted if neither token has a USD price or a value is not finite),
+    fees(f): { liquidityProviderFeeA(lpfa): String, liquidityProviderFeeB(lpfb): String, protocolFeeA(pfa): String, protocolFeeB(pfb): String, minFeeRate(mnfr): u32, maxFeeRate(mxfr): u32, vwapFeeRate(vwfr): u32, estimatedTradeCount(etc): u64 } (omitted if no trades),
+    traders(tr): { tokenAuthorityCount(tac): u64, payerCount(pc): u64 } (omitted if no trades),
   },
+  topTraders(tt): [
+    { tokenAuthority(ta): String(base58 encoding), tradeCount(c): u64, volumeA(va): String, volumeB(vb): String },
+    ...
+  ],
 }
 
 Whirlpool OHLCV Minutely JSON Lines Format
 
 To reduce data size, we use short field names.
-Also, data for minutes with no trades at all will be omitted.
+Long key format (long_key::KeyFormat::Long) uses the names in front of the parentheses.
+Also, data for minutes with no trades at all will be omitted (unless the file is dense, see below).
 
 Each line is a JSON object with the following schema:
 
@@ -48,42 +91,277 @@ Each line is a JSON object with the following schema:
   tokenB(tb): { mint(m): String(base58 encoding), decimals(d): u8 },
   tickSpacing(ts): u16,
   initialState(is):
-    { t: "existing(e)", p: { previousCloseSqrtPrice(pcsp): String, previousCloseDecimalPrice(pcdp): String } } |
-    { t: "new(n)", p: { initialSqrtPrice(isp): String, initialDecimalPrice(idp): String, initializedSlot: u64, initializedBlockTime(ibt): i64 } },
+    { type(t): "existing(e)", payload(p): { previousCloseSqrtPrice(pcsp): String, previousCloseDecimalPrice(pcdp): String } } |
+    { type(t): "new(n)", payload(p): { initialSqrtPrice(isp): String, initialDecimalPrice(idp): String, initializedSlot(is): u64, initializedBlockTime(ibt): i64 } },
   estimatedFees(ef): {
     liquidityProviderFeeA(lpfa): u64,
     liquidityProviderFeeB(lpfb): u64,
     protocolFeeA(pfa): u64,
     protocolFeeB(pfb): u64,
   },
+  liquidity(l): {
+    ohlc(p): { open(o): String, high(h): String, low(l): String, close(c): String },
+    depositCount(dc): u64,
+    withdrawalCount(wc): u64,
+    repositionCount(rc): u64,
+    addedA(aa): String,
+    addedB(ab): String,
+    removedA(ra): String,
+    removedB(rb): String,
+    positionCount(pc): u64,
+  },
   daily(d): {
     timestamp(t): i64(UTC, UNIX timestamp in seconds, first second of the day),
     ohlc(p): { sqrtPrice(sp): { open(o): String, high(h): String, low(l): String, close(c): String }, decimalPrice(dp): { open(o): String, high(h): String, low(l): String, close(c): String } },
     volume(v): {
-      ab: { totalIn(ti): String, totalOut(to): String, count(c): u64 },
-      ba: { totalIn(ti): String, totalOut(to): String, count(c): u64 },
+      aToB(ab): { totalIn(ti): String, totalOut(to): String, count(c): u64 },
+      bToA(ba): { totalIn(ti): String, totalOut(to): String, count(c): u64 },
     },
+    vwap(vw): { sqrtPrice(sp): String, decimalPrice(dp): String } (omitted if no trades),
+    twap(tw): { sqrtPrice(sp): String, decimalPrice(dp): String },
+    usd(u): { closeA(ca): String, closeB(cb): String, volume(v): String } (omitted if neither token has a USD price or a value is not finite),
+    fees(f): { liquidityProviderFeeA(lpfa): String, liquidityProviderFeeB(lpfb): String, protocolFeeA(pfa): String, protocolFeeB(pfb): String, minFeeRate(mnfr): u32, maxFeeRate(mxfr): u32, vwapFeeRate(vwfr): u32, estimatedTradeCount(etc): u64 } (omitted if no trades),
+    traders(tr): { tokenAuthorityCount(tac): u64, payerCount(pc): u64 } (omitted if no trades),
   },
   minutely(m): [
     {
       timestamp(t): i64(UTC, UNIX timestamp in seconds, first second of the minute),
       ohlc(p): { sqrtPrice(sp): { open(o): String, high(h): String, low(l): String, close(c): String }, decimalPrice(dp): { open(o): String, high(h): String, low(l): String, close(c): String } },
       volume(v): {
-        ab: { totalIn(ti): String, totalOut(to): String, count(c): u64 },
-        ba: { totalIn(ti): String, totalOut(to): String, count(c): u64 },
+        aToB(ab): { totalIn(ti): String, totalOut(to): String, count(c): u64 },
+        bToA(ba): { totalIn(ti): String, totalOut(to): String, count(c): u64 },
       },
+      vwap(vw): { sqrtPrice(sp): String, decimalPrice(dp): String } (omitted if no trades),
+      twap(tw): { sqrtPrice(sp): String, decimalPrice(dp): String },
+      usd(u): { closeA(ca): String, closeB(cb): String, volume(v): String } (omitted if neither token has a USD price or a value is not finite),
+      fees(f): { liquidityProviderFeeA(lpfa): String, liquidityProviderFeeB(lpfb): String, protocolFeeA(pfa): String, protocolFeeB(pfb): String, minFeeRate(mnfr): u32, maxFeeRate(mxfr): u32, vwapFeeRate(vwfr): u32, estimatedTradeCount(etc): u64 } (omitted if no trades),
+      synthetic(s): bool (only in dense files, omitted if false),
+    },
+    ...
+  ],
+}
+
+Dense minutely file (sedimentology-convert ohlcv --dense, not archived)
+
+For pools with at least one trade in the day, every minute of the day is stored (1440 elements).
+For new pools, minutes before the pool was initialized are not stored.
+Minutes with no trades are synthetic: open, high, low and close are the previous close
+(the close of the previous minute or the initial state), volume is zero, usd and fees are omitted and synthetic is true.
+Pools with no trades have no minutely data as in the sparse file.
+
+Whirlpool OHLCV Interval JSON Lines Format (whirlpool-ohlcv-<interval>-yyyymmdd.jsonl.gz, e.g. 5m, 15m, 1h, 4h)
+
+Same as the minutely format, but candles of the interval are stored instead of minutely.
+Data for candles with no trades at all will be omitted.
+
+{
+  whirlpool(w) ... liquidity(l): same as the minutely format,
+  daily(d): same as the minutely format,
+  interval(i): u32(seconds of a candle, e.g. 300 for 5m),
+  candles(c): [
+    {
+      timestamp(t): i64(UTC, UNIX timestamp in seconds, first second of the candle),
+      ohlc(p), volume(v), vwap(vw), twap(tw), usd(u), fees(f): same as the minutely format,
+      traders(tr): same as daily (only in candles of an hour or longer, e.g. 1h, 4h),
+    },
+    ...
+  ],
+}
+
+Whirlpool OHLCV Pair JSON Lines Format (whirlpool-ohlcv-pair-yyyymmdd.jsonl.gz)
+
+Daily data of the pools of each mint pair (pools of any tick spacing and config are merged).
+Token A and token B of a pair are the same as its pools (the mints of a pool are always ordered in the same way).
+
+{
+  tokenA(ta): { mint(m): String(base58 encoding), decimals(d): u8 },
+  tokenB(tb): { mint(m): String(base58 encoding), decimals(d): u8 },
+  timestamp(t): i64(UTC, UNIX timestamp in seconds, first second of the day),
+  whirlpools(w): [String(base58 encoding), ...],
+  volume(v): {
+    aToB(ab): { totalIn(ti): String, totalOut(to): String, count(c): u64 },
+    bToA(ba): { totalIn(ti): String, totalOut(to): String, count(c): u64 },
+  },
+  vwap(vw): { sqrtPrice(sp): String, decimalPrice(dp): String } (omitted if no trades),
+  fees(f): { liquidityProviderFeeA(lpfa): String, liquidityProviderFeeB(lpfb): String, protocolFeeA(pfa): String, protocolFeeB(pfb): String },
+}
+
+Whirlpool OHLCV Token JSON Lines Format (whirlpool-ohlcv-token-yyyymmdd.jsonl.gz)
+
+Daily data of the pools of each mint.
+
+{
+  mint(m): String(base58 encoding),
+  decimals(d): u8,
+  timestamp(t): i64(UTC, UNIX timestamp in seconds, first second of the day),
+  poolCount(pc): u64 (pools with the mint as token A or token B),
+  tradeCount(c): u64,
+  volumeIn(vi): String (amount of the mint paid into the pools by the trades),
+  volumeOut(vo): String (amount of the mint paid out of the pools by the trades),
+  liquidityProviderFee(lpf): String (fees earned by LPs in the mint),
+  protocolFee(pf): String,
+}
+
+Whirlpool OHLCV Rollup JSON Lines Format (whirlpool-ohlcv-weekly-yyyymmdd.jsonl.gz, whirlpool-ohlcv-monthly-yyyymmdd.jsonl.gz)
+
+Daily files of an ISO week (monday to sunday) or a month are merged into a candle of the period.
+yyyymmdd is the last date of the period (the file is archived with the last date).
+Pools initialized within the period have the initial state of the day they were initialized,
+and pools with no trades have the previous close as open, high, low and close as in the daily file.
+
+{
+  whirlpool(w) ... estimatedFees(ef): same as the daily format (initialState is the state at the beginning of the period),
+  liquidity(l): same as the daily format (omitted if no daily data has liquidity),
+    open is the open of the first day, high and low are the extremes of the days, close is the close of the last day.
+    positionCount is omitted (distinct positions of the period cannot be derived from the daily files).
+  period(p): {
+    timestamp(t): i64(UTC, UNIX timestamp in seconds, first second of the period),
+    ohlc(p), volume(v), vwap(vw), usd(u): same as the daily format,
+      usd is the close of the last day with USD prices, and its volume is the sum of the days.
+    twap(tw): average of the twap prices of the days weighted by the seconds priced in the day (omitted if a day has no twap),
+    fees(f): same as the daily format (omitted if no trades),
+      vwapFeeRate is the average of the vwapFeeRate of the days weighted by the amount of token A of the day.
+  },
+  dayCount(dc): u32 (number of days merged, less than the length of the period for pools initialized within it),
+}
+
+Distinct traders cannot be derived from the daily files, so the period has no traders, and there is no topTraders.
+Daily files of the dates before the first archived date are skipped.
+A daily file missing after the first archived date fails the rollup (it is retried once the date has been archived).
+
+Whirlpool OHLCV Stats JSON Lines Format (whirlpool-ohlcv-stats-yyyymmdd.jsonl.gz)
+
+Daily volatility and price impact of each pool (one line per pool, same pools as the daily file).
+
+{
+  whirlpool(w) ... tickSpacing(ts): same as the daily format,
+  stats(s): {
+    timestamp(t): i64(UTC, UNIX timestamp in seconds, first second of the day),
+    realizedVolatility(rv): String (square root of the sum of squared minutely log returns of decimal price, not annualized, omitted if not finite),
+    returnCount(rc): u64 (number of minutes with trades),
+    tradeCount(c): u64,
+    priceImpact(pi): { (omitted if no trades or not finite)
+      average(a): String,
+      max(m): String,
+    },
+    initializedTickCrossingTrades(itc): { (omitted if no trades or the events do not record tick crossings)
+      count(c): u64 (trades crossing at least one initialized tick),
+      share(s): String (count / tradeCount),
     },
+  },
+}
+
+Volatility and price impact (stats)
+
+- realizedVolatility: log return of a minute is ln(close / previous close) of decimal price,
+  where the previous close of the first minute is the previous close of the day (or the initial price for new pools).
+  Minutes with no trades have no return, so only the minutes with trades are summed up (the same as the dense minutely candles).
+  Multiply by sqrt(365) to annualize it.
+- priceImpact: relative change of the price by each trade (|newPrice / oldPrice - 1| derived from oldSqrtPrice and newSqrtPrice)
+  For two hop swaps, each hop is a trade of its pool.
+- initializedTickCrossingTrades: a trade is counted if it crossed at least one initialized tick (crossedInitializedTick of Traded event)
+  It is derived from the tick arrays written by the trade during the replay, so a trade crossing ticks whose net liquidity cancels out is also counted.
+  Tick crossings are recorded in Traded events since event format version 4, so the stats built from older events have no initializedTickCrossingTrades.
+- Values derived with floating point (realizedVolatility, priceImpact) are omitted rather than written as 0 if they are not finite.
+
+Whirlpool OHLCV Adaptive Fee JSON Lines Format (whirlpool-ohlcv-adaptive-fee-yyyymmdd.jsonl.gz)
+
+Daily and minutely activity of the oracle of each adaptive fee pool (pools without oracle are not included).
+
+{
+  whirlpool(w) ... tickSpacing(ts): same as the daily format,
+  adaptiveFeeConstants(afc): same as adaptiveFeeConstants of PoolInitialized event (constants at the end of the day, omitted if unknown),
+  constantsUpdateCount(cuc): u64 (number of PoolAdaptiveFeeConstantsUpdated events),
+  daily(d): AdaptiveFeeDataUnit,
+  minutely(m): [
+    AdaptiveFeeDataUnit (minutes with trades only),
     ...
   ],
 }
 
+AdaptiveFeeDataUnit: {
+  timestamp(t): i64(UTC, UNIX timestamp in seconds, first second of the candle),
+  tradeCount(c): u64,
+  volatilityAccumulator(va): { open(o): u32, high(h): u32, low(l): u32, close(c): u32 } (omitted if no trades),
+  majorSwapCount(msc): u64,
+  referenceResetCount(rrc): u64,
+  feeRate(fr): { min(mn): u32, percentile25(p25): u32, median(p50): u32, percentile75(p75): u32, percentile90(p90): u32, max(mx): u32 } (omitted if no trades),
+}
+
+Adaptive fee (adaptiveFeeConstants, volatilityAccumulator, feeRate)
+
+Oracle variables are observed at the trades (oldAdaptiveFeeVariables and newAdaptiveFeeVariables of Traded events).
+- volatilityAccumulator: open is the value before the first trade, high and low are the extremes of the values before and after each trade,
+  and close is the value after the last trade (the value stored in the oracle, decay until the next trade is not reflected).
+- majorSwapCount: trades updating lastMajorSwapTimestamp
+  A major swap in the same second as the previous major swap does not change the timestamp, so it is not counted.
+- referenceResetCount: trades updating lastReferenceUpdateTimestamp (volatilityReference and tickGroupIndexReference are reset or decayed)
+- feeRate: distribution (nearest-rank percentiles) of the effective fee rate of the trades, same as minFeeRate and maxFeeRate of the fees
+  The fee rate includes the variable fee only for the events with exact fees (event format version 3 or later).
+
+Average prices (vwap, twap)
+
+- vwap: volume-weighted average price of the trades in the candle (total amount of token B / total amount of token A, transfer fees included)
+- twap: time-weighted average price, each price is weighted by the seconds it stood within the candle
+  (from the beginning of the candle, or the initialization of the pool, to the end of the candle)
+  The average is taken over prices (not sqrt prices), sqrtPrice is the square root of the average price.
+Block time is in seconds, so the prices of the trades in the same block except the last one have no weight.
+The files written before version 3 have no vwap and twap.
+
+Fees (estimatedFees, fees)
+
+Fees are in the input token of the trades (token A for aToB, token B for bToA).
+- estimatedFees: fees of the day, same as the fees of daily (kept for compatibility)
+- liquidityProviderFeeA/B, protocolFeeA/B: fees charged by the trades in the candle
+  Exact fees (lpFee, protocolFee) are recorded in Traded events since event format version 3.
+  For older events, and for the trades whose lpFee is not recorded because the active liquidity changed during the trade,
+  fees are estimated from the fee rate and the input amount (less transfer fee).
+- minFeeRate, maxFeeRate: fee rate of the trades in the candle (in hundredths of a basis point, e.g. 3000 = 0.3%)
+  For adaptive fee pools, the fee rate of a trade is the effective one (total fee / input amount less transfer fee),
+  so it includes the variable fee.
+- vwapFeeRate: average fee rate of the trades weighted by the amount of token A of each trade
+- estimatedTradeCount: number of the trades whose fees are estimated (0 if all fees are exact)
+  The files written before version 7 have no estimatedTradeCount.
+The files written before version 4 have no fees, and their estimatedFees are always estimated.
+
+Liquidity (liquidity)
+
+Liquidity activity of the day from LiquidityDeposited, LiquidityWithdrawn, LiquidityRepositioned and LiquidityPatched events.
+- ohlc: active liquidity of the pool
+  open is the liquidity at the beginning of the day (0 for new pools).
+  high, low and close are observed at the liquidity events and the trades (the liquidity before and after each of them).
+  The liquidity of a trade is recorded since event format version 4,
+  so changes by older swaps crossing initialized ticks are reflected only at the next liquidity event.
+- depositCount, withdrawalCount, repositionCount: number of the events (patches are not counted)
+- addedA/B, removedA/B: amount of tokens transferred into and out of the pool by the events (transfer fees included)
+  Tokens transferred from the owner by repositioning are added, and tokens returned to the owner are removed.
+- positionCount: number of distinct positions deposited, withdrawn or repositioned
+The files written before version 5 have no liquidity.
+
+Traders (traders, topTraders)
+
+- traders: number of distinct token authorities and fee payers (payer of the transaction) of the trades in the candle
+  Only daily data and candles of an hour or longer have traders (minutely and shorter candles omit it).
+- topTraders: token authorities with the largest volume of the day (only in the daily file, top 10 in the archive)
+  volumeA and volumeB are the amounts of token A and token B traded (input and output),
+  and the traders are sorted by volumeA (then volumeB) in descending order.
+The files written before version 6 have no traders and topTraders.
+
+USD prices (usd)
+
+USD prices are derived from the pools of the day (process/ohlcv/pricing.rs), no external price feed is used.
+- closeA, closeB: USD price of 1 token A and 1 token B (UI amount) at the close
+- volume: USD value of the input tokens (totalIn of aToB and bToA) of the trades
+Prices of a candle longer than a minute are the prices of its last minute, and its volume is the sum of its minutes.
+The files written before version 2 have no usd.
+
 */
 
 pub type PubkeyString = String;
 pub type DecimalPrice = bigdecimal::BigDecimal;
 pub type Decimals = u8;
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct WhirlpoolOhlcvDailyData {
   #[serde(flatten)]
   pub metadata: WhirlpoolOhlcvMetadata,
@@ -91,11 +369,15 @@ pub struct WhirlpoolOhlcvDailyData {
   pub initial_state: InitialState,
   #[serde(rename = "ef")]
   pub estimated_fees: EstimatedFees,
+  #[serde(rename = "l", default, skip_serializing_if = "Option::is_none")]
+  pub liquidity: Option<LiquidityData>,
   #[serde(rename = "d")]
   pub daily: WhirlpoolOhlcvDataUnit,
+  #[serde(rename = "tt", default, skip_serializing_if = "Option::is_none")]
+  pub top_traders: Option<Vec<TopTraderData>>,
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct WhirlpoolOhlcvMinutelyData {
   #[serde(flatten)]
   pub metadata: WhirlpoolOhlcvMetadata,
@@ -103,13 +385,49 @@ pub struct WhirlpoolOhlcvMinutelyData {
   pub initial_state: InitialState,
   #[serde(rename = "ef")]
   pub estimated_fees: EstimatedFees,
+  #[serde(rename = "l", default, skip_serializing_if = "Option::is_none")]
+  pub liquidity: Option<LiquidityData>,
   #[serde(rename = "d")]
   pub daily: WhirlpoolOhlcvDataUnit,
   #[serde(rename = "m")]
   pub minutely: Vec<WhirlpoolOhlcvDataUnit>,
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WhirlpoolOhlcvIntervalData {
+  #[serde(flatten)]
+  pub metadata: WhirlpoolOhlcvMetadata,
+  #[serde(rename = "is")]
+  pub initial_state: InitialState,
+  #[serde(rename = "ef")]
+  pub estimated_fees: EstimatedFees,
+  #[serde(rename = "l", default, skip_serializing_if = "Option::is_none")]
+  pub liquidity: Option<LiquidityData>,
+  #[serde(rename = "d")]
+  pub daily: WhirlpoolOhlcvDataUnit,
+  #[serde(rename = "i")]
+  pub interval: u32,
+  #[serde(rename = "c")]
+  pub candles: Vec<WhirlpoolOhlcvDataUnit>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WhirlpoolOhlcvRollupData {
+  #[serde(flatten)]
+  pub metadata: WhirlpoolOhlcvMetadata,
+  #[serde(rename = "is")]
+  pub initial_state: InitialState,
+  #[serde(rename = "ef")]
+  pub estimated_fees: EstimatedFees,
+  #[serde(rename = "l", default, skip_serializing_if = "Option::is_none")]
+  pub liquidity: Option<LiquidityData>,
+  #[serde(rename = "p")]
+  pub period: WhirlpoolOhlcvDataUnit,
+  #[serde(rename = "dc")]
+  pub day_count: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct WhirlpoolOhlcvMetadata {
   #[serde(rename = "w")]
   pub whirlpool: PubkeyString,
@@ -123,7 +441,7 @@ pub struct WhirlpoolOhlcvMetadata {
   pub tick_spacing: u16,
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct TokenData {
   #[serde(rename = "m")]
   pub mint: PubkeyString,
@@ -131,7 +449,7 @@ pub struct TokenData {
   pub decimals: Decimals,
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde(tag = "t", content = "p")]
 pub enum InitialState {
   #[serde(rename = "e")]
@@ -154,7 +472,7 @@ pub enum InitialState {
   },
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct EstimatedFees {
   #[serde(rename = "lpfa", with = "string_u64")]
   pub liquidity_provider_fee_a: u64,
@@ -166,7 +484,42 @@ pub struct EstimatedFees {
   pub protocol_fee_b: u64,
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct LiquidityData {
+  #[serde(rename = "p")]
+  pub ohlc: LiquidityOhlcData,
+  #[serde(rename = "dc")]
+  pub deposit_count: u64,
+  #[serde(rename = "wc")]
+  pub withdrawal_count: u64,
+  #[serde(rename = "rc")]
+  pub reposition_count: u64,
+  #[serde(rename = "aa", with = "string_u128")]
+  pub added_a: u128,
+  #[serde(rename = "ab", with = "string_u128")]
+  pub added_b: u128,
+  #[serde(rename = "ra", with = "string_u128")]
+  pub removed_a: u128,
+  #[serde(rename = "rb", with = "string_u128")]
+  pub removed_b: u128,
+  // None in rollups (distinct positions of the period cannot be derived from the daily files)
+  #[serde(rename = "pc", default, skip_serializing_if = "Option::is_none")]
+  pub position_count: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct LiquidityOhlcData {
+  #[serde(rename = "o", with = "string_u128")]
+  pub open: u128,
+  #[serde(rename = "h", with = "string_u128")]
+  pub high: u128,
+  #[serde(rename = "l", with = "string_u128")]
+  pub low: u128,
+  #[serde(rename = "c", with = "string_u128")]
+  pub close: u128,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct WhirlpoolOhlcvDataUnit {
   #[serde(rename = "t")]
   pub timestamp: i64,
@@ -174,9 +527,22 @@ pub struct WhirlpoolOhlcvDataUnit {
   pub ohlc: WhirlpoolOhlcvData,
   #[serde(rename = "v")]
   pub volume: VolumeData,
+  #[serde(rename = "vw", default, skip_serializing_if = "Option::is_none")]
+  pub vwap: Option<AveragePriceData>,
+  #[serde(rename = "tw", default, skip_serializing_if = "Option::is_none")]
+  pub twap: Option<AveragePriceData>,
+  #[serde(rename = "u", default, skip_serializing_if = "Option::is_none")]
+  pub usd: Option<UsdData>,
+  #[serde(rename = "f", default, skip_serializing_if = "Option::is_none")]
+  pub fees: Option<FeeData>,
+  #[serde(rename = "tr", default, skip_serializing_if = "Option::is_none")]
+  pub traders: Option<TraderData>,
+  // forward-filled minute with no trades (dense file only)
+  #[serde(rename = "s", default, skip_serializing_if = "std::ops::Not::not")]
+  pub synthetic: bool,
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct WhirlpoolOhlcvData {
   #[serde(rename = "sp")]
   pub sqrt_price: SqrtPriceData,
@@ -184,7 +550,7 @@ pub struct WhirlpoolOhlcvData {
   pub decimal_price: DecimalPriceData,
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct SqrtPriceData {
   #[serde(rename = "o", with = "string_u128")]
   pub open: u128,
@@ -196,7 +562,7 @@ pub struct SqrtPriceData {
   pub close: u128,
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct DecimalPriceData {
   #[serde(rename = "o", with = "string_decimal_price")]
   pub open: DecimalPrice,
@@ -208,13 +574,13 @@ pub struct DecimalPriceData {
   pub close: DecimalPrice,
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct VolumeData {
   pub ab: VolumeDirectionData,
   pub ba: VolumeDirectionData,
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct VolumeDirectionData {
   #[serde(rename = "ti", with = "string_u128")]
   pub total_in: u128,
@@ -223,3 +589,201 @@ pub struct VolumeDirectionData {
   #[serde(rename = "c")]
   pub count: u64,
 }
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct AveragePriceData {
+  #[serde(rename = "sp", with = "string_u128")]
+  pub sqrt_price: u128,
+  #[serde(rename = "dp", with = "string_decimal_price")]
+  pub decimal_price: DecimalPrice,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct UsdData {
+  #[serde(rename = "ca", with = "string_decimal_price")]
+  pub close_a: DecimalPrice,
+  #[serde(rename = "cb", with = "string_decimal_price")]
+  pub close_b: DecimalPrice,
+  #[serde(rename = "v", with = "string_decimal_price")]
+  pub volume: DecimalPrice,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct FeeData {
+  #[serde(rename = "lpfa", with = "string_u64")]
+  pub liquidity_provider_fee_a: u64,
+  #[serde(rename = "lpfb", with = "string_u64")]
+  pub liquidity_provider_fee_b: u64,
+  #[serde(rename = "pfa", with = "string_u64")]
+  pub protocol_fee_a: u64,
+  #[serde(rename = "pfb", with = "string_u64")]
+  pub protocol_fee_b: u64,
+  #[serde(rename = "mnfr")]
+  pub min_fee_rate: u32,
+  #[serde(rename = "mxfr")]
+  pub max_fee_rate: u32,
+  #[serde(rename = "vwfr")]
+  pub vwap_fee_rate: u32,
+  // missing in the files written before version 7
+  #[serde(rename = "etc", default)]
+  pub estimated_trade_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct TraderData {
+  #[serde(rename = "tac")]
+  pub token_authority_count: u64,
+  #[serde(rename = "pc")]
+  pub payer_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct TopTraderData {
+  #[serde(rename = "ta")]
+  pub token_authority: PubkeyString,
+  #[serde(rename = "c")]
+  pub trade_count: u64,
+  #[serde(rename = "va", with = "string_u128")]
+  pub volume_a: u128,
+  #[serde(rename = "vb", with = "string_u128")]
+  pub volume_b: u128,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WhirlpoolOhlcvPairData {
+  #[serde(rename = "ta")]
+  pub token_a: TokenData,
+  #[serde(rename = "tb")]
+  pub token_b: TokenData,
+  #[serde(rename = "t")]
+  pub timestamp: i64,
+  #[serde(rename = "w")]
+  pub whirlpools: Vec<PubkeyString>,
+  #[serde(rename = "v")]
+  pub volume: VolumeData,
+  #[serde(rename = "vw", default, skip_serializing_if = "Option::is_none")]
+  pub vwap: Option<AveragePriceData>,
+  #[serde(rename = "f")]
+  pub fees: EstimatedFees,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WhirlpoolOhlcvTokenData {
+  #[serde(rename = "m")]
+  pub mint: PubkeyString,
+  #[serde(rename = "d")]
+  pub decimals: Decimals,
+  #[serde(rename = "t")]
+  pub timestamp: i64,
+  #[serde(rename = "pc")]
+  pub pool_count: u64,
+  #[serde(rename = "c")]
+  pub trade_count: u64,
+  #[serde(rename = "vi", with = "string_u128")]
+  pub volume_in: u128,
+  #[serde(rename = "vo", with = "string_u128")]
+  pub volume_out: u128,
+  #[serde(rename = "lpf", with = "string_u128")]
+  pub liquidity_provider_fee: u128,
+  #[serde(rename = "pf", with = "string_u128")]
+  pub protocol_fee: u128,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WhirlpoolOhlcvStatsData {
+  #[serde(flatten)]
+  pub metadata: WhirlpoolOhlcvMetadata,
+  #[serde(rename = "s")]
+  pub stats: StatsData,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct StatsData {
+  #[serde(rename = "t")]
+  pub timestamp: i64,
+  #[serde(rename = "rv", default, skip_serializing_if = "Option::is_none", with = "string_option_decimal_price")]
+  pub realized_volatility: Option<DecimalPrice>,
+  #[serde(rename = "rc")]
+  pub return_count: u64,
+  #[serde(rename = "c")]
+  pub trade_count: u64,
+  #[serde(rename = "pi", default, skip_serializing_if = "Option::is_none")]
+  pub price_impact: Option<PriceImpactData>,
+  #[serde(rename = "itc", default, skip_serializing_if = "Option::is_none")]
+  pub initialized_tick_crossing_trades: Option<TickCrossingTradeData>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct PriceImpactData {
+  #[serde(rename = "a", with = "string_decimal_price")]
+  pub average: DecimalPrice,
+  #[serde(rename = "m", with = "string_decimal_price")]
+  pub max: DecimalPrice,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct TickCrossingTradeData {
+  #[serde(rename = "c")]
+  pub count: u64,
+  #[serde(rename = "s", with = "string_decimal_price")]
+  pub share: DecimalPrice,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WhirlpoolOhlcvAdaptiveFeeData {
+  #[serde(flatten)]
+  pub metadata: WhirlpoolOhlcvMetadata,
+  #[serde(rename = "afc", default, skip_serializing_if = "Option::is_none")]
+  pub adaptive_fee_constants: Option<AdaptiveFeeConstants>,
+  #[serde(rename = "cuc")]
+  pub constants_update_count: u64,
+  #[serde(rename = "d")]
+  pub daily: AdaptiveFeeDataUnit,
+  #[serde(rename = "m")]
+  pub minutely: Vec<AdaptiveFeeDataUnit>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct AdaptiveFeeDataUnit {
+  #[serde(rename = "t")]
+  pub timestamp: i64,
+  #[serde(rename = "c")]
+  pub trade_count: u64,
+  #[serde(rename = "va", default, skip_serializing_if = "Option::is_none")]
+  pub volatility_accumulator: Option<VolatilityAccumulatorData>,
+  #[serde(rename = "msc")]
+  pub major_swap_count: u64,
+  #[serde(rename = "rrc")]
+  pub reference_reset_count: u64,
+  #[serde(rename = "fr", default, skip_serializing_if = "Option::is_none")]
+  pub fee_rate: Option<FeeRateDistributionData>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct VolatilityAccumulatorData {
+  #[serde(rename = "o")]
+  pub open: u32,
+  #[serde(rename = "h")]
+  pub high: u32,
+  #[serde(rename = "l")]
+  pub low: u32,
+  #[serde(rename = "c")]
+  pub close: u32,
+}
+
+// in hundredths of a basis point
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct FeeRateDistributionData {
+  #[serde(rename = "mn")]
+  pub min: u32,
+  #[serde(rename = "p25")]
+  pub percentile_25: u32,
+  #[serde(rename = "p50")]
+  pub median: u32,
+  #[serde(rename = "p75")]
+  pub percentile_75: u32,
+  #[serde(rename = "p90")]
+  pub percentile_90: u32,
+  #[serde(rename = "mx")]
+  pub max: u32,
+}
@@ -0,0 +1,63 @@
+use crate::date;
+
+// period of whirlpool-ohlcv-<period>-yyyymmdd.jsonl.gz rolled up from the daily files (yyyymmdd is the last date of the period)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum OhlcvPeriod {
+    // ISO week (monday to sunday)
+    Weekly,
+    Monthly,
+}
+
+impl OhlcvPeriod {
+    pub const ALL: [OhlcvPeriod; 2] = [
+        OhlcvPeriod::Weekly,
+        OhlcvPeriod::Monthly,
+    ];
+
+    pub fn from_name(name: &str) -> Option<OhlcvPeriod> {
+        OhlcvPeriod::ALL.into_iter().find(|period| period.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OhlcvPeriod::Weekly => "weekly",
+            OhlcvPeriod::Monthly => "monthly",
+        }
+    }
+
+    // name of ArtifactType::OhlcvRollup
+    pub fn artifact_name(&self) -> &'static str {
+        match self {
+            OhlcvPeriod::Weekly => "ohlcv-weekly",
+            OhlcvPeriod::Monthly => "ohlcv-monthly",
+        }
+    }
+
+    // first date of the period containing the date
+    pub fn first_yyyymmdd_date(&self, yyyymmdd_date: u32) -> u32 {
+        match self {
+            OhlcvPeriod::Weekly => date::first_yyyymmdd_date_of_week(yyyymmdd_date),
+            OhlcvPeriod::Monthly => date::first_yyyymmdd_date_of_month(yyyymmdd_date),
+        }
+    }
+
+    // the period closes on the date (the next date is the first date of the next period)
+    pub fn is_last_yyyymmdd_date(&self, yyyymmdd_date: u32) -> bool {
+        let next_yyyymmdd_date = date::next_yyyymmdd_date(yyyymmdd_date);
+        self.first_yyyymmdd_date(next_yyyymmdd_date) == next_yyyymmdd_date
+    }
+
+    // all dates of the period containing the date (in order)
+    pub fn yyyymmdd_dates(&self, yyyymmdd_date: u32) -> Vec<u32> {
+        let mut yyyymmdd_dates = vec![self.first_yyyymmdd_date(yyyymmdd_date)];
+        while !self.is_last_yyyymmdd_date(*yyyymmdd_dates.last().unwrap()) {
+            yyyymmdd_dates.push(date::next_yyyymmdd_date(*yyyymmdd_dates.last().unwrap()));
+        }
+        yyyymmdd_dates
+    }
+
+    // periods closing on the date
+    pub fn closing_on(yyyymmdd_date: u32) -> Vec<OhlcvPeriod> {
+        OhlcvPeriod::ALL.into_iter().filter(|period| period.is_last_yyyymmdd_date(yyyymmdd_date)).collect()
+    }
+}
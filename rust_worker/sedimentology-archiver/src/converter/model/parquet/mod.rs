@@ -0,0 +1,142 @@
+// format version of parquet files (recorded in whirlpool-manifest-yyyymmdd.json)
+pub const WHIRLPOOL_PARQUET_FORMAT_VERSION: u32 = 1;
+
+/*
+
+Whirlpool Parquet Files
+
+Parquet files are optional copies of the JSON Lines files for analytics (e.g. DuckDB, Spark).
+They are built from the JSON Lines files of the same date, so the values are the same as the JSON Lines files.
+
+Column names are the long keys (long_key::KeyFormat::Long) in snake_case,
+and the keys of the nested objects are joined with "_" (e.g. transferIn.amount to transfer_in_amount).
+
+- whirlpool-transaction-instruction-yyyymmdd.parquet: one row per instruction
+  slot, block_height, block_time, transaction_index, signature, payer, instruction_index, name, payload(JSON)
+
+- whirlpool-transaction-balance-yyyymmdd.parquet: one row per token balance
+  slot, block_height, block_time, transaction_index, signature, payer, account, pre, post
+
+- whirlpool-event-<event>-yyyymmdd.parquet (e.g. whirlpool-event-traded-yyyymmdd.parquet): one row per event, one file per event type
+  slot, block_height, block_time, signature, payer, (flattened payload columns)
+
+- whirlpool-ohlcv-daily-yyyymmdd.parquet: one row per whirlpool
+  (flattened columns of a line of the ohlcv daily file)
+
+- whirlpool-ohlcv-minutely-yyyymmdd.parquet: one row per whirlpool and minute (minutes with no trades are omitted)
+  whirlpool, whirlpools_config, token_a_mint, token_a_decimals, token_b_mint, token_b_decimals, tick_spacing,
+  timestamp, (flattened columns of an element of minutely)
+
+Column types:
+- integer: INT64
+- boolean: BOOLEAN
+- string: BYTE_ARRAY(UTF8)
+  u64 amounts, u128 values (e.g. sqrt prices, liquidity) and decimal prices are strings as in the JSON Lines files,
+  so they keep their exact values (cast them in the query, e.g. CAST(sqrt_price AS HUGEINT) or CAST(decimal_price AS DECIMAL(38, 18))).
+- array: BYTE_ARRAY(UTF8, JSON encoding)
+
+All columns are nullable. The columns are derived from the data of the date,
+so a column whose value is omitted on all rows (e.g. adaptive fee fields) is not included in the file.
+Use union_by_name (DuckDB) or mergeSchema (Spark) to read the files of multiple dates.
+
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ParquetTable {
+    TransactionInstruction,
+    TransactionBalance,
+    Event(&'static EventTable),
+    OhlcvDaily,
+    OhlcvMinutely,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EventTable {
+    // event name in the event file (e.g. T)
+    pub event: &'static str,
+    // table name (e.g. event-traded)
+    pub name: &'static str,
+    // artifact name (e.g. parquet-event-traded)
+    pub artifact: &'static str,
+}
+
+// one table for each variant of WhirlpoolEvent
+pub static EVENT_TABLES: [EventTable; 37] = [
+    EventTable { event: "PD", name: "event-program-deployed", artifact: "parquet-event-program-deployed" },
+    EventTable { event: "T", name: "event-traded", artifact: "parquet-event-traded" },
+    EventTable { event: "LD", name: "event-liquidity-deposited", artifact: "parquet-event-liquidity-deposited" },
+    EventTable { event: "LW", name: "event-liquidity-withdrawn", artifact: "parquet-event-liquidity-withdrawn" },
+    EventTable { event: "LR", name: "event-liquidity-repositioned", artifact: "parquet-event-liquidity-repositioned" },
+    EventTable { event: "PI", name: "event-pool-initialized", artifact: "parquet-event-pool-initialized" },
+    EventTable { event: "RI", name: "event-reward-initialized", artifact: "parquet-event-reward-initialized" },
+    EventTable { event: "REU", name: "event-reward-emissions-updated", artifact: "parquet-event-reward-emissions-updated" },
+    EventTable { event: "RAU", name: "event-reward-authority-updated", artifact: "parquet-event-reward-authority-updated" },
+    EventTable { event: "PHU", name: "event-position-harvest-updated", artifact: "parquet-event-position-harvest-updated" },
+    EventTable { event: "PFH", name: "event-position-fees-harvested", artifact: "parquet-event-position-fees-harvested" },
+    EventTable { event: "PRH", name: "event-position-reward-harvested", artifact: "parquet-event-position-reward-harvested" },
+    EventTable { event: "PFC", name: "event-protocol-fees-collected", artifact: "parquet-event-protocol-fees-collected" },
+    EventTable { event: "PO", name: "event-position-opened", artifact: "parquet-event-position-opened" },
+    EventTable { event: "PC", name: "event-position-closed", artifact: "parquet-event-position-closed" },
+    EventTable { event: "PRR", name: "event-position-range-reset", artifact: "parquet-event-position-range-reset" },
+    EventTable { event: "PL", name: "event-position-locked", artifact: "parquet-event-position-locked" },
+    EventTable { event: "PLT", name: "event-position-locked-transferred", artifact: "parquet-event-position-locked-transferred" },
+    EventTable { event: "PBI", name: "event-position-bundle-initialized", artifact: "parquet-event-position-bundle-initialized" },
+    EventTable { event: "PBD", name: "event-position-bundle-deleted", artifact: "parquet-event-position-bundle-deleted" },
+    EventTable { event: "PFRU", name: "event-pool-fee-rate-updated", artifact: "parquet-event-pool-fee-rate-updated" },
+    EventTable { event: "PPFRU", name: "event-pool-protocol-fee-rate-updated", artifact: "parquet-event-pool-protocol-fee-rate-updated" },
+    EventTable { event: "PAFCU", name: "event-pool-adaptive-fee-constants-updated", artifact: "parquet-event-pool-adaptive-fee-constants-updated" },
+    EventTable { event: "TAI", name: "event-tick-array-initialized", artifact: "parquet-event-tick-array-initialized" },
+    EventTable { event: "CI", name: "event-config-initialized", artifact: "parquet-event-config-initialized" },
+    EventTable { event: "CU", name: "event-config-updated", artifact: "parquet-event-config-updated" },
+    EventTable { event: "FTI", name: "event-fee-tier-initialized", artifact: "parquet-event-fee-tier-initialized" },
+    EventTable { event: "FTU", name: "event-fee-tier-updated", artifact: "parquet-event-fee-tier-updated" },
+    EventTable { event: "AFTI", name: "event-adaptive-fee-tier-initialized", artifact: "parquet-event-adaptive-fee-tier-initialized" },
+    EventTable { event: "AFTU", name: "event-adaptive-fee-tier-updated", artifact: "parquet-event-adaptive-fee-tier-updated" },
+    EventTable { event: "CEI", name: "event-config-extension-initialized", artifact: "parquet-event-config-extension-initialized" },
+    EventTable { event: "CEU", name: "event-config-extension-updated", artifact: "parquet-event-config-extension-updated" },
+    EventTable { event: "TBI", name: "event-token-badge-initialized", artifact: "parquet-event-token-badge-initialized" },
+    EventTable { event: "TBD", name: "event-token-badge-deleted", artifact: "parquet-event-token-badge-deleted" },
+    EventTable { event: "TBU", name: "event-token-badge-updated", artifact: "parquet-event-token-badge-updated" },
+    EventTable { event: "LP", name: "event-liquidity-patched", artifact: "parquet-event-liquidity-patched" },
+    EventTable { event: "PM", name: "event-pool-migrated", artifact: "parquet-event-pool-migrated" },
+];
+
+impl ParquetTable {
+    pub fn all() -> Vec<ParquetTable> {
+        let mut tables = vec![ParquetTable::TransactionInstruction, ParquetTable::TransactionBalance];
+        tables.extend(EVENT_TABLES.iter().map(ParquetTable::Event));
+        tables.push(ParquetTable::OhlcvDaily);
+        tables.push(ParquetTable::OhlcvMinutely);
+        tables
+    }
+
+    pub fn event_tables() -> Vec<ParquetTable> {
+        EVENT_TABLES.iter().map(ParquetTable::Event).collect()
+    }
+
+    pub fn from_name(name: &str) -> Option<ParquetTable> {
+        ParquetTable::all().into_iter().find(|table| table.name() == name)
+    }
+
+    // e.g. event-traded (whirlpool-event-traded-yyyymmdd.parquet)
+    pub fn name(&self) -> &'static str {
+        match self {
+            ParquetTable::TransactionInstruction => "transaction-instruction",
+            ParquetTable::TransactionBalance => "transaction-balance",
+            ParquetTable::Event(table) => table.name,
+            ParquetTable::OhlcvDaily => "ohlcv-daily",
+            ParquetTable::OhlcvMinutely => "ohlcv-minutely",
+        }
+    }
+
+    // e.g. parquet-event-traded (artifact name in manifest and admArchiverStage)
+    pub fn artifact_name(&self) -> &'static str {
+        match self {
+            ParquetTable::TransactionInstruction => "parquet-transaction-instruction",
+            ParquetTable::TransactionBalance => "parquet-transaction-balance",
+            ParquetTable::Event(table) => table.artifact,
+            ParquetTable::OhlcvDaily => "parquet-ohlcv-daily",
+            ParquetTable::OhlcvMinutely => "parquet-ohlcv-minutely",
+        }
+    }
+}
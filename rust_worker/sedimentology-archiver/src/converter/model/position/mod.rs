@@ -0,0 +1,443 @@
+use serde_derive::{Serialize, Deserialize};
+use super::ohlcv::{DecimalPrice, PubkeyString, TokenData};
+use super::serde::{string_decimal_price, string_i128, string_option_decimal_price, string_option_u128, string_u128, string_u64};
+
+// format version of position files (recorded in whirlpool-manifest-yyyymmdd.json)
+pub const WHIRLPOOL_POSITION_LEDGER_FORMAT_VERSION: u32 = 1;
+pub const WHIRLPOOL_POSITION_VALUATION_FORMAT_VERSION: u32 = 1;
+pub const WHIRLPOOL_POSITION_RANGE_FORMAT_VERSION: u32 = 1;
+pub const WHIRLPOOL_POSITION_FORMAT_VERSION: u32 = 1;
+
+/*
+
+Whirlpool Position Ledger JSON Lines Format (whirlpool-position-ledger-yyyymmdd.jsonl.gz)
+
+Lifecycle of each position derived from the position events (PositionOpened, LiquidityDeposited, LiquidityWithdrawn,
+LiquidityRepositioned, PositionFeesHarvested, PositionRewardHarvested, PositionRangeReset and PositionClosed).
+The ledger of a date is built from the ledger of the previous date and the event file of the date,
+so the running state (liquidity, range and cumulative amounts) is carried over from the day the position was opened.
+
+One line per position open at the end of the day or closed in the day (sorted by position).
+Positions closed on a previous date are not included.
+
+To reduce data size, we use short field names.
+Long key format (long_key::KeyFormat::Long) uses the names in front of the parentheses.
+
+{
+  position(p): String(base58 encoding),
+  whirlpool(w): String(base58 encoding),
+  tokenA(ta): { mint(m): String(base58 encoding), decimals(d): u8 } (omitted until a transfer of token A is seen),
+  tokenB(tb): { mint(m): String(base58 encoding), decimals(d): u8 } (omitted until a transfer of token B is seen),
+  opened(o): { slot(s): u64, blockTime(bt): i64 } (omitted if the position was opened before the ledger started),
+  closed(cl): { slot(s): u64, blockTime(bt): i64 } (omitted if the position is open),
+  lowerTickIndex(lti): i32 (omitted if unknown),
+  upperTickIndex(uti): i32 (omitted if unknown),
+  liquidity(l): String (omitted if unknown),
+  decimalPrice(dp): String (price of the pool at the last liquidity event, omitted if unknown),
+  deposited(d): { amountA(a): String, amountB(b): String },
+  withdrawn(wd): { amountA(a): String, amountB(b): String },
+  fees(f): { amountA(a): String, amountB(b): String },
+  rewards(r): [
+    { index(i): u8, mint(m): String(base58 encoding), amount(a): String },
+    ...
+  ],
+  realizedPnl(rp): { (only for positions opened and closed within the ledger)
+    amountA(a): String (withdrawn + fees - deposited of token A, signed),
+    amountB(b): String (withdrawn + fees - deposited of token B, signed),
+    value(v): String (amountA * decimalPrice + amountB in token B (UI amount), omitted if the tokens or the price are unknown),
+  },
+  entries(e): [
+    {
+      slot(s): u64,
+      blockTime(bt): i64,
+      signature(sg): String(base58 encoding),
+      type(t): "opened(o)" | "deposited(d)" | "withdrawn(w)" | "repositioned(rp)" | "feesHarvested(fh)" | "rewardHarvested(rh)" | "rangeReset(rr)" | "closed(c)",
+      liquidity(l): String (after the entry, omitted if unknown),
+      lowerTickIndex(lti): i32 (after the entry, omitted if unknown),
+      upperTickIndex(uti): i32 (after the entry, omitted if unknown),
+      deposited(d): { amountA(a): String, amountB(b): String } (omitted if no tokens are deposited),
+      withdrawn(wd): { amountA(a): String, amountB(b): String } (omitted if no tokens are withdrawn),
+      fees(f): { amountA(a): String, amountB(b): String } (feesHarvested only),
+      reward(r): { index(i): u8, mint(m): String(base58 encoding), amount(a): String } (rewardHarvested only),
+    },
+    ... (entries of the day in the order of the events)
+  ],
+}
+
+Amounts (deposited, withdrawn, fees, rewards)
+
+Amounts are the raw amounts of the transfers recorded in the events (transfer fees included).
+- deposited, withdrawn: tokens transferred by LiquidityDeposited and LiquidityWithdrawn events
+  Tokens transferred from the owner by LiquidityRepositioned events are deposited, and tokens returned to the owner are withdrawn.
+- fees: tokens transferred by PositionFeesHarvested events
+- rewards: tokens transferred by PositionRewardHarvested events for each reward index
+
+Realized PnL (realizedPnl)
+
+Realized PnL is the token balance of the owner over the lifecycle of a closed position: withdrawn + fees - deposited.
+It is recorded only if the position was opened within the ledger (opened is known), otherwise deposits before the ledger are missing.
+value is the PnL in token B at the price of the last liquidity event (usually the withdrawal just before closing).
+Rewards are not included because they are paid in other tokens.
+
+A bundled position can be reopened at the same address after it is closed.
+In that case, the closed position and the reopened position are written in separate lines (the closed one first).
+
+*/
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WhirlpoolPositionLedgerData {
+  #[serde(rename = "p")]
+  pub position: PubkeyString,
+  #[serde(rename = "w")]
+  pub whirlpool: PubkeyString,
+  #[serde(rename = "ta", default, skip_serializing_if = "Option::is_none")]
+  pub token_a: Option<TokenData>,
+  #[serde(rename = "tb", default, skip_serializing_if = "Option::is_none")]
+  pub token_b: Option<TokenData>,
+  #[serde(rename = "o", default, skip_serializing_if = "Option::is_none")]
+  pub opened: Option<LedgerBlockData>,
+  #[serde(rename = "cl", default, skip_serializing_if = "Option::is_none")]
+  pub closed: Option<LedgerBlockData>,
+  #[serde(rename = "lti", default, skip_serializing_if = "Option::is_none")]
+  pub lower_tick_index: Option<i32>,
+  #[serde(rename = "uti", default, skip_serializing_if = "Option::is_none")]
+  pub upper_tick_index: Option<i32>,
+  #[serde(rename = "l", default, skip_serializing_if = "Option::is_none", with = "string_option_u128")]
+  pub liquidity: Option<u128>,
+  #[serde(rename = "dp", default, skip_serializing_if = "Option::is_none", with = "string_option_decimal_price")]
+  pub decimal_price: Option<DecimalPrice>,
+  #[serde(rename = "d")]
+  pub deposited: LedgerAmountData,
+  #[serde(rename = "wd")]
+  pub withdrawn: LedgerAmountData,
+  #[serde(rename = "f")]
+  pub fees: LedgerAmountData,
+  #[serde(rename = "r")]
+  pub rewards: Vec<LedgerRewardData>,
+  #[serde(rename = "rp", default, skip_serializing_if = "Option::is_none")]
+  pub realized_pnl: Option<RealizedPnlData>,
+  #[serde(rename = "e")]
+  pub entries: Vec<LedgerEntryData>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct LedgerBlockData {
+  #[serde(rename = "s")]
+  pub slot: u64,
+  #[serde(rename = "bt")]
+  pub block_time: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct LedgerAmountData {
+  #[serde(rename = "a", with = "string_u128")]
+  pub amount_a: u128,
+  #[serde(rename = "b", with = "string_u128")]
+  pub amount_b: u128,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct LedgerRewardData {
+  #[serde(rename = "i")]
+  pub index: u8,
+  #[serde(rename = "m")]
+  pub mint: PubkeyString,
+  #[serde(rename = "a", with = "string_u128")]
+  pub amount: u128,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct RealizedPnlData {
+  #[serde(rename = "a", with = "string_i128")]
+  pub amount_a: i128,
+  #[serde(rename = "b", with = "string_i128")]
+  pub amount_b: i128,
+  #[serde(rename = "v", default, skip_serializing_if = "Option::is_none", with = "string_option_decimal_price")]
+  pub value: Option<DecimalPrice>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct LedgerEntryData {
+  #[serde(rename = "s")]
+  pub slot: u64,
+  #[serde(rename = "bt")]
+  pub block_time: i64,
+  #[serde(rename = "sg")]
+  pub signature: String,
+  #[serde(rename = "t")]
+  pub entry_type: LedgerEntryType,
+  #[serde(rename = "l", default, skip_serializing_if = "Option::is_none", with = "string_option_u128")]
+  pub liquidity: Option<u128>,
+  #[serde(rename = "lti", default, skip_serializing_if = "Option::is_none")]
+  pub lower_tick_index: Option<i32>,
+  #[serde(rename = "uti", default, skip_serializing_if = "Option::is_none")]
+  pub upper_tick_index: Option<i32>,
+  #[serde(rename = "d", default, skip_serializing_if = "Option::is_none")]
+  pub deposited: Option<LedgerAmountData>,
+  #[serde(rename = "wd", default, skip_serializing_if = "Option::is_none")]
+  pub withdrawn: Option<LedgerAmountData>,
+  #[serde(rename = "f", default, skip_serializing_if = "Option::is_none")]
+  pub fees: Option<LedgerAmountData>,
+  #[serde(rename = "r", default, skip_serializing_if = "Option::is_none")]
+  pub reward: Option<LedgerRewardData>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LedgerEntryType {
+  #[serde(rename = "o")]
+  Opened,
+  #[serde(rename = "d")]
+  Deposited,
+  #[serde(rename = "w")]
+  Withdrawn,
+  #[serde(rename = "rp")]
+  Repositioned,
+  #[serde(rename = "fh")]
+  FeesHarvested,
+  #[serde(rename = "rh")]
+  RewardHarvested,
+  #[serde(rename = "rr")]
+  RangeReset,
+  #[serde(rename = "c")]
+  Closed,
+}
+
+/*
+
+Whirlpool Position Valuation JSON Lines Format (whirlpool-position-valuation-yyyymmdd.jsonl.gz)
+
+Value of each open position at the daily close price of the pool, built by the position valuation process
+from the position ledger of the date (whirlpool-position-ledger-yyyymmdd.jsonl.gz) and the prices of the day
+(rebuilt from the state of the previous date, token and event in the same way as the OHLCV process).
+
+One line per position open at the end of the day with known liquidity and range (sorted by position).
+Positions closed in the day are not included (see realizedPnl of the ledger).
+Positions without any event since the ledger started have no line in the ledger, so they are seeded from the Position accounts
+of the state of the previous date (no event in the day, so liquidity and range are the same at the end of the day).
+Their deposits are unknown as well as the positions opened before the ledger started (holdValue and impermanentLoss are omitted).
+
+To reduce data size, we use short field names.
+Long key format (long_key::KeyFormat::Long) uses the names in front of the parentheses.
+
+{
+  position(p): String(base58 encoding),
+  whirlpool(w): String(base58 encoding),
+  timestamp(t): i64 (the first second of the day),
+  tokenA(ta): { mint(m): String(base58 encoding), decimals(d): u8 },
+  tokenB(tb): { mint(m): String(base58 encoding), decimals(d): u8 },
+  lowerTickIndex(lti): i32,
+  upperTickIndex(uti): i32,
+  liquidity(l): String,
+  sqrtPrice(sp): String (daily close sqrt price of the pool),
+  decimalPrice(dp): String (daily close price of the pool),
+  tickIndex(ti): i32 (current tick index of the pool at the end of the day),
+  inRange(ir): bool (lowerTickIndex <= tickIndex < upperTickIndex),
+  amounts(a): { amountA(a): String, amountB(b): String } (token amounts of the liquidity at the close price),
+  value(v): String (amountA * decimalPrice + amountB in token B (UI amount)),
+  netDeposited(nd): { amountA(a): String, amountB(b): String } (deposited - withdrawn of the ledger, signed),
+  holdValue(hv): String (netDeposited valued at the close price in token B (UI amount), omitted if opened is unknown),
+  impermanentLoss(il): String (value - holdValue in token B (UI amount), omitted if opened is unknown),
+  impermanentLossRatio(ilr): String (impermanentLoss / holdValue, omitted if opened is unknown or holdValue is not positive),
+}
+
+Amounts (amounts)
+
+Token amounts are calculated from the liquidity and the range as the Whirlpool program does (whirlpool_base::math, rounded down).
+- below the range: token A only, between the lower and upper sqrt prices
+- in the range: token A between the close and upper sqrt prices, token B between the lower and close sqrt prices
+- above the range: token B only, between the lower and upper sqrt prices
+Uncollected fees and rewards are not included.
+
+Impermanent Loss (holdValue, impermanentLoss)
+
+holdValue is the value of simply holding the tokens deposited into the position instead of providing liquidity.
+Withdrawn tokens are subtracted from the deposited tokens, so partial withdrawals reduce the held tokens.
+It is recorded only if the position was opened within the ledger (opened is known), otherwise deposits before the ledger are missing.
+Harvested fees are not included, so impermanentLoss is usually negative.
+
+*/
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WhirlpoolPositionValuationData {
+  #[serde(rename = "p")]
+  pub position: PubkeyString,
+  #[serde(rename = "w")]
+  pub whirlpool: PubkeyString,
+  #[serde(rename = "t")]
+  pub timestamp: i64,
+  #[serde(rename = "ta")]
+  pub token_a: TokenData,
+  #[serde(rename = "tb")]
+  pub token_b: TokenData,
+  #[serde(rename = "lti")]
+  pub lower_tick_index: i32,
+  #[serde(rename = "uti")]
+  pub upper_tick_index: i32,
+  #[serde(rename = "l", with = "string_u128")]
+  pub liquidity: u128,
+  #[serde(rename = "sp", with = "string_u128")]
+  pub sqrt_price: u128,
+  #[serde(rename = "dp", with = "string_decimal_price")]
+  pub decimal_price: DecimalPrice,
+  #[serde(rename = "ti")]
+  pub tick_index: i32,
+  #[serde(rename = "ir")]
+  pub in_range: bool,
+  #[serde(rename = "a")]
+  pub amounts: LedgerAmountData,
+  #[serde(rename = "v", with = "string_decimal_price")]
+  pub value: DecimalPrice,
+  #[serde(rename = "nd")]
+  pub net_deposited: NetAmountData,
+  #[serde(rename = "hv", default, skip_serializing_if = "Option::is_none", with = "string_option_decimal_price")]
+  pub hold_value: Option<DecimalPrice>,
+  #[serde(rename = "il", default, skip_serializing_if = "Option::is_none", with = "string_option_decimal_price")]
+  pub impermanent_loss: Option<DecimalPrice>,
+  #[serde(rename = "ilr", default, skip_serializing_if = "Option::is_none", with = "string_option_decimal_price")]
+  pub impermanent_loss_ratio: Option<DecimalPrice>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct NetAmountData {
+  #[serde(rename = "a", with = "string_i128")]
+  pub amount_a: i128,
+  #[serde(rename = "b", with = "string_i128")]
+  pub amount_b: i128,
+}
+
+/*
+
+Whirlpool Position Range JSON Lines Format (whirlpool-position-range-yyyymmdd.jsonl.gz)
+
+Minutes the pool price sat inside the range of each position in the day, built together with the position valuation
+from the position ledger of the date (whirlpool-position-ledger-yyyymmdd.jsonl.gz).
+
+The tick series of the pool starts with the current tick index of the previous-day state (the initial tick for new pools),
+followed by newCurrentTickIndex of each Traded event. Each tick stands until the next trade.
+The position is in range while lowerTickIndex <= tick index < upperTickIndex.
+
+One line per range of each position tracked for at least a second in the day
+(sorted by position, the ranges of a position in time order).
+Positions not in the ledger are seeded from the state of the previous date in the same way as the position valuation.
+A position is tracked while it is open and its range is known:
+- from the beginning of the day (the initialization of the pool for new pools), or from the opening of the position
+- until the end of the day, or until the closing of the position
+- repositions and range resets in the day start a new range, the range before the first of them is taken from the earlier entries of the day
+  (if the first entry of the day changes the range, the position is tracked from that entry)
+A position repositioned in the day has a line for each range it held (a range held twice in the day has two lines).
+
+To reduce data size, we use short field names.
+Long key format (long_key::KeyFormat::Long) uses the names in front of the parentheses.
+
+{
+  position(p): String(base58 encoding),
+  whirlpool(w): String(base58 encoding),
+  timestamp(t): i64 (the first second of the day),
+  startTime(st): i64 (block time the range was taken, or the first second of the day),
+  endTime(et): i64 (block time the range was changed or the position was closed, or the first second of the next day),
+  lowerTickIndex(lti): i32,
+  upperTickIndex(uti): i32,
+  trackedMinutes(tm): u32 (minutes tracked in the range, rounded down),
+  inRangeMinutes(irm): u32 (minutes in range, rounded down),
+}
+
+*/
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WhirlpoolPositionRangeData {
+  #[serde(rename = "p")]
+  pub position: PubkeyString,
+  #[serde(rename = "w")]
+  pub whirlpool: PubkeyString,
+  #[serde(rename = "t")]
+  pub timestamp: i64,
+  #[serde(rename = "st")]
+  pub start_time: i64,
+  #[serde(rename = "et")]
+  pub end_time: i64,
+  #[serde(rename = "lti")]
+  pub lower_tick_index: i32,
+  #[serde(rename = "uti")]
+  pub upper_tick_index: i32,
+  #[serde(rename = "tm")]
+  pub tracked_minutes: u32,
+  #[serde(rename = "irm")]
+  pub in_range_minutes: u32,
+}
+
+/*
+
+Whirlpool Position JSON Lines Format (whirlpool-position-yyyymmdd.jsonl.gz)
+
+Position accounts in the state file of the date (whirlpool-state-yyyymmdd.json.gz) decoded with Anchor,
+so consumers do not need to decode the raw account data.
+
+One line per Position account (sorted by position).
+Bundle membership is resolved from PositionBundle accounts in the same state file.
+
+To reduce data size, we use short field names.
+Long key format (long_key::KeyFormat::Long) uses the names in front of the parentheses.
+
+{
+  position(p): String(base58 encoding),
+  whirlpool(w): String(base58 encoding),
+  positionMint(pm): String(base58 encoding) (the mint of the position bundle for bundled positions),
+  liquidity(l): String,
+  lowerTickIndex(lti): i32,
+  upperTickIndex(uti): i32,
+  feeGrowthCheckpointA(fgca): String (X64 fixed point),
+  feeOwedA(foa): String,
+  feeGrowthCheckpointB(fgcb): String (X64 fixed point),
+  feeOwedB(fob): String,
+  rewards(r): [
+    { growthInsideCheckpoint(gic): String (X64 fixed point), amountOwed(ao): String },
+    ... (3 reward slots in the order of the reward index)
+  ],
+  positionBundle(pb): String(base58 encoding) (omitted if not bundled),
+  positionBundleMint(pbm): String(base58 encoding) (omitted if not bundled),
+  positionBundleIndex(pbi): u16 (omitted if not bundled),
+}
+
+Fee owed and reward owed are the amounts at the last update of the position (fees and rewards accrued after it are not included).
+
+*/
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct WhirlpoolPositionData {
+  #[serde(rename = "p")]
+  pub position: PubkeyString,
+  #[serde(rename = "w")]
+  pub whirlpool: PubkeyString,
+  #[serde(rename = "pm")]
+  pub position_mint: PubkeyString,
+  #[serde(rename = "l", with = "string_u128")]
+  pub liquidity: u128,
+  #[serde(rename = "lti")]
+  pub lower_tick_index: i32,
+  #[serde(rename = "uti")]
+  pub upper_tick_index: i32,
+  #[serde(rename = "fgca", with = "string_u128")]
+  pub fee_growth_checkpoint_a: u128,
+  #[serde(rename = "foa", with = "string_u64")]
+  pub fee_owed_a: u64,
+  #[serde(rename = "fgcb", with = "string_u128")]
+  pub fee_growth_checkpoint_b: u128,
+  #[serde(rename = "fob", with = "string_u64")]
+  pub fee_owed_b: u64,
+  #[serde(rename = "r")]
+  pub rewards: Vec<PositionRewardData>,
+  #[serde(rename = "pb", default, skip_serializing_if = "Option::is_none")]
+  pub position_bundle: Option<PubkeyString>,
+  #[serde(rename = "pbm", default, skip_serializing_if = "Option::is_none")]
+  pub position_bundle_mint: Option<PubkeyString>,
+  #[serde(rename = "pbi", default, skip_serializing_if = "Option::is_none")]
+  pub position_bundle_index: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct PositionRewardData {
+  #[serde(rename = "gic", with = "string_u128")]
+  pub growth_inside_checkpoint: u128,
+  #[serde(rename = "ao", with = "string_u64")]
+  pub amount_owed: u64,
+}
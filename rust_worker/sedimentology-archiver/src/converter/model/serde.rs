@@ -38,6 +38,26 @@ pub mod string_u128 {
     }
 }
 
+pub mod string_i128 {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+    use std::str::FromStr;
+
+    pub fn serialize<S>(data: &i128, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&data.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<i128, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        i128::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
 pub mod string_option_u64 {
     use serde::{self, Deserialize, Deserializer, Serializer};
     use std::str::FromStr;
@@ -62,6 +82,30 @@ pub mod string_option_u64 {
     }
 }
 
+pub mod string_option_u128 {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+    use std::str::FromStr;
+
+    pub fn serialize<S>(data: &Option<u128>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // must be Some
+        // skip_serializing_if = "Option::is_none" is must
+        serializer.serialize_str(&data.unwrap().to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<u128>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // must be Some
+        // default = "Option::default" is must
+        let s = String::deserialize(deserializer)?;
+        Ok(Some(u128::from_str(&s).map_err(serde::de::Error::custom)?))
+    }
+}
+
 pub mod string_decimal_price {
     use bigdecimal::BigDecimal;
     use serde::{self, Deserialize, Deserializer, Serializer};
@@ -88,3 +132,26 @@ pub mod string_decimal_price {
         BigDecimal::from_str(&s).map_err(serde::de::Error::custom)
     }
 }
+
+pub mod string_option_decimal_price {
+    use bigdecimal::BigDecimal;
+    use serde::{self, Serializer, Deserializer};
+
+    pub fn serialize<S>(data: &Option<BigDecimal>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // must be Some
+        // skip_serializing_if = "Option::is_none" is must
+        super::string_decimal_price::serialize(data.as_ref().unwrap(), serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<BigDecimal>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // must be Some
+        // default = "Option::default" is must
+        Ok(Some(super::string_decimal_price::deserialize(deserializer)?))
+    }
+}
@@ -0,0 +1,258 @@
+use super::super::model::account::{
+  DecodedAdaptiveFeeTierData, DecodedFeeTierData, DecodedLockConfigData, DecodedOracleData, DecodedRewardData, DecodedTokenBadgeData,
+  DecodedWhirlpoolData, DecodedWhirlpoolsConfigData, DecodedWhirlpoolsConfigExtensionData, WhirlpoolAccountType,
+};
+use super::super::model::event::definition::{AdaptiveFeeConstants, AdaptiveFeeVariables, PositionLockType};
+use super::super::model::long_key::{self, KeyFormat};
+use super::super::model::ohlcv::{DecimalPrice, TokenData};
+use super::ohlcv::sqrt_price_to_decimal_price;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Result};
+use bigdecimal::BigDecimal;
+use flate2::write::GzEncoder;
+use std::collections::HashMap;
+use std::{
+  fs::File,
+  io::{LineWriter, Write},
+};
+use whirlpool_base::state::{AdaptiveFeeTier, FeeTier, LockConfig, LockTypeLabel, Oracle, TokenBadge, Whirlpool, WhirlpoolsConfig, WhirlpoolsConfigExtension};
+use whirlpool_replayer::serde::AccountDataStoreConfig;
+
+// build whirlpool-account-<type>-yyyymmdd.jsonl.gz from the state file and token file (decimals) of the date
+// only the account types in out_whirlpool_account_file_paths are written
+pub fn process(
+  in_whirlpool_state_file_path: String,
+  in_whirlpool_token_file_path: String,
+  out_whirlpool_account_file_paths: &[(WhirlpoolAccountType, String)],
+  key_format: KeyFormat,
+) -> Result<()> {
+  println!("open state file...");
+  let state = whirlpool_replayer::io::load_from_local_whirlpool_state_file(
+    &in_whirlpool_state_file_path,
+    &AccountDataStoreConfig::OnDisk(None),
+  );
+  let token = whirlpool_replayer::io::load_from_local_whirlpool_token_file(&in_whirlpool_token_file_path);
+  let decimals: HashMap<String, u8> = token.tokens.iter().map(|t| (t.mint.clone(), t.decimals)).collect();
+
+  let mut writers = HashMap::new();
+  for (account_type, path) in out_whirlpool_account_file_paths {
+    let f = File::create(path)?;
+    let encoder = GzEncoder::new(f, flate2::Compression::default());
+    writers.insert(*account_type, LineWriter::new(encoder));
+  }
+
+  // accounts are sorted by pubkey in the state file
+  println!("write account files...");
+  state.accounts.traverse(|pubkey, data| {
+    let Some(account_type) = detect_account_type(data) else {
+      return Ok(());
+    };
+    let Some(writer) = writers.get_mut(&account_type) else {
+      return Ok(());
+    };
+
+    let jsonl = match account_type {
+      WhirlpoolAccountType::Whirlpool => long_key::to_string(&whirlpool(pubkey, deserialize(account_type, pubkey, data)?, &decimals)?, key_format),
+      WhirlpoolAccountType::WhirlpoolsConfig => long_key::to_string(&whirlpools_config(pubkey, deserialize(account_type, pubkey, data)?), key_format),
+      WhirlpoolAccountType::WhirlpoolsConfigExtension => long_key::to_string(&whirlpools_config_extension(pubkey, deserialize(account_type, pubkey, data)?), key_format),
+      WhirlpoolAccountType::FeeTier => long_key::to_string(&fee_tier(pubkey, deserialize(account_type, pubkey, data)?), key_format),
+      WhirlpoolAccountType::AdaptiveFeeTier => long_key::to_string(&adaptive_fee_tier(pubkey, deserialize(account_type, pubkey, data)?), key_format),
+      WhirlpoolAccountType::Oracle => long_key::to_string(&oracle(pubkey, deserialize(account_type, pubkey, data)?), key_format),
+      WhirlpoolAccountType::TokenBadge => long_key::to_string(&token_badge(pubkey, deserialize(account_type, pubkey, data)?), key_format),
+      WhirlpoolAccountType::LockConfig => long_key::to_string(&lock_config(pubkey, deserialize(account_type, pubkey, data)?)?, key_format),
+    }?;
+    writer.write_all(jsonl.as_bytes())?;
+    writer.write_all(b"\n")?;
+    Ok(())
+  })?;
+
+  for writer in writers.values_mut() {
+    writer.flush()?;
+  }
+
+  Ok(())
+}
+
+fn deserialize<T: AccountDeserialize>(account_type: WhirlpoolAccountType, pubkey: &str, mut data: &[u8]) -> Result<T> {
+  T::try_deserialize(&mut data).map_err(|err| anyhow!("failed to deserialize {} account {}: {}", account_type.name(), pubkey, err))
+}
+
+// None for the accounts not decoded (e.g. Position, TickArray)
+fn detect_account_type(data: &[u8]) -> Option<WhirlpoolAccountType> {
+  if data.starts_with(&Whirlpool::DISCRIMINATOR) {
+    Some(WhirlpoolAccountType::Whirlpool)
+  } else if data.starts_with(&WhirlpoolsConfig::DISCRIMINATOR) {
+    Some(WhirlpoolAccountType::WhirlpoolsConfig)
+  } else if data.starts_with(&WhirlpoolsConfigExtension::DISCRIMINATOR) {
+    Some(WhirlpoolAccountType::WhirlpoolsConfigExtension)
+  } else if data.starts_with(&FeeTier::DISCRIMINATOR) {
+    Some(WhirlpoolAccountType::FeeTier)
+  } else if data.starts_with(&AdaptiveFeeTier::DISCRIMINATOR) {
+    Some(WhirlpoolAccountType::AdaptiveFeeTier)
+  } else if data.starts_with(&Oracle::DISCRIMINATOR) {
+    Some(WhirlpoolAccountType::Oracle)
+  } else if data.starts_with(&TokenBadge::DISCRIMINATOR) {
+    Some(WhirlpoolAccountType::TokenBadge)
+  } else if data.starts_with(&LockConfig::DISCRIMINATOR) {
+    Some(WhirlpoolAccountType::LockConfig)
+  } else {
+    None
+  }
+}
+
+// the decimals of token A and B must be in the token file (the decimal price cannot be omitted)
+fn whirlpool(pubkey: &str, whirlpool: Whirlpool, decimals: &HashMap<String, u8>) -> Result<DecodedWhirlpoolData> {
+  let token_decimals = |mint: &Pubkey| decimals.get(&mint.to_string()).copied()
+    .ok_or_else(|| anyhow!("decimals of {} (a token of Whirlpool {}) not found in the token file", mint, pubkey));
+  let decimals_a = token_decimals(&whirlpool.token_mint_a)?;
+  let decimals_b = token_decimals(&whirlpool.token_mint_b)?;
+
+  let rewards = whirlpool.reward_infos.iter().enumerate()
+    .filter(|(_, reward_info)| reward_info.mint != Pubkey::default())
+    .map(|(index, reward_info)| DecodedRewardData {
+      index: index as u8,
+      mint: reward_info.mint.to_string(),
+      vault: reward_info.vault.to_string(),
+      // the reward authority is stored in the extension field (same as the event file)
+      authority: bs58::encode(&reward_info.extension).into_string(),
+      emissions_per_second_x64: reward_info.emissions_per_second_x64,
+      emissions_per_day: decimals
+        .get(&reward_info.mint.to_string())
+        .map(|decimals| emissions_per_day(reward_info.emissions_per_second_x64, *decimals)),
+      growth_global_x64: reward_info.growth_global_x64,
+    })
+    .collect();
+
+  Ok(DecodedWhirlpoolData {
+    account: pubkey.to_string(),
+    whirlpools_config: whirlpool.whirlpools_config.to_string(),
+    tick_spacing: whirlpool.tick_spacing,
+    fee_tier_index: whirlpool.fee_tier_index(),
+    adaptive_fee_enabled: whirlpool.is_initialized_with_adaptive_fee_tier(),
+    token_a: TokenData { mint: whirlpool.token_mint_a.to_string(), decimals: decimals_a },
+    token_b: TokenData { mint: whirlpool.token_mint_b.to_string(), decimals: decimals_b },
+    token_vault_a: whirlpool.token_vault_a.to_string(),
+    token_vault_b: whirlpool.token_vault_b.to_string(),
+    fee_rate: whirlpool.fee_rate,
+    protocol_fee_rate: whirlpool.protocol_fee_rate,
+    liquidity: whirlpool.liquidity,
+    sqrt_price: whirlpool.sqrt_price,
+    decimal_price: sqrt_price_to_decimal_price(whirlpool.sqrt_price, decimals_a, decimals_b),
+    current_tick_index: whirlpool.tick_current_index,
+    protocol_fee_owed_a: whirlpool.protocol_fee_owed_a,
+    protocol_fee_owed_b: whirlpool.protocol_fee_owed_b,
+    fee_growth_global_a: whirlpool.fee_growth_global_a,
+    fee_growth_global_b: whirlpool.fee_growth_global_b,
+    reward_last_updated_timestamp: whirlpool.reward_last_updated_timestamp,
+    rewards,
+  })
+}
+
+fn whirlpools_config(pubkey: &str, config: WhirlpoolsConfig) -> DecodedWhirlpoolsConfigData {
+  DecodedWhirlpoolsConfigData {
+    account: pubkey.to_string(),
+    fee_authority: config.fee_authority.to_string(),
+    collect_protocol_fees_authority: config.collect_protocol_fees_authority.to_string(),
+    reward_emissions_super_authority: config.reward_emissions_super_authority.to_string(),
+    default_protocol_fee_rate: config.default_protocol_fee_rate,
+    feature_flags: config.feature_flags,
+  }
+}
+
+fn whirlpools_config_extension(pubkey: &str, config_extension: WhirlpoolsConfigExtension) -> DecodedWhirlpoolsConfigExtensionData {
+  DecodedWhirlpoolsConfigExtensionData {
+    account: pubkey.to_string(),
+    whirlpools_config: config_extension.whirlpools_config.to_string(),
+    config_extension_authority: config_extension.config_extension_authority.to_string(),
+    token_badge_authority: config_extension.token_badge_authority.to_string(),
+  }
+}
+
+fn fee_tier(pubkey: &str, fee_tier: FeeTier) -> DecodedFeeTierData {
+  DecodedFeeTierData {
+    account: pubkey.to_string(),
+    whirlpools_config: fee_tier.whirlpools_config.to_string(),
+    tick_spacing: fee_tier.tick_spacing,
+    default_fee_rate: fee_tier.default_fee_rate,
+  }
+}
+
+fn adaptive_fee_tier(pubkey: &str, adaptive_fee_tier: AdaptiveFeeTier) -> DecodedAdaptiveFeeTierData {
+  DecodedAdaptiveFeeTierData {
+    account: pubkey.to_string(),
+    whirlpools_config: adaptive_fee_tier.whirlpools_config.to_string(),
+    fee_tier_index: adaptive_fee_tier.fee_tier_index,
+    tick_spacing: adaptive_fee_tier.tick_spacing,
+    initialize_pool_authority: adaptive_fee_tier.initialize_pool_authority.to_string(),
+    delegated_fee_authority: adaptive_fee_tier.delegated_fee_authority.to_string(),
+    default_base_fee_rate: adaptive_fee_tier.default_base_fee_rate,
+    adaptive_fee_constants: AdaptiveFeeConstants {
+      filter_period: adaptive_fee_tier.filter_period,
+      decay_period: adaptive_fee_tier.decay_period,
+      reduction_factor: adaptive_fee_tier.reduction_factor,
+      adaptive_fee_control_factor: adaptive_fee_tier.adaptive_fee_control_factor,
+      max_volatility_accumulator: adaptive_fee_tier.max_volatility_accumulator,
+      tick_group_size: adaptive_fee_tier.tick_group_size,
+      major_swap_threshold_ticks: adaptive_fee_tier.major_swap_threshold_ticks,
+    },
+  }
+}
+
+fn oracle(pubkey: &str, oracle: Oracle) -> DecodedOracleData {
+  DecodedOracleData {
+    account: pubkey.to_string(),
+    whirlpool: oracle.whirlpool.to_string(),
+    trade_enable_timestamp: oracle.trade_enable_timestamp,
+    adaptive_fee_constants: AdaptiveFeeConstants {
+      filter_period: oracle.adaptive_fee_constants.filter_period,
+      decay_period: oracle.adaptive_fee_constants.decay_period,
+      reduction_factor: oracle.adaptive_fee_constants.reduction_factor,
+      adaptive_fee_control_factor: oracle.adaptive_fee_constants.adaptive_fee_control_factor,
+      max_volatility_accumulator: oracle.adaptive_fee_constants.max_volatility_accumulator,
+      tick_group_size: oracle.adaptive_fee_constants.tick_group_size,
+      major_swap_threshold_ticks: oracle.adaptive_fee_constants.major_swap_threshold_ticks,
+    },
+    adaptive_fee_variables: AdaptiveFeeVariables {
+      last_reference_update_timestamp: oracle.adaptive_fee_variables.last_reference_update_timestamp,
+      last_major_swap_timestamp: oracle.adaptive_fee_variables.last_major_swap_timestamp,
+      volatility_reference: oracle.adaptive_fee_variables.volatility_reference,
+      tick_group_index_reference: oracle.adaptive_fee_variables.tick_group_index_reference,
+      volatility_accumulator: oracle.adaptive_fee_variables.volatility_accumulator,
+    },
+  }
+}
+
+fn token_badge(pubkey: &str, token_badge: TokenBadge) -> DecodedTokenBadgeData {
+  DecodedTokenBadgeData {
+    account: pubkey.to_string(),
+    whirlpools_config: token_badge.whirlpools_config.to_string(),
+    token_mint: token_badge.token_mint.to_string(),
+    attribute_require_non_transferable_position: token_badge.attribute_require_non_transferable_position,
+  }
+}
+
+// lock types added to the program later are reported as errors until PositionLockType supports them
+fn lock_config(pubkey: &str, lock_config: LockConfig) -> Result<DecodedLockConfigData> {
+  Ok(DecodedLockConfigData {
+    account: pubkey.to_string(),
+    position: lock_config.position.to_string(),
+    position_owner: lock_config.position_owner.to_string(),
+    whirlpool: lock_config.whirlpool.to_string(),
+    locked_timestamp: lock_config.locked_timestamp,
+    lock_type: match lock_config.lock_type {
+      LockTypeLabel::Permanent => PositionLockType::Permanent,
+      #[allow(unreachable_patterns)]
+      _ => bail!("unsupported lock type of LockConfig {}", pubkey),
+    },
+  })
+}
+
+// emissions per second (X64 fixed point) to the UI amount emitted per day
+static X64: std::sync::OnceLock<BigDecimal> = std::sync::OnceLock::new();
+fn emissions_per_day(emissions_per_second_x64: u128, decimals: u8) -> DecimalPrice {
+  let x64 = X64.get_or_init(|| BigDecimal::from(1u128 << 64));
+  let per_day = BigDecimal::from(emissions_per_second_x64) * BigDecimal::from(86400u32) / x64;
+  let (i, scale) = per_day.as_bigint_and_exponent();
+  BigDecimal::new(i, scale + decimals as i64)
+}
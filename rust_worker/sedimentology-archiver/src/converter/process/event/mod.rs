@@ -1,7 +1,8 @@
 use super::super::model::event::{
   convert::build_whirlpool_events, definition::ProgramDeployedEventPayload, WhirlpoolEvent,
-  WhirlpoolEventBlock, WhirlpoolEventTransaction,
+  WhirlpoolEventBlock, WhirlpoolEventTransaction, WHIRLPOOL_EVENT_FORMAT_VERSION,
 };
+use super::super::model::long_key::{self, KeyFormat};
 use anyhow::Result;
 use flate2::write::GzEncoder;
 use replay_engine::decoded_instructions;
@@ -15,6 +16,7 @@ pub fn process(
   in_whirlpool_token_file_path: String,
   in_whirlpool_transaction_file_path: String,
   out_whirlpool_event_file_path: String,
+  key_format: KeyFormat,
 ) -> Result<()> {
   let f = File::create(out_whirlpool_event_file_path).unwrap();
   let encoder = GzEncoder::new(f, flate2::Compression::default());
@@ -85,13 +87,14 @@ pub fn process(
       }
 
       let event_block = WhirlpoolEventBlock {
+          format_version: WHIRLPOOL_EVENT_FORMAT_VERSION,
           slot: whirlpool_transaction.slot,
           block_height: whirlpool_transaction.block_height,
           block_time: whirlpool_transaction.block_time,
           transactions: event_block_transactions,
       };
 
-      let jsonl = serde_json::to_string(&event_block).unwrap();
+      let jsonl = long_key::to_string(&event_block, key_format).unwrap();
       writer.write_all(jsonl.as_bytes()).unwrap();
       writer.write_all(b"\n").unwrap();
 
@@ -0,0 +1,35 @@
+use super::super::model::event::{group::WhirlpoolEventGroup, reader::WhirlpoolEventReader};
+use super::super::model::long_key::{self, KeyFormat};
+use anyhow::Result;
+use flate2::write::GzEncoder;
+use std::{
+  fs::File,
+  io::{LineWriter, Write},
+};
+
+// build group-filtered event file (e.g. whirlpool-event-trade-yyyymmdd.jsonl.gz) from event file
+pub fn process(
+  in_whirlpool_event_file_path: String,
+  group: WhirlpoolEventGroup,
+  out_whirlpool_event_group_file_path: String,
+  key_format: KeyFormat,
+) -> Result<()> {
+  // event files of any format version
+  let reader = WhirlpoolEventReader::open(&in_whirlpool_event_file_path)?;
+
+  let f = File::create(out_whirlpool_event_group_file_path)?;
+  let encoder = GzEncoder::new(f, flate2::Compression::default());
+  let mut writer = LineWriter::new(encoder);
+
+  for event_block in reader {
+      if let Some(event_block) = group.filter(event_block?) {
+          let jsonl = long_key::to_string(&event_block, key_format)?;
+          writer.write_all(jsonl.as_bytes())?;
+          writer.write_all(b"\n")?;
+      }
+  }
+
+  writer.flush()?;
+
+  Ok(())
+}
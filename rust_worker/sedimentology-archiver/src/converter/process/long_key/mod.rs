@@ -0,0 +1,156 @@
+use super::super::model::event::reader::WhirlpoolEventReader;
+use super::super::model::long_key::{self, KeyFormat};
+use super::super::model::ohlcv::{WhirlpoolOhlcvAdaptiveFeeData, WhirlpoolOhlcvDailyData, WhirlpoolOhlcvIntervalData, WhirlpoolOhlcvMinutelyData, WhirlpoolOhlcvPairData, WhirlpoolOhlcvRollupData, WhirlpoolOhlcvStatsData, WhirlpoolOhlcvTokenData};
+use super::super::model::position::{WhirlpoolPositionData, WhirlpoolPositionLedgerData, WhirlpoolPositionRangeData, WhirlpoolPositionValuationData};
+use anyhow::{Context, Result};
+use flate2::{read::GzDecoder, write::GzEncoder};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+  fs::File,
+  io::{BufRead, BufReader, LineWriter, Write},
+};
+
+// rewrite short-key event file (or group-filtered event file) with long keys
+pub fn process_event(
+  in_whirlpool_event_file_path: String,
+  out_whirlpool_event_file_path: String,
+) -> Result<()> {
+  // event files of any format version
+  let reader = WhirlpoolEventReader::open(&in_whirlpool_event_file_path)?;
+  write_long_key_file(reader, out_whirlpool_event_file_path)
+}
+
+// rewrite short-key ohlcv daily file with long keys
+pub fn process_ohlcv_daily(
+  in_whirlpool_ohlcv_daily_file_path: String,
+  out_whirlpool_ohlcv_daily_file_path: String,
+) -> Result<()> {
+  let reader = read_short_key_file::<WhirlpoolOhlcvDailyData>(&in_whirlpool_ohlcv_daily_file_path)?;
+  write_long_key_file(reader, out_whirlpool_ohlcv_daily_file_path)
+}
+
+// rewrite short-key ohlcv minutely file with long keys
+pub fn process_ohlcv_minutely(
+  in_whirlpool_ohlcv_minutely_file_path: String,
+  out_whirlpool_ohlcv_minutely_file_path: String,
+) -> Result<()> {
+  let reader = read_short_key_file::<WhirlpoolOhlcvMinutelyData>(&in_whirlpool_ohlcv_minutely_file_path)?;
+  write_long_key_file(reader, out_whirlpool_ohlcv_minutely_file_path)
+}
+
+// rewrite short-key ohlcv pair file with long keys
+pub fn process_ohlcv_pair(
+  in_whirlpool_ohlcv_pair_file_path: String,
+  out_whirlpool_ohlcv_pair_file_path: String,
+) -> Result<()> {
+  let reader = read_short_key_file::<WhirlpoolOhlcvPairData>(&in_whirlpool_ohlcv_pair_file_path)?;
+  write_long_key_file(reader, out_whirlpool_ohlcv_pair_file_path)
+}
+
+// rewrite short-key ohlcv token file with long keys
+pub fn process_ohlcv_token(
+  in_whirlpool_ohlcv_token_file_path: String,
+  out_whirlpool_ohlcv_token_file_path: String,
+) -> Result<()> {
+  let reader = read_short_key_file::<WhirlpoolOhlcvTokenData>(&in_whirlpool_ohlcv_token_file_path)?;
+  write_long_key_file(reader, out_whirlpool_ohlcv_token_file_path)
+}
+
+// rewrite short-key ohlcv stats file with long keys
+pub fn process_ohlcv_stats(
+  in_whirlpool_ohlcv_stats_file_path: String,
+  out_whirlpool_ohlcv_stats_file_path: String,
+) -> Result<()> {
+  let reader = read_short_key_file::<WhirlpoolOhlcvStatsData>(&in_whirlpool_ohlcv_stats_file_path)?;
+  write_long_key_file(reader, out_whirlpool_ohlcv_stats_file_path)
+}
+
+// rewrite short-key ohlcv adaptive fee file with long keys
+pub fn process_ohlcv_adaptive_fee(
+  in_whirlpool_ohlcv_adaptive_fee_file_path: String,
+  out_whirlpool_ohlcv_adaptive_fee_file_path: String,
+) -> Result<()> {
+  let reader = read_short_key_file::<WhirlpoolOhlcvAdaptiveFeeData>(&in_whirlpool_ohlcv_adaptive_fee_file_path)?;
+  write_long_key_file(reader, out_whirlpool_ohlcv_adaptive_fee_file_path)
+}
+
+// rewrite short-key ohlcv interval file (e.g. ohlcv-5m) with long keys
+pub fn process_ohlcv_interval(
+  in_whirlpool_ohlcv_interval_file_path: String,
+  out_whirlpool_ohlcv_interval_file_path: String,
+) -> Result<()> {
+  let reader = read_short_key_file::<WhirlpoolOhlcvIntervalData>(&in_whirlpool_ohlcv_interval_file_path)?;
+  write_long_key_file(reader, out_whirlpool_ohlcv_interval_file_path)
+}
+
+// rewrite short-key ohlcv rollup file (ohlcv-weekly, ohlcv-monthly) with long keys
+pub fn process_ohlcv_rollup(
+  in_whirlpool_ohlcv_rollup_file_path: String,
+  out_whirlpool_ohlcv_rollup_file_path: String,
+) -> Result<()> {
+  let reader = read_short_key_file::<WhirlpoolOhlcvRollupData>(&in_whirlpool_ohlcv_rollup_file_path)?;
+  write_long_key_file(reader, out_whirlpool_ohlcv_rollup_file_path)
+}
+
+// rewrite short-key position file with long keys
+pub fn process_position(
+  in_whirlpool_position_file_path: String,
+  out_whirlpool_position_file_path: String,
+) -> Result<()> {
+  let reader = read_short_key_file::<WhirlpoolPositionData>(&in_whirlpool_position_file_path)?;
+  write_long_key_file(reader, out_whirlpool_position_file_path)
+}
+
+// rewrite short-key position ledger file with long keys
+pub fn process_position_ledger(
+  in_whirlpool_position_ledger_file_path: String,
+  out_whirlpool_position_ledger_file_path: String,
+) -> Result<()> {
+  let reader = read_short_key_file::<WhirlpoolPositionLedgerData>(&in_whirlpool_position_ledger_file_path)?;
+  write_long_key_file(reader, out_whirlpool_position_ledger_file_path)
+}
+
+// rewrite short-key position valuation file with long keys
+pub fn process_position_valuation(
+  in_whirlpool_position_valuation_file_path: String,
+  out_whirlpool_position_valuation_file_path: String,
+) -> Result<()> {
+  let reader = read_short_key_file::<WhirlpoolPositionValuationData>(&in_whirlpool_position_valuation_file_path)?;
+  write_long_key_file(reader, out_whirlpool_position_valuation_file_path)
+}
+
+// rewrite short-key position range file with long keys
+pub fn process_position_range(
+  in_whirlpool_position_range_file_path: String,
+  out_whirlpool_position_range_file_path: String,
+) -> Result<()> {
+  let reader = read_short_key_file::<WhirlpoolPositionRangeData>(&in_whirlpool_position_range_file_path)?;
+  write_long_key_file(reader, out_whirlpool_position_range_file_path)
+}
+
+pub(crate) fn read_short_key_file<T: DeserializeOwned>(path: &str) -> Result<impl Iterator<Item = Result<T>>> {
+  let file = File::open(path).with_context(|| format!("failed to open {}", path))?;
+  let lines = BufReader::new(GzDecoder::new(file)).lines();
+
+  Ok(lines.enumerate().filter_map(|(index, line)| match line {
+    Ok(line) if line.trim().is_empty() => None,
+    Ok(line) => Some(serde_json::from_str(&line).with_context(|| format!("line {}", index + 1))),
+    Err(err) => Some(Err(err.into())),
+  }))
+}
+
+fn write_long_key_file<T: Serialize>(reader: impl Iterator<Item = Result<T>>, path: String) -> Result<()> {
+  let f = File::create(path)?;
+  let encoder = GzEncoder::new(f, flate2::Compression::default());
+  let mut writer = LineWriter::new(encoder);
+
+  for data in reader {
+      let jsonl = long_key::to_string(&data?, KeyFormat::Long)?;
+      writer.write_all(jsonl.as_bytes())?;
+      writer.write_all(b"\n")?;
+  }
+
+  writer.flush()?;
+
+  Ok(())
+}
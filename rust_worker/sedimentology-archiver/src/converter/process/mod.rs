@@ -1,2 +1,7 @@
+pub mod account;
 pub mod event;
+pub mod event_group;
+pub mod long_key;
 pub mod ohlcv;
+pub mod parquet;
+pub mod position;
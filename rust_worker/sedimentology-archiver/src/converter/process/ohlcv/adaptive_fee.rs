@@ -0,0 +1,122 @@
+use super::super::super::model::{event::definition::{AdaptiveFeeConstants, AdaptiveFeeVariables}, ohlcv};
+use super::data::{OhlcvData, MINUTELY_SECONDS};
+use std::collections::HashMap;
+
+// oracle activity of an adaptive fee pool in the day
+#[derive(Debug)]
+pub struct AdaptiveFeeActivity {
+  // constants at the end of the day (None until the oracle or the initialization of the pool is seen)
+  constants: Option<AdaptiveFeeConstants>,
+  constants_update_count: u64,
+  daily: AdaptiveFeeDataUnit,
+  minutely: HashMap<i64, AdaptiveFeeDataUnit>,
+}
+
+impl AdaptiveFeeActivity {
+  pub fn new(timestamp: i64, constants: Option<AdaptiveFeeConstants>) -> Self {
+    Self {
+      constants,
+      constants_update_count: 0,
+      daily: AdaptiveFeeDataUnit::new(timestamp),
+      minutely: HashMap::new(),
+    }
+  }
+
+  pub fn update_constants(&mut self, constants: AdaptiveFeeConstants) {
+    self.constants = Some(constants);
+    self.constants_update_count += 1;
+  }
+
+  // fee rate is the effective one of the trade (in hundredths of a basis point)
+  pub fn update(&mut self, block_time: i64, old_variables: &AdaptiveFeeVariables, new_variables: &AdaptiveFeeVariables, fee_rate: u32) {
+    self.daily.update(old_variables, new_variables, fee_rate);
+
+    let minutely_timestamp = block_time / MINUTELY_SECONDS * MINUTELY_SECONDS;
+    self.minutely
+      .entry(minutely_timestamp)
+      .or_insert_with(|| AdaptiveFeeDataUnit::new(minutely_timestamp))
+      .update(old_variables, new_variables, fee_rate);
+  }
+
+  pub fn to_adaptive_fee_data(&self, data: &OhlcvData) -> ohlcv::WhirlpoolOhlcvAdaptiveFeeData {
+    let mut minutely = self.minutely.values().map(AdaptiveFeeDataUnit::to_adaptive_fee_data_unit).collect::<Vec<_>>();
+    minutely.sort_by_key(|unit| unit.timestamp);
+
+    ohlcv::WhirlpoolOhlcvAdaptiveFeeData {
+      metadata: ohlcv::WhirlpoolOhlcvMetadata::from(data),
+      adaptive_fee_constants: self.constants.clone(),
+      constants_update_count: self.constants_update_count,
+      daily: self.daily.to_adaptive_fee_data_unit(),
+      minutely,
+    }
+  }
+}
+
+#[derive(Debug)]
+struct AdaptiveFeeDataUnit {
+  timestamp: i64,
+  trade_count: u64,
+  // open, high, low and close of volatility accumulator (None if no trades)
+  volatility_accumulator: Option<(u32, u32, u32, u32)>,
+  major_swap_count: u64,
+  reference_reset_count: u64,
+  fee_rates: Vec<u32>,
+}
+
+impl AdaptiveFeeDataUnit {
+  fn new(timestamp: i64) -> Self {
+    Self {
+      timestamp,
+      trade_count: 0,
+      volatility_accumulator: None,
+      major_swap_count: 0,
+      reference_reset_count: 0,
+      fee_rates: vec![],
+    }
+  }
+
+  fn update(&mut self, old_variables: &AdaptiveFeeVariables, new_variables: &AdaptiveFeeVariables, fee_rate: u32) {
+    let old = old_variables.volatility_accumulator;
+    let new = new_variables.volatility_accumulator;
+    self.volatility_accumulator = Some(match self.volatility_accumulator {
+      None => (old, old.max(new), old.min(new), new),
+      Some((open, high, low, _)) => (open, high.max(old).max(new), low.min(old).min(new), new),
+    });
+
+    self.trade_count += 1;
+    if new_variables.last_major_swap_timestamp != old_variables.last_major_swap_timestamp {
+      self.major_swap_count += 1;
+    }
+    if new_variables.last_reference_update_timestamp != old_variables.last_reference_update_timestamp {
+      self.reference_reset_count += 1;
+    }
+    self.fee_rates.push(fee_rate);
+  }
+
+  fn to_adaptive_fee_data_unit(&self) -> ohlcv::AdaptiveFeeDataUnit {
+    let mut fee_rates = self.fee_rates.clone();
+    fee_rates.sort();
+
+    ohlcv::AdaptiveFeeDataUnit {
+      timestamp: self.timestamp,
+      trade_count: self.trade_count,
+      volatility_accumulator: self.volatility_accumulator.map(|(open, high, low, close)| ohlcv::VolatilityAccumulatorData { open, high, low, close }),
+      major_swap_count: self.major_swap_count,
+      reference_reset_count: self.reference_reset_count,
+      fee_rate: (!fee_rates.is_empty()).then(|| ohlcv::FeeRateDistributionData {
+        min: fee_rates[0],
+        percentile_25: percentile(&fee_rates, 25),
+        median: percentile(&fee_rates, 50),
+        percentile_75: percentile(&fee_rates, 75),
+        percentile_90: percentile(&fee_rates, 90),
+        max: fee_rates[fee_rates.len() - 1],
+      }),
+    }
+  }
+}
+
+// nearest-rank percentile of the sorted values (must not be empty)
+fn percentile(sorted: &[u32], p: usize) -> u32 {
+  let rank = (sorted.len() * p).div_ceil(100).max(1);
+  sorted[rank - 1]
+}
@@ -0,0 +1,116 @@
+use super::super::super::model::ohlcv;
+use super::data::{self, OhlcvData, OhlcvDataManager};
+use std::collections::BTreeMap;
+
+/*
+
+Daily aggregates across pools
+
+- pair: pools of the same (mint A, mint B) pair are merged (tick spacing and config are ignored)
+- token: pools with the mint as token A or token B are merged
+
+Both are built from the daily candle of each pool, so they are consistent with the daily file.
+
+*/
+
+// sorted by (mint A, mint B)
+pub fn pairs(manager: &OhlcvDataManager) -> Vec<ohlcv::WhirlpoolOhlcvPairData> {
+  let mut pairs: BTreeMap<(&str, &str), Vec<&OhlcvData>> = BTreeMap::new();
+  for data in manager.data.values() {
+    pairs.entry((&data.metadata.mint_a, &data.metadata.mint_b)).or_default().push(data);
+  }
+
+  pairs.into_values().map(|mut pools| {
+    pools.sort_by(|a, b| a.metadata.whirlpool.cmp(&b.metadata.whirlpool));
+    let metadata = &pools[0].metadata;
+
+    let mut volume_a_to_b = data::VolumeData::default();
+    let mut volume_b_to_a = data::VolumeData::default();
+    let mut fees = ohlcv::EstimatedFees {
+      liquidity_provider_fee_a: 0,
+      liquidity_provider_fee_b: 0,
+      protocol_fee_a: 0,
+      protocol_fee_b: 0,
+    };
+    for pool in pools.iter() {
+      add_volume(&mut volume_a_to_b, &pool.daily.volume_a_to_b);
+      add_volume(&mut volume_b_to_a, &pool.daily.volume_b_to_a);
+      fees.liquidity_provider_fee_a += pool.daily.fees.liquidity_provider_fee_a;
+      fees.liquidity_provider_fee_b += pool.daily.fees.liquidity_provider_fee_b;
+      fees.protocol_fee_a += pool.daily.fees.protocol_fee_a;
+      fees.protocol_fee_b += pool.daily.fees.protocol_fee_b;
+    }
+
+    // decimals are the same for all pools of the pair, so the amounts can be summed up
+    let total_a = volume_a_to_b.total_in + volume_b_to_a.total_out;
+    let total_b = volume_a_to_b.total_out + volume_b_to_a.total_in;
+    let vwap = data::vwap(total_a, total_b).map(|vwap| data::average_price_data(vwap, metadata.decimals_a, metadata.decimals_b));
+
+    ohlcv::WhirlpoolOhlcvPairData {
+      token_a: ohlcv::TokenData {
+        mint: metadata.mint_a.clone(),
+        decimals: metadata.decimals_a,
+      },
+      token_b: ohlcv::TokenData {
+        mint: metadata.mint_b.clone(),
+        decimals: metadata.decimals_b,
+      },
+      timestamp: manager.timestamp,
+      whirlpools: pools.iter().map(|pool| pool.metadata.whirlpool.clone()).collect(),
+      volume: ohlcv::VolumeData {
+        ab: volume_direction_data(&volume_a_to_b),
+        ba: volume_direction_data(&volume_b_to_a),
+      },
+      vwap,
+      fees,
+    }
+  }).collect()
+}
+
+// sorted by mint
+pub fn tokens(manager: &OhlcvDataManager) -> Vec<ohlcv::WhirlpoolOhlcvTokenData> {
+  let mut tokens: BTreeMap<&str, ohlcv::WhirlpoolOhlcvTokenData> = BTreeMap::new();
+  for data in manager.data.values() {
+    let daily = &data.daily;
+    let sides = [
+      // (mint, decimals, trades paying the mint in, trades paying the mint out, LP fee, protocol fee)
+      (&data.metadata.mint_a, data.metadata.decimals_a, &daily.volume_a_to_b, &daily.volume_b_to_a, daily.fees.liquidity_provider_fee_a, daily.fees.protocol_fee_a),
+      (&data.metadata.mint_b, data.metadata.decimals_b, &daily.volume_b_to_a, &daily.volume_a_to_b, daily.fees.liquidity_provider_fee_b, daily.fees.protocol_fee_b),
+    ];
+    for (mint, decimals, volume_in, volume_out, liquidity_provider_fee, protocol_fee) in sides {
+      let token = tokens.entry(mint).or_insert_with(|| ohlcv::WhirlpoolOhlcvTokenData {
+        mint: mint.clone(),
+        decimals,
+        timestamp: manager.timestamp,
+        pool_count: 0,
+        trade_count: 0,
+        volume_in: 0,
+        volume_out: 0,
+        liquidity_provider_fee: 0,
+        protocol_fee: 0,
+      });
+      token.pool_count += 1;
+      token.trade_count += volume_in.count + volume_out.count;
+      token.volume_in += volume_in.total_in;
+      token.volume_out += volume_out.total_out;
+      token.liquidity_provider_fee += liquidity_provider_fee as u128;
+      token.protocol_fee += protocol_fee as u128;
+    }
+  }
+
+  tokens.into_values().collect()
+}
+
+fn add_volume(total: &mut data::VolumeData, volume: &data::VolumeData) {
+  total.total_in += volume.total_in;
+  total.total_out += volume.total_out;
+  total.count += volume.count;
+}
+
+fn volume_direction_data(volume: &data::VolumeData) -> ohlcv::VolumeDirectionData {
+  ohlcv::VolumeDirectionData {
+    total_in: volume.total_in,
+    total_out: volume.total_out,
+    count: volume.count,
+  }
+}
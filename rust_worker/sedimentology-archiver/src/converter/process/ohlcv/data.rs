@@ -1,36 +1,73 @@
-use super::super::super::model::{event::definition::{PoolInitializedEventPayload, TradeDirection, TradedEventPayload}, ohlcv};
-use bigdecimal::BigDecimal;
-use std::collections::HashMap;
+use super::super::super::model::{event::definition::{AdaptiveFeeConstants, LiquidityDepositedEventPayload, LiquidityPatchedEventPayload, LiquidityRepositionedEventPayload, LiquidityWithdrawnEventPayload, PoolAdaptiveFeeConstantsUpdatedEventPayload, PoolInitializedEventPayload, TradeDirection, TradedEventPayload}, ohlcv::{self, interval::OhlcvInterval}};
+use super::adaptive_fee::AdaptiveFeeActivity;
+use bigdecimal::{num_bigint::BigUint, BigDecimal, ToPrimitive};
+use std::collections::{HashMap, HashSet};
+
+pub const MINUTELY_SECONDS: i64 = 60;
+const HOURLY_SECONDS: i64 = 60 * 60;
+pub(crate) const DAILY_SECONDS: i64 = 24 * 60 * 60;
 
 #[derive(Debug)]
 pub struct OhlcvDataManager {
   pub timestamp: i64,
+  // candle intervals in seconds (minutely and the configured intervals)
+  interval_seconds: Vec<i64>,
   pub data: HashMap<String, OhlcvData>,
+  // whirlpool -> oracle activity of adaptive fee pools
+  pub adaptive_fee: HashMap<String, AdaptiveFeeActivity>,
 }
 
 impl OhlcvDataManager {
-  pub fn new(timestamp: i64) -> Self {
-    Self { timestamp, data: HashMap::new() }
+  pub fn new(timestamp: i64, intervals: &[OhlcvInterval]) -> Self {
+    let mut interval_seconds = vec![MINUTELY_SECONDS];
+    interval_seconds.extend(intervals.iter().map(|interval| interval.seconds() as i64));
+    interval_seconds.sort();
+    interval_seconds.dedup();
+    Self { timestamp, interval_seconds, data: HashMap::new(), adaptive_fee: HashMap::new() }
+  }
+
+  fn empty_candles(&self) -> Vec<(i64, HashMap<i64, SqrtPriceOhlcvDataUnit>)> {
+    self.interval_seconds.iter().map(|seconds| (*seconds, HashMap::new())).collect()
   }
 
-  pub fn initialize_with_previous_close(&mut self, metadata: Metadata, previous_close_sqrt_price: u128) {
+  pub fn initialize_with_previous_close(&mut self, metadata: Metadata, previous_close_sqrt_price: u128, previous_close_liquidity: u128, previous_close_tick_index: i32) {
+    let candles = self.empty_candles();
     self.data.insert(metadata.whirlpool.clone(), OhlcvData {
       metadata,
       initial_state: InitialState::Existing(previous_close_sqrt_price),
-      estimated_fees: EstimatedFees::default(),
-      daily: SqrtPriceOhlcvDataUnit {
-        timestamp: self.timestamp,
-        open: previous_close_sqrt_price,
-        high: previous_close_sqrt_price,
-        low: previous_close_sqrt_price,
-        close: previous_close_sqrt_price,
-        volume_a_to_b: VolumeData::default(),
-        volume_b_to_a: VolumeData::default(),
-      },
-      minutely: HashMap::new(),
+      initial_liquidity: previous_close_liquidity,
+      liquidity_changes: vec![],
+      initial_tick_index: previous_close_tick_index,
+      tick_changes: vec![],
+      liquidity: LiquidityActivity::new(previous_close_liquidity),
+      trader_volumes: HashMap::new(),
+      trade_stats: TradeStats::new(),
+      daily: SqrtPriceOhlcvDataUnit::new(self.timestamp, DAILY_SECONDS, self.timestamp, previous_close_sqrt_price),
+      candles,
     });
   }
 
+  // oracle of an existing adaptive fee pool
+  pub fn initialize_adaptive_fee(&mut self, whirlpool: String, constants: AdaptiveFeeConstants) {
+    self.adaptive_fee.insert(whirlpool, AdaptiveFeeActivity::new(self.timestamp, Some(constants)));
+  }
+
+  pub fn process_pool_adaptive_fee_constants_updated_event(&mut self, updated: &PoolAdaptiveFeeConstantsUpdatedEventPayload) {
+    let timestamp = self.timestamp;
+    self.adaptive_fee
+      .entry(updated.whirlpool.clone())
+      .or_insert_with(|| AdaptiveFeeActivity::new(timestamp, None))
+      .update_constants(AdaptiveFeeConstants {
+        filter_period: updated.new_filter_period,
+        decay_period: updated.new_decay_period,
+        reduction_factor: updated.new_reduction_factor,
+        adaptive_fee_control_factor: updated.new_adaptive_fee_control_factor,
+        max_volatility_accumulator: updated.new_max_volatility_accumulator,
+        tick_group_size: updated.new_tick_group_size,
+        major_swap_threshold_ticks: updated.new_major_swap_threshold_ticks,
+      });
+  }
+
   pub fn process_pool_initialized_event(&mut self, slot: u64, block_time: i64, pool_initialized: &PoolInitializedEventPayload) {
     let metadata = Metadata {
       whirlpool: pool_initialized.whirlpool.clone(),
@@ -43,95 +80,279 @@ impl OhlcvDataManager {
     };
     let initial_sqrt_price = pool_initialized.sqrt_price;
 
+    let candles = self.empty_candles();
     self.data.insert(metadata.whirlpool.clone(), OhlcvData {
       metadata,
       initial_state: InitialState::New(initial_sqrt_price, slot, block_time),
-      estimated_fees: EstimatedFees::default(),
-      daily: SqrtPriceOhlcvDataUnit {
-        timestamp: self.timestamp,
-        open: initial_sqrt_price,
-        high: initial_sqrt_price,
-        low: initial_sqrt_price,
-        close: initial_sqrt_price,
-        volume_a_to_b: VolumeData::default(),
-        volume_b_to_a: VolumeData::default(),
-      },
-      minutely: HashMap::new(),
+      initial_liquidity: 0,
+      liquidity_changes: vec![],
+      initial_tick_index: pool_initialized.current_tick_index,
+      tick_changes: vec![],
+      liquidity: LiquidityActivity::new(0),
+      trader_volumes: HashMap::new(),
+      trade_stats: TradeStats::new(),
+      // price exists since the pool was initialized
+      daily: SqrtPriceOhlcvDataUnit::new(self.timestamp, DAILY_SECONDS, block_time, initial_sqrt_price),
+      candles,
     });
+
+    if let Some(constants) = pool_initialized.adaptive_fee_constants.as_ref() {
+      self.adaptive_fee.insert(pool_initialized.whirlpool.clone(), AdaptiveFeeActivity::new(self.timestamp, Some(constants.clone())));
+    }
   }
 
-  pub fn process_traded_event(&mut self, block_time: i64, traded: &TradedEventPayload) {
-    let whirlpool = self.data.get_mut(&traded.whirlpool).unwrap();
+  pub fn process_liquidity_deposited_event(&mut self, block_time: i64, deposited: &LiquidityDepositedEventPayload) {
+    let whirlpool = self.change_liquidity(block_time, &deposited.whirlpool, deposited.old_whirlpool_liquidity, deposited.new_whirlpool_liquidity);
+    let liquidity = &mut whirlpool.liquidity;
+    liquidity.deposit_count += 1;
+    liquidity.added_a += deposited.transfer_a.amount as u128;
+    liquidity.added_b += deposited.transfer_b.amount as u128;
+    liquidity.positions.insert(deposited.position.clone());
+  }
 
-    // updating estimated_fees
-    let post_transfer_fee = calculate_post_transfer_fee(traded.transfer_in.amount, traded.transfer_in.transfer_fee_bps, traded.transfer_in.transfer_fee_max);
-    let trade_fee = calculate_trade_fee(post_transfer_fee, traded.fee_rate);
-    let (liquidity_provider_fee, protocol_fee) = split_fee(trade_fee, traded.protocol_fee_rate);
-    match traded.trade_direction {
-      TradeDirection::AtoB => {
-        whirlpool.estimated_fees.liquidity_provider_fee_a += liquidity_provider_fee;
-        whirlpool.estimated_fees.protocol_fee_a += protocol_fee;
-      }
-      TradeDirection::BtoA => {
-        whirlpool.estimated_fees.liquidity_provider_fee_b += liquidity_provider_fee;
-        whirlpool.estimated_fees.protocol_fee_b += protocol_fee;
-      }
+  pub fn process_liquidity_withdrawn_event(&mut self, block_time: i64, withdrawn: &LiquidityWithdrawnEventPayload) {
+    let whirlpool = self.change_liquidity(block_time, &withdrawn.whirlpool, withdrawn.old_whirlpool_liquidity, withdrawn.new_whirlpool_liquidity);
+    let liquidity = &mut whirlpool.liquidity;
+    liquidity.withdrawal_count += 1;
+    liquidity.removed_a += withdrawn.transfer_a.amount as u128;
+    liquidity.removed_b += withdrawn.transfer_b.amount as u128;
+    liquidity.positions.insert(withdrawn.position.clone());
+  }
+
+  pub fn process_liquidity_repositioned_event(&mut self, block_time: i64, repositioned: &LiquidityRepositionedEventPayload) {
+    let whirlpool = self.change_liquidity(block_time, &repositioned.whirlpool, repositioned.old_whirlpool_liquidity, repositioned.new_whirlpool_liquidity);
+    let liquidity = &mut whirlpool.liquidity;
+    liquidity.reposition_count += 1;
+    match repositioned.transfer_from_owner_a {
+      true => liquidity.added_a += repositioned.transfer_a.amount as u128,
+      false => liquidity.removed_a += repositioned.transfer_a.amount as u128,
     }
+    match repositioned.transfer_from_owner_b {
+      true => liquidity.added_b += repositioned.transfer_b.amount as u128,
+      false => liquidity.removed_b += repositioned.transfer_b.amount as u128,
+    }
+    liquidity.positions.insert(repositioned.position.clone());
+  }
+
+  pub fn process_liquidity_patched_event(&mut self, block_time: i64, patched: &LiquidityPatchedEventPayload) {
+    self.change_liquidity(block_time, &patched.whirlpool, patched.old_whirlpool_liquidity, patched.new_whirlpool_liquidity);
+  }
+
+  fn change_liquidity(&mut self, block_time: i64, whirlpool: &String, old_whirlpool_liquidity: u128, new_whirlpool_liquidity: u128) -> &mut OhlcvData {
+    let whirlpool = self.data.get_mut(whirlpool).unwrap();
+    whirlpool.liquidity_changes.push((block_time, new_whirlpool_liquidity));
+    whirlpool.liquidity.update(old_whirlpool_liquidity, new_whirlpool_liquidity);
+    whirlpool
+  }
+
+  // payer is the fee payer of the transaction
+  pub fn process_traded_event(&mut self, block_time: i64, payer: &str, traded: &TradedEventPayload) {
+    let whirlpool = self.data.get_mut(&traded.whirlpool).unwrap();
+
+    let trade_fee = TradeFee::from(traded);
+
+    // updating volume of the trader
+    let (amount_a, amount_b) = match traded.trade_direction {
+      TradeDirection::AtoB => (traded.transfer_in.amount, traded.transfer_out.amount),
+      TradeDirection::BtoA => (traded.transfer_out.amount, traded.transfer_in.amount),
+    };
+    let trader_volume = whirlpool.trader_volumes.entry(traded.token_authority.clone()).or_default();
+    trader_volume.trade_count += 1;
+    trader_volume.volume_a += amount_a as u128;
+    trader_volume.volume_b += amount_b as u128;
 
     // updating daily
-    whirlpool.daily.high = whirlpool.daily.high.max(traded.new_sqrt_price);
-    whirlpool.daily.low = whirlpool.daily.low.min(traded.new_sqrt_price);
-    whirlpool.daily.close = traded.new_sqrt_price;
-    match traded.trade_direction {
-      TradeDirection::AtoB => {
-        whirlpool.daily.volume_a_to_b.total_in += traded.transfer_in.amount as u128;
-        whirlpool.daily.volume_a_to_b.total_out += traded.transfer_out.amount as u128;
-        whirlpool.daily.volume_a_to_b.count += 1;
-      }
-      TradeDirection::BtoA => {
-        whirlpool.daily.volume_b_to_a.total_in += traded.transfer_in.amount as u128;
-        whirlpool.daily.volume_b_to_a.total_out += traded.transfer_out.amount as u128;
-        whirlpool.daily.volume_b_to_a.count += 1;
+    whirlpool.daily.update(block_time, payer, traded, &trade_fee);
+    whirlpool.tick_changes.push((block_time, traded.new_current_tick_index));
+    whirlpool.trade_stats.update(traded);
+
+    // active liquidity changes if the trade crossed initialized ticks (recorded since event format version 4)
+    if let (Some(old_liquidity), Some(new_liquidity)) = (traded.old_liquidity, traded.new_liquidity) {
+      if old_liquidity != new_liquidity {
+        whirlpool.liquidity_changes.push((block_time, new_liquidity));
       }
+      whirlpool.liquidity.update(old_liquidity, new_liquidity);
     }
 
-    // updating minutely
-    let minutely_timestamp = block_time / 60 * 60;
-    let minutely_data = whirlpool.minutely.entry(minutely_timestamp).or_insert(SqrtPriceOhlcvDataUnit {
-      timestamp: minutely_timestamp,
-      open: traded.old_sqrt_price,
-      high: traded.old_sqrt_price,
-      low: traded.old_sqrt_price,
-      close: traded.old_sqrt_price,
-      volume_a_to_b: VolumeData::default(),
-      volume_b_to_a: VolumeData::default(),
-    });
+    // updating candles of each interval (minutely, 5m, ...)
+    let initialized_block_time = match whirlpool.initial_state {
+      InitialState::Existing(_) => i64::MIN,
+      InitialState::New(_, _, block_time) => block_time,
+    };
+    for (seconds, candles) in whirlpool.candles.iter_mut() {
+      let candle_timestamp = block_time / *seconds * *seconds;
+      let priced_from = candle_timestamp.max(initialized_block_time);
+      candles
+        .entry(candle_timestamp)
+        .or_insert(SqrtPriceOhlcvDataUnit::new(candle_timestamp, *seconds, priced_from, traded.old_sqrt_price))
+        .update(block_time, payer, traded, &trade_fee);
+    }
 
-    minutely_data.high = minutely_data.high.max(traded.new_sqrt_price);
-    minutely_data.low = minutely_data.low.min(traded.new_sqrt_price);
-    minutely_data.close = traded.new_sqrt_price;
-    match traded.trade_direction {
-      TradeDirection::AtoB => {
-        minutely_data.volume_a_to_b.total_in += traded.transfer_in.amount as u128;
-        minutely_data.volume_a_to_b.total_out += traded.transfer_out.amount as u128;
-        minutely_data.volume_a_to_b.count += 1;
-      }
-      TradeDirection::BtoA => {
-        minutely_data.volume_b_to_a.total_in += traded.transfer_in.amount as u128;
-        minutely_data.volume_b_to_a.total_out += traded.transfer_out.amount as u128;
-        minutely_data.volume_b_to_a.count += 1;
-      }
+    // updating oracle activity of adaptive fee pool
+    if let (Some(old_variables), Some(new_variables)) = (traded.old_adaptive_fee_variables.as_ref(), traded.new_adaptive_fee_variables.as_ref()) {
+      let timestamp = self.timestamp;
+      self.adaptive_fee
+        .entry(traded.whirlpool.clone())
+        .or_insert_with(|| AdaptiveFeeActivity::new(timestamp, None))
+        .update(block_time, old_variables, new_variables, trade_fee.fee_rate);
     }
   }
 }
 
 #[derive(Debug)]
 pub struct OhlcvData {
-  metadata: Metadata,
-  initial_state: InitialState,
-  estimated_fees: EstimatedFees,
-  daily: SqrtPriceOhlcvDataUnit,
-  minutely: HashMap<i64, SqrtPriceOhlcvDataUnit>,
+  pub(crate) metadata: Metadata,
+  pub(super) initial_state: InitialState,
+  // active liquidity at the beginning of the day (0 for new pools)
+  pub(super) initial_liquidity: u128,
+  // (block time, active liquidity) for each liquidity event and each trade crossing initialized ticks
+  // (trades in the events before format version 4 have no liquidity, so they are not tracked)
+  pub(super) liquidity_changes: Vec<(i64, u128)>,
+  // current tick index at the beginning of the day (at the initialization for new pools)
+  initial_tick_index: i32,
+  // (block time, current tick index) after each trade
+  tick_changes: Vec<(i64, i32)>,
+  liquidity: LiquidityActivity,
+  // token authority -> volume of the day
+  trader_volumes: HashMap<String, TraderVolume>,
+  trade_stats: TradeStats,
+  pub(super) daily: SqrtPriceOhlcvDataUnit,
+  // (interval in seconds, candles keyed by timestamp) for minutely and the configured intervals
+  candles: Vec<(i64, HashMap<i64, SqrtPriceOhlcvDataUnit>)>,
+}
+
+impl OhlcvData {
+  // (from, current tick index) since the beginning of the day (or the initialization of the pool), each tick stands until the next one
+  pub(crate) fn tick_series(&self) -> Vec<(i64, i32)> {
+    let mut series = vec![(self.daily.priced_from, self.initial_tick_index)];
+    series.extend(self.tick_changes.iter().copied());
+    series
+  }
+
+  pub(crate) fn close_tick_index(&self) -> i32 {
+    self.tick_changes.last().map(|(_, tick_index)| *tick_index).unwrap_or(self.initial_tick_index)
+  }
+
+  pub(crate) fn close_sqrt_price(&self) -> u128 {
+    self.daily.close
+  }
+
+  pub(super) fn minutely(&self) -> &HashMap<i64, SqrtPriceOhlcvDataUnit> {
+    self.candles(MINUTELY_SECONDS)
+  }
+
+  fn candles(&self, seconds: i64) -> &HashMap<i64, SqrtPriceOhlcvDataUnit> {
+    let (_, candles) = self.candles.iter().find(|(interval_seconds, _)| *interval_seconds == seconds).unwrap();
+    candles
+  }
+
+  fn sorted_candles(&self, seconds: i64) -> Vec<ohlcv::WhirlpoolOhlcvDataUnit> {
+    let mut candles = self.candles(seconds).values().map(|data| convert_to_ohlcv_data_unit(data, self.metadata.decimals_a, self.metadata.decimals_b)).collect::<Vec<_>>();
+    candles.sort_by_key(|data| data.timestamp);
+    candles
+  }
+
+  // every minute from the beginning of the day (or the initialization of the pool), empty minutes carry the previous close
+  pub fn to_dense_minutely_data(&self) -> ohlcv::WhirlpoolOhlcvMinutelyData {
+    let mut data = ohlcv::WhirlpoolOhlcvMinutelyData::from(self);
+    if data.minutely.is_empty() {
+      return data;
+    }
+
+    let (start, mut previous_close) = match self.initial_state {
+      InitialState::Existing(previous_close_sqrt_price) => (self.daily.timestamp, previous_close_sqrt_price),
+      InitialState::New(initial_sqrt_price, _, block_time) => (block_time / MINUTELY_SECONDS * MINUTELY_SECONDS, initial_sqrt_price),
+    };
+    let end = self.daily.timestamp + DAILY_SECONDS;
+
+    let mut traded = data.minutely.into_iter().peekable();
+    let mut minutely = vec![];
+    for timestamp in (start..end).step_by(MINUTELY_SECONDS as usize) {
+      match traded.next_if(|unit| unit.timestamp == timestamp) {
+        Some(unit) => {
+          previous_close = unit.ohlc.sqrt_price.close;
+          minutely.push(unit);
+        }
+        None => {
+          let empty = SqrtPriceOhlcvDataUnit::new(timestamp, MINUTELY_SECONDS, timestamp, previous_close);
+          let mut unit = convert_to_ohlcv_data_unit(&empty, self.metadata.decimals_a, self.metadata.decimals_b);
+          unit.synthetic = true;
+          minutely.push(unit);
+        }
+      }
+    }
+    data.minutely = minutely;
+    data
+  }
+
+  // token authorities with the largest volume of token A (then token B)
+  pub fn top_traders(&self, n: usize) -> Vec<ohlcv::TopTraderData> {
+    let mut traders = self.trader_volumes.iter().collect::<Vec<_>>();
+    traders.sort_by(|(authority_a, a), (authority_b, b)| {
+      b.volume_a.cmp(&a.volume_a)
+        .then(b.volume_b.cmp(&a.volume_b))
+        .then(authority_a.cmp(authority_b))
+    });
+    traders.into_iter().take(n).map(|(token_authority, volume)| ohlcv::TopTraderData {
+      token_authority: token_authority.clone(),
+      trade_count: volume.trade_count,
+      volume_a: volume.volume_a,
+      volume_b: volume.volume_b,
+    }).collect()
+  }
+
+  pub fn to_stats_data(&self) -> ohlcv::WhirlpoolOhlcvStatsData {
+    let (realized_volatility, return_count) = self.realized_volatility();
+    let stats = &self.trade_stats;
+    ohlcv::WhirlpoolOhlcvStatsData {
+      metadata: ohlcv::WhirlpoolOhlcvMetadata::from(self),
+      stats: ohlcv::StatsData {
+        timestamp: self.daily.timestamp,
+        realized_volatility: super::f64_decimal(realized_volatility),
+        return_count,
+        trade_count: stats.trade_count,
+        price_impact: (stats.trade_count > 0).then(|| Some(ohlcv::PriceImpactData {
+          average: super::f64_decimal(stats.price_impact_sum / stats.trade_count as f64)?,
+          max: super::f64_decimal(stats.price_impact_max)?,
+        })).flatten(),
+        initialized_tick_crossing_trades: stats.tick_crossing_trade_count.filter(|_| stats.trade_count > 0).and_then(|count| Some(ohlcv::TickCrossingTradeData {
+          count,
+          share: super::f64_decimal(count as f64 / stats.trade_count as f64)?,
+        })),
+      },
+    }
+  }
+
+  // square root of the sum of squared log returns of the minutes with trades, and the number of the minutes
+  // (decimal price is sqrt price squared times a constant, so its log return is twice the one of sqrt price)
+  fn realized_volatility(&self) -> (f64, u64) {
+    let mut previous_close = match self.initial_state {
+      InitialState::Existing(previous_close_sqrt_price) => previous_close_sqrt_price,
+      InitialState::New(initial_sqrt_price, _, _) => initial_sqrt_price,
+    };
+    let mut minutely = self.minutely().values().collect::<Vec<_>>();
+    minutely.sort_by_key(|unit| unit.timestamp);
+
+    let mut squared_return_sum = 0.0;
+    for unit in minutely.iter() {
+      let log_return = 2.0 * (unit.close as f64 / previous_close as f64).ln();
+      squared_return_sum += log_return * log_return;
+      previous_close = unit.close;
+    }
+    (squared_return_sum.sqrt(), minutely.len() as u64)
+  }
+
+  pub fn to_interval_data(&self, interval: OhlcvInterval) -> ohlcv::WhirlpoolOhlcvIntervalData {
+    ohlcv::WhirlpoolOhlcvIntervalData {
+      metadata: ohlcv::WhirlpoolOhlcvMetadata::from(self),
+      initial_state: ohlcv::InitialState::from(self),
+      estimated_fees: ohlcv::EstimatedFees::from(self),
+      liquidity: Some(ohlcv::LiquidityData::from(self)),
+      daily: convert_to_ohlcv_data_unit(&self.daily, self.metadata.decimals_a, self.metadata.decimals_b),
+      interval: interval.seconds(),
+      candles: self.sorted_candles(interval.seconds() as i64),
+    }
+  }
 }
 
 // impl OhlcvData into WhirlpoolOhlcvDailyData
@@ -141,7 +362,10 @@ impl From<&OhlcvData> for ohlcv::WhirlpoolOhlcvDailyData {
       metadata: ohlcv::WhirlpoolOhlcvMetadata::from(ohlcv_data),
       initial_state: ohlcv::InitialState::from(ohlcv_data),
       estimated_fees: ohlcv::EstimatedFees::from(ohlcv_data),
+      liquidity: Some(ohlcv::LiquidityData::from(ohlcv_data)),
       daily: convert_to_ohlcv_data_unit(&ohlcv_data.daily, ohlcv_data.metadata.decimals_a, ohlcv_data.metadata.decimals_b),
+      // filled with the number of traders in options
+      top_traders: None,
     }
   }
 }
@@ -149,14 +373,13 @@ impl From<&OhlcvData> for ohlcv::WhirlpoolOhlcvDailyData {
 // impl OhlcvData into WhirlpoolOhlcvMinutelyData
 impl From<&OhlcvData> for ohlcv::WhirlpoolOhlcvMinutelyData {
   fn from(ohlcv_data: &OhlcvData) -> Self {
-    let mut minutely = ohlcv_data.minutely.values().map(|data| convert_to_ohlcv_data_unit(data, ohlcv_data.metadata.decimals_a, ohlcv_data.metadata.decimals_b)).collect::<Vec<_>>();
-    minutely.sort_by_key(|data| data.timestamp);
     Self {
       metadata: ohlcv::WhirlpoolOhlcvMetadata::from(ohlcv_data),
       initial_state: ohlcv::InitialState::from(ohlcv_data),
       estimated_fees: ohlcv::EstimatedFees::from(ohlcv_data),
+      liquidity: Some(ohlcv::LiquidityData::from(ohlcv_data)),
       daily: convert_to_ohlcv_data_unit(&ohlcv_data.daily, ohlcv_data.metadata.decimals_a, ohlcv_data.metadata.decimals_b),
-      minutely,
+      minutely: ohlcv_data.sorted_candles(MINUTELY_SECONDS),
     }
   }
 }
@@ -192,7 +415,7 @@ impl From<&OhlcvData> for ohlcv::WhirlpoolOhlcvMetadata {
 }
 
 #[derive(Debug)]
-enum InitialState {
+pub(super) enum InitialState {
   Existing(u128), // previous close sqrt price
   New(u128, u64, i64), // initial sqrt price, slot, block time
 }
@@ -216,42 +439,343 @@ impl From<&OhlcvData> for ohlcv::InitialState {
   }
 }
 
+// liquidity activity of the day
+#[derive(Debug)]
+struct LiquidityActivity {
+  open: u128,
+  high: u128,
+  low: u128,
+  close: u128,
+  deposit_count: u64,
+  withdrawal_count: u64,
+  reposition_count: u64,
+  added_a: u128,
+  added_b: u128,
+  removed_a: u128,
+  removed_b: u128,
+  positions: HashSet<String>,
+}
+
+impl LiquidityActivity {
+  fn new(liquidity: u128) -> Self {
+    Self {
+      open: liquidity,
+      high: liquidity,
+      low: liquidity,
+      close: liquidity,
+      deposit_count: 0,
+      withdrawal_count: 0,
+      reposition_count: 0,
+      added_a: 0,
+      added_b: 0,
+      removed_a: 0,
+      removed_b: 0,
+      positions: HashSet::new(),
+    }
+  }
+
+  // old liquidity may differ from the close if the swaps crossing ticks were not tracked (events before format version 4)
+  fn update(&mut self, old_whirlpool_liquidity: u128, new_whirlpool_liquidity: u128) {
+    self.high = self.high.max(old_whirlpool_liquidity).max(new_whirlpool_liquidity);
+    self.low = self.low.min(old_whirlpool_liquidity).min(new_whirlpool_liquidity);
+    self.close = new_whirlpool_liquidity;
+  }
+}
+
+impl From<&OhlcvData> for ohlcv::LiquidityData {
+  fn from(data: &OhlcvData) -> Self {
+    let liquidity = &data.liquidity;
+    Self {
+      ohlc: ohlcv::LiquidityOhlcData {
+        open: liquidity.open,
+        high: liquidity.high,
+        low: liquidity.low,
+        close: liquidity.close,
+      },
+      deposit_count: liquidity.deposit_count,
+      withdrawal_count: liquidity.withdrawal_count,
+      reposition_count: liquidity.reposition_count,
+      added_a: liquidity.added_a,
+      added_b: liquidity.added_b,
+      removed_a: liquidity.removed_a,
+      removed_b: liquidity.removed_b,
+      position_count: Some(liquidity.positions.len() as u64),
+    }
+  }
+}
+
+#[derive(Default, Debug)]
+struct TraderVolume {
+  trade_count: u64,
+  volume_a: u128,
+  volume_b: u128,
+}
+
+// price impact and initialized tick crossings of the trades of the day
+#[derive(Debug)]
+struct TradeStats {
+  trade_count: u64,
+  price_impact_sum: f64,
+  price_impact_max: f64,
+  // None if a trade does not record tick crossings (events before format version 4)
+  tick_crossing_trade_count: Option<u64>,
+}
+
+impl TradeStats {
+  fn new() -> Self {
+    Self {
+      trade_count: 0,
+      price_impact_sum: 0.0,
+      price_impact_max: 0.0,
+      tick_crossing_trade_count: Some(0),
+    }
+  }
+
+  fn update(&mut self, traded: &TradedEventPayload) {
+    // |new price / old price - 1|
+    let sqrt_price_ratio = traded.new_sqrt_price as f64 / traded.old_sqrt_price as f64;
+    let price_impact = (sqrt_price_ratio * sqrt_price_ratio - 1.0).abs();
+
+    self.trade_count += 1;
+    self.price_impact_sum += price_impact;
+    self.price_impact_max = self.price_impact_max.max(price_impact);
+    self.tick_crossing_trade_count = match (self.tick_crossing_trade_count, traded.crossed_initialized_tick) {
+      (Some(count), Some(crossed)) => Some(count + crossed as u64),
+      _ => None,
+    };
+  }
+}
+
+// distinct traders of a candle
+#[derive(Default, Debug)]
+struct Traders {
+  token_authorities: HashSet<String>,
+  payers: HashSet<String>,
+}
+
+impl Traders {
+  // None if no trades
+  fn to_trader_data(&self) -> Option<ohlcv::TraderData> {
+    if self.token_authorities.is_empty() {
+      return None;
+    }
+    Some(ohlcv::TraderData {
+      token_authority_count: self.token_authorities.len() as u64,
+      payer_count: self.payers.len() as u64,
+    })
+  }
+}
+
+// fees of a trade in the input token
+struct TradeFee {
+  liquidity_provider_fee: u64,
+  protocol_fee: u64,
+  // in hundredths of a basis point
+  fee_rate: u32,
+  // fees are estimated from the fee rate (not recorded in the event)
+  estimated: bool,
+}
+
+impl From<&TradedEventPayload> for TradeFee {
+  fn from(traded: &TradedEventPayload) -> Self {
+    let post_transfer_fee = calculate_post_transfer_fee(traded.transfer_in.amount, traded.transfer_in.transfer_fee_bps, traded.transfer_in.transfer_fee_max);
+
+    // exact fees are recorded since event format version 3, estimated for older events
+    // and for the trades whose LP fee cannot be restored exactly (the active liquidity changed during the trade)
+    let (liquidity_provider_fee, protocol_fee, estimated) = match (traded.lp_fee, traded.protocol_fee) {
+      (Some(lp_fee), Some(protocol_fee)) => (lp_fee, protocol_fee, false),
+      _ => {
+        let (liquidity_provider_fee, protocol_fee) = split_fee(calculate_trade_fee(post_transfer_fee, traded.fee_rate), traded.protocol_fee_rate);
+        (liquidity_provider_fee, protocol_fee, true)
+      }
+    };
+
+    // fee rate of adaptive fee pools varies with the volatility, so the effective one is derived from the fees
+    let is_adaptive_fee = traded.old_adaptive_fee_variables.is_some();
+    let fee_rate = if is_adaptive_fee && !estimated && post_transfer_fee > 0 {
+      let fee = (liquidity_provider_fee + protocol_fee) as u128;
+      (fee * whirlpool_base::math::FEE_RATE_MUL_VALUE / post_transfer_fee as u128).try_into().unwrap_or(u32::MAX)
+    } else {
+      traded.fee_rate as u32
+    };
+
+    Self { liquidity_provider_fee, protocol_fee, fee_rate, estimated }
+  }
+}
+
 #[derive(Default, Debug)]
-struct EstimatedFees {
-  liquidity_provider_fee_a: u64,
-  liquidity_provider_fee_b: u64,
-  protocol_fee_a: u64,
-  protocol_fee_b: u64,
+pub(super) struct FeeData {
+  pub(super) liquidity_provider_fee_a: u64,
+  pub(super) liquidity_provider_fee_b: u64,
+  pub(super) protocol_fee_a: u64,
+  pub(super) protocol_fee_b: u64,
+  // None if no trades
+  min_fee_rate: Option<u32>,
+  max_fee_rate: u32,
+  // sum of (fee rate * amount of token A) and amount of token A of the trades
+  fee_rate_amount_a: u128,
+  amount_a: u128,
+  estimated_trade_count: u64,
 }
 
+impl FeeData {
+  fn update(&mut self, traded: &TradedEventPayload, trade_fee: &TradeFee) {
+    let amount_a = match traded.trade_direction {
+      TradeDirection::AtoB => {
+        self.liquidity_provider_fee_a += trade_fee.liquidity_provider_fee;
+        self.protocol_fee_a += trade_fee.protocol_fee;
+        traded.transfer_in.amount
+      }
+      TradeDirection::BtoA => {
+        self.liquidity_provider_fee_b += trade_fee.liquidity_provider_fee;
+        self.protocol_fee_b += trade_fee.protocol_fee;
+        traded.transfer_out.amount
+      }
+    };
+    self.min_fee_rate = Some(self.min_fee_rate.map_or(trade_fee.fee_rate, |rate| rate.min(trade_fee.fee_rate)));
+    self.max_fee_rate = self.max_fee_rate.max(trade_fee.fee_rate);
+    self.fee_rate_amount_a += trade_fee.fee_rate as u128 * amount_a as u128;
+    self.amount_a += amount_a as u128;
+    if trade_fee.estimated {
+      self.estimated_trade_count += 1;
+    }
+  }
+
+  // None if no trades
+  fn to_fee_data(&self) -> Option<ohlcv::FeeData> {
+    let min_fee_rate = self.min_fee_rate?;
+    let vwap_fee_rate = match self.amount_a {
+      0 => min_fee_rate,
+      amount_a => (self.fee_rate_amount_a / amount_a) as u32,
+    };
+    Some(ohlcv::FeeData {
+      liquidity_provider_fee_a: self.liquidity_provider_fee_a,
+      liquidity_provider_fee_b: self.liquidity_provider_fee_b,
+      protocol_fee_a: self.protocol_fee_a,
+      protocol_fee_b: self.protocol_fee_b,
+      min_fee_rate,
+      max_fee_rate: self.max_fee_rate,
+      vwap_fee_rate,
+      estimated_trade_count: self.estimated_trade_count,
+    })
+  }
+}
+
+// fees of the day
 impl From<&OhlcvData> for ohlcv::EstimatedFees {
   fn from(data: &OhlcvData) -> Self {
-    let estimated_fees = &data.estimated_fees;
+    let fees = &data.daily.fees;
     Self {
-      liquidity_provider_fee_a: estimated_fees.liquidity_provider_fee_a,
-      liquidity_provider_fee_b: estimated_fees.liquidity_provider_fee_b,
-      protocol_fee_a: estimated_fees.protocol_fee_a,
-      protocol_fee_b: estimated_fees.protocol_fee_b,
+      liquidity_provider_fee_a: fees.liquidity_provider_fee_a,
+      liquidity_provider_fee_b: fees.liquidity_provider_fee_b,
+      protocol_fee_a: fees.protocol_fee_a,
+      protocol_fee_b: fees.protocol_fee_b,
     }
   }
 }
 
 #[derive(Debug)]
-struct SqrtPriceOhlcvDataUnit {
+pub(super) struct SqrtPriceOhlcvDataUnit {
   timestamp: i64,
   open: u128,
   high: u128,
   low: u128,
-  close: u128,
-  volume_a_to_b: VolumeData,
-  volume_b_to_a: VolumeData,
+  pub(super) close: u128,
+  pub(super) volume_a_to_b: VolumeData,
+  pub(super) volume_b_to_a: VolumeData,
+  pub(super) fees: FeeData,
+  // tracked for candles of an hour or longer only (to bound memory usage)
+  traders: Option<Traders>,
+  // end of the candle (exclusive)
+  end: i64,
+  // sum of (price * seconds it stood) from priced_from to close_since, close has stood since close_since
+  priced_from: i64,
+  close_since: i64,
+  price_seconds: BigUint,
+}
+
+impl SqrtPriceOhlcvDataUnit {
+  // price has stood since priced_from (the beginning of the candle or the initialization of the pool)
+  fn new(timestamp: i64, seconds: i64, priced_from: i64, sqrt_price: u128) -> Self {
+    Self {
+      timestamp,
+      open: sqrt_price,
+      high: sqrt_price,
+      low: sqrt_price,
+      close: sqrt_price,
+      volume_a_to_b: VolumeData::default(),
+      volume_b_to_a: VolumeData::default(),
+      fees: FeeData::default(),
+      traders: (seconds >= HOURLY_SECONDS).then(Traders::default),
+      end: timestamp + seconds,
+      priced_from,
+      close_since: priced_from,
+      price_seconds: BigUint::default(),
+    }
+  }
+
+  fn update(&mut self, block_time: i64, payer: &str, traded: &TradedEventPayload, trade_fee: &TradeFee) {
+    let block_time = block_time.clamp(self.close_since, self.end);
+    self.price_seconds += price_seconds(self.close, block_time - self.close_since);
+    self.close_since = block_time;
+
+    self.high = self.high.max(traded.new_sqrt_price);
+    self.low = self.low.min(traded.new_sqrt_price);
+    self.close = traded.new_sqrt_price;
+    let volume = match traded.trade_direction {
+      TradeDirection::AtoB => &mut self.volume_a_to_b,
+      TradeDirection::BtoA => &mut self.volume_b_to_a,
+    };
+    volume.total_in += traded.transfer_in.amount as u128;
+    volume.total_out += traded.transfer_out.amount as u128;
+    volume.count += 1;
+    self.fees.update(traded, trade_fee);
+    if let Some(traders) = self.traders.as_mut() {
+      traders.token_authorities.insert(traded.token_authority.clone());
+      traders.payers.insert(payer.to_string());
+    }
+  }
+
+  // time-weighted average price until the end of the candle (as sqrt price)
+  fn twap(&self) -> u128 {
+    let price_seconds = &self.price_seconds + price_seconds(self.close, self.end - self.close_since);
+    twap(&price_seconds, (self.end - self.priced_from).max(1) as u128)
+  }
+
+  // volume-weighted average price (total B / total A of the trades) as sqrt price, None if no trades
+  fn vwap(&self) -> Option<u128> {
+    let total_a = self.volume_a_to_b.total_in + self.volume_b_to_a.total_out;
+    let total_b = self.volume_a_to_b.total_out + self.volume_b_to_a.total_in;
+    vwap(total_a, total_b)
+  }
 }
 
 #[derive(Default, Debug)]
-struct VolumeData {
-  total_in: u128,
-  total_out: u128,
-  count: u64,
+pub(super) struct VolumeData {
+  pub(super) total_in: u128,
+  pub(super) total_out: u128,
+  pub(super) count: u64,
+}
+
+// price (square of sqrt price, X128) * seconds, the price overflows u128
+pub(super) fn price_seconds(sqrt_price: u128, seconds: i64) -> BigUint {
+  BigUint::from(sqrt_price).pow(2) * seconds as u64
+}
+
+// sum of (price * seconds) / seconds as sqrt price
+pub(super) fn twap(price_seconds: &BigUint, seconds: u128) -> u128 {
+  (price_seconds / seconds).sqrt().to_u128().unwrap()
+}
+
+// total B / total A as sqrt price, None if either is 0
+pub(super) fn vwap(total_a: u128, total_b: u128) -> Option<u128> {
+  if total_a == 0 || total_b == 0 {
+    return None;
+  }
+  let x64 = X64.get_or_init(|| BigDecimal::from(1u128 << 64));
+  let sqrt_price = (BigDecimal::from(total_b) / BigDecimal::from(total_a)).sqrt()? * x64;
+  let (sqrt_price, _) = sqrt_price.with_scale(0).into_bigint_and_exponent();
+  sqrt_price.to_u128()
 }
 
 fn convert_to_ohlcv_data_unit(data: &SqrtPriceOhlcvDataUnit, decimals_a: u8, decimals_b: u8) -> ohlcv::WhirlpoolOhlcvDataUnit {
@@ -283,6 +807,20 @@ fn convert_to_ohlcv_data_unit(data: &SqrtPriceOhlcvDataUnit, decimals_a: u8, dec
         count: data.volume_b_to_a.count,
       },
     },
+    vwap: data.vwap().map(|vwap| average_price_data(vwap, decimals_a, decimals_b)),
+    twap: Some(average_price_data(data.twap(), decimals_a, decimals_b)),
+    // filled by pricing
+    usd: None,
+    fees: data.fees.to_fee_data(),
+    traders: data.traders.as_ref().and_then(Traders::to_trader_data),
+    synthetic: false,
+  }
+}
+
+pub(super) fn average_price_data(sqrt_price: u128, decimals_a: u8, decimals_b: u8) -> ohlcv::AveragePriceData {
+  ohlcv::AveragePriceData {
+    sqrt_price,
+    decimal_price: sqrt_price_to_decimal_price(sqrt_price, decimals_a, decimals_b),
   }
 }
 
@@ -341,7 +879,7 @@ mod transfer_fee {
 
 // TODO: refactor (dedup event/convert.rs)
 static X64: std::sync::OnceLock<BigDecimal> = std::sync::OnceLock::new();
-fn sqrt_price_to_decimal_price(
+pub(crate) fn sqrt_price_to_decimal_price(
     sqrt_price: u128,
     decimals_a: u8,
     decimals_b: u8,
@@ -1,11 +1,5 @@
-use anyhow::Result;
-use flate2::read::GzDecoder;
-use std::{
-  collections::HashMap,
-  fs::File,
-  io::{BufRead, BufReader},
-};
-use super::super::super::model::event::WhirlpoolEventBlock;
+use std::collections::HashMap;
+use super::super::super::model::event::{reader::WhirlpoolEventReader, WhirlpoolEventBlock};
 use whirlpool_replayer::{schema::WhirlpoolState, serde::AccountDataStoreConfig};
 
 pub fn build_with_local_file_storage(
@@ -40,16 +34,8 @@ pub fn build_with_local_file_storage(
 fn load_from_local_whirlpool_event_file(
   whirlpool_event_file_path: &str,
 ) -> impl Iterator<Item = WhirlpoolEventBlock> {
-  let file = File::open(whirlpool_event_file_path).unwrap();
+  // event files of any format version
+  let reader = WhirlpoolEventReader::open(whirlpool_event_file_path).unwrap();
 
-  let decoder = GzDecoder::new(file);
-  let buf = BufReader::new(decoder);
-
-  let iter = buf.lines().map(|jsonl| jsonl.unwrap()).map(|jsonl| {
-      let t: Result<WhirlpoolEventBlock, serde_json::Error> =
-          serde_json::from_str(jsonl.as_str());
-      t.unwrap()
-  });
-
-  iter
+  reader.map(|block| block.unwrap())
 }
@@ -1,6 +1,8 @@
-use super::super::model::{event::WhirlpoolEvent, ohlcv};
+use super::super::model::{event::{definition::AdaptiveFeeConstants, WhirlpoolEvent}, long_key::{self, KeyFormat}, ohlcv::{self, interval::OhlcvInterval}};
 use anchor_lang::Discriminator;
 use anyhow::Result;
+use bigdecimal::BigDecimal;
+use std::str::FromStr;
 use flate2::write::GzEncoder;
 use std::{
   fs::File, io::LineWriter, io::Write,
@@ -10,14 +12,176 @@ use anchor_lang::AccountDeserialize;
 
 mod io;
 mod data;
+mod aggregate;
+mod adaptive_fee;
+pub mod rollup;
+pub mod pricing;
 
+// shared with the decoded account files
+pub(crate) use data::sqrt_price_to_decimal_price;
+// shared with the position valuation
+pub(crate) use data::{OhlcvData, OhlcvDataManager, DAILY_SECONDS, MINUTELY_SECONDS};
+
+// number of traders in topTraders of the archived daily file
+pub const DEFAULT_TOP_TRADERS: usize = 10;
+
+#[derive(Debug, Clone, Default)]
+pub struct OhlcvOptions {
+  // USD prices are derived from the pools paired with these mints (e.g. pricing::DEFAULT_ANCHOR_MINTS)
+  pub anchor_mints: Vec<String>,
+  // write every minute of the day into the minutely file (not archived, see the dense minutely file in model/ohlcv)
+  pub dense_minutely: bool,
+  // number of traders in topTraders of the daily file
+  pub top_traders: usize,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn process(
   in_whirlpool_state_file_path: String,
   in_whirlpool_token_file_path: String,
   in_whirlpool_event_file_path: String,
   out_whirlpool_ohlcv_daily_file_path: String,
   out_whirlpool_ohlcv_minutely_file_path: String,
+  // whirlpool-ohlcv-<interval>-yyyymmdd.jsonl.gz for each interval (e.g. 5m, 1h)
+  out_whirlpool_ohlcv_interval_file_paths: &[(OhlcvInterval, String)],
+  // daily aggregates across pools (whirlpool-ohlcv-pair-yyyymmdd.jsonl.gz, whirlpool-ohlcv-token-yyyymmdd.jsonl.gz)
+  out_whirlpool_ohlcv_pair_file_path: String,
+  out_whirlpool_ohlcv_token_file_path: String,
+  // daily volatility and price impact of each pool (whirlpool-ohlcv-stats-yyyymmdd.jsonl.gz)
+  out_whirlpool_ohlcv_stats_file_path: String,
+  // oracle activity of adaptive fee pools (whirlpool-ohlcv-adaptive-fee-yyyymmdd.jsonl.gz)
+  out_whirlpool_ohlcv_adaptive_fee_file_path: String,
+  options: &OhlcvOptions,
+  key_format: KeyFormat,
 ) -> Result<()> {
+  let intervals = out_whirlpool_ohlcv_interval_file_paths.iter().map(|(interval, _)| *interval).collect::<Vec<_>>();
+  let ohlcv_data_manager = build_data_manager(
+    in_whirlpool_state_file_path,
+    in_whirlpool_token_file_path,
+    in_whirlpool_event_file_path,
+    &intervals,
+    |_, _| Ok(()),
+  )?;
+
+  println!("price in USD...");
+  let usd_pricing = pricing::UsdPricing::build(&ohlcv_data_manager, &options.anchor_mints);
+
+  // write daily file
+  println!("write daily file...");
+  let f = File::create(out_whirlpool_ohlcv_daily_file_path).unwrap();
+  let encoder = GzEncoder::new(f, flate2::Compression::default());
+  let mut writer = LineWriter::new(encoder);
+  ohlcv_data_manager.data.values().for_each(|ohlcv_data| {
+    let mut data = ohlcv::WhirlpoolOhlcvDailyData::from(ohlcv_data);
+    data.daily.usd = usd_pricing.daily.get(&data.metadata.whirlpool).and_then(usd_data);
+    data.top_traders = Some(ohlcv_data.top_traders(options.top_traders));
+    let jsonl = long_key::to_string(&data, key_format).unwrap();
+    writer.write_all(jsonl.as_bytes()).unwrap();
+    writer.write_all(b"\n").unwrap();
+  });
+  writer.flush().unwrap();
+
+  // write minutely file
+  println!("write minutely file...");
+  let f = File::create(out_whirlpool_ohlcv_minutely_file_path).unwrap();
+  let encoder = GzEncoder::new(f, flate2::Compression::default());
+  let mut writer = LineWriter::new(encoder);
+  let to_minutely_data = |ohlcv_data: &data::OhlcvData| match options.dense_minutely {
+    true => ohlcv_data.to_dense_minutely_data(),
+    false => ohlcv::WhirlpoolOhlcvMinutelyData::from(ohlcv_data),
+  };
+  ohlcv_data_manager.data.values().map(to_minutely_data).for_each(|mut data| {
+    data.daily.usd = usd_pricing.daily.get(&data.metadata.whirlpool).and_then(usd_data);
+    for minutely in data.minutely.iter_mut() {
+      minutely.usd = usd_pricing.minutely.get(&(data.metadata.whirlpool.clone(), minutely.timestamp)).and_then(usd_data);
+    }
+    let jsonl = long_key::to_string(&data, key_format).unwrap();
+    writer.write_all(jsonl.as_bytes()).unwrap();
+    writer.write_all(b"\n").unwrap();
+  });
+  writer.flush().unwrap();
+
+  // write interval files
+  for (interval, out_whirlpool_ohlcv_interval_file_path) in out_whirlpool_ohlcv_interval_file_paths.iter() {
+    println!("write {} file...", interval.name());
+    let f = File::create(out_whirlpool_ohlcv_interval_file_path).unwrap();
+    let encoder = GzEncoder::new(f, flate2::Compression::default());
+    let mut writer = LineWriter::new(encoder);
+    ohlcv_data_manager.data.values().map(|data| data.to_interval_data(*interval)).for_each(|mut data| {
+      data.daily.usd = usd_pricing.daily.get(&data.metadata.whirlpool).and_then(usd_data);
+      for candle in data.candles.iter_mut() {
+        candle.usd = usd_pricing.candle(&data.metadata.whirlpool, candle.timestamp, data.interval as i64).as_ref().and_then(usd_data);
+      }
+      let jsonl = long_key::to_string(&data, key_format).unwrap();
+      writer.write_all(jsonl.as_bytes()).unwrap();
+      writer.write_all(b"\n").unwrap();
+    });
+    writer.flush().unwrap();
+  }
+
+  // write pair file
+  println!("write pair file...");
+  let f = File::create(out_whirlpool_ohlcv_pair_file_path).unwrap();
+  let encoder = GzEncoder::new(f, flate2::Compression::default());
+  let mut writer = LineWriter::new(encoder);
+  aggregate::pairs(&ohlcv_data_manager).iter().for_each(|data| {
+    let jsonl = long_key::to_string(data, key_format).unwrap();
+    writer.write_all(jsonl.as_bytes()).unwrap();
+    writer.write_all(b"\n").unwrap();
+  });
+  writer.flush().unwrap();
+
+  // write token file
+  println!("write token file...");
+  let f = File::create(out_whirlpool_ohlcv_token_file_path).unwrap();
+  let encoder = GzEncoder::new(f, flate2::Compression::default());
+  let mut writer = LineWriter::new(encoder);
+  aggregate::tokens(&ohlcv_data_manager).iter().for_each(|data| {
+    let jsonl = long_key::to_string(data, key_format).unwrap();
+    writer.write_all(jsonl.as_bytes()).unwrap();
+    writer.write_all(b"\n").unwrap();
+  });
+  writer.flush().unwrap();
+
+  // write stats file
+  println!("write stats file...");
+  let f = File::create(out_whirlpool_ohlcv_stats_file_path).unwrap();
+  let encoder = GzEncoder::new(f, flate2::Compression::default());
+  let mut writer = LineWriter::new(encoder);
+  ohlcv_data_manager.data.values().map(|data| data.to_stats_data()).for_each(|data| {
+    let jsonl = long_key::to_string(&data, key_format).unwrap();
+    writer.write_all(jsonl.as_bytes()).unwrap();
+    writer.write_all(b"\n").unwrap();
+  });
+  writer.flush().unwrap();
+
+  // write adaptive fee file (pools in the daily file only)
+  println!("write adaptive fee file...");
+  let f = File::create(out_whirlpool_ohlcv_adaptive_fee_file_path).unwrap();
+  let encoder = GzEncoder::new(f, flate2::Compression::default());
+  let mut writer = LineWriter::new(encoder);
+  ohlcv_data_manager.adaptive_fee.iter().filter_map(|(whirlpool, activity)| {
+    ohlcv_data_manager.data.get(whirlpool).map(|data| activity.to_adaptive_fee_data(data))
+  }).for_each(|data| {
+    let jsonl = long_key::to_string(&data, key_format).unwrap();
+    writer.write_all(jsonl.as_bytes()).unwrap();
+    writer.write_all(b"\n").unwrap();
+  });
+  writer.flush().unwrap();
+
+  Ok(())
+}
+
+// candles of the pools of the day from state (previous date), token and event
+// (also used to value the positions at the prices of the day, see position::valuation)
+pub(crate) fn build_data_manager(
+  in_whirlpool_state_file_path: String,
+  in_whirlpool_token_file_path: String,
+  in_whirlpool_event_file_path: String,
+  intervals: &[OhlcvInterval],
+  // called with every account of the state (the position valuation collects Position accounts in the same traversal)
+  mut on_account: impl FnMut(String, &[u8]) -> Result<()>,
+) -> Result<OhlcvDataManager> {
   println!("open files...");
   let (state, event_block_iter, decimals) = io::build_with_local_file_storage(
     in_whirlpool_state_file_path,
@@ -31,7 +195,7 @@ pub fn process(
   let yesterday_timestamp = state.block_time / seconds_per_day * seconds_per_day;
   let daily_timestamp = yesterday_timestamp + seconds_per_day;
 
-  let mut ohlcv_data_manager = data::OhlcvDataManager::new(daily_timestamp);
+  let mut ohlcv_data_manager = data::OhlcvDataManager::new(daily_timestamp, intervals);
 
   println!("traverse accounts...");
   state.accounts.traverse(|pubkey, data| {
@@ -49,9 +213,21 @@ pub fn process(
         tick_spacing: whirlpool.tick_spacing,
         decimals_a,
         decimals_b,
-      }, whirlpool.sqrt_price);
+      }, whirlpool.sqrt_price, whirlpool.liquidity, whirlpool.tick_current_index);
+    }
+    if data.starts_with(&whirlpool_base::state::Oracle::DISCRIMINATOR) {
+      let oracle = whirlpool_base::state::Oracle::try_deserialize(&mut data.as_slice()).unwrap();
+      ohlcv_data_manager.initialize_adaptive_fee(oracle.whirlpool.to_string(), AdaptiveFeeConstants {
+        filter_period: oracle.adaptive_fee_constants.filter_period,
+        decay_period: oracle.adaptive_fee_constants.decay_period,
+        reduction_factor: oracle.adaptive_fee_constants.reduction_factor,
+        adaptive_fee_control_factor: oracle.adaptive_fee_constants.adaptive_fee_control_factor,
+        max_volatility_accumulator: oracle.adaptive_fee_constants.max_volatility_accumulator,
+        tick_group_size: oracle.adaptive_fee_constants.tick_group_size,
+        major_swap_threshold_ticks: oracle.adaptive_fee_constants.major_swap_threshold_ticks,
+      });
     }
-    Ok(())
+    on_account(pubkey.to_string(), data.as_slice())
   })?;
 
   println!("process events...");
@@ -60,40 +236,49 @@ pub fn process(
       transaction.events.iter().for_each(|event| {
         match event {
           WhirlpoolEvent::Traded(traded) => {
-            ohlcv_data_manager.process_traded_event(event_block.block_time, traded);
+            ohlcv_data_manager.process_traded_event(event_block.block_time, &transaction.payer, traded);
           }
           WhirlpoolEvent::PoolInitialized(pool_initialized) => {
             ohlcv_data_manager.process_pool_initialized_event(event_block.slot, event_block.block_time, pool_initialized);
           }
+          WhirlpoolEvent::LiquidityDeposited(deposited) => {
+            ohlcv_data_manager.process_liquidity_deposited_event(event_block.block_time, deposited);
+          }
+          WhirlpoolEvent::LiquidityWithdrawn(withdrawn) => {
+            ohlcv_data_manager.process_liquidity_withdrawn_event(event_block.block_time, withdrawn);
+          }
+          WhirlpoolEvent::LiquidityRepositioned(repositioned) => {
+            ohlcv_data_manager.process_liquidity_repositioned_event(event_block.block_time, repositioned);
+          }
+          WhirlpoolEvent::LiquidityPatched(patched) => {
+            ohlcv_data_manager.process_liquidity_patched_event(event_block.block_time, patched);
+          }
+          WhirlpoolEvent::PoolAdaptiveFeeConstantsUpdated(updated) => {
+            ohlcv_data_manager.process_pool_adaptive_fee_constants_updated_event(updated);
+          }
           _ => { /* ignore */ }
         }
       });
     });
   }
 
-  // write daily file
-  println!("write daily file...");
-  let f = File::create(out_whirlpool_ohlcv_daily_file_path).unwrap();
-  let encoder = GzEncoder::new(f, flate2::Compression::default());
-  let mut writer = LineWriter::new(encoder);
-  ohlcv_data_manager.data.values().map(ohlcv::WhirlpoolOhlcvDailyData::from).for_each(|data| {
-    let jsonl = serde_json::to_string(&data).unwrap();
-    writer.write_all(jsonl.as_bytes()).unwrap();
-    writer.write_all(b"\n").unwrap();
-  });
-  writer.flush().unwrap();
+  Ok(ohlcv_data_manager)
+}
 
-  // write minutely file
-  println!("write minutely file...");
-  let f = File::create(out_whirlpool_ohlcv_minutely_file_path).unwrap();
-  let encoder = GzEncoder::new(f, flate2::Compression::default());
-  let mut writer = LineWriter::new(encoder);
-  ohlcv_data_manager.data.values().map(ohlcv::WhirlpoolOhlcvMinutelyData::from).for_each(|data| {
-    let jsonl = serde_json::to_string(&data).unwrap();
-    writer.write_all(jsonl.as_bytes()).unwrap();
-    writer.write_all(b"\n").unwrap();
-  });
-  writer.flush().unwrap();
+// None (usd is omitted) if any value is not finite
+fn usd_data(usd: &pricing::Usd) -> Option<ohlcv::UsdData> {
+  Some(ohlcv::UsdData {
+    close_a: f64_decimal(usd.close_a)?,
+    close_b: f64_decimal(usd.close_b)?,
+    volume: f64_decimal(usd.volume)?,
+  })
+}
 
-  Ok(())
+// shortest representation of f64 (not the exact binary value), used for USD prices and stats
+// None if the value is NaN or infinite (written as absent, not as 0)
+fn f64_decimal(value: f64) -> Option<BigDecimal> {
+  if !value.is_finite() {
+    return None;
+  }
+  BigDecimal::from_str(&value.to_string()).ok()
 }
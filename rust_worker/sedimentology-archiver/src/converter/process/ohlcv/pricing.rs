@@ -0,0 +1,282 @@
+use super::data::{InitialState, OhlcvData, OhlcvDataManager, MINUTELY_SECONDS};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+// USDC, USDT
+pub const DEFAULT_ANCHOR_MINTS: [&str; 2] = [
+  "EPjFWdd5AufqSSqeM2qN1xyybapC8G4wEGGkZwyTDt1v",
+  "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB",
+];
+
+const MINUTES_PER_DAY: usize = 60 * 24;
+const X64: f64 = 18446744073709551616.0;
+
+/*
+
+USD pricing graph
+
+Mints are the nodes and pools are the edges of the graph.
+Anchor mints (stablecoins) are priced at 1 USD, and the other mints are priced through the pools.
+
+For each minute of the day, the USD price of each mint is derived from the most liquid path from an anchor mint.
+The liquidity of a path is the liquidity of its least liquid pool (widest path),
+and the liquidity of a pool is the USD value of its virtual reserves (2 * reserve of the priced token side).
+
+The state of each pool at a minute is its close sqrt price and its active liquidity.
+- sqrt price: the close of the last minute with trades (or the initial state)
+- active liquidity: the last liquidity event or trade crossing initialized ticks
+  (or the state at the beginning of the day, 0 for new pools)
+  so the virtual reserves are those of the pool at the minute.
+  Trades in the events before format version 4 have no liquidity, so the changes by them are not tracked (an estimation).
+
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Usd {
+  // USD price of 1 token A and 1 token B (UI amount)
+  pub close_a: f64,
+  pub close_b: f64,
+  // USD value of the input tokens
+  pub volume: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct UsdPricing {
+  // whirlpool -> USD data of the day
+  pub daily: HashMap<String, Usd>,
+  // (whirlpool, minutely timestamp) -> USD data of the minute
+  pub minutely: HashMap<(String, i64), Usd>,
+}
+
+struct Pool<'a> {
+  data: &'a OhlcvData,
+  mint_a: usize,
+  mint_b: usize,
+  // None until the pool is initialized
+  sqrt_price: Option<u128>,
+  liquidity: u128,
+}
+
+enum PoolChange {
+  Initialized(u128),
+  Close(u128),
+  Liquidity(u128),
+}
+
+impl UsdPricing {
+  pub fn build(manager: &OhlcvDataManager, anchor_mints: &[String]) -> UsdPricing {
+    let daily_timestamp = manager.timestamp;
+    let minute_of = |timestamp: i64| ((timestamp - daily_timestamp) / 60).clamp(0, MINUTES_PER_DAY as i64 - 1) as usize;
+
+    // nodes
+    let mut mint_index: HashMap<&str, usize> = HashMap::new();
+
+    // edges & their changes in each minute (in the order of events)
+    let mut pools = vec![];
+    let mut changes: Vec<Vec<(usize, PoolChange)>> = (0..MINUTES_PER_DAY).map(|_| vec![]).collect();
+    // sorted to make the result deterministic
+    let mut data = manager.data.values().collect::<Vec<_>>();
+    data.sort_by(|a, b| a.metadata.whirlpool.cmp(&b.metadata.whirlpool));
+    for data in data {
+      let pool_index = pools.len();
+      let sqrt_price = match data.initial_state {
+        InitialState::Existing(previous_close_sqrt_price) => Some(previous_close_sqrt_price),
+        InitialState::New(initial_sqrt_price, _, block_time) => {
+          changes[minute_of(block_time)].push((pool_index, PoolChange::Initialized(initial_sqrt_price)));
+          None
+        }
+      };
+      for (block_time, liquidity) in data.liquidity_changes.iter() {
+        changes[minute_of(*block_time)].push((pool_index, PoolChange::Liquidity(*liquidity)));
+      }
+      for (timestamp, minutely) in data.minutely().iter() {
+        changes[minute_of(*timestamp)].push((pool_index, PoolChange::Close(minutely.close)));
+      }
+      pools.push(Pool {
+        data,
+        mint_a: index_of(&mut mint_index, &data.metadata.mint_a),
+        mint_b: index_of(&mut mint_index, &data.metadata.mint_b),
+        sqrt_price,
+        liquidity: data.initial_liquidity,
+      });
+    }
+    // close is applied after the other changes in the same minute
+    changes.iter_mut().for_each(|changes| changes.sort_by_key(|(_, change)| matches!(change, PoolChange::Close(_))));
+
+    let mut adjacency = vec![vec![]; mint_index.len()];
+    for (pool_index, pool) in pools.iter().enumerate() {
+      adjacency[pool.mint_a].push(pool_index);
+      adjacency[pool.mint_b].push(pool_index);
+    }
+    let anchors = anchor_mints.iter().filter_map(|mint| mint_index.get(mint.as_str()).copied()).collect::<Vec<_>>();
+
+    let mut pricing = UsdPricing::default();
+    let mut prices = vec![];
+    for (minute, changes) in changes.into_iter().enumerate() {
+      let changed = minute == 0 || !changes.is_empty();
+      let mut traded = vec![];
+      for (pool_index, change) in changes {
+        let pool = &mut pools[pool_index];
+        match change {
+          PoolChange::Initialized(sqrt_price) => pool.sqrt_price = Some(sqrt_price),
+          PoolChange::Close(sqrt_price) => {
+            pool.sqrt_price = Some(sqrt_price);
+            traded.push(pool_index);
+          }
+          PoolChange::Liquidity(liquidity) => pool.liquidity = liquidity,
+        }
+      }
+
+      if changed {
+        prices = widest_path_prices(&pools, &adjacency, &anchors);
+      }
+
+      let minutely_timestamp = daily_timestamp + minute as i64 * 60;
+      for pool_index in traded {
+        let pool = &pools[pool_index];
+        let Some((close_a, close_b)) = usd_close(pool, &prices) else {
+          continue;
+        };
+        let minutely = &pool.data.minutely()[&minutely_timestamp];
+        let volume = ui_amount(minutely.volume_a_to_b.total_in, pool.data.metadata.decimals_a) * close_a
+          + ui_amount(minutely.volume_b_to_a.total_in, pool.data.metadata.decimals_b) * close_b;
+        pricing.minutely.insert((pool.data.metadata.whirlpool.clone(), minutely_timestamp), Usd { close_a, close_b, volume });
+      }
+    }
+
+    // daily close is the close of the last minute
+    for pool in pools.iter() {
+      if let Some((close_a, close_b)) = usd_close(pool, &prices) {
+        let volume = pool.data.minutely().keys()
+          .filter_map(|timestamp| pricing.minutely.get(&(pool.data.metadata.whirlpool.clone(), *timestamp)))
+          .map(|usd| usd.volume)
+          .sum();
+        pricing.daily.insert(pool.data.metadata.whirlpool.clone(), Usd { close_a, close_b, volume });
+      }
+    }
+
+    pricing
+  }
+
+  // USD data of a candle longer than a minute: prices of its last priced minute and the sum of the volume of its minutes
+  pub fn candle(&self, whirlpool: &str, timestamp: i64, seconds: i64) -> Option<Usd> {
+    let mut candle: Option<Usd> = None;
+    for minutely_timestamp in (timestamp..timestamp + seconds).step_by(MINUTELY_SECONDS as usize) {
+      if let Some(minutely) = self.minutely.get(&(whirlpool.to_string(), minutely_timestamp)) {
+        let volume = candle.map(|candle| candle.volume).unwrap_or_default() + minutely.volume;
+        candle = Some(Usd { volume, ..*minutely });
+      }
+    }
+    candle
+  }
+}
+
+fn index_of<'a>(mint_index: &mut HashMap<&'a str, usize>, mint: &'a str) -> usize {
+  let next = mint_index.len();
+  *mint_index.entry(mint).or_insert(next)
+}
+
+// (USD price of token A, USD price of token B), the other side is derived from the pool price if only one side is priced
+fn usd_close(pool: &Pool, prices: &[Option<f64>]) -> Option<(f64, f64)> {
+  let price = pool_price(pool)?;
+  match (prices[pool.mint_a], prices[pool.mint_b]) {
+    (Some(a), Some(b)) => Some((a, b)),
+    (Some(a), None) => Some((a, a / price)),
+    (None, Some(b)) => Some((b * price, b)),
+    (None, None) => None,
+  }
+}
+
+// token B per token A (UI amount)
+fn pool_price(pool: &Pool) -> Option<f64> {
+  let sqrt_price = pool.sqrt_price? as f64 / X64;
+  let price = sqrt_price * sqrt_price * 10f64.powi(pool.data.metadata.decimals_a as i32 - pool.data.metadata.decimals_b as i32);
+  (price.is_finite() && price > 0.0).then_some(price)
+}
+
+// virtual reserves (UI amount) of token A and token B
+fn pool_reserves(pool: &Pool) -> Option<(f64, f64)> {
+  let sqrt_price = pool.sqrt_price? as f64 / X64;
+  let liquidity = pool.liquidity as f64;
+  if liquidity == 0.0 || sqrt_price == 0.0 {
+    return None;
+  }
+  Some((
+    ui_amount_f64(liquidity / sqrt_price, pool.data.metadata.decimals_a),
+    ui_amount_f64(liquidity * sqrt_price, pool.data.metadata.decimals_b),
+  ))
+}
+
+fn ui_amount(amount: u128, decimals: u8) -> f64 {
+  ui_amount_f64(amount as f64, decimals)
+}
+
+fn ui_amount_f64(amount: f64, decimals: u8) -> f64 {
+  amount / 10f64.powi(decimals as i32)
+}
+
+// max-heap entry ordered by path liquidity
+struct Path {
+  liquidity: f64,
+  mint: usize,
+}
+
+impl PartialEq for Path {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for Path {}
+
+impl PartialOrd for Path {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for Path {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.liquidity.total_cmp(&other.liquidity).then_with(|| other.mint.cmp(&self.mint))
+  }
+}
+
+// USD price of each mint through the most liquid path (None if no path from the anchors)
+fn widest_path_prices(pools: &[Pool], adjacency: &[Vec<usize>], anchors: &[usize]) -> Vec<Option<f64>> {
+  let mut prices = vec![None; adjacency.len()];
+  let mut liquidities = vec![0f64; adjacency.len()];
+  let mut heap = BinaryHeap::new();
+  for anchor in anchors {
+    prices[*anchor] = Some(1.0);
+    liquidities[*anchor] = f64::INFINITY;
+    heap.push(Path { liquidity: f64::INFINITY, mint: *anchor });
+  }
+
+  while let Some(Path { liquidity, mint }) = heap.pop() {
+    if liquidity < liquidities[mint] {
+      continue;
+    }
+    let mint_price = prices[mint].unwrap();
+
+    for pool_index in adjacency[mint].iter() {
+      let pool = &pools[*pool_index];
+      let (Some(price), Some((reserve_a, reserve_b))) = (pool_price(pool), pool_reserves(pool)) else {
+        continue;
+      };
+      let (other, other_price, reserve) = if pool.mint_a == mint {
+        (pool.mint_b, mint_price / price, reserve_a)
+      } else {
+        (pool.mint_a, mint_price * price, reserve_b)
+      };
+
+      let path_liquidity = liquidity.min(2.0 * reserve * mint_price);
+      if path_liquidity > liquidities[other] && other_price.is_finite() {
+        liquidities[other] = path_liquidity;
+        prices[other] = Some(other_price);
+        heap.push(Path { liquidity: path_liquidity, mint: other });
+      }
+    }
+  }
+
+  prices
+}
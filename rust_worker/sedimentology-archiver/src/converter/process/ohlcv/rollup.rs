@@ -0,0 +1,238 @@
+use super::super::super::model::long_key::{self, KeyFormat};
+use super::super::super::model::ohlcv::{self, period::OhlcvPeriod};
+use super::super::long_key::read_short_key_file;
+use super::data;
+use crate::date;
+use anyhow::{ensure, Result};
+use bigdecimal::num_bigint::BigUint;
+use flate2::write::GzEncoder;
+use std::collections::BTreeMap;
+use std::{
+  fs::File,
+  io::{LineWriter, Write},
+};
+
+const SECONDS_PER_DAY: i64 = 60 * 60 * 24;
+
+// build whirlpool-ohlcv-<period>-yyyymmdd.jsonl.gz from the daily files of the period (in date order, missing dates are allowed)
+pub fn process(
+  period: OhlcvPeriod,
+  in_whirlpool_ohlcv_daily_file_paths: &[String],
+  out_whirlpool_ohlcv_rollup_file_path: String,
+  key_format: KeyFormat,
+) -> Result<()> {
+  // whirlpool -> rollup (sorted to make the result deterministic)
+  let mut rollups: BTreeMap<String, Rollup> = BTreeMap::new();
+  let mut period_timestamp = None;
+
+  for in_whirlpool_ohlcv_daily_file_path in in_whirlpool_ohlcv_daily_file_paths {
+    println!("read {} ...", in_whirlpool_ohlcv_daily_file_path);
+    for daily in read_short_key_file::<ohlcv::WhirlpoolOhlcvDailyData>(in_whirlpool_ohlcv_daily_file_path)? {
+      let daily = daily?;
+      let timestamp = period_timestamp_of(period, daily.daily.timestamp);
+      ensure!(*period_timestamp.get_or_insert(timestamp) == timestamp, "{} is not a daily file of the {} period", in_whirlpool_ohlcv_daily_file_path, period.name());
+
+      match rollups.get_mut(&daily.metadata.whirlpool) {
+        Some(rollup) => {
+          ensure!(rollup.last_timestamp < daily.daily.timestamp, "daily files must be in date order ({})", in_whirlpool_ohlcv_daily_file_path);
+          rollup.merge(daily);
+        }
+        None => {
+          rollups.insert(daily.metadata.whirlpool.clone(), Rollup::new(daily));
+        }
+      }
+    }
+  }
+
+  println!("write {} file...", period.name());
+  let f = File::create(out_whirlpool_ohlcv_rollup_file_path)?;
+  let encoder = GzEncoder::new(f, flate2::Compression::default());
+  let mut writer = LineWriter::new(encoder);
+  for rollup in rollups.into_values() {
+    let data = rollup.into_rollup_data(period_timestamp.unwrap());
+    let jsonl = long_key::to_string(&data, key_format)?;
+    writer.write_all(jsonl.as_bytes())?;
+    writer.write_all(b"\n")?;
+  }
+  writer.flush()?;
+
+  Ok(())
+}
+
+// first second of the period containing the timestamp
+fn period_timestamp_of(period: OhlcvPeriod, timestamp: i64) -> i64 {
+  let first_yyyymmdd_date = period.first_yyyymmdd_date(date::convert_unixtime_to_yyyymmdd(timestamp));
+  date::convert_yyyymmdd_to_unixtime(first_yyyymmdd_date)
+}
+
+// daily data of the first day merged with the following days
+struct Rollup {
+  data: ohlcv::WhirlpoolOhlcvDailyData,
+  day_count: u32,
+  last_timestamp: i64,
+  // sum of (twap price * seconds priced in the day) and the seconds, None if a day has no twap (files before version 3)
+  twap: Option<(BigUint, u128)>,
+  // sum of (vwap fee rate * amount of token A) and amount of token A of the days
+  fee_rate_amount_a: u128,
+  amount_a: u128,
+}
+
+impl Rollup {
+  fn new(data: ohlcv::WhirlpoolOhlcvDailyData) -> Self {
+    let mut rollup = Self {
+      day_count: 1,
+      last_timestamp: data.daily.timestamp,
+      twap: Some((BigUint::default(), 0)),
+      fee_rate_amount_a: 0,
+      amount_a: 0,
+      data,
+    };
+    rollup.add_averages(&DayAverages::from(&rollup.data));
+    rollup
+  }
+
+  fn merge(&mut self, daily: ohlcv::WhirlpoolOhlcvDailyData) {
+    self.add_averages(&DayAverages::from(&daily));
+    self.day_count += 1;
+    self.last_timestamp = daily.daily.timestamp;
+
+    let estimated_fees = &mut self.data.estimated_fees;
+    estimated_fees.liquidity_provider_fee_a += daily.estimated_fees.liquidity_provider_fee_a;
+    estimated_fees.liquidity_provider_fee_b += daily.estimated_fees.liquidity_provider_fee_b;
+    estimated_fees.protocol_fee_a += daily.estimated_fees.protocol_fee_a;
+    estimated_fees.protocol_fee_b += daily.estimated_fees.protocol_fee_b;
+
+    self.data.liquidity = match (self.data.liquidity.take(), daily.liquidity) {
+      (Some(total), Some(liquidity)) => Some(merge_liquidity(total, liquidity)),
+      (total, liquidity) => total.or(liquidity),
+    };
+
+    let total = &mut self.data.daily;
+    let day = daily.daily;
+    if day.ohlc.sqrt_price.high > total.ohlc.sqrt_price.high {
+      total.ohlc.sqrt_price.high = day.ohlc.sqrt_price.high;
+      total.ohlc.decimal_price.high = day.ohlc.decimal_price.high;
+    }
+    if day.ohlc.sqrt_price.low < total.ohlc.sqrt_price.low {
+      total.ohlc.sqrt_price.low = day.ohlc.sqrt_price.low;
+      total.ohlc.decimal_price.low = day.ohlc.decimal_price.low;
+    }
+    total.ohlc.sqrt_price.close = day.ohlc.sqrt_price.close;
+    total.ohlc.decimal_price.close = day.ohlc.decimal_price.close;
+
+    add_volume(&mut total.volume.ab, &day.volume.ab);
+    add_volume(&mut total.volume.ba, &day.volume.ba);
+
+    total.usd = match (total.usd.take(), day.usd) {
+      (Some(total), Some(usd)) => Some(ohlcv::UsdData { volume: total.volume + usd.volume, ..usd }),
+      (total, usd) => total.or(usd),
+    };
+
+    total.fees = match (total.fees.take(), day.fees) {
+      (Some(total), Some(fees)) => Some(ohlcv::FeeData {
+        liquidity_provider_fee_a: total.liquidity_provider_fee_a + fees.liquidity_provider_fee_a,
+        liquidity_provider_fee_b: total.liquidity_provider_fee_b + fees.liquidity_provider_fee_b,
+        protocol_fee_a: total.protocol_fee_a + fees.protocol_fee_a,
+        protocol_fee_b: total.protocol_fee_b + fees.protocol_fee_b,
+        min_fee_rate: total.min_fee_rate.min(fees.min_fee_rate),
+        max_fee_rate: total.max_fee_rate.max(fees.max_fee_rate),
+        vwap_fee_rate: total.vwap_fee_rate,
+        estimated_trade_count: total.estimated_trade_count + fees.estimated_trade_count,
+      }),
+      (total, fees) => total.or(fees),
+    };
+  }
+
+  fn add_averages(&mut self, averages: &DayAverages) {
+    self.twap = match (self.twap.take(), averages.twap) {
+      (Some((price_seconds, seconds)), Some(twap)) => Some((price_seconds + data::price_seconds(twap, averages.seconds), seconds + averages.seconds as u128)),
+      _ => None,
+    };
+    if let Some(vwap_fee_rate) = averages.vwap_fee_rate {
+      self.fee_rate_amount_a += vwap_fee_rate as u128 * averages.amount_a;
+      self.amount_a += averages.amount_a;
+    }
+  }
+
+  fn into_rollup_data(self, period_timestamp: i64) -> ohlcv::WhirlpoolOhlcvRollupData {
+    let mut data = self.data;
+    let decimals_a = data.metadata.token_a.decimals;
+    let decimals_b = data.metadata.token_b.decimals;
+
+    let period = &mut data.daily;
+    period.timestamp = period_timestamp;
+    let total_a = period.volume.ab.total_in + period.volume.ba.total_out;
+    let total_b = period.volume.ab.total_out + period.volume.ba.total_in;
+    period.vwap = data::vwap(total_a, total_b).map(|vwap| data::average_price_data(vwap, decimals_a, decimals_b));
+    period.twap = self.twap
+      .filter(|(_, seconds)| *seconds > 0)
+      .map(|(price_seconds, seconds)| data::average_price_data(data::twap(&price_seconds, seconds), decimals_a, decimals_b));
+    if let (Some(fees), Some(vwap_fee_rate)) = (period.fees.as_mut(), self.fee_rate_amount_a.checked_div(self.amount_a)) {
+      fees.vwap_fee_rate = vwap_fee_rate as u32;
+    }
+    // distinct traders and positions of the period are unknown
+    period.traders = None;
+    if let Some(liquidity) = data.liquidity.as_mut() {
+      liquidity.position_count = None;
+    }
+
+    ohlcv::WhirlpoolOhlcvRollupData {
+      metadata: data.metadata,
+      initial_state: data.initial_state,
+      estimated_fees: data.estimated_fees,
+      liquidity: data.liquidity,
+      period: data.daily,
+      day_count: self.day_count,
+    }
+  }
+}
+
+// weights of the averages of a day
+struct DayAverages {
+  // seconds priced in the day (from the initialization for new pools)
+  seconds: i64,
+  twap: Option<u128>,
+  vwap_fee_rate: Option<u32>,
+  amount_a: u128,
+}
+
+impl From<&ohlcv::WhirlpoolOhlcvDailyData> for DayAverages {
+  fn from(data: &ohlcv::WhirlpoolOhlcvDailyData) -> Self {
+    let day_end = data.daily.timestamp + SECONDS_PER_DAY;
+    let priced_from = match data.initial_state {
+      ohlcv::InitialState::Existing { .. } => data.daily.timestamp,
+      ohlcv::InitialState::New { initialized_block_time, .. } => initialized_block_time.max(data.daily.timestamp),
+    };
+    Self {
+      seconds: (day_end - priced_from).max(1),
+      twap: data.daily.twap.as_ref().map(|twap| twap.sqrt_price),
+      vwap_fee_rate: data.daily.fees.as_ref().map(|fees| fees.vwap_fee_rate),
+      amount_a: data.daily.volume.ab.total_in + data.daily.volume.ba.total_out,
+    }
+  }
+}
+
+fn add_volume(total: &mut ohlcv::VolumeDirectionData, volume: &ohlcv::VolumeDirectionData) {
+  total.total_in += volume.total_in;
+  total.total_out += volume.total_out;
+  total.count += volume.count;
+}
+
+fn merge_liquidity(total: ohlcv::LiquidityData, liquidity: ohlcv::LiquidityData) -> ohlcv::LiquidityData {
+  ohlcv::LiquidityData {
+    ohlc: ohlcv::LiquidityOhlcData {
+      open: total.ohlc.open,
+      high: total.ohlc.high.max(liquidity.ohlc.high),
+      low: total.ohlc.low.min(liquidity.ohlc.low),
+      close: liquidity.ohlc.close,
+    },
+    deposit_count: total.deposit_count + liquidity.deposit_count,
+    withdrawal_count: total.withdrawal_count + liquidity.withdrawal_count,
+    reposition_count: total.reposition_count + liquidity.reposition_count,
+    added_a: total.added_a + liquidity.added_a,
+    added_b: total.added_b + liquidity.added_b,
+    removed_a: total.removed_a + liquidity.removed_a,
+    removed_b: total.removed_b + liquidity.removed_b,
+    position_count: None,
+  }
+}
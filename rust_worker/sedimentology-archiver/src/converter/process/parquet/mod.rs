@@ -0,0 +1,242 @@
+use super::super::model::event::reader::WhirlpoolEventReader;
+use super::super::model::long_key::{long_variant_name, LongKey};
+use super::super::model::ohlcv::{WhirlpoolOhlcvDailyData, WhirlpoolOhlcvMinutelyData};
+use super::super::model::parquet::ParquetTable;
+use crate::schema::WhirlpoolTransaction;
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::{
+  collections::HashMap,
+  fs::File,
+  io::{BufRead, BufReader},
+};
+
+mod table;
+
+use table::{flatten, ColumnType, Row, TableSchema, TableWriter};
+
+// columns of the ohlcv files which are repeated on each minute row of ohlcv-minutely table
+const OHLCV_METADATA_KEYS: [&str; 5] = ["whirlpool", "whirlpoolsConfig", "tokenA", "tokenB", "tickSpacing"];
+
+// build whirlpool-transaction-instruction-yyyymmdd.parquet and whirlpool-transaction-balance-yyyymmdd.parquet from transaction file
+pub fn process_transaction(
+  in_whirlpool_transaction_file_path: String,
+  out_whirlpool_transaction_instruction_file_path: String,
+  out_whirlpool_transaction_balance_file_path: String,
+) -> Result<()> {
+  let base_columns = [
+    ("slot", ColumnType::Int64),
+    ("block_height", ColumnType::Int64),
+    ("block_time", ColumnType::Int64),
+    ("transaction_index", ColumnType::Int64),
+    ("signature", ColumnType::Utf8),
+    ("payer", ColumnType::Utf8),
+  ];
+  let tables = vec![
+    (ParquetTable::TransactionInstruction, TableSchema::new(&base_columns), out_whirlpool_transaction_instruction_file_path),
+    (ParquetTable::TransactionBalance, TableSchema::new(&base_columns), out_whirlpool_transaction_balance_file_path),
+  ];
+
+  write_tables(tables, || {
+    let reader = read_jsonl_file::<WhirlpoolTransaction>(&in_whirlpool_transaction_file_path)?;
+    Ok(reader.flat_map(|block| match block {
+      Ok(block) => transaction_rows(block).into_iter().map(Ok).collect::<Vec<_>>(),
+      Err(err) => vec![Err(err)],
+    }))
+  })
+}
+
+fn transaction_rows(block: WhirlpoolTransaction) -> Vec<(ParquetTable, Row)> {
+  let mut rows = vec![];
+  for transaction in block.transactions {
+    let base = vec![
+      ("slot".to_string(), Value::from(block.slot)),
+      ("block_height".to_string(), Value::from(block.block_height)),
+      ("block_time".to_string(), Value::from(block.block_time)),
+      ("transaction_index".to_string(), Value::from(transaction.index)),
+      ("signature".to_string(), Value::from(transaction.signature)),
+      ("payer".to_string(), Value::from(transaction.payer)),
+    ];
+
+    for (index, instruction) in transaction.instructions.into_iter().enumerate() {
+      let mut row = base.clone();
+      row.push(("instruction_index".to_string(), Value::from(index)));
+      row.push(("name".to_string(), Value::from(instruction.name)));
+      // payload differs by instruction, so it is kept as JSON
+      row.push(("payload".to_string(), Value::from(instruction.payload.to_string())));
+      rows.push((ParquetTable::TransactionInstruction, row));
+    }
+
+    for balance in transaction.balances {
+      let mut row = base.clone();
+      row.push(("account".to_string(), Value::from(balance.account)));
+      // u64 as string (same as the transaction file)
+      row.push(("pre".to_string(), Value::from(balance.pre.to_string())));
+      row.push(("post".to_string(), Value::from(balance.post.to_string())));
+      rows.push((ParquetTable::TransactionBalance, row));
+    }
+  }
+  rows
+}
+
+// build whirlpool-event-<event>-yyyymmdd.parquet (one file for each event type) from event file
+pub fn process_event(
+  in_whirlpool_event_file_path: String,
+  out_whirlpool_event_file_path: impl Fn(ParquetTable) -> String,
+) -> Result<()> {
+  let base_columns = [
+    ("slot", ColumnType::Int64),
+    ("block_height", ColumnType::Int64),
+    ("block_time", ColumnType::Int64),
+    ("signature", ColumnType::Utf8),
+    ("payer", ColumnType::Utf8),
+  ];
+
+  // long event name (e.g. Traded) to table
+  let mut event_tables = HashMap::new();
+  let mut tables = vec![];
+  for table in ParquetTable::event_tables() {
+    let ParquetTable::Event(event_table) = table else { unreachable!() };
+    let name = long_variant_name("WhirlpoolEvent", event_table.event).unwrap_or(event_table.event);
+    event_tables.insert(name, table);
+    tables.push((table, TableSchema::new(&base_columns), out_whirlpool_event_file_path(table)));
+  }
+
+  let event_tables = &event_tables;
+  write_tables(tables, || {
+    // event files of any format version
+    let reader = WhirlpoolEventReader::open(&in_whirlpool_event_file_path)?;
+    Ok(reader.flat_map(move |block| match block {
+      Ok(block) => {
+        let mut rows = vec![];
+        for transaction in block.transactions {
+          for event in transaction.events {
+            let row = serde_json::to_value(LongKey::new(&event)).map_err(anyhow::Error::from).and_then(|mut value| {
+              let name = value["name"].as_str().unwrap_or_default().to_string();
+              let table = *event_tables.get(name.as_str()).ok_or_else(|| anyhow!("no table for event {}", name))?;
+
+              let mut row = vec![
+                ("slot".to_string(), Value::from(block.slot)),
+                ("block_height".to_string(), Value::from(block.block_height)),
+                ("block_time".to_string(), Value::from(block.block_time)),
+                ("signature".to_string(), Value::from(transaction.signature.clone())),
+                ("payer".to_string(), Value::from(transaction.payer.clone())),
+              ];
+              flatten("", value["payload"].take(), &mut row);
+              Ok((table, row))
+            });
+            rows.push(row);
+          }
+        }
+        rows
+      }
+      Err(err) => vec![Err(err)],
+    }))
+  })
+}
+
+// build whirlpool-ohlcv-daily-yyyymmdd.parquet from ohlcv daily file
+pub fn process_ohlcv_daily(
+  in_whirlpool_ohlcv_daily_file_path: String,
+  out_whirlpool_ohlcv_daily_file_path: String,
+) -> Result<()> {
+  let tables = vec![
+    (ParquetTable::OhlcvDaily, TableSchema::new(&[]), out_whirlpool_ohlcv_daily_file_path),
+  ];
+
+  write_tables(tables, || {
+    let reader = read_jsonl_file::<WhirlpoolOhlcvDailyData>(&in_whirlpool_ohlcv_daily_file_path)?;
+    Ok(reader.map(|data| {
+      let mut row = vec![];
+      flatten("", serde_json::to_value(LongKey::new(&data?))?, &mut row);
+      Ok((ParquetTable::OhlcvDaily, row))
+    }))
+  })
+}
+
+// build whirlpool-ohlcv-minutely-yyyymmdd.parquet from ohlcv minutely file
+pub fn process_ohlcv_minutely(
+  in_whirlpool_ohlcv_minutely_file_path: String,
+  out_whirlpool_ohlcv_minutely_file_path: String,
+) -> Result<()> {
+  let tables = vec![
+    (ParquetTable::OhlcvMinutely, TableSchema::new(&[]), out_whirlpool_ohlcv_minutely_file_path),
+  ];
+
+  write_tables(tables, || {
+    let reader = read_jsonl_file::<WhirlpoolOhlcvMinutelyData>(&in_whirlpool_ohlcv_minutely_file_path)?;
+    Ok(reader.flat_map(|data| match data.and_then(ohlcv_minutely_rows) {
+      Ok(rows) => rows.into_iter().map(Ok).collect::<Vec<_>>(),
+      Err(err) => vec![Err(err)],
+    }))
+  })
+}
+
+fn ohlcv_minutely_rows(data: WhirlpoolOhlcvMinutelyData) -> Result<Vec<(ParquetTable, Row)>> {
+  let mut value = serde_json::to_value(LongKey::new(&data))?;
+
+  let mut metadata = vec![];
+  let metadata_value = OHLCV_METADATA_KEYS.iter().map(|key| (key.to_string(), value[*key].take())).collect();
+  flatten("", Value::Object(metadata_value), &mut metadata);
+
+  let minutely = match value["minutely"].take() {
+    Value::Array(minutely) => minutely,
+    _ => vec![],
+  };
+  Ok(minutely.into_iter().map(|minute| {
+    let mut row = metadata.clone();
+    flatten("", minute, &mut row);
+    (ParquetTable::OhlcvMinutely, row)
+  }).collect())
+}
+
+// the rows are read twice (the first pass infers the columns) to keep memory usage bounded
+fn write_tables<I>(
+  tables: Vec<(ParquetTable, TableSchema, String)>,
+  rows: impl Fn() -> Result<I>,
+) -> Result<()>
+where
+  I: Iterator<Item = Result<(ParquetTable, Row)>>,
+{
+  let mut schemas = HashMap::new();
+  let mut paths = vec![];
+  for (table, schema, path) in tables {
+    schemas.insert(table, schema);
+    paths.push((table, path));
+  }
+
+  for row in rows()? {
+    let (table, row) = row?;
+    let schema = schemas.get_mut(&table).ok_or_else(|| anyhow!("unexpected table {}", table.name()))?;
+    schema.observe(&row);
+  }
+
+  let mut writers = HashMap::new();
+  for (table, path) in paths.iter() {
+    writers.insert(*table, TableWriter::create(path, &schemas[table])?);
+  }
+
+  for row in rows()? {
+    let (table, row) = row?;
+    writers.get_mut(&table).unwrap().push(row)?;
+  }
+
+  for (table, _) in paths.iter() {
+    writers.remove(table).unwrap().close()?;
+  }
+
+  Ok(())
+}
+
+fn read_jsonl_file<T: DeserializeOwned>(path: &str) -> Result<impl Iterator<Item = Result<T>>> {
+  let file = File::open(path).with_context(|| format!("failed to open {}", path))?;
+  let lines = BufReader::new(GzDecoder::new(file)).lines();
+
+  Ok(lines.enumerate().filter_map(|(index, line)| match line {
+    Ok(line) if line.trim().is_empty() => None,
+    Ok(line) => Some(serde_json::from_str(&line).with_context(|| format!("line {}", index + 1))),
+    Err(err) => Some(Err(err.into())),
+  }))
+}
@@ -0,0 +1,207 @@
+use anyhow::{Context, Result};
+use parquet::basic::{Compression, LogicalType, Repetition, Type as PhysicalType};
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::types::Type;
+use serde_json::Value;
+use std::{collections::HashMap, fs::File, sync::Arc};
+
+// buffered rows are written as a row group
+const ROW_GROUP_SIZE: usize = 100_000;
+
+// (column name, value) pairs of a row
+pub type Row = Vec<(String, Value)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+  Int64,
+  Boolean,
+  Utf8,
+}
+
+impl ColumnType {
+  fn of(value: &Value) -> Option<ColumnType> {
+    match value {
+      Value::Null => None,
+      Value::Bool(_) => Some(ColumnType::Boolean),
+      Value::Number(number) if number.is_i64() => Some(ColumnType::Int64),
+      // strings, u64 over i64::MAX, arrays (JSON encoding)
+      _ => Some(ColumnType::Utf8),
+    }
+  }
+
+  fn merge(self, other: ColumnType) -> ColumnType {
+    if self == other { self } else { ColumnType::Utf8 }
+  }
+}
+
+// columns of a table in the order of appearance (types are inferred from the values)
+pub struct TableSchema {
+  columns: Vec<(String, Option<ColumnType>)>,
+  index: HashMap<String, usize>,
+}
+
+impl TableSchema {
+  // the base columns are included even if the table has no rows
+  pub fn new(base_columns: &[(&str, ColumnType)]) -> TableSchema {
+    let mut schema = TableSchema { columns: vec![], index: HashMap::new() };
+    for (name, column_type) in base_columns {
+      schema.index.insert(name.to_string(), schema.columns.len());
+      schema.columns.push((name.to_string(), Some(*column_type)));
+    }
+    schema
+  }
+
+  pub fn observe(&mut self, row: &Row) {
+    for (name, value) in row {
+      let observed = ColumnType::of(value);
+      match self.index.get(name) {
+        Some(&index) => {
+          let column_type = &mut self.columns[index].1;
+          *column_type = match (*column_type, observed) {
+            (Some(current), Some(observed)) => Some(current.merge(observed)),
+            (current, observed) => current.or(observed),
+          };
+        }
+        None => {
+          self.index.insert(name.clone(), self.columns.len());
+          self.columns.push((name.clone(), observed));
+        }
+      }
+    }
+  }
+
+  fn parquet_schema(&self) -> Result<Type> {
+    let mut fields = vec![];
+    for (name, column_type) in self.columns.iter() {
+      // null on all rows
+      let field = match column_type.unwrap_or(ColumnType::Utf8) {
+        ColumnType::Int64 => Type::primitive_type_builder(name, PhysicalType::INT64),
+        ColumnType::Boolean => Type::primitive_type_builder(name, PhysicalType::BOOLEAN),
+        ColumnType::Utf8 => Type::primitive_type_builder(name, PhysicalType::BYTE_ARRAY)
+          .with_logical_type(Some(LogicalType::String)),
+      };
+      fields.push(Arc::new(field.with_repetition(Repetition::OPTIONAL).build()?));
+    }
+    Ok(Type::group_type_builder("schema").with_fields(fields).build()?)
+  }
+}
+
+pub struct TableWriter {
+  columns: Vec<(String, ColumnType)>,
+  index: HashMap<String, usize>,
+  buffers: Vec<Vec<Option<Value>>>,
+  rows: usize,
+  writer: SerializedFileWriter<File>,
+}
+
+impl TableWriter {
+  pub fn create(path: &str, schema: &TableSchema) -> Result<TableWriter> {
+    let file = File::create(path).with_context(|| format!("failed to create {}", path))?;
+    let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let writer = SerializedFileWriter::new(file, Arc::new(schema.parquet_schema()?), Arc::new(properties))?;
+
+    let columns = schema.columns
+      .iter()
+      .map(|(name, column_type)| (name.clone(), column_type.unwrap_or(ColumnType::Utf8)))
+      .collect::<Vec<_>>();
+    Ok(TableWriter {
+      buffers: vec![vec![]; columns.len()],
+      index: schema.index.clone(),
+      columns,
+      rows: 0,
+      writer,
+    })
+  }
+
+  pub fn push(&mut self, row: Row) -> Result<()> {
+    self.buffers.iter_mut().for_each(|buffer| buffer.push(None));
+    for (name, value) in row {
+      let index = *self.index.get(&name).with_context(|| format!("unknown column {}", name))?;
+      if !value.is_null() {
+        self.buffers[index][self.rows] = Some(value);
+      }
+    }
+    self.rows += 1;
+
+    if self.rows >= ROW_GROUP_SIZE {
+      self.flush()?;
+    }
+    Ok(())
+  }
+
+  pub fn close(mut self) -> Result<()> {
+    self.flush()?;
+    self.writer.close()?;
+    Ok(())
+  }
+
+  fn flush(&mut self) -> Result<()> {
+    if self.rows == 0 {
+      return Ok(());
+    }
+
+    let mut row_group = self.writer.next_row_group()?;
+    for ((name, column_type), buffer) in self.columns.iter().zip(self.buffers.iter_mut()) {
+      let values = std::mem::take(buffer);
+      // definition level: 1 (value) or 0 (null)
+      let def_levels = values.iter().map(|value| value.is_some() as i16).collect::<Vec<_>>();
+      let values = values.into_iter().flatten();
+
+      let mut column = row_group.next_column()?.with_context(|| format!("no column writer for {}", name))?;
+      match column_type {
+        ColumnType::Int64 => {
+          let values = values.map(|value| value.as_i64().unwrap()).collect::<Vec<_>>();
+          column.typed::<Int64Type>().write_batch(&values, Some(&def_levels), None)?;
+        }
+        ColumnType::Boolean => {
+          let values = values.map(|value| value.as_bool().unwrap()).collect::<Vec<_>>();
+          column.typed::<BoolType>().write_batch(&values, Some(&def_levels), None)?;
+        }
+        ColumnType::Utf8 => {
+          let values = values.map(|value| match value {
+            Value::String(string) => ByteArray::from(string.into_bytes()),
+            value => ByteArray::from(value.to_string().into_bytes()),
+          }).collect::<Vec<_>>();
+          column.typed::<ByteArrayType>().write_batch(&values, Some(&def_levels), None)?;
+        }
+      }
+      column.close()?;
+    }
+    row_group.close()?;
+
+    self.rows = 0;
+    Ok(())
+  }
+}
+
+// nested objects are flattened with "_" (e.g. transferIn.amount to transfer_in_amount)
+pub fn flatten(prefix: &str, value: Value, row: &mut Row) {
+  match value {
+    Value::Object(map) => {
+      for (key, value) in map {
+        flatten(&column_name(prefix, &key), value, row);
+      }
+    }
+    value => row.push((prefix.to_string(), value)),
+  }
+}
+
+fn column_name(prefix: &str, key: &str) -> String {
+  let mut name = String::from(prefix);
+  if !name.is_empty() {
+    name.push('_');
+  }
+  for (i, c) in key.chars().enumerate() {
+    if c.is_ascii_uppercase() {
+      if i > 0 {
+        name.push('_');
+      }
+      name.push(c.to_ascii_lowercase());
+    } else {
+      name.push(c);
+    }
+  }
+  name
+}
@@ -0,0 +1,251 @@
+use super::super::super::model::event::{definition::TransferInfo, reader::WhirlpoolEventReader, WhirlpoolEvent};
+use super::super::super::model::long_key::{self, KeyFormat};
+use super::super::super::model::ohlcv::TokenData;
+use super::super::super::model::position::{LedgerAmountData, LedgerBlockData, LedgerEntryData, LedgerEntryType, LedgerRewardData, RealizedPnlData, WhirlpoolPositionLedgerData};
+use super::super::long_key::read_short_key_file;
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use flate2::write::GzEncoder;
+use std::collections::BTreeMap;
+use std::{
+  fs::File,
+  io::{LineWriter, Write},
+};
+
+// build whirlpool-position-ledger-yyyymmdd.jsonl.gz from the ledger of the previous date (None for the first date) and event file
+pub fn process(
+  in_previous_whirlpool_position_ledger_file_path: Option<String>,
+  in_whirlpool_event_file_path: String,
+  out_whirlpool_position_ledger_file_path: String,
+  key_format: KeyFormat,
+) -> Result<()> {
+  // position -> ledger (sorted to make the result deterministic)
+  let mut ledgers: BTreeMap<String, WhirlpoolPositionLedgerData> = BTreeMap::new();
+  // positions closed in the day and reopened at the same address (bundled positions), in the order of closing
+  let mut reopened: Vec<WhirlpoolPositionLedgerData> = vec![];
+
+  if let Some(in_previous_whirlpool_position_ledger_file_path) = in_previous_whirlpool_position_ledger_file_path {
+    println!("read {} ...", in_previous_whirlpool_position_ledger_file_path);
+    for ledger in read_short_key_file::<WhirlpoolPositionLedgerData>(&in_previous_whirlpool_position_ledger_file_path)? {
+      let mut ledger = ledger?;
+      if ledger.closed.is_some() {
+        continue;
+      }
+      ledger.entries.clear();
+      ledgers.insert(ledger.position.clone(), ledger);
+    }
+  }
+
+  println!("read {} ...", in_whirlpool_event_file_path);
+  // event files of any format version
+  let reader = WhirlpoolEventReader::open(&in_whirlpool_event_file_path)?;
+  for event_block in reader {
+    let event_block = event_block?;
+    for transaction in event_block.transactions.iter() {
+      let block = LedgerBlockData { slot: event_block.slot, block_time: event_block.block_time };
+      for event in transaction.events.iter() {
+        let mut entry = LedgerEntryData {
+          slot: event_block.slot,
+          block_time: event_block.block_time,
+          signature: transaction.signature.clone(),
+          entry_type: LedgerEntryType::Opened,
+          liquidity: None,
+          lower_tick_index: None,
+          upper_tick_index: None,
+          deposited: None,
+          withdrawn: None,
+          fees: None,
+          reward: None,
+        };
+
+        let ledger = match event {
+          WhirlpoolEvent::PositionOpened(payload) => {
+            if let Some(closed) = ledgers.remove(&payload.position).filter(|ledger| ledger.closed.is_some()) {
+              reopened.push(closed);
+            }
+            let ledger = ledger_of(&mut ledgers, &payload.position, &payload.whirlpool);
+            ledger.opened = Some(LedgerBlockData { slot: block.slot, block_time: block.block_time });
+            ledger.lower_tick_index = Some(payload.lower_tick_index);
+            ledger.upper_tick_index = Some(payload.upper_tick_index);
+            ledger.liquidity = Some(0);
+            ledger
+          }
+          WhirlpoolEvent::LiquidityDeposited(payload) => {
+            let ledger = ledger_of(&mut ledgers, &payload.position, &payload.whirlpool);
+            update_tokens(ledger, &payload.transfer_a, &payload.transfer_b);
+            ledger.lower_tick_index = Some(payload.lower_tick_index);
+            ledger.upper_tick_index = Some(payload.upper_tick_index);
+            ledger.liquidity = Some(payload.new_position_liquidity);
+            ledger.decimal_price = Some(payload.whirlpool_decimal_price.clone());
+            let deposited = amount_data(payload.transfer_a.amount, payload.transfer_b.amount);
+            add_amount(&mut ledger.deposited, &deposited);
+            entry.entry_type = LedgerEntryType::Deposited;
+            entry.deposited = Some(deposited);
+            ledger
+          }
+          WhirlpoolEvent::LiquidityWithdrawn(payload) => {
+            let ledger = ledger_of(&mut ledgers, &payload.position, &payload.whirlpool);
+            update_tokens(ledger, &payload.transfer_a, &payload.transfer_b);
+            ledger.lower_tick_index = Some(payload.lower_tick_index);
+            ledger.upper_tick_index = Some(payload.upper_tick_index);
+            ledger.liquidity = Some(payload.new_position_liquidity);
+            ledger.decimal_price = Some(payload.whirlpool_decimal_price.clone());
+            let withdrawn = amount_data(payload.transfer_a.amount, payload.transfer_b.amount);
+            add_amount(&mut ledger.withdrawn, &withdrawn);
+            entry.entry_type = LedgerEntryType::Withdrawn;
+            entry.withdrawn = Some(withdrawn);
+            ledger
+          }
+          WhirlpoolEvent::LiquidityRepositioned(payload) => {
+            let ledger = ledger_of(&mut ledgers, &payload.position, &payload.whirlpool);
+            update_tokens(ledger, &payload.transfer_a, &payload.transfer_b);
+            ledger.lower_tick_index = Some(payload.new_lower_tick_index);
+            ledger.upper_tick_index = Some(payload.new_upper_tick_index);
+            ledger.liquidity = Some(payload.new_position_liquidity);
+            ledger.decimal_price = Some(payload.whirlpool_decimal_price.clone());
+            // tokens are transferred from the owner or returned to the owner for each token
+            let mut deposited = LedgerAmountData::default();
+            let mut withdrawn = LedgerAmountData::default();
+            match payload.transfer_from_owner_a {
+              true => deposited.amount_a = payload.transfer_a.amount as u128,
+              false => withdrawn.amount_a = payload.transfer_a.amount as u128,
+            }
+            match payload.transfer_from_owner_b {
+              true => deposited.amount_b = payload.transfer_b.amount as u128,
+              false => withdrawn.amount_b = payload.transfer_b.amount as u128,
+            }
+            add_amount(&mut ledger.deposited, &deposited);
+            add_amount(&mut ledger.withdrawn, &withdrawn);
+            entry.entry_type = LedgerEntryType::Repositioned;
+            entry.deposited = Some(deposited).filter(|amount| !is_zero(amount));
+            entry.withdrawn = Some(withdrawn).filter(|amount| !is_zero(amount));
+            ledger
+          }
+          WhirlpoolEvent::PositionFeesHarvested(payload) => {
+            let ledger = ledger_of(&mut ledgers, &payload.position, &payload.whirlpool);
+            update_tokens(ledger, &payload.transfer_a, &payload.transfer_b);
+            let fees = amount_data(payload.transfer_a.amount, payload.transfer_b.amount);
+            add_amount(&mut ledger.fees, &fees);
+            entry.entry_type = LedgerEntryType::FeesHarvested;
+            entry.fees = Some(fees);
+            ledger
+          }
+          WhirlpoolEvent::PositionRewardHarvested(payload) => {
+            let ledger = ledger_of(&mut ledgers, &payload.position, &payload.whirlpool);
+            let amount = payload.transfer_reward.amount as u128;
+            match ledger.rewards.iter_mut().find(|reward| reward.index == payload.reward_index) {
+              Some(reward) => reward.amount += amount,
+              None => {
+                ledger.rewards.push(LedgerRewardData { index: payload.reward_index, mint: payload.transfer_reward.mint.clone(), amount });
+                ledger.rewards.sort_by_key(|reward| reward.index);
+              }
+            }
+            entry.entry_type = LedgerEntryType::RewardHarvested;
+            entry.reward = Some(LedgerRewardData { index: payload.reward_index, mint: payload.transfer_reward.mint.clone(), amount });
+            ledger
+          }
+          WhirlpoolEvent::PositionRangeReset(payload) => {
+            let ledger = ledger_of(&mut ledgers, &payload.position, &payload.whirlpool);
+            ledger.lower_tick_index = Some(payload.new_lower_tick_index);
+            ledger.upper_tick_index = Some(payload.new_upper_tick_index);
+            entry.entry_type = LedgerEntryType::RangeReset;
+            ledger
+          }
+          WhirlpoolEvent::PositionClosed(payload) => {
+            let ledger = ledger_of(&mut ledgers, &payload.position, &payload.whirlpool);
+            ledger.closed = Some(LedgerBlockData { slot: block.slot, block_time: block.block_time });
+            ledger.lower_tick_index = Some(payload.lower_tick_index);
+            ledger.upper_tick_index = Some(payload.upper_tick_index);
+            // only empty positions can be closed
+            ledger.liquidity = Some(0);
+            ledger.realized_pnl = realized_pnl(ledger);
+            entry.entry_type = LedgerEntryType::Closed;
+            ledger
+          }
+          _ => continue,
+        };
+
+        entry.liquidity = ledger.liquidity;
+        entry.lower_tick_index = ledger.lower_tick_index;
+        entry.upper_tick_index = ledger.upper_tick_index;
+        ledger.entries.push(entry);
+      }
+    }
+  }
+
+  // the closed position is written before the reopened one (stable sort)
+  let mut lines = reopened;
+  lines.extend(ledgers.into_values());
+  lines.sort_by(|a, b| a.position.cmp(&b.position));
+
+  println!("write position ledger file...");
+  let f = File::create(out_whirlpool_position_ledger_file_path)?;
+  let encoder = GzEncoder::new(f, flate2::Compression::default());
+  let mut writer = LineWriter::new(encoder);
+  for ledger in lines {
+    let jsonl = long_key::to_string(&ledger, key_format)?;
+    writer.write_all(jsonl.as_bytes())?;
+    writer.write_all(b"\n")?;
+  }
+  writer.flush()?;
+
+  Ok(())
+}
+
+// ledger of the position (positions opened before the ledger started have no opened)
+fn ledger_of<'a>(ledgers: &'a mut BTreeMap<String, WhirlpoolPositionLedgerData>, position: &str, whirlpool: &str) -> &'a mut WhirlpoolPositionLedgerData {
+  ledgers.entry(position.to_string()).or_insert_with(|| WhirlpoolPositionLedgerData {
+    position: position.to_string(),
+    whirlpool: whirlpool.to_string(),
+    token_a: None,
+    token_b: None,
+    opened: None,
+    closed: None,
+    lower_tick_index: None,
+    upper_tick_index: None,
+    liquidity: None,
+    decimal_price: None,
+    deposited: LedgerAmountData::default(),
+    withdrawn: LedgerAmountData::default(),
+    fees: LedgerAmountData::default(),
+    rewards: vec![],
+    realized_pnl: None,
+    entries: vec![],
+  })
+}
+
+fn update_tokens(ledger: &mut WhirlpoolPositionLedgerData, transfer_a: &TransferInfo, transfer_b: &TransferInfo) {
+  ledger.token_a.get_or_insert_with(|| TokenData { mint: transfer_a.mint.clone(), decimals: transfer_a.decimals });
+  ledger.token_b.get_or_insert_with(|| TokenData { mint: transfer_b.mint.clone(), decimals: transfer_b.decimals });
+}
+
+fn amount_data(amount_a: u64, amount_b: u64) -> LedgerAmountData {
+  LedgerAmountData { amount_a: amount_a as u128, amount_b: amount_b as u128 }
+}
+
+fn add_amount(total: &mut LedgerAmountData, amount: &LedgerAmountData) {
+  total.amount_a += amount.amount_a;
+  total.amount_b += amount.amount_b;
+}
+
+fn is_zero(amount: &LedgerAmountData) -> bool {
+  amount.amount_a == 0 && amount.amount_b == 0
+}
+
+// withdrawn + fees - deposited (None if the position was opened before the ledger started)
+fn realized_pnl(ledger: &WhirlpoolPositionLedgerData) -> Option<RealizedPnlData> {
+  ledger.opened.as_ref()?;
+
+  let amount_a = (ledger.withdrawn.amount_a + ledger.fees.amount_a) as i128 - ledger.deposited.amount_a as i128;
+  let amount_b = (ledger.withdrawn.amount_b + ledger.fees.amount_b) as i128 - ledger.deposited.amount_b as i128;
+  let value = match (&ledger.token_a, &ledger.token_b, &ledger.decimal_price) {
+    (Some(token_a), Some(token_b), Some(decimal_price)) => Some(ui_amount(amount_a, token_a.decimals) * decimal_price + ui_amount(amount_b, token_b.decimals)),
+    _ => None,
+  };
+
+  Some(RealizedPnlData { amount_a, amount_b, value })
+}
+
+fn ui_amount(amount: i128, decimals: u8) -> BigDecimal {
+  BigDecimal::from(amount) / BigDecimal::from(10u128.pow(decimals as u32))
+}
@@ -0,0 +1,4 @@
+pub mod ledger;
+pub mod snapshot;
+pub mod valuation;
+mod range;
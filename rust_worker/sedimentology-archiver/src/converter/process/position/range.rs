@@ -0,0 +1,89 @@
+use super::super::super::model::position::{LedgerEntryType, WhirlpoolPositionLedgerData, WhirlpoolPositionRangeData};
+use super::super::ohlcv::{OhlcvDataManager, DAILY_SECONDS, MINUTELY_SECONDS};
+use std::collections::HashMap;
+
+// minutes in range of each range of the positions (in the order of the ledgers, ranges in time order)
+pub fn positions(manager: &OhlcvDataManager, ledgers: &[WhirlpoolPositionLedgerData]) -> Vec<WhirlpoolPositionRangeData> {
+  let day_end = manager.timestamp + DAILY_SECONDS;
+  // whirlpool -> tick series (shared by the positions of the pool)
+  let mut tick_series: HashMap<&str, Vec<(i64, i32)>> = HashMap::new();
+
+  let mut ranges = vec![];
+  for ledger in ledgers.iter() {
+    let Some(data) = manager.data.get(&ledger.whirlpool) else {
+      continue;
+    };
+    let ticks = tick_series.entry(ledger.whirlpool.as_str()).or_insert_with(|| data.tick_series());
+
+    for (from, to, lower_tick_index, upper_tick_index) in range_segments(ledger, manager.timestamp, day_end) {
+      let mut tracked_seconds = 0;
+      let mut in_range_seconds = 0;
+      // the tick standing at the beginning of the segment (none before the initialization of the pool)
+      let start = ticks.partition_point(|(tick_from, _)| *tick_from <= from).saturating_sub(1);
+      for (i, (tick_from, tick_index)) in ticks.iter().enumerate().skip(start) {
+        if *tick_from >= to {
+          break;
+        }
+        let tick_to = ticks.get(i + 1).map(|(next_from, _)| *next_from).unwrap_or(day_end);
+        let seconds = tick_to.min(to) - (*tick_from).max(from);
+        if seconds <= 0 {
+          continue;
+        }
+        tracked_seconds += seconds;
+        if lower_tick_index <= *tick_index && *tick_index < upper_tick_index {
+          in_range_seconds += seconds;
+        }
+      }
+
+      if tracked_seconds == 0 {
+        continue;
+      }
+
+      ranges.push(WhirlpoolPositionRangeData {
+        position: ledger.position.clone(),
+        whirlpool: ledger.whirlpool.clone(),
+        timestamp: manager.timestamp,
+        start_time: from,
+        end_time: to,
+        lower_tick_index,
+        upper_tick_index,
+        tracked_minutes: (tracked_seconds / MINUTELY_SECONDS) as u32,
+        in_range_minutes: (in_range_seconds / MINUTELY_SECONDS) as u32,
+      });
+    }
+  }
+  ranges
+}
+
+// (from, to, lower tick index, upper tick index) of each range while the position is open and its range is known in the day
+fn range_segments(ledger: &WhirlpoolPositionLedgerData, day_start: i64, day_end: i64) -> Vec<(i64, i64, i32, i32)> {
+  // range at the beginning of the day is the range before the first entry
+  let mut range = match ledger.entries.first() {
+    None => ledger.lower_tick_index.zip(ledger.upper_tick_index),
+    // not open before the opening, the range before a range change is unknown
+    Some(entry) if matches!(entry.entry_type, LedgerEntryType::Opened | LedgerEntryType::Repositioned | LedgerEntryType::RangeReset) => None,
+    Some(entry) => entry.lower_tick_index.zip(entry.upper_tick_index),
+  };
+  let mut since = day_start;
+
+  let mut segments = vec![];
+  for entry in ledger.entries.iter() {
+    let next_range = match entry.entry_type {
+      LedgerEntryType::Closed => None,
+      _ => entry.lower_tick_index.zip(entry.upper_tick_index),
+    };
+    // entries keeping the range (deposits, harvests, ...) do not split the segment
+    if next_range.is_some() && next_range == range {
+      continue;
+    }
+    if let Some((lower_tick_index, upper_tick_index)) = range {
+      segments.push((since, entry.block_time, lower_tick_index, upper_tick_index));
+    }
+    since = entry.block_time;
+    range = next_range;
+  }
+  if let Some((lower_tick_index, upper_tick_index)) = range {
+    segments.push((since, day_end, lower_tick_index, upper_tick_index));
+  }
+  segments
+}
@@ -0,0 +1,97 @@
+use super::super::super::model::long_key::{self, KeyFormat};
+use super::super::super::model::position::{PositionRewardData, WhirlpoolPositionData};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::{anyhow, Result};
+use flate2::write::GzEncoder;
+use std::collections::HashMap;
+use std::{
+  fs::File,
+  io::{LineWriter, Write},
+};
+use whirlpool_base::state::{Position, PositionBundle};
+use whirlpool_replayer::serde::AccountDataStoreConfig;
+
+// build whirlpool-position-yyyymmdd.jsonl.gz from the state file of the date
+pub fn process(
+  in_whirlpool_state_file_path: String,
+  out_whirlpool_position_file_path: String,
+  key_format: KeyFormat,
+) -> Result<()> {
+  println!("open state file...");
+  let state = whirlpool_replayer::io::load_from_local_whirlpool_state_file(
+    &in_whirlpool_state_file_path,
+    &AccountDataStoreConfig::OnDisk(None),
+  );
+
+  // bundled position -> (position bundle, position bundle mint, bundle index)
+  println!("resolve position bundles...");
+  let mut bundled_positions: HashMap<String, (String, String, u16)> = HashMap::new();
+  state.accounts.traverse(|pubkey, data| {
+    if data.starts_with(&PositionBundle::DISCRIMINATOR) {
+      let position_bundle = PositionBundle::try_deserialize(&mut data.as_slice())
+        .map_err(|err| anyhow!("failed to deserialize PositionBundle account {}: {}", pubkey, err))?;
+      for bundle_index in 0..(position_bundle.position_bitmap.len() * 8) as u16 {
+        // bit of the index is set while the bundled position is open
+        if position_bundle.position_bitmap[bundle_index as usize / 8] & (1 << (bundle_index % 8)) == 0 {
+          continue;
+        }
+        let position = bundled_position_address(&position_bundle.position_bundle_mint, bundle_index);
+        bundled_positions.insert(position.to_string(), (pubkey.to_string(), position_bundle.position_bundle_mint.to_string(), bundle_index));
+      }
+    }
+    Ok(())
+  })?;
+
+  // accounts are sorted by pubkey in the state file
+  println!("write position file...");
+  let f = File::create(out_whirlpool_position_file_path)?;
+  let encoder = GzEncoder::new(f, flate2::Compression::default());
+  let mut writer = LineWriter::new(encoder);
+  state.accounts.traverse(|pubkey, data| {
+    if data.starts_with(&Position::DISCRIMINATOR) {
+      let position = Position::try_deserialize(&mut data.as_slice())
+        .map_err(|err| anyhow!("failed to deserialize Position account {}: {}", pubkey, err))?;
+      let (position_bundle, position_bundle_mint, position_bundle_index) = match bundled_positions.get(&pubkey.to_string()) {
+        Some((position_bundle, position_bundle_mint, bundle_index)) => (Some(position_bundle.clone()), Some(position_bundle_mint.clone()), Some(*bundle_index)),
+        None => (None, None, None),
+      };
+
+      let position_data = WhirlpoolPositionData {
+        position: pubkey.to_string(),
+        whirlpool: position.whirlpool.to_string(),
+        position_mint: position.position_mint.to_string(),
+        liquidity: position.liquidity,
+        lower_tick_index: position.tick_lower_index,
+        upper_tick_index: position.tick_upper_index,
+        fee_growth_checkpoint_a: position.fee_growth_checkpoint_a,
+        fee_owed_a: position.fee_owed_a,
+        fee_growth_checkpoint_b: position.fee_growth_checkpoint_b,
+        fee_owed_b: position.fee_owed_b,
+        rewards: position.reward_infos.iter().map(|reward_info| PositionRewardData {
+          growth_inside_checkpoint: reward_info.growth_inside_checkpoint,
+          amount_owed: reward_info.amount_owed,
+        }).collect(),
+        position_bundle,
+        position_bundle_mint,
+        position_bundle_index,
+      };
+      let jsonl = long_key::to_string(&position_data, key_format)?;
+      writer.write_all(jsonl.as_bytes())?;
+      writer.write_all(b"\n")?;
+    }
+    Ok(())
+  })?;
+  writer.flush()?;
+
+  Ok(())
+}
+
+// PDA of the bundled position (seeds: "bundled_position", position bundle mint, bundle index as a decimal string)
+fn bundled_position_address(position_bundle_mint: &Pubkey, bundle_index: u16) -> Pubkey {
+  let (address, _bump) = Pubkey::find_program_address(
+    &[b"bundled_position", position_bundle_mint.as_ref(), bundle_index.to_string().as_bytes()],
+    &whirlpool_base::ID,
+  );
+  address
+}
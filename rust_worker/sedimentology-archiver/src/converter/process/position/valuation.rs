@@ -0,0 +1,213 @@
+use super::super::super::model::long_key::{self, KeyFormat};
+use super::super::super::model::{ohlcv, position::{LedgerAmountData, NetAmountData, WhirlpoolPositionLedgerData, WhirlpoolPositionValuationData}};
+use super::super::long_key::read_short_key_file;
+use super::super::ohlcv::{build_data_manager, sqrt_price_to_decimal_price, OhlcvData, OhlcvDataManager};
+use super::range;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::{anyhow, Result};
+use bigdecimal::{BigDecimal, Zero};
+use flate2::write::GzEncoder;
+use std::collections::{HashMap, HashSet};
+use std::{
+  fs::File,
+  io::{LineWriter, Write},
+};
+use whirlpool_base::state::Position;
+use whirlpool_base::math::{get_amount_delta_a, get_amount_delta_b, sqrt_price_from_tick_index};
+
+// build whirlpool-position-valuation-yyyymmdd.jsonl.gz and whirlpool-position-range-yyyymmdd.jsonl.gz
+// from the position ledger of the date and the prices of the day (rebuilt from state (previous date), token and event in the same way as ohlcv)
+// positions not in the ledger are seeded from the Position accounts of the state,
+// and the liquidity and the range unknown to the ledger (positions opened before the ledger started) are filled from them
+pub fn process(
+  in_whirlpool_state_file_path: String,
+  in_whirlpool_token_file_path: String,
+  in_whirlpool_event_file_path: String,
+  in_whirlpool_position_ledger_file_path: String,
+  out_whirlpool_position_valuation_file_path: String,
+  out_whirlpool_position_range_file_path: String,
+  key_format: KeyFormat,
+) -> Result<()> {
+  // Position accounts at the end of the previous date (collected while the state is traversed)
+  let mut position_accounts = vec![];
+
+  // daily and minutely candles only
+  let ohlcv_data_manager = build_data_manager(
+    in_whirlpool_state_file_path,
+    in_whirlpool_token_file_path,
+    in_whirlpool_event_file_path,
+    &[],
+    |pubkey, data| {
+      if data.starts_with(&Position::DISCRIMINATOR) {
+        let position = Position::try_deserialize(&mut &data[..])
+          .map_err(|err| anyhow!("failed to deserialize Position account {}: {}", pubkey, err))?;
+        position_accounts.push(seed_ledger(pubkey, &position));
+      }
+      Ok(())
+    },
+  )?;
+
+  println!("read {} ...", in_whirlpool_position_ledger_file_path);
+  let mut ledgers = read_short_key_file::<WhirlpoolPositionLedgerData>(&in_whirlpool_position_ledger_file_path)?.collect::<Result<Vec<_>>>()?;
+
+  // positions with only harvests since the ledger started have no liquidity and range in the ledger
+  let seeds: HashMap<String, WhirlpoolPositionLedgerData> = position_accounts.into_iter().map(|seed| (seed.position.clone(), seed)).collect();
+  for ledger in ledgers.iter_mut() {
+    if let Some(seed) = seeds.get(&ledger.position) {
+      fill_from_seed(ledger, seed);
+    }
+  }
+
+  // positions without events since the ledger started have no line in the ledger
+  let positions_in_ledger: HashSet<String> = ledgers.iter().map(|ledger| ledger.position.clone()).collect();
+  ledgers.extend(seeds.into_values().filter(|seed| !positions_in_ledger.contains(&seed.position)));
+  // stable sort keeps a closed bundled position before the position reopened at the same address
+  ledgers.sort_by(|a, b| a.position.cmp(&b.position));
+
+  // write position valuation file
+  println!("write position valuation file...");
+  let f = File::create(out_whirlpool_position_valuation_file_path)?;
+  let encoder = GzEncoder::new(f, flate2::Compression::default());
+  let mut writer = LineWriter::new(encoder);
+  for data in positions(&ohlcv_data_manager, &ledgers) {
+    let jsonl = long_key::to_string(&data, key_format)?;
+    writer.write_all(jsonl.as_bytes())?;
+    writer.write_all(b"\n")?;
+  }
+  writer.flush()?;
+
+  // write position range file
+  println!("write position range file...");
+  let f = File::create(out_whirlpool_position_range_file_path)?;
+  let encoder = GzEncoder::new(f, flate2::Compression::default());
+  let mut writer = LineWriter::new(encoder);
+  for data in range::positions(&ohlcv_data_manager, &ledgers) {
+    let jsonl = long_key::to_string(&data, key_format)?;
+    writer.write_all(jsonl.as_bytes())?;
+    writer.write_all(b"\n")?;
+  }
+  writer.flush()?;
+
+  Ok(())
+}
+
+// ledger line of a position without events since the ledger started (no event in the day, so the account is also the state at the end of the day)
+// deposits are unknown (opened is omitted), so only the value is known
+fn seed_ledger(position_address: String, position: &Position) -> WhirlpoolPositionLedgerData {
+  WhirlpoolPositionLedgerData {
+    position: position_address,
+    whirlpool: position.whirlpool.to_string(),
+    token_a: None,
+    token_b: None,
+    opened: None,
+    closed: None,
+    lower_tick_index: Some(position.tick_lower_index),
+    upper_tick_index: Some(position.tick_upper_index),
+    liquidity: Some(position.liquidity),
+    decimal_price: None,
+    deposited: LedgerAmountData::default(),
+    withdrawn: LedgerAmountData::default(),
+    fees: LedgerAmountData::default(),
+    rewards: vec![],
+    realized_pnl: None,
+    entries: vec![],
+  }
+}
+
+// liquidity and range are unknown to the ledger only if the position has had no liquidity events since the ledger started,
+// so the account at the end of the previous date holds them for the whole day (and for all entries of the day)
+fn fill_from_seed(ledger: &mut WhirlpoolPositionLedgerData, seed: &WhirlpoolPositionLedgerData) {
+  if ledger.liquidity.is_none() {
+    ledger.liquidity = seed.liquidity;
+    ledger.entries.iter_mut().for_each(|entry| entry.liquidity = seed.liquidity);
+  }
+  if ledger.lower_tick_index.is_none() || ledger.upper_tick_index.is_none() {
+    ledger.lower_tick_index = seed.lower_tick_index;
+    ledger.upper_tick_index = seed.upper_tick_index;
+    ledger.entries.iter_mut().for_each(|entry| {
+      entry.lower_tick_index = seed.lower_tick_index;
+      entry.upper_tick_index = seed.upper_tick_index;
+    });
+  }
+}
+
+// valuation of the open positions at the daily close price of their pools (in the order of the ledgers)
+fn positions(manager: &OhlcvDataManager, ledgers: &[WhirlpoolPositionLedgerData]) -> Vec<WhirlpoolPositionValuationData> {
+  ledgers.iter()
+    .filter(|ledger| ledger.closed.is_none())
+    .filter_map(|ledger| {
+      let data = manager.data.get(&ledger.whirlpool)?;
+      valuation(manager.timestamp, data, ledger)
+    })
+    .collect()
+}
+
+// None if the liquidity or the range is unknown, or the amounts are out of u64
+fn valuation(timestamp: i64, data: &OhlcvData, ledger: &WhirlpoolPositionLedgerData) -> Option<WhirlpoolPositionValuationData> {
+  let liquidity = ledger.liquidity?;
+  let lower_tick_index = ledger.lower_tick_index?;
+  let upper_tick_index = ledger.upper_tick_index?;
+
+  let metadata = &data.metadata;
+  let sqrt_price = data.close_sqrt_price();
+  let decimal_price = sqrt_price_to_decimal_price(sqrt_price, metadata.decimals_a, metadata.decimals_b);
+  let tick_index = data.close_tick_index();
+  let (amount_a, amount_b) = token_amounts(sqrt_price, tick_index, lower_tick_index, upper_tick_index, liquidity)?;
+
+  let value = ui_amount(amount_a as i128, metadata.decimals_a) * &decimal_price + ui_amount(amount_b as i128, metadata.decimals_b);
+  let net_deposited = NetAmountData {
+    amount_a: ledger.deposited.amount_a as i128 - ledger.withdrawn.amount_a as i128,
+    amount_b: ledger.deposited.amount_b as i128 - ledger.withdrawn.amount_b as i128,
+  };
+  // deposits before the ledger are missing if opened is unknown
+  let hold_value = ledger.opened.as_ref().map(|_| {
+    ui_amount(net_deposited.amount_a, metadata.decimals_a) * &decimal_price + ui_amount(net_deposited.amount_b, metadata.decimals_b)
+  });
+  let impermanent_loss = hold_value.as_ref().map(|hold_value| &value - hold_value);
+  let impermanent_loss_ratio = match (&impermanent_loss, &hold_value) {
+    (Some(impermanent_loss), Some(hold_value)) if *hold_value > BigDecimal::zero() => Some(impermanent_loss / hold_value),
+    _ => None,
+  };
+
+  Some(WhirlpoolPositionValuationData {
+    position: ledger.position.clone(),
+    whirlpool: ledger.whirlpool.clone(),
+    timestamp,
+    token_a: ohlcv::TokenData { mint: metadata.mint_a.clone(), decimals: metadata.decimals_a },
+    token_b: ohlcv::TokenData { mint: metadata.mint_b.clone(), decimals: metadata.decimals_b },
+    lower_tick_index,
+    upper_tick_index,
+    liquidity,
+    sqrt_price,
+    decimal_price,
+    tick_index,
+    in_range: lower_tick_index <= tick_index && tick_index < upper_tick_index,
+    amounts: LedgerAmountData { amount_a: amount_a as u128, amount_b: amount_b as u128 },
+    value,
+    net_deposited,
+    hold_value,
+    impermanent_loss,
+    impermanent_loss_ratio,
+  })
+}
+
+// token amounts of the liquidity at the sqrt price (rounded down as the Whirlpool program does on withdrawal)
+fn token_amounts(sqrt_price: u128, tick_index: i32, lower_tick_index: i32, upper_tick_index: i32, liquidity: u128) -> Option<(u64, u64)> {
+  let lower_sqrt_price = sqrt_price_from_tick_index(lower_tick_index);
+  let upper_sqrt_price = sqrt_price_from_tick_index(upper_tick_index);
+
+  if tick_index < lower_tick_index {
+    Some((get_amount_delta_a(lower_sqrt_price, upper_sqrt_price, liquidity, false).ok()?, 0))
+  } else if tick_index < upper_tick_index {
+    Some((
+      get_amount_delta_a(sqrt_price, upper_sqrt_price, liquidity, false).ok()?,
+      get_amount_delta_b(lower_sqrt_price, sqrt_price, liquidity, false).ok()?,
+    ))
+  } else {
+    Some((0, get_amount_delta_b(lower_sqrt_price, upper_sqrt_price, liquidity, false).ok()?))
+  }
+}
+
+fn ui_amount(amount: i128, decimals: u8) -> BigDecimal {
+  BigDecimal::from(amount) / BigDecimal::from(10u128.pow(decimals as u32))
+}
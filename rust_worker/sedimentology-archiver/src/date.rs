@@ -29,3 +29,23 @@ pub fn convert_yyyymmdd_to_unixtime(yyyymmdd: u32) -> i64 {
   let dt = dt.and_hms(0, 0, 0);
   return dt.timestamp();
 }
+
+pub fn convert_unixtime_to_yyyymmdd(unixtime: i64) -> u32 {
+  let dt = chrono::DateTime::from_timestamp(unixtime, 0).unwrap().date_naive();
+  dt.format("%Y%m%d").to_string().parse::<u32>().unwrap()
+}
+
+// monday of the ISO week
+pub fn first_yyyymmdd_date_of_week(yyyymmdd: u32) -> u32 {
+  let dt = NaiveDate::from_ymd_opt(
+    (yyyymmdd / 10000) as i32,
+    (yyyymmdd % 10000) / 100,
+    yyyymmdd % 100,
+  ).unwrap();
+  let first_dt = dt.week(chrono::Weekday::Mon).first_day();
+  first_dt.format("%Y%m%d").to_string().parse::<u32>().unwrap()
+}
+
+pub fn first_yyyymmdd_date_of_month(yyyymmdd: u32) -> u32 {
+  yyyymmdd / 100 * 100 + 1
+}
@@ -12,6 +12,8 @@ use std::{
 };
 
 use crate::date;
+use crate::artifact::{ArtifactStage, ArtifactType};
+use crate::storage::ObjectSummary;
 
 use crate::schema::{TokenInfo, Transaction, TransactionBalance, TransactionInstruction, WhirlpoolState, WhirlpoolStateAccount, WhirlpoolToken, WhirlpoolTransaction};
 
@@ -440,3 +442,71 @@ pub fn advance_archiver_state(profile: &String, yyyymmdd_date: u32, database: &m
 
   return Ok(());  
 }
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ArchiverStage {
+    pub date: u32,
+    pub artifact: ArtifactType,
+    pub stage: ArtifactStage,
+    // summary of the tmp file (exported, derived) or the uploaded file (uploaded, verified)
+    pub summary: ObjectSummary,
+    pub updated_at: String,
+}
+
+pub fn fetch_archiver_stages(profile: &String, from_yyyymmdd_date: u32, to_yyyymmdd_date: u32, database: &mut PooledConn) -> anyhow::Result<Vec<ArchiverStage>> {
+  let rows: Vec<(u32, String, String, u64, String, String)> = database.exec(
+    "
+    SELECT date, artifact, stage, size, sha256, CAST(updatedAt AS CHAR)
+    FROM admArchiverStage
+    WHERE profile = :p AND date BETWEEN :s AND :e
+    ORDER BY date ASC
+    ",
+    params! {
+        "p" => profile,
+        "s" => from_yyyymmdd_date,
+        "e" => to_yyyymmdd_date,
+    },
+  )?;
+
+  // a row written by a newer archiver must not be resumed as if it were absent
+  rows
+    .into_iter()
+    .map(|(date, artifact, stage, size, sha256, updated_at)| -> anyhow::Result<ArchiverStage> {
+      Ok(ArchiverStage {
+        date,
+        artifact: ArtifactType::from_name(&artifact).ok_or_else(|| anyhow::anyhow!("unknown artifact {} at {} in admArchiverStage", artifact, date))?,
+        stage: ArtifactStage::from_name(&stage).ok_or_else(|| anyhow::anyhow!("unknown stage {} of {} at {} in admArchiverStage", stage, artifact, date))?,
+        summary: ObjectSummary { size, sha256 },
+        updated_at,
+      })
+    })
+    .collect()
+}
+
+pub fn update_archiver_stage(profile: &String, yyyymmdd_date: u32, artifact: ArtifactType, stage: ArtifactStage, summary: &ObjectSummary, database: &mut PooledConn) -> Result<()> {
+  database.exec_drop(
+    "
+    INSERT INTO admArchiverStage (profile, date, artifact, stage, size, sha256)
+    VALUES (:p, :d, :a, :s, :z, :h)
+    ON DUPLICATE KEY UPDATE stage = VALUES(stage), size = VALUES(size), sha256 = VALUES(sha256), updatedAt = CURRENT_TIMESTAMP
+    ",
+    params! {
+        "p" => profile,
+        "d" => yyyymmdd_date,
+        "a" => artifact.name(),
+        "s" => stage.name(),
+        "z" => summary.size,
+        "h" => &summary.sha256,
+    },
+  )
+}
+
+pub fn reset_archiver_stages(profile: &String, yyyymmdd_date: u32, database: &mut PooledConn) -> Result<()> {
+  database.exec_drop(
+    "DELETE FROM admArchiverStage WHERE profile = :p AND date = :d",
+    params! {
+        "p" => profile,
+        "d" => yyyymmdd_date,
+    },
+  )
+}
@@ -1,5 +1,9 @@
 pub mod io;
 pub mod date;
 pub mod schema;
-pub mod command;
+pub mod storage;
 pub mod converter;
+pub mod artifact;
+pub mod manifest;
+pub mod archive;
+pub mod backfill;
@@ -1,15 +1,24 @@
 use std::thread::sleep;
 use std::time::Duration;
-use std::sync::mpsc::channel;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
+use anyhow::{bail, ensure, Result};
 use mysql::*;
 use clap::Parser;
 
 mod io;
 mod date;
 mod schema;
-mod command;
+mod storage;
 mod converter;
+mod artifact;
+mod manifest;
+mod archive;
+mod backfill;
+
+use converter::model::event::group::WhirlpoolEventGroup;
+use converter::model::ohlcv::interval::OhlcvInterval;
 
 #[derive(Parser, Debug)]
 struct Args {
@@ -31,16 +40,66 @@ struct Args {
     #[clap(long, id = "mariadb-database", default_value = "whirlpool")]
     mariadb_database: Option<String>,
 
-    // rclone copyto <localfile> <rclone-remote-path>/<filename>
-    // e.g. r2:sedimentology/alpha
-    #[clap(long, id = "rclone-remote-path")]
-    rclone_remote_path: String,
+    // uploaded to <storage root>/<yyyy>/<mmdd>/<filename>
+    #[clap(flatten)]
+    storage: storage::StorageArgs,
 
     #[clap(long, id = "working-directory")]
     working_directory: String,
+
+    // group-filtered event files to publish in addition to the event file (e.g. trade,liquidity)
+    #[clap(long, id = "event-groups", value_delimiter = ',', value_parser = parse_event_group)]
+    event_groups: Vec<WhirlpoolEventGroup>,
+
+    // ohlcv files of the candle intervals to publish in addition to daily and minutely (e.g. 5m,15m,1h,4h)
+    #[clap(long, id = "ohlcv-intervals", value_delimiter = ',', value_parser = parse_ohlcv_interval)]
+    ohlcv_intervals: Vec<OhlcvInterval>,
+
+    // publish parquet copies of transaction, event and ohlcv files in addition to the JSON lines files
+    #[clap(long, id = "parquet")]
+    parquet: bool,
+
+    // mints priced at 1 USD to derive USD prices in ohlcv files (default: USDC, USDT)
+    #[clap(long, id = "anchor-mints", value_delimiter = ',')]
+    anchor_mints: Vec<String>,
+
+    // the position ledger starts over on this date (yyyymmdd) even if older ledgers are archived
+    // (without it, the ledger starts over on the first date archived with the ledger), the ledger of the previous date is required on the other dates
+    #[clap(long, id = "position-ledger-start-date")]
+    position_ledger_start_date: Option<u32>,
+
+    // backfill mode: re-archive dates in [from, to] (yyyymmdd) instead of advancing latestArchivedDate
+    #[clap(long, id = "from", requires = "to")]
+    from: Option<u32>,
+
+    #[clap(long, id = "to", requires = "from")]
+    to: Option<u32>,
+
+    // backfill mode: number of dates archived in parallel (position-ledger, rollups and manifests are archived in date order afterwards)
+    #[clap(long, id = "workers", default_value = "1")]
+    workers: Option<usize>,
+
+    // backfill mode: resume from the recorded stages instead of regenerating the dates from scratch
+    #[clap(long, id = "resume")]
+    resume: bool,
+}
+
+fn parse_event_group(name: &str) -> Result<WhirlpoolEventGroup> {
+    match WhirlpoolEventGroup::from_name(name) {
+        // all is the event file itself
+        Some(WhirlpoolEventGroup::All) | None => bail!("unknown event group: {} (trade or liquidity)", name),
+        Some(group) => Ok(group),
+    }
 }
 
-fn main() {
+fn parse_ohlcv_interval(name: &str) -> Result<OhlcvInterval> {
+    match OhlcvInterval::from_name(name) {
+        Some(interval) => Ok(interval),
+        None => bail!("unknown ohlcv interval: {} (5m, 15m, 30m, 1h, 4h or 12h)", name),
+    }
+}
+
+fn main() -> Result<()> {
     // connect to mariadb
     let args = Args::parse();
     let mariadb_url = format!("mysql://{}:{}@{}:{}/{}",
@@ -50,24 +109,41 @@ fn main() {
                       args.mariadb_port.unwrap(),
                       args.mariadb_database.unwrap());
     let pool = Pool::new(mariadb_url.as_str()).unwrap();
-    let mut conn = pool.get_conn().unwrap();
 
     let profile = args.profile;
-    let rclone_remote_path = args.rclone_remote_path;
+    let storage = storage::build_storage(&args.storage)?;
     let tmpdir = args.working_directory;
+    let mut event_groups = args.event_groups;
+    event_groups.sort();
+    event_groups.dedup();
+    let mut ohlcv_intervals = args.ohlcv_intervals;
+    ohlcv_intervals.sort();
+    ohlcv_intervals.dedup();
+    let anchor_mints = match args.anchor_mints.is_empty() {
+        true => converter::process::ohlcv::pricing::DEFAULT_ANCHOR_MINTS.iter().map(|mint| mint.to_string()).collect(),
+        false => args.anchor_mints,
+    };
+    let options = archive::ArchiveOptions { event_groups, ohlcv_intervals, parquet: args.parquet, anchor_mints, position_ledger_start_date: args.position_ledger_start_date };
 
     // setup handler for graceful shutdown
-    let (tx, rx) = channel();
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let shutdown_handler = shutdown.clone();
     ctrlc::set_handler(move || {
         println!("received Ctrl-C!");
-        tx.send(()).unwrap();
+        shutdown_handler.store(true, Ordering::SeqCst);
     }).expect("Error setting Ctrl-C handler");
 
+    if let (Some(from), Some(to)) = (args.from, args.to) {
+        return backfill(&profile, from, to, args.workers.unwrap(), args.resume, &tmpdir, &options, storage.as_ref(), &pool, &shutdown);
+    }
+
+    let mut conn = pool.get_conn().unwrap();
+
     // archive loop
     let sleep_duration = Duration::from_secs(600);
     loop {
         // graceful shutdown
-        let should_shutdown = rx.try_recv().is_ok();
+        let should_shutdown = shutdown.load(Ordering::SeqCst);
         if should_shutdown {
             println!("shutting down ...");
             break;
@@ -87,149 +163,7 @@ fn main() {
 
             println!("archiving {} ...", archiving_yyyymmdd_date);
 
-            // export token & state & transaction to tmp file
-            println!("exporting token to tmp file ...");
-            let token_file_tmpfile = format!("{}/{}.token.tmp", tmpdir, profile);
-            io::export_token(archiving_yyyymmdd_date, &token_file_tmpfile, &mut conn);
-            let token_hash = command::sha256sum(&token_file_tmpfile);
-
-            println!("exporting state to tmp file ...");
-            let state_file_tmpfile = format!("{}/{}.state.tmp", tmpdir, profile);
-            io::export_state(archiving_yyyymmdd_date, &state_file_tmpfile, &mut conn);
-            let state_hash = command::sha256sum(&state_file_tmpfile);
-
-            println!("exporting transaction to tmp file ...");
-            let transaction_file_tmpfile = format!("{}/{}.transaction.tmp", tmpdir, profile);
-            io::export_transaction(archiving_yyyymmdd_date, &transaction_file_tmpfile, &mut conn);
-            let transaction_hash = command::sha256sum(&transaction_file_tmpfile);
-
-            println!("token_hash = {}", token_hash);
-            println!("state_hash = {}", state_hash);
-            println!("transaction_hash = {}", transaction_hash);
-
-            let yyyy = archiving_yyyymmdd_date.to_string().chars().take(4).collect::<String>();
-            let mmdd = archiving_yyyymmdd_date.to_string().chars().skip(4).collect::<String>();
-            let token_file_dest = format!("{}/{}/{}/whirlpool-token-{}.json.gz", rclone_remote_path, yyyy, mmdd, archiving_yyyymmdd_date);
-            let state_file_dest = format!("{}/{}/{}/whirlpool-state-{}.json.gz", rclone_remote_path, yyyy, mmdd, archiving_yyyymmdd_date);
-            let transaction_file_dest = format!("{}/{}/{}/whirlpool-transaction-{}.jsonl.gz", rclone_remote_path, yyyy, mmdd, archiving_yyyymmdd_date);
-
-            println!("uploading {} to {} ...", token_file_tmpfile, token_file_dest);
-            command::rclone_copyto(&token_file_tmpfile, &token_file_dest);
-
-            println!("uploading {} to {} ...", state_file_tmpfile, state_file_dest);
-            command::rclone_copyto(&state_file_tmpfile, &state_file_dest);
-
-            println!("uploading {} to {} ...", transaction_file_tmpfile, transaction_file_dest);
-            command::rclone_copyto(&transaction_file_tmpfile, &transaction_file_dest);
-
-            let token_file_verify = format!("{}/{}.token.verify", tmpdir, profile);
-            let state_file_verify = format!("{}/{}.state.verify", tmpdir, profile);
-            let transaction_file_verify = format!("{}/{}.transaction.verify", tmpdir, profile);
-
-            println!("downloading {} to {} ...", token_file_dest, token_file_verify);
-            command::rclone_copyto(&token_file_dest, &token_file_verify);
-
-            println!("downloading {} to {} ...", state_file_dest, state_file_verify);
-            command::rclone_copyto(&state_file_dest, &state_file_verify);
-
-            println!("downloading {} to {} ...", transaction_file_dest, transaction_file_verify);
-            command::rclone_copyto(&transaction_file_dest, &transaction_file_verify);
-
-            println!("verifying ...");
-            let token_verify_hash = command::sha256sum(&token_file_verify);
-            let state_verify_hash = command::sha256sum(&state_file_verify);
-            let transaction_verify_hash = command::sha256sum(&transaction_file_verify);
-            assert!(token_hash == token_verify_hash, "token_hash != token_verify_hash");
-            assert!(state_hash == state_verify_hash, "state_hash != state_verify_hash");
-            assert!(transaction_hash == transaction_verify_hash, "transaction_hash != transaction_verify_hash");
-
-            // token & state & transaction upload completed
-            // now we need to generate event & ohlcv
-
-            let previous_yyyymmdd_date = date::prev_yyyymmdd_date(archiving_yyyymmdd_date);
-
-            println!("exporting previous state to tmp file ...");
-            let previous_state_file_tmpfile = format!("{}/{}.previous-state.tmp", tmpdir, profile);
-            io::export_state(previous_yyyymmdd_date, &previous_state_file_tmpfile, &mut conn);
-
-            println!("processing event to tmp file ...");
-            let event_file_tmpfile = format!("{}/{}.event.tmp", tmpdir, profile);
-            converter::process::event::process(
-                previous_state_file_tmpfile.clone(),
-                token_file_tmpfile.clone(),
-                transaction_file_tmpfile.clone(),
-                event_file_tmpfile.clone(),
-            ).unwrap(); // TODO: error handling
-
-            println!("processing ohlcv to tmp file ...");
-            let ohlcv_daily_file_tmpfile = format!("{}/{}.ohlcv-daily.tmp", tmpdir, profile);
-            let ohlcv_minutely_file_tmpfile = format!("{}/{}.ohlcv-minutely.tmp", tmpdir, profile);
-            converter::process::ohlcv::process(
-                previous_state_file_tmpfile.clone(),
-                token_file_tmpfile.clone(),
-                event_file_tmpfile.clone(),
-                ohlcv_daily_file_tmpfile.clone(),
-                ohlcv_minutely_file_tmpfile.clone(),
-            ).unwrap(); // TODO: error handling
-
-            // upload event & ohlcv
-
-            let event_hash = command::sha256sum(&event_file_tmpfile);
-            let ohlcv_daily_hash = command::sha256sum(&ohlcv_daily_file_tmpfile);
-            let ohlcv_minutely_hash = command::sha256sum(&ohlcv_minutely_file_tmpfile);
-            println!("event_hash = {}", event_hash);
-            println!("ohlcv_daily_hash = {}", ohlcv_daily_hash);
-            println!("ohlcv_minutely_hash = {}", ohlcv_minutely_hash);
-
-            let event_file_dest = format!("{}/{}/{}/whirlpool-event-{}.jsonl.gz", rclone_remote_path, yyyy, mmdd, archiving_yyyymmdd_date);
-            let ohlcv_daily_file_dest = format!("{}/{}/{}/whirlpool-ohlcv-daily-{}.jsonl.gz", rclone_remote_path, yyyy, mmdd, archiving_yyyymmdd_date);
-            let ohlcv_minutely_file_dest = format!("{}/{}/{}/whirlpool-ohlcv-minutely-{}.jsonl.gz", rclone_remote_path, yyyy, mmdd, archiving_yyyymmdd_date);
-
-            println!("uploading {} to {} ...", event_file_tmpfile, event_file_dest);
-            command::rclone_copyto(&event_file_tmpfile, &event_file_dest);
-
-            println!("uploading {} to {} ...", ohlcv_daily_file_tmpfile, ohlcv_daily_file_dest);
-            command::rclone_copyto(&ohlcv_daily_file_tmpfile, &ohlcv_daily_file_dest);
-
-            println!("uploading {} to {} ...", ohlcv_minutely_file_tmpfile, ohlcv_minutely_file_dest);
-            command::rclone_copyto(&ohlcv_minutely_file_tmpfile, &ohlcv_minutely_file_dest);
-
-            let event_file_verify = format!("{}/{}.event.verify", tmpdir, profile);
-            let ohlcv_daily_file_verify = format!("{}/{}.ohlcv-daily.verify", tmpdir, profile);
-            let ohlcv_minutely_file_verify = format!("{}/{}.ohlcv-minutely.verify", tmpdir, profile);
-
-            println!("downloading {} to {} ...", event_file_dest, event_file_verify);
-            command::rclone_copyto(&event_file_dest, &event_file_verify);
-
-            println!("downloading {} to {} ...", ohlcv_daily_file_dest, ohlcv_daily_file_verify);
-            command::rclone_copyto(&ohlcv_daily_file_dest, &ohlcv_daily_file_verify);
-
-            println!("downloading {} to {} ...", ohlcv_minutely_file_dest, ohlcv_minutely_file_verify);
-            command::rclone_copyto(&ohlcv_minutely_file_dest, &ohlcv_minutely_file_verify);
-
-            println!("verifying ...");
-            let event_verify_hash = command::sha256sum(&event_file_verify);
-            let ohlcv_daily_verify_hash = command::sha256sum(&ohlcv_daily_file_verify);
-            let ohlcv_minutely_verify_hash = command::sha256sum(&ohlcv_minutely_file_verify);
-            assert!(event_hash == event_verify_hash, "event_hash != event_verify_hash");
-            assert!(ohlcv_daily_hash == ohlcv_daily_verify_hash, "ohlcv_daily_hash != ohlcv_daily_verify_hash");
-            assert!(ohlcv_minutely_hash == ohlcv_minutely_verify_hash, "ohlcv_minutely_hash != ohlcv_minutely_verify_hash");
-
-            // remove tmp & verify files
-            std::fs::remove_file(&token_file_tmpfile).unwrap();
-            std::fs::remove_file(&state_file_tmpfile).unwrap();
-            std::fs::remove_file(&transaction_file_tmpfile).unwrap();
-            std::fs::remove_file(&token_file_verify).unwrap();
-            std::fs::remove_file(&state_file_verify).unwrap();
-            std::fs::remove_file(&transaction_file_verify).unwrap();
-
-            std::fs::remove_file(&previous_state_file_tmpfile).unwrap();
-            std::fs::remove_file(&event_file_tmpfile).unwrap();
-            std::fs::remove_file(&ohlcv_daily_file_tmpfile).unwrap();
-            std::fs::remove_file(&ohlcv_minutely_file_tmpfile).unwrap();
-            std::fs::remove_file(&event_file_verify).unwrap();
-            std::fs::remove_file(&ohlcv_daily_file_verify).unwrap();
-            std::fs::remove_file(&ohlcv_minutely_file_verify).unwrap();
+            archive::archive_date(&profile, archiving_yyyymmdd_date, archive::ArchivePass::All, &tmpdir, &options, storage.as_ref(), &mut conn)?;
 
             // update latest archived date
             println!("updating latest archived date to {} ...", archiving_yyyymmdd_date);
@@ -245,5 +179,89 @@ fn main() {
             println!("sleeping for {} seconds ...", sleep_duration.as_secs());
             sleep(sleep_duration);
         }
-    }    
+    }
+
+    Ok(())
+}
+
+// backfill runs beside the live archiver, so it never updates admArchiverState
+#[allow(clippy::too_many_arguments)]
+fn backfill(
+    profile: &String,
+    from_yyyymmdd_date: u32,
+    to_yyyymmdd_date: u32,
+    workers: usize,
+    resume: bool,
+    tmpdir: &String,
+    options: &archive::ArchiveOptions,
+    storage: &dyn storage::ArchiveStorage,
+    pool: &Pool,
+    shutdown: &AtomicBool,
+) -> Result<()> {
+    ensure!(from_yyyymmdd_date <= to_yyyymmdd_date, "--from must be less than or equal to --to");
+    ensure!(workers > 0, "--workers must be greater than 0");
+
+    // the live archiver is working on the dates after the latest archived date
+    let mut conn = pool.get_conn()?;
+    let latest_archived_yyyymmdd_date = io::fetch_latest_archived_date(profile, &mut conn);
+    ensure!(
+        to_yyyymmdd_date <= latest_archived_yyyymmdd_date,
+        "--to must be less than or equal to the latest archived date ({})", latest_archived_yyyymmdd_date
+    );
+    drop(conn);
+
+    let dates = backfill::dates(from_yyyymmdd_date, to_yyyymmdd_date);
+
+    println!("backfilling {} dates ({} - {}) with {} workers ...", dates.len(), from_yyyymmdd_date, to_yyyymmdd_date, workers);
+
+    let backfill::ParallelPassResult { failed, attempted, ready } = backfill::run_parallel_pass(
+        &dates,
+        workers,
+        shutdown,
+        // each worker has its own connection and working directory
+        |worker| {
+            std::fs::create_dir_all(format!("{}/backfill-{}", tmpdir, worker))?;
+            Ok(pool.get_conn()?)
+        },
+        |worker, conn, yyyymmdd_date| {
+            // regenerate the date from scratch
+            if !resume {
+                io::reset_archiver_stages(profile, yyyymmdd_date, conn)?;
+            }
+            let worker_tmpdir = format!("{}/backfill-{}", tmpdir, worker);
+            archive::archive_date(profile, yyyymmdd_date, archive::ArchivePass::Independent, &worker_tmpdir, options, storage, conn)
+        },
+    );
+
+    // position-ledger is chained to the previous date and rollups read the previous dates, so they are archived in date order
+    println!("backfilling position-ledger, rollups and manifests of {} dates in date order ...", ready);
+    let ordered_tmpdir = format!("{}/backfill-ordered", tmpdir);
+    std::fs::create_dir_all(&ordered_tmpdir)?;
+    let mut conn = pool.get_conn()?;
+    for (i, yyyymmdd_date) in dates[..ready].iter().enumerate() {
+        if shutdown.load(Ordering::SeqCst) {
+            bail!("backfill was not completed, {} dates remain (rerun with --resume to continue)", dates.len() - i);
+        }
+
+        println!("backfilling {} in date order ...", yyyymmdd_date);
+        // the date resumes from the stages recorded by the parallel pass
+        let archived = backfill::catch_panic(&mut conn, || Ok(pool.get_conn()?), |conn| {
+            archive::archive_date(profile, *yyyymmdd_date, archive::ArchivePass::All, &ordered_tmpdir, options, storage, conn)
+        })?;
+        if let Err(err) = archived {
+            // the following dates would be chained to the incomplete date
+            bail!("failed to backfill {}, {} dates remain (rerun with --resume to continue): {:?}", yyyymmdd_date, dates.len() - i, err);
+        }
+        println!("backfilled {}", yyyymmdd_date);
+    }
+
+    if !failed.is_empty() {
+        bail!("failed to backfill {} dates: {:?} (the dates from {} are not completed, rerun with --resume to continue)", failed.len(), failed, failed[0]);
+    }
+    if attempted < dates.len() {
+        bail!("backfill was not completed, {} dates remain (rerun with --resume to continue)", dates.len() - ready);
+    }
+
+    println!("backfilled {} dates", dates.len());
+    Ok(())
 }
@@ -0,0 +1,245 @@
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use serde::de::IgnoredAny;
+use serde::Deserializer;
+use serde_derive::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter};
+
+use crate::artifact::ArtifactType;
+use crate::storage::{self, ObjectSummary};
+
+pub const WHIRLPOOL_MANIFEST_FORMAT_VERSION: u32 = 1;
+
+/*
+
+Whirlpool Manifest File JSON Schema
+
+A whirlpool manifest file (whirlpool-manifest-yyyymmdd.json) is uncompressed JSON file.
+It is uploaded after all other files of the date have been uploaded and verified,
+so the date is complete if the manifest file exists.
+
+{
+  formatVersion: u32,
+  date: u32(yyyymmdd),
+  firstSlot: u64,
+  lastSlot: u64,
+  firstBlockHeight: u64,
+  lastBlockHeight: u64,
+  artifacts: [
+    {
+      name: String(token | state | transaction | event | event-trade | event-liquidity | ohlcv-daily | ohlcv-minutely | ohlcv-pair | ohlcv-token | ohlcv-stats | ohlcv-adaptive-fee | ohlcv-weekly | ohlcv-monthly | position | position-ledger | position-valuation | position-range | parquet-<table>),
+      path: String(relative to the archive root, e.g. 2024/0101/whirlpool-token-20240101.json.gz),
+      size: u64(bytes),
+      sha256: String(hex encoding),
+      recordCount: u64,
+      formatVersion: u32,
+    },
+    ...
+  ]
+}
+
+recordCount:
+- token: number of tokens
+- state: number of accounts
+- transaction: number of blocks (lines)
+- event, event-trade, event-liquidity: number of blocks (lines)
+- ohlcv-daily, ohlcv-minutely, ohlcv-stats: number of whirlpools (lines)
+- ohlcv-pair: number of mint pairs (lines)
+- ohlcv-token: number of mints (lines)
+- ohlcv-adaptive-fee: number of adaptive fee pools (lines)
+- ohlcv-weekly, ohlcv-monthly: number of whirlpools (lines), only on the last date of the period
+- position: number of Position accounts (lines)
+- position-ledger: number of positions open at the end of the date or closed on the date (lines)
+- position-valuation: number of positions valued at the end of the date (lines)
+- position-range: number of positions tracked in the date (lines)
+- parquet-<table> (e.g. parquet-event-traded): number of rows
+
+formatVersion:
+- event-trade, event-liquidity: version of the events included in the group (the format of each block is recorded in the block)
+- others: version of the file format
+
+firstSlot, lastSlot, firstBlockHeight, lastBlockHeight are the first and last block of the transaction file.
+
+*/
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WhirlpoolManifest {
+    pub format_version: u32,
+    pub date: u32,
+    pub first_slot: u64,
+    pub last_slot: u64,
+    pub first_block_height: u64,
+    pub last_block_height: u64,
+    pub artifacts: Vec<ManifestArtifact>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestArtifact {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    pub record_count: u64,
+    pub format_version: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockInfo {
+    pub slot: u64,
+    pub block_height: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordSummary {
+    pub record_count: u64,
+    pub first_block: Option<BlockInfo>,
+    pub last_block: Option<BlockInfo>,
+}
+
+// e.g. 2024/0101/whirlpool-manifest-20240101.json
+pub fn manifest_key(yyyymmdd_date: u32) -> String {
+    storage::date_key(yyyymmdd_date, &format!("whirlpool-manifest-{}.json", yyyymmdd_date))
+}
+
+pub fn build_manifest(yyyymmdd_date: u32, artifacts: &[(ArtifactType, &str, &ObjectSummary)]) -> Result<WhirlpoolManifest> {
+    let mut manifest_artifacts = Vec::new();
+    let mut block_range = None;
+
+    for (artifact, local_file_path, summary) in artifacts {
+        let record_summary = inspect(*artifact, local_file_path)
+            .with_context(|| format!("failed to inspect {} file {}", artifact.name(), local_file_path))?;
+
+        if *artifact == ArtifactType::Transaction {
+            block_range = record_summary.first_block.zip(record_summary.last_block);
+        }
+
+        manifest_artifacts.push(ManifestArtifact {
+            name: artifact.name().to_string(),
+            path: artifact.key(yyyymmdd_date),
+            size: summary.size,
+            sha256: summary.sha256.clone(),
+            record_count: record_summary.record_count,
+            format_version: artifact.format_version(),
+        });
+    }
+
+    let (first_block, last_block) = block_range.ok_or_else(|| anyhow!("no block found in transaction file"))?;
+
+    Ok(WhirlpoolManifest {
+        format_version: WHIRLPOOL_MANIFEST_FORMAT_VERSION,
+        date: yyyymmdd_date,
+        first_slot: first_block.slot,
+        last_slot: last_block.slot,
+        first_block_height: first_block.block_height,
+        last_block_height: last_block.block_height,
+        artifacts: manifest_artifacts,
+    })
+}
+
+pub fn save_to_whirlpool_manifest_file(file_path: &String, manifest: &WhirlpoolManifest) -> Result<()> {
+    let file = File::create(file_path)?;
+    let writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(writer, manifest)?;
+    Ok(())
+}
+
+// only the fields needed for RecordSummary are deserialized (others are skipped)
+
+#[derive(Deserialize)]
+struct TokenFileSummary {
+    #[serde(deserialize_with = "deserialize_count")]
+    tokens: u64,
+}
+
+#[derive(Deserialize)]
+struct StateFileSummary {
+    #[serde(deserialize_with = "deserialize_count")]
+    accounts: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionBlockSummary {
+    slot: u64,
+    block_height: u64,
+}
+
+#[derive(Deserialize)]
+struct EventBlockSummary {
+    #[serde(rename = "s")]
+    slot: u64,
+    #[serde(rename = "h")]
+    block_height: u64,
+}
+
+fn deserialize_count<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let elements: Vec<IgnoredAny> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(elements.len() as u64)
+}
+
+pub fn inspect(artifact: ArtifactType, local_file_path: &str) -> Result<RecordSummary> {
+    let reader = BufReader::new(GzDecoder::new(File::open(local_file_path)?));
+
+    match artifact {
+        ArtifactType::Token => {
+            let token: TokenFileSummary = serde_json::from_reader(reader)?;
+            Ok(RecordSummary { record_count: token.tokens, first_block: None, last_block: None })
+        }
+        ArtifactType::State => {
+            let state: StateFileSummary = serde_json::from_reader(reader)?;
+            Ok(RecordSummary { record_count: state.accounts, first_block: None, last_block: None })
+        }
+        ArtifactType::Transaction | ArtifactType::Event | ArtifactType::EventGroup(_) | ArtifactType::OhlcvDaily | ArtifactType::OhlcvMinutely | ArtifactType::OhlcvPair | ArtifactType::OhlcvToken | ArtifactType::OhlcvStats | ArtifactType::OhlcvAdaptiveFee | ArtifactType::OhlcvInterval(_) | ArtifactType::OhlcvRollup(_) | ArtifactType::Position | ArtifactType::PositionLedger | ArtifactType::PositionValuation | ArtifactType::PositionRange => {
+            let mut record_count = 0u64;
+            let mut first_line = None;
+            let mut last_line = None;
+            for line in reader.lines() {
+                let line = line?;
+                if line.is_empty() {
+                    continue;
+                }
+                record_count += 1;
+                if first_line.is_none() {
+                    first_line = Some(line.clone());
+                }
+                last_line = Some(line);
+            }
+
+            let parse_block = |line: Option<String>| -> Result<Option<BlockInfo>> {
+                let Some(line) = line else {
+                    return Ok(None);
+                };
+                match artifact {
+                    ArtifactType::Transaction => {
+                        let block: TransactionBlockSummary = serde_json::from_str(&line)?;
+                        Ok(Some(BlockInfo { slot: block.slot, block_height: block.block_height }))
+                    }
+                    ArtifactType::Event | ArtifactType::EventGroup(_) => {
+                        let block: EventBlockSummary = serde_json::from_str(&line)?;
+                        Ok(Some(BlockInfo { slot: block.slot, block_height: block.block_height }))
+                    }
+                    _ => Ok(None),
+                }
+            };
+
+            Ok(RecordSummary {
+                record_count,
+                first_block: parse_block(first_line)?,
+                last_block: parse_block(last_line)?,
+            })
+        }
+        ArtifactType::Parquet(_) => {
+            // number of rows is stored in the footer (not GZIP compressed)
+            let parquet = SerializedFileReader::new(File::open(local_file_path)?)?;
+            let record_count = parquet.metadata().file_metadata().num_rows() as u64;
+            Ok(RecordSummary { record_count, first_block: None, last_block: None })
+        }
+    }
+}
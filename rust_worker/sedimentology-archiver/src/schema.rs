@@ -2,6 +2,12 @@ use serde_derive::{Deserialize, Serialize};
 use serde_json::Value;
 use replay_engine::decoded_instructions::{deserialize_u64, deserialize_base64, serialize_base64};
 
+// format version of each file (recorded in whirlpool-manifest-yyyymmdd.json)
+// increment it when the schema of the file is changed
+pub const WHIRLPOOL_STATE_FORMAT_VERSION: u32 = 1;
+pub const WHIRLPOOL_TRANSACTION_FORMAT_VERSION: u32 = 1;
+pub const WHIRLPOOL_TOKEN_FORMAT_VERSION: u32 = 1;
+
 // u64 to u64 string
 pub fn serialize_u64<S>(data: &u64, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -0,0 +1,99 @@
+use super::{ArchiveStorage, HashingReader, HashingWriter, ObjectMeta, ObjectSummary};
+use anyhow::{Context, Result};
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+pub struct LocalFileStorage {
+    root: PathBuf,
+}
+
+impl LocalFileStorage {
+    pub fn new(root: String) -> Self {
+        Self { root: PathBuf::from(root) }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+impl ArchiveStorage for LocalFileStorage {
+    fn put(&self, local_file_path: &str, key: &str) -> Result<ObjectSummary> {
+        let dest = self.path(key);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // write to .partial file, then rename it (other readers never see incomplete file)
+        let mut partial = dest.clone().into_os_string();
+        partial.push(".partial");
+        let partial = PathBuf::from(partial);
+        let mut reader = HashingReader::new(BufReader::new(
+            File::open(local_file_path).with_context(|| format!("failed to open {}", local_file_path))?,
+        ));
+        let mut writer = BufWriter::new(File::create(&partial)?);
+        std::io::copy(&mut reader, &mut writer)?;
+        writer.into_inner()?.sync_all()?;
+        fs::rename(&partial, &dest)?;
+
+        Ok(reader.finalize())
+    }
+
+    fn get(&self, key: &str, local_file_path: &str) -> Result<ObjectSummary> {
+        let src = self.path(key);
+        let mut reader = BufReader::new(File::open(&src).with_context(|| format!("failed to open {}", src.display()))?);
+        let mut writer = HashingWriter::new(BufWriter::new(File::create(local_file_path)?));
+        std::io::copy(&mut reader, &mut writer)?;
+        Ok(writer.finalize()?)
+    }
+
+    fn head(&self, key: &str) -> Result<Option<ObjectMeta>> {
+        match fs::metadata(self.path(key)) {
+            Ok(metadata) if metadata.is_file() => Ok(Some(ObjectMeta {
+                key: key.to_string(),
+                size: metadata.len(),
+            })),
+            Ok(_) => Ok(None),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<ObjectMeta>> {
+        // walk the deepest existing directory of the prefix, then filter by prefix
+        let dir = match prefix.rfind('/') {
+            Some(pos) => self.path(&prefix[..pos]),
+            None => self.root.clone(),
+        };
+
+        let mut objects = Vec::new();
+        if dir.is_dir() {
+            walk(&self.root, &dir, &mut objects)?;
+        }
+        objects.retain(|object| object.key.starts_with(prefix));
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(objects)
+    }
+}
+
+fn walk(root: &Path, dir: &Path, objects: &mut Vec<ObjectMeta>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            walk(root, &path, objects)?;
+        } else if path.extension() != Some(OsStr::new("partial")) {
+            let key = path
+                .strip_prefix(root)?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            objects.push(ObjectMeta { key, size: metadata.len() });
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use anyhow::{bail, Result};
+use clap::{Args, ValueEnum};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+
+mod local;
+mod s3;
+
+pub use local::LocalFileStorage;
+pub use s3::S3Storage;
+
+/*
+
+Archive Storage
+
+Archived files are addressed by a key relative to the storage root.
+e.g. 2024/0101/whirlpool-transaction-20240101.jsonl.gz
+
+- local: <storage-local-path>/<key>
+- s3: s3://<s3-bucket>/<s3-prefix>/<key> (any S3 compatible service: AWS S3, Cloudflare R2, MinIO, ...)
+
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectSummary {
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectMeta {
+    pub key: String,
+    pub size: u64,
+}
+
+pub trait ArchiveStorage: Send + Sync {
+    // upload local file to key, sha256 is calculated while streaming
+    fn put(&self, local_file_path: &str, key: &str) -> Result<ObjectSummary>;
+    // download key to local file, sha256 is calculated while streaming
+    fn get(&self, key: &str, local_file_path: &str) -> Result<ObjectSummary>;
+    // None if key does not exist
+    fn head(&self, key: &str) -> Result<Option<ObjectMeta>>;
+    // all objects whose key starts with prefix (sorted by key)
+    fn list(&self, prefix: &str) -> Result<Vec<ObjectMeta>>;
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageType {
+    Local,
+    S3,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct StorageArgs {
+    #[clap(long, id = "storage", value_enum, default_value = "local")]
+    pub storage: StorageType,
+
+    // --storage local
+    // e.g. ./rust_worker/tmp/dst
+    #[clap(long, id = "storage-local-path", required_if_eq("storage", "local"))]
+    pub storage_local_path: Option<String>,
+
+    // --storage s3
+    // e.g. https://<account_id>.r2.cloudflarestorage.com, http://localhost:9000 (MinIO)
+    #[clap(long, id = "s3-endpoint", required_if_eq("storage", "s3"))]
+    pub s3_endpoint: Option<String>,
+
+    #[clap(long, id = "s3-region", default_value = "auto")]
+    pub s3_region: Option<String>,
+
+    #[clap(long, id = "s3-bucket", required_if_eq("storage", "s3"))]
+    pub s3_bucket: Option<String>,
+
+    // e.g. alpha
+    #[clap(long, id = "s3-prefix", default_value = "")]
+    pub s3_prefix: Option<String>,
+
+    #[clap(long, id = "s3-access-key-id", required_if_eq("storage", "s3"))]
+    pub s3_access_key_id: Option<String>,
+
+    #[clap(long, id = "s3-secret-access-key", required_if_eq("storage", "s3"))]
+    pub s3_secret_access_key: Option<String>,
+
+    // MinIO requires path-style (http://host/bucket/key) requests by default
+    #[clap(long, id = "s3-virtual-hosted-style")]
+    pub s3_virtual_hosted_style: bool,
+}
+
+pub fn build_storage(args: &StorageArgs) -> Result<Box<dyn ArchiveStorage>> {
+    match args.storage {
+        StorageType::Local => {
+            let Some(path) = args.storage_local_path.clone() else {
+                bail!("--storage-local-path is required for local storage");
+            };
+            Ok(Box::new(LocalFileStorage::new(path)))
+        }
+        StorageType::S3 => {
+            let (Some(endpoint), Some(bucket), Some(access_key_id), Some(secret_access_key)) = (
+                args.s3_endpoint.clone(),
+                args.s3_bucket.clone(),
+                args.s3_access_key_id.clone(),
+                args.s3_secret_access_key.clone(),
+            ) else {
+                bail!("--s3-endpoint, --s3-bucket, --s3-access-key-id and --s3-secret-access-key are required for s3 storage");
+            };
+            Ok(Box::new(S3Storage::new(
+                endpoint,
+                args.s3_region.clone().unwrap_or("auto".to_string()),
+                bucket,
+                args.s3_prefix.clone().unwrap_or_default(),
+                access_key_id,
+                secret_access_key,
+                !args.s3_virtual_hosted_style,
+            )))
+        }
+    }
+}
+
+// archive key for the file of the date
+// e.g. (20240101, "whirlpool-transaction-20240101.jsonl.gz") -> 2024/0101/whirlpool-transaction-20240101.jsonl.gz
+pub fn date_key(yyyymmdd_date: u32, file_name: &str) -> String {
+    format!("{:04}/{:04}/{}", yyyymmdd_date / 10000, yyyymmdd_date % 10000, file_name)
+}
+
+// size & sha256 of the local file, None if it does not exist
+pub fn local_file_summary(local_file_path: &str) -> Result<Option<ObjectSummary>> {
+    let file = match File::open(local_file_path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let mut reader = HashingReader::new(BufReader::new(file));
+    std::io::copy(&mut reader, &mut std::io::sink())?;
+    Ok(Some(reader.finalize()))
+}
+
+pub struct HashingReader<R: Read> {
+    inner: R,
+    hasher: Sha256,
+    size: u64,
+}
+
+impl<R: Read> HashingReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, hasher: Sha256::new(), size: 0 }
+    }
+
+    pub fn finalize(self) -> ObjectSummary {
+        ObjectSummary {
+            size: self.size,
+            sha256: hex::encode(self.hasher.finalize()),
+        }
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.size += n as u64;
+        Ok(n)
+    }
+}
+
+pub struct HashingWriter<W: Write> {
+    inner: W,
+    hasher: Sha256,
+    size: u64,
+}
+
+impl<W: Write> HashingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner, hasher: Sha256::new(), size: 0 }
+    }
+
+    pub fn finalize(mut self) -> std::io::Result<ObjectSummary> {
+        self.inner.flush()?;
+        Ok(ObjectSummary {
+            size: self.size,
+            sha256: hex::encode(self.hasher.finalize()),
+        })
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.size += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,234 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountSerialize;
+use base64::Engine;
+use flate2::{read::GzDecoder, write::GzEncoder};
+use sedimentology_archiver::artifact::ArtifactType;
+use sedimentology_archiver::converter;
+use sedimentology_archiver::converter::model::account::WhirlpoolAccountType;
+use sedimentology_archiver::converter::model::long_key::KeyFormat;
+use serde_json::{json, Value};
+use whirlpool_base::state::{FeeTier, LockConfig, LockTypeLabel, Position, PositionBundle, Whirlpool, WhirlpoolRewardInfo, WhirlpoolsConfig};
+
+const SOL_USDC: &str = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+const WHIRLPOOLS_CONFIG: &str = "2LecshUwdy9xi7meFgHtFJQNSKk4KdTrcpvaB56dP2NQ";
+const FEE_TIER: &str = "62dSkn5ktwY1PoKPNMArZA4bZsvyemuknWUnnQ2ATTuN";
+const LOCK_CONFIG: &str = "8SbKeDbvL3DjRYVZ4p9NqJZXYyUg5bPVDoGWcaz4PXxH";
+const POSITION: &str = "5Ye4bNzMv2N2uNVRPJRYv4gWa1RNpv3MqVHBZwmx4nTS";
+const POSITION_BUNDLE: &str = "DqAEf9Ya9jeaPJ2XHWzD3bVPpJVxUvzrUfSBKQXGAFu5";
+// account of the other program (no known discriminator)
+const UNKNOWN_ACCOUNT: &str = "2xQJBqVCnaTTSm2T2XCkDkWBTrE3TgcxwRJEzxGjVUuJ";
+
+const SOL: &str = "So11111111111111111111111111111111111111112";
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xyybapC8G4wEGGkZwyTDt1v";
+const ORCA: &str = "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE";
+
+const OWNER: &str = "9Ebyb3ZMfYbcYz5dDpPgaJjhTnJC2gpL7LXjsG3WM1KB";
+const REWARD_AUTHORITY: &str = "DjDsi34mSB66p2nhBL6YvhbcLtZbkGfNybFeLDjJqxJW";
+
+// 150 USDC/SOL
+const SQRT_PRICE: u128 = 7144393258922745604;
+
+fn tmpdir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("whirlpool-account-test-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn read_file(path: &Path) -> Vec<Value> {
+    let mut jsonl = String::new();
+    GzDecoder::new(File::open(path).unwrap()).read_to_string(&mut jsonl).unwrap();
+    jsonl.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+fn write_gzip(path: &Path, data: &[u8]) -> String {
+    let mut encoder = GzEncoder::new(File::create(path).unwrap(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn pubkey(address: &str) -> Pubkey {
+    Pubkey::from_str(address).unwrap()
+}
+
+fn encode(account: &impl AccountSerialize) -> String {
+    let mut data = vec![];
+    account.try_serialize(&mut data).unwrap();
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+// state of 20231115 (accounts are sorted by pubkey in the state file)
+fn write_state_file(dir: &Path) -> String {
+    let mut reward_infos = [WhirlpoolRewardInfo::default(); 3];
+    reward_infos[0] = WhirlpoolRewardInfo {
+        mint: pubkey(ORCA),
+        vault: pubkey("2tU3tKvj7RBxEatryyMYTUxBoLSSWCQXsdv1X6yce4T2"),
+        // the reward authority is stored in the extension field
+        extension: pubkey(REWARD_AUTHORITY).as_ref().try_into().unwrap(),
+        emissions_per_second_x64: 1 << 64,
+        growth_global_x64: 0,
+    };
+    let whirlpool = Whirlpool {
+        whirlpools_config: pubkey(WHIRLPOOLS_CONFIG),
+        tick_spacing: 64,
+        fee_rate: 3000,
+        protocol_fee_rate: 1300,
+        liquidity: 1234567890123456789,
+        sqrt_price: SQRT_PRICE,
+        tick_current_index: -18973,
+        protocol_fee_owed_a: 10,
+        protocol_fee_owed_b: 1500,
+        token_mint_a: pubkey(SOL),
+        token_vault_a: pubkey("3YQm7ujtXWJU2e9jhp2QGHpnn1ShXn12QjvzMvDgabpX"),
+        token_mint_b: pubkey(USDC),
+        token_vault_b: pubkey("2JTw1fE2wz1SymWUQ7UqpVtrTuKjcd6mWwYwUJUCh2rq"),
+        fee_growth_global_a: 1 << 64,
+        reward_last_updated_timestamp: 1700092799,
+        reward_infos,
+        ..Default::default()
+    };
+    let whirlpools_config = WhirlpoolsConfig {
+        fee_authority: pubkey(OWNER),
+        collect_protocol_fees_authority: pubkey(OWNER),
+        reward_emissions_super_authority: pubkey(REWARD_AUTHORITY),
+        default_protocol_fee_rate: 1300,
+        ..Default::default()
+    };
+    let fee_tier = FeeTier { whirlpools_config: pubkey(WHIRLPOOLS_CONFIG), tick_spacing: 64, default_fee_rate: 3000 };
+    let lock_config = LockConfig {
+        position: pubkey(POSITION),
+        position_owner: pubkey(OWNER),
+        whirlpool: pubkey(SOL_USDC),
+        locked_timestamp: 1700006400,
+        lock_type: LockTypeLabel::Permanent,
+    };
+    let position = Position { whirlpool: pubkey(SOL_USDC), liquidity: 1000000, ..Default::default() };
+    let position_bundle = PositionBundle::default();
+
+    let state = json!({
+        "slot": 1999, "blockHeight": 1989, "blockTime": 1700092799,
+        "accounts": [
+            { "pubkey": WHIRLPOOLS_CONFIG, "data": encode(&whirlpools_config) },
+            { "pubkey": UNKNOWN_ACCOUNT, "data": base64::engine::general_purpose::STANDARD.encode([0u8; 16]) },
+            { "pubkey": POSITION, "data": encode(&position) },
+            { "pubkey": FEE_TIER, "data": encode(&fee_tier) },
+            { "pubkey": LOCK_CONFIG, "data": encode(&lock_config) },
+            { "pubkey": POSITION_BUNDLE, "data": encode(&position_bundle) },
+            { "pubkey": SOL_USDC, "data": encode(&whirlpool) },
+        ],
+        "programData": "",
+    });
+    write_gzip(&dir.join(ArtifactType::State.file_name(20231115)), state.to_string().as_bytes())
+}
+
+fn write_token_file(dir: &Path) -> String {
+    let token = json!({
+        "slot": 1999, "blockHeight": 1989, "blockTime": 1700092799,
+        "tokens": [{ "mint": SOL, "decimals": 9 }, { "mint": USDC, "decimals": 6 }, { "mint": ORCA, "decimals": 6 }],
+    });
+    write_gzip(&dir.join(ArtifactType::Token.file_name(20231115)), token.to_string().as_bytes())
+}
+
+// lines of the file of each account type
+fn process(name: &str, account_types: &[WhirlpoolAccountType], key_format: KeyFormat) -> Vec<(WhirlpoolAccountType, Vec<Value>)> {
+    let dir = tmpdir(name);
+    let paths = account_types.iter()
+        .map(|account_type| (*account_type, dir.join(account_type.file_name(20231115)).to_string_lossy().to_string()))
+        .collect::<Vec<_>>();
+    converter::process::account::process(write_state_file(&dir), write_token_file(&dir), &paths, key_format).unwrap();
+
+    let files = paths.iter().map(|(account_type, path)| (*account_type, read_file(Path::new(path)))).collect();
+    std::fs::remove_dir_all(dir).unwrap();
+    files
+}
+
+#[test]
+fn decoded_accounts() {
+    for account_type in WhirlpoolAccountType::ALL {
+        assert_eq!(WhirlpoolAccountType::from_name(account_type.name()), Some(account_type));
+    }
+    assert_eq!(WhirlpoolAccountType::AdaptiveFeeTier.file_name(20240102), "whirlpool-account-adaptive-fee-tier-20240102.jsonl.gz");
+
+    let files = process("short", &WhirlpoolAccountType::ALL, KeyFormat::Short);
+
+    // each account goes to the file of the type detected by its discriminator
+    // Position, PositionBundle and unknown accounts are not decoded
+    let accounts = files.iter()
+        .map(|(account_type, lines)| (*account_type, lines.iter().map(|line| line["a"].as_str().unwrap()).collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+    assert_eq!(accounts, vec![
+        (WhirlpoolAccountType::Whirlpool, vec![SOL_USDC]),
+        (WhirlpoolAccountType::WhirlpoolsConfig, vec![WHIRLPOOLS_CONFIG]),
+        (WhirlpoolAccountType::WhirlpoolsConfigExtension, vec![]),
+        (WhirlpoolAccountType::FeeTier, vec![FEE_TIER]),
+        (WhirlpoolAccountType::AdaptiveFeeTier, vec![]),
+        (WhirlpoolAccountType::Oracle, vec![]),
+        (WhirlpoolAccountType::TokenBadge, vec![]),
+        (WhirlpoolAccountType::LockConfig, vec![LOCK_CONFIG]),
+    ]);
+
+    // decimal price and rewards are decoded with the decimals of the token file
+    let whirlpool = &files[0].1[0];
+    assert_eq!(whirlpool["wc"], WHIRLPOOLS_CONFIG);
+    assert_eq!(whirlpool["ta"], json!({ "m": SOL, "d": 9 }));
+    assert_eq!(whirlpool["tb"], json!({ "m": USDC, "d": 6 }));
+    assert_eq!(whirlpool["sp"], SQRT_PRICE.to_string());
+    let decimal_price: f64 = whirlpool["dp"].as_str().unwrap().parse().unwrap();
+    assert!((decimal_price - 150.0).abs() < 1e-6);
+    assert_eq!(whirlpool["cti"], -18973);
+    assert_eq!(whirlpool["l"], "1234567890123456789");
+    assert_eq!(whirlpool["fgga"], "18446744073709551616");
+    // rewards without a mint are omitted
+    assert_eq!(whirlpool["r"], json!([{
+        "i": 0,
+        "m": ORCA,
+        "v": "2tU3tKvj7RBxEatryyMYTUxBoLSSWCQXsdv1X6yce4T2",
+        "au": REWARD_AUTHORITY,
+        "eps": "18446744073709551616",
+        // 1 per second (6 decimals) for 86400 seconds
+        "epd": "8.640000000e-2",
+        "gg": "0",
+    }]));
+
+    let whirlpools_config = &files[1].1[0];
+    assert_eq!(whirlpools_config["fa"], OWNER);
+    assert_eq!(whirlpools_config["resa"], REWARD_AUTHORITY);
+    assert_eq!(whirlpools_config["dpfr"], 1300);
+
+    assert_eq!(files[3].1[0], json!({ "a": FEE_TIER, "wc": WHIRLPOOLS_CONFIG, "ts": 64, "dfr": 3000 }));
+
+    let lock_config = &files[7].1[0];
+    assert_eq!(lock_config["p"], POSITION);
+    assert_eq!(lock_config["po"], OWNER);
+    assert_eq!(lock_config["w"], SOL_USDC);
+    assert_eq!(lock_config["lts"], "1700006400");
+    assert_eq!(lock_config["lt"], json!({ "n": "p" }));
+}
+
+#[test]
+fn decoded_accounts_of_requested_types_only() {
+    let files = process("subset", &[WhirlpoolAccountType::LockConfig], KeyFormat::Short);
+
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].1.len(), 1);
+    assert_eq!(files[0].1[0]["a"], LOCK_CONFIG);
+}
+
+#[test]
+fn decoded_accounts_in_long_key_format() {
+    let files = process("long", &[WhirlpoolAccountType::Whirlpool, WhirlpoolAccountType::LockConfig], KeyFormat::Long);
+
+    let whirlpool = &files[0].1[0];
+    assert_eq!(whirlpool["account"], SOL_USDC);
+    assert_eq!(whirlpool["tokenA"], json!({ "mint": SOL, "decimals": 9 }));
+    assert_eq!(whirlpool["feeGrowthGlobalA"], "18446744073709551616");
+    assert_eq!(whirlpool["rewards"][0]["emissionsPerSecondX64"], "18446744073709551616");
+    assert_eq!(whirlpool["rewards"][0]["authority"], REWARD_AUTHORITY);
+
+    assert_eq!(files[1].1[0]["lockType"], json!({ "name": "Permanent" }));
+}
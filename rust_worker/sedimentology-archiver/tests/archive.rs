@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+use sedimentology_archiver::archive;
+use sedimentology_archiver::artifact::ArtifactType;
+use sedimentology_archiver::storage::{ArchiveStorage, LocalFileStorage};
+
+fn tmpdir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("whirlpool-archive-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn last_archived_date_before() {
+    let dir = tmpdir("last-archived");
+    let storage = LocalFileStorage::new(dir.join("storage").to_string_lossy().to_string());
+    let file = dir.join("file");
+    std::fs::write(&file, b"archived").unwrap();
+    let file = file.to_string_lossy().to_string();
+    let daily = ArtifactType::OhlcvDaily;
+
+    // nothing archived
+    assert_eq!(archive::last_archived_yyyymmdd_date_before(daily, 20240110, &storage).unwrap(), None);
+
+    // the archive starts at 20231230 (the other artifacts do not count)
+    for yyyymmdd_date in [20231230, 20231231, 20240101, 20240201] {
+        storage.put(&file, &daily.key(yyyymmdd_date)).unwrap();
+    }
+    storage.put(&file, &ArtifactType::State.key(20231229)).unwrap();
+
+    assert_eq!(archive::last_archived_yyyymmdd_date_before(daily, 20231230, &storage).unwrap(), None);
+    assert_eq!(archive::last_archived_yyyymmdd_date_before(daily, 20231231, &storage).unwrap(), Some(20231230));
+    // across the end of the year
+    assert_eq!(archive::last_archived_yyyymmdd_date_before(daily, 20240102, &storage).unwrap(), Some(20240101));
+    assert_eq!(archive::last_archived_yyyymmdd_date_before(daily, 20240101, &storage).unwrap(), Some(20231231));
+    // across the month without the artifact (only other artifacts and later dates in the month)
+    assert_eq!(archive::last_archived_yyyymmdd_date_before(daily, 20240201, &storage).unwrap(), Some(20240101));
+    // the months without any object before the missing date
+    assert_eq!(archive::last_archived_yyyymmdd_date_before(daily, 20240415, &storage).unwrap(), None);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,61 @@
+use sedimentology_archiver::artifact::{ArtifactStage, ArtifactType};
+use sedimentology_archiver::converter::model::event::group::WhirlpoolEventGroup;
+use sedimentology_archiver::converter::model::ohlcv::interval::OhlcvInterval;
+
+use ArtifactStage::{Derived, Exported, Uploaded, Verified};
+
+#[test]
+fn resume_at_first_incomplete_stage() {
+    // nothing recorded
+    assert_eq!(ArtifactType::Token.remaining_stages(None, false), vec![Exported, Uploaded, Verified]);
+    assert_eq!(ArtifactType::Event.remaining_stages(None, false), vec![Derived, Uploaded, Verified]);
+
+    // produced, the tmp file is still there
+    assert_eq!(ArtifactType::Transaction.remaining_stages(Some(Exported), true), vec![Uploaded, Verified]);
+    assert_eq!(ArtifactType::PositionLedger.remaining_stages(Some(Derived), true), vec![Uploaded, Verified]);
+
+    // produced, but the tmp file was lost before the upload
+    assert_eq!(ArtifactType::State.remaining_stages(Some(Exported), false), vec![Exported, Uploaded, Verified]);
+    assert_eq!(ArtifactType::OhlcvDaily.remaining_stages(Some(Derived), false), vec![Derived, Uploaded, Verified]);
+
+    // uploaded, the tmp file is not needed any more
+    assert_eq!(ArtifactType::State.remaining_stages(Some(Uploaded), false), vec![Verified]);
+    assert_eq!(ArtifactType::Event.remaining_stages(Some(Uploaded), true), vec![Verified]);
+
+    // verified
+    assert!(ArtifactType::Token.remaining_stages(Some(Verified), false).is_empty());
+    assert!(ArtifactType::OhlcvMinutely.remaining_stages(Some(Verified), true).is_empty());
+}
+
+#[test]
+fn resume_date_from_recorded_stages() {
+    // interrupted while uploading the event file
+    let recorded = [
+        (ArtifactType::Token, Some(Verified)),
+        (ArtifactType::State, Some(Verified)),
+        (ArtifactType::Transaction, Some(Verified)),
+        (ArtifactType::Event, Some(Derived)),
+        (ArtifactType::Position, None),
+    ];
+
+    let first_incomplete = recorded
+        .iter()
+        .find_map(|(artifact, stage)| {
+            artifact.remaining_stages(*stage, true).first().map(|next| (*artifact, *next))
+        });
+    assert_eq!(first_incomplete, Some((ArtifactType::Event, Uploaded)));
+}
+
+#[test]
+fn stage_and_artifact_names() {
+    for stage in ArtifactStage::ALL {
+        assert_eq!(ArtifactStage::from_name(stage.name()), Some(stage));
+    }
+    assert_eq!(ArtifactStage::from_name("archived"), None);
+
+    let artifacts = ArtifactType::of_profile(20240107, &[WhirlpoolEventGroup::Trade], &[OhlcvInterval::from_name("5m").unwrap()], true);
+    for artifact in artifacts {
+        assert_eq!(ArtifactType::from_name(artifact.name()), Some(artifact));
+    }
+    assert_eq!(ArtifactType::from_name("ohlcv-yearly"), None);
+}
@@ -0,0 +1,160 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, bail};
+use sedimentology_archiver::backfill::{self, ParallelPassResult};
+
+#[test]
+fn dates() {
+    assert_eq!(backfill::dates(20240101, 20240101), vec![20240101]);
+    // across the end of the month and the year
+    assert_eq!(backfill::dates(20231230, 20240102), vec![20231230, 20231231, 20240101, 20240102]);
+    assert_eq!(backfill::dates(20240228, 20240301), vec![20240228, 20240229, 20240301]);
+}
+
+#[test]
+fn parallel_pass_archives_each_date_once() {
+    let dates = backfill::dates(20240101, 20240131);
+    let shutdown = AtomicBool::new(false);
+    let connected = Mutex::new(Vec::new());
+    let archived = Mutex::new(Vec::new());
+
+    let result = backfill::run_parallel_pass(
+        &dates,
+        4,
+        &shutdown,
+        |worker| {
+            connected.lock().unwrap().push(worker);
+            Ok(worker)
+        },
+        |worker, conn, yyyymmdd_date| {
+            // each worker archives with its own connection
+            assert_eq!(*conn, worker);
+            archived.lock().unwrap().push(yyyymmdd_date);
+            Ok(())
+        },
+    );
+
+    assert_eq!(result, ParallelPassResult { failed: vec![], attempted: 31, ready: 31 });
+    let mut connected = connected.into_inner().unwrap();
+    connected.sort();
+    assert_eq!(connected, vec![0, 1, 2, 3]);
+    let mut archived = archived.into_inner().unwrap();
+    archived.sort();
+    assert_eq!(archived, dates);
+}
+
+#[test]
+fn parallel_pass_spawns_no_more_workers_than_dates() {
+    let dates = backfill::dates(20240101, 20240102);
+    let shutdown = AtomicBool::new(false);
+    let connected = AtomicUsize::new(0);
+
+    let result = backfill::run_parallel_pass(
+        &dates,
+        8,
+        &shutdown,
+        |_| {
+            connected.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        },
+        |_, _, _| Ok(()),
+    );
+
+    assert_eq!(result.ready, 2);
+    assert_eq!(connected.into_inner(), 2);
+}
+
+#[test]
+fn parallel_pass_records_failed_and_panicked_dates() {
+    let dates = backfill::dates(20240101, 20240110);
+    let shutdown = AtomicBool::new(false);
+    let connected = AtomicUsize::new(0);
+    let archived = Mutex::new(Vec::new());
+
+    let result = backfill::run_parallel_pass(
+        &dates,
+        2,
+        &shutdown,
+        |_| Ok(connected.fetch_add(1, Ordering::SeqCst)),
+        |_, _, yyyymmdd_date| {
+            match yyyymmdd_date {
+                20240104 => bail!("export failed"),
+                20240107 => panic!("unwrap in export"),
+                _ => {}
+            }
+            archived.lock().unwrap().push(yyyymmdd_date);
+            Ok(())
+        },
+    );
+
+    // the workers move on to the next dates, only the dates before the first failed date can be resumed in date order
+    assert_eq!(result, ParallelPassResult { failed: vec![20240104, 20240107], attempted: 10, ready: 3 });
+    assert_eq!(archived.into_inner().unwrap().len(), 8);
+    // the panicked worker reconnects
+    assert_eq!(connected.into_inner(), 3);
+}
+
+#[test]
+fn parallel_pass_stops_on_shutdown() {
+    let dates = backfill::dates(20240101, 20240110);
+    let shutdown = AtomicBool::new(false);
+
+    let result = backfill::run_parallel_pass(
+        &dates,
+        1,
+        &shutdown,
+        |_| Ok(()),
+        |_, _, yyyymmdd_date| {
+            if yyyymmdd_date == 20240103 {
+                shutdown.store(true, Ordering::SeqCst);
+            }
+            Ok(())
+        },
+    );
+
+    // the dates after the shutdown are not attempted
+    assert_eq!(result, ParallelPassResult { failed: vec![], attempted: 3, ready: 3 });
+}
+
+#[test]
+fn parallel_pass_stops_worker_without_connection() {
+    let dates = backfill::dates(20240101, 20240103);
+    let shutdown = AtomicBool::new(false);
+
+    let result = backfill::run_parallel_pass(
+        &dates,
+        2,
+        &shutdown,
+        |worker| if worker == 0 { Err(anyhow!("connection refused")) } else { Ok(()) },
+        |_, _, _| Ok(()),
+    );
+
+    // the other worker archives all dates
+    assert_eq!(result, ParallelPassResult { failed: vec![], attempted: 3, ready: 3 });
+}
+
+#[test]
+fn catch_panic() {
+    let mut conn = 0;
+
+    let archived = backfill::catch_panic(&mut conn, || Ok(1), |_| Ok(())).unwrap();
+    assert!(archived.is_ok());
+    assert_eq!(conn, 0);
+
+    let archived = backfill::catch_panic(&mut conn, || Ok(1), |_| Err(anyhow!("failed"))).unwrap();
+    assert_eq!(archived.unwrap_err().to_string(), "failed");
+    assert_eq!(conn, 0);
+
+    // the connection is replaced after a panic
+    let archived = backfill::catch_panic(&mut conn, || Ok(1), |_| panic!("unwrap on {}", "None")).unwrap();
+    assert_eq!(archived.unwrap_err().to_string(), "panicked: unwrap on None");
+    assert_eq!(conn, 1);
+
+    let archived = backfill::catch_panic(&mut conn, || Ok(2), |_| std::panic::panic_any(7)).unwrap();
+    assert_eq!(archived.unwrap_err().to_string(), "panicked: unknown panic");
+    assert_eq!(conn, 2);
+
+    // a failed reconnection fails the backfill
+    assert!(backfill::catch_panic(&mut conn, || Err(anyhow!("connection refused")), |_| panic!("unwrap")).is_err());
+}
@@ -0,0 +1,162 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use flate2::{read::GzDecoder, write::GzEncoder};
+use sedimentology_archiver::artifact::ArtifactType;
+use sedimentology_archiver::converter::model::event::group::WhirlpoolEventGroup;
+use sedimentology_archiver::converter::model::ohlcv::interval::OhlcvInterval;
+use serde_json::{json, Value};
+
+const SOL_USDC: &str = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+
+const SOL: &str = "So11111111111111111111111111111111111111112";
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xyybapC8G4wEGGkZwyTDt1v";
+const ORCA: &str = "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE";
+
+const DAILY_TIMESTAMP: i64 = 1700006400;
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn tmpdir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("whirlpool-convert-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn read_file(path: &Path) -> Vec<Value> {
+    let mut jsonl = String::new();
+    GzDecoder::new(File::open(path).unwrap()).read_to_string(&mut jsonl).unwrap();
+    jsonl.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+fn write_gzip(path: &Path, data: &[u8]) -> String {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let mut encoder = GzEncoder::new(File::create(path).unwrap(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap();
+    path.to_string_lossy().to_string()
+}
+
+// archive directory with the files needed to build the files of 20231115 (<dir>/<yyyy>/<mmdd>/<filename>)
+fn write_archive_dir(dir: &Path) {
+    // state at the end of 20231114 (no pools yet, all pools are initialized in the day)
+    let state = json!({ "slot": 999, "blockHeight": 989, "blockTime": DAILY_TIMESTAMP - 1, "accounts": [], "programData": "" });
+    write_gzip(&dir.join(ArtifactType::State.key(20231114)), state.to_string().as_bytes());
+    let token = json!({
+        "slot": 999, "blockHeight": 989, "blockTime": DAILY_TIMESTAMP - 1,
+        "tokens": [{ "mint": SOL, "decimals": 9 }, { "mint": USDC, "decimals": 6 }, { "mint": ORCA, "decimals": 6 }],
+    });
+    write_gzip(&dir.join(ArtifactType::Token.key(20231115)), token.to_string().as_bytes());
+    write_gzip(&dir.join(ArtifactType::Event.key(20231115)), &std::fs::read(fixture_path("event/ohlcv.jsonl")).unwrap());
+}
+
+fn convert(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sedimentology-convert")).args(args).output().unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+#[test]
+fn convert_ohlcv_from_archive_dir() {
+    let dir = tmpdir("ohlcv");
+    write_archive_dir(&dir);
+    let archive_dir = dir.to_string_lossy().to_string();
+
+    let output = convert(&["ohlcv", "--intervals", "1h", "--archive-dir", &archive_dir, "--from", "20231115", "--to", "20231115"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    // written next to the input files (the same layout as the archive)
+    let artifacts = [
+        ArtifactType::OhlcvDaily,
+        ArtifactType::OhlcvMinutely,
+        ArtifactType::OhlcvInterval(OhlcvInterval::from_name("1h").unwrap()),
+        ArtifactType::OhlcvPair,
+        ArtifactType::OhlcvToken,
+        ArtifactType::OhlcvStats,
+        ArtifactType::OhlcvAdaptiveFee,
+    ];
+    for artifact in artifacts {
+        assert!(dir.join(artifact.key(20231115)).is_file(), "{} is not written", artifact.name());
+    }
+    let daily = read_file(&dir.join(ArtifactType::OhlcvDaily.key(20231115)));
+    let sol_usdc = daily.iter().find(|line| line["w"] == SOL_USDC).unwrap();
+    assert_eq!(sol_usdc["d"]["t"], DAILY_TIMESTAMP);
+
+    // long key files are written into the output directory
+    let long_dir = dir.join("long").to_string_lossy().to_string();
+    let output = convert(&[
+        "ohlcv", "--key-format", "long", "--archive-dir", &archive_dir, "--output-dir", &long_dir, "--from", "20231115", "--to", "20231115",
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let daily = read_file(&Path::new(&long_dir).join(ArtifactType::OhlcvDaily.key(20231115)));
+    let sol_usdc = daily.iter().find(|line| line["whirlpool"] == SOL_USDC).unwrap();
+    assert_eq!(sol_usdc["daily"]["timestamp"], DAILY_TIMESTAMP);
+    // only the requested intervals are written
+    assert!(!Path::new(&long_dir).join(ArtifactType::OhlcvInterval(OhlcvInterval::from_name("1h").unwrap()).key(20231115)).exists());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn convert_event_group_and_long_key_with_explicit_paths() {
+    let dir = tmpdir("explicit");
+    let event = write_gzip(&dir.join(ArtifactType::Event.file_name(20231115)), &std::fs::read(fixture_path("event/ohlcv.jsonl")).unwrap());
+    let group = dir.join(ArtifactType::EventGroup(WhirlpoolEventGroup::Trade).file_name(20231115));
+
+    let output = convert(&["event-group", "--group", "trade", "--event", &event, "--output", &group.to_string_lossy()]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let blocks = read_file(&group);
+    let names = blocks.iter()
+        .flat_map(|block| block["x"].as_array().unwrap())
+        .flat_map(|transaction| transaction["e"].as_array().unwrap())
+        .map(|event| event["n"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["T"; 6]);
+
+    let long = dir.join("long.jsonl.gz");
+    let output = convert(&["long-key", "--artifact", "event-trade", "--input", &group.to_string_lossy(), "--output", &long.to_string_lossy()]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let long_blocks = read_file(&long);
+    assert_eq!(long_blocks.len(), blocks.len());
+    assert_eq!(long_blocks[0]["slot"], blocks[0]["s"]);
+    assert_eq!(long_blocks[0]["transactions"][0]["events"][0]["name"], "Traded");
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn convert_fails_on_invalid_arguments() {
+    let dir = tmpdir("invalid");
+    write_archive_dir(&dir);
+    let archive_dir = dir.to_string_lossy().to_string();
+
+    // the state file of 20231115 is missing
+    let output = convert(&["ohlcv", "--archive-dir", &archive_dir, "--from", "20231115", "--to", "20231116"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains(&format!("{} not found", dir.join(ArtifactType::State.key(20231115)).display())));
+    // the dates before the missing file are converted
+    assert!(dir.join(ArtifactType::OhlcvDaily.key(20231115)).is_file());
+
+    let output = convert(&["ohlcv", "--archive-dir", &archive_dir, "--from", "20231116", "--to", "20231115"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--from must be less than or equal to --to"));
+
+    // long key files must not replace the archived files
+    let output = convert(&["ohlcv", "--key-format", "long", "--archive-dir", &archive_dir, "--from", "20231115", "--to", "20231115"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--output-dir other than --archive-dir is required for long key files"));
+
+    // neither explicit paths nor a date range
+    let output = convert(&["event-group", "--group", "trade"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("either input/output paths or --archive-dir with --from and --to are required"));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
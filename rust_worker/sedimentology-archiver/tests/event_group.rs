@@ -0,0 +1,91 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use sedimentology_archiver::converter::model::event::group::WhirlpoolEventGroup;
+use sedimentology_archiver::converter::model::event::reader::WhirlpoolEventReader;
+use sedimentology_archiver::converter::model::event::{WhirlpoolEvent, WhirlpoolEventBlock};
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/event/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn read_fixture(name: &str) -> Vec<WhirlpoolEventBlock> {
+    let file = File::open(fixture_path(name)).unwrap();
+    WhirlpoolEventReader::new(BufReader::new(file))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .unwrap()
+}
+
+fn filter(group: WhirlpoolEventGroup, name: &str) -> Vec<WhirlpoolEventBlock> {
+    read_fixture(name).into_iter().filter_map(|block| group.filter(block)).collect()
+}
+
+// (slot, events of each transaction)
+fn shape(blocks: &[WhirlpoolEventBlock]) -> Vec<(u64, Vec<usize>)> {
+    blocks
+        .iter()
+        .map(|block| (block.slot, block.transactions.iter().map(|transaction| transaction.events.len()).collect()))
+        .collect()
+}
+
+#[test]
+fn trade_group_drops_blocks_without_trades() {
+    let blocks = filter(WhirlpoolEventGroup::Trade, "ohlcv.jsonl");
+
+    // pool initializations (1000, 1030, 1120) and the adaptive fee update (1300) are dropped
+    assert_eq!(
+        shape(&blocks),
+        vec![(1060, vec![1]), (1090, vec![1]), (1180, vec![1]), (1240, vec![1]), (1250, vec![1]), (1400, vec![1])]
+    );
+    assert!(blocks
+        .iter()
+        .flat_map(|block| block.transactions.iter())
+        .flat_map(|transaction| transaction.events.iter())
+        .all(|event| matches!(event, WhirlpoolEvent::Traded(_))));
+
+    // the block is kept as is except for the filtered transactions
+    let original = read_fixture("ohlcv.jsonl").into_iter().find(|block| block.slot == 1060).unwrap();
+    assert_eq!(blocks[0].format_version, original.format_version);
+    assert_eq!(blocks[0].block_height, original.block_height);
+    assert_eq!(blocks[0].block_time, original.block_time);
+    assert_eq!(blocks[0].transactions[0].signature, original.transactions[0].signature);
+    assert_eq!(blocks[0].transactions[0].payer, original.transactions[0].payer);
+
+    // no trades at all
+    assert!(filter(WhirlpoolEventGroup::Trade, "position.jsonl").is_empty());
+}
+
+#[test]
+fn liquidity_group_drops_transactions_without_liquidity_events() {
+    let blocks = filter(WhirlpoolEventGroup::Liquidity, "position.jsonl");
+
+    // 400: opened and deposited -> deposited
+    // 401: harvests only (both transactions are dropped, so the block is dropped)
+    // 402: repositioned
+    // 403: withdrawn and closed -> withdrawn
+    // 404: opened and deposited -> deposited, withdrawn
+    assert_eq!(
+        shape(&blocks),
+        vec![(400, vec![1]), (402, vec![1]), (403, vec![1]), (404, vec![1, 1])]
+    );
+    let events = blocks
+        .iter()
+        .flat_map(|block| block.transactions.iter())
+        .flat_map(|transaction| transaction.events.iter())
+        .collect::<Vec<_>>();
+    assert!(matches!(events[0], WhirlpoolEvent::LiquidityDeposited(_)));
+    assert!(matches!(events[1], WhirlpoolEvent::LiquidityRepositioned(_)));
+    assert!(matches!(events[2], WhirlpoolEvent::LiquidityWithdrawn(_)));
+    assert!(matches!(events[3], WhirlpoolEvent::LiquidityDeposited(_)));
+    assert!(matches!(events[4], WhirlpoolEvent::LiquidityWithdrawn(_)));
+}
+
+#[test]
+fn all_group_keeps_blocks_with_events() {
+    let blocks = filter(WhirlpoolEventGroup::All, "position.jsonl");
+    assert_eq!(shape(&blocks), shape(&read_fixture("position.jsonl")));
+
+    // a transaction without events is dropped even by the all group
+    let blocks = filter(WhirlpoolEventGroup::All, "v4.jsonl");
+    assert_eq!(shape(&blocks), vec![(300, vec![2]), (301, vec![1])]);
+}
@@ -0,0 +1,199 @@
+use std::fs::File;
+use std::io::{BufReader, Write};
+
+use flate2::write::GzEncoder;
+use sedimentology_archiver::converter::model::event::definition::{
+    LiquidityDepositedEventOrigin, PoolInitializedEventOrigin, TokenProgram, TradedEventOrigin,
+};
+use sedimentology_archiver::converter::model::event::reader::{parse_whirlpool_event_block, WhirlpoolEventReader};
+use sedimentology_archiver::converter::model::event::{WhirlpoolEvent, WhirlpoolEventBlock, WHIRLPOOL_EVENT_FORMAT_VERSION};
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/event/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn read_fixture(name: &str) -> Vec<WhirlpoolEventBlock> {
+    let file = File::open(fixture_path(name)).unwrap();
+    WhirlpoolEventReader::new(BufReader::new(file))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .unwrap()
+}
+
+fn events(blocks: &[WhirlpoolEventBlock]) -> Vec<&WhirlpoolEvent> {
+    blocks
+        .iter()
+        .flat_map(|block| block.transactions.iter())
+        .flat_map(|transaction| transaction.events.iter())
+        .collect()
+}
+
+#[test]
+fn read_v1_pre_token_2022() {
+    let blocks = read_fixture("v1-pre-token-2022.jsonl");
+
+    // empty line is skipped
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks.iter().map(|block| block.slot).collect::<Vec<_>>(), vec![100, 101, 102]);
+    assert!(blocks.iter().all(|block| block.format_version == WHIRLPOOL_EVENT_FORMAT_VERSION));
+
+    let events = events(&blocks);
+    assert_eq!(events.len(), 4);
+
+    // token programs are filled with Token program
+    let WhirlpoolEvent::PoolInitialized(pool_initialized) = events[0] else {
+        panic!("PoolInitialized expected");
+    };
+    assert_eq!(pool_initialized.origin, PoolInitializedEventOrigin::InitializePool);
+    assert_eq!(pool_initialized.token_program_a, TokenProgram::Token);
+    assert_eq!(pool_initialized.token_program_b, TokenProgram::Token);
+    assert_eq!(pool_initialized.fee_tier_index, None);
+    assert_eq!(pool_initialized.adaptive_fee_constants, None);
+
+    let WhirlpoolEvent::RewardInitialized(reward_initialized) = events[1] else {
+        panic!("RewardInitialized expected");
+    };
+    assert_eq!(reward_initialized.reward_token_program, TokenProgram::Token);
+
+    let WhirlpoolEvent::LiquidityDeposited(liquidity_deposited) = events[2] else {
+        panic!("LiquidityDeposited expected");
+    };
+    assert_eq!(liquidity_deposited.origin, LiquidityDepositedEventOrigin::IncreaseLiquidity);
+    assert_eq!(liquidity_deposited.transfer_b.transfer_fee_bps, None);
+    assert_eq!(liquidity_deposited.transfer_b.transfer_fee_max, None);
+
+    let WhirlpoolEvent::Traded(traded) = events[3] else {
+        panic!("Traded expected");
+    };
+    assert_eq!(traded.origin, TradedEventOrigin::Swap);
+    assert_eq!(traded.transfer_in.amount, 1_000_000_000);
+    assert_eq!(traded.old_sqrt_price, 7145929705339707732);
+    assert_eq!(traded.old_adaptive_fee_variables, None);
+    assert_eq!(traded.new_adaptive_fee_variables, None);
+}
+
+#[test]
+fn read_v1_adaptive_fee() {
+    let blocks = read_fixture("v1-adaptive-fee.jsonl");
+    assert_eq!(blocks.len(), 3);
+
+    let events = events(&blocks);
+    assert_eq!(events.len(), 4);
+
+    // token programs in the file are kept
+    let WhirlpoolEvent::PoolInitialized(pool_initialized) = events[0] else {
+        panic!("PoolInitialized expected");
+    };
+    assert_eq!(pool_initialized.origin, PoolInitializedEventOrigin::InitializePoolWithAdaptiveFee);
+    assert_eq!(pool_initialized.token_program_a, TokenProgram::Token);
+    assert_eq!(pool_initialized.token_program_b, TokenProgram::Token2022);
+    assert_eq!(pool_initialized.fee_tier_index, Some(1024));
+    assert_eq!(pool_initialized.trade_enable_timestamp, Some(1700000500));
+    assert_eq!(pool_initialized.adaptive_fee_constants.as_ref().unwrap().tick_group_size, 64);
+
+    let WhirlpoolEvent::RewardInitialized(reward_initialized) = events[1] else {
+        panic!("RewardInitialized expected");
+    };
+    assert_eq!(reward_initialized.reward_token_program, TokenProgram::Token2022);
+
+    let WhirlpoolEvent::LiquidityDeposited(liquidity_deposited) = events[2] else {
+        panic!("LiquidityDeposited expected");
+    };
+    assert_eq!(liquidity_deposited.origin, LiquidityDepositedEventOrigin::IncreaseLiquidityByTokenAmountsV2);
+    assert_eq!(liquidity_deposited.transfer_b.transfer_fee_bps, Some(100));
+    assert_eq!(liquidity_deposited.transfer_b.transfer_fee_max, Some(5000));
+
+    let WhirlpoolEvent::Traded(traded) = events[3] else {
+        panic!("Traded expected");
+    };
+    assert_eq!(traded.origin, TradedEventOrigin::SwapV2);
+    assert_eq!(traded.old_adaptive_fee_variables.as_ref().unwrap().volatility_accumulator, 10000);
+    assert_eq!(traded.new_adaptive_fee_variables.as_ref().unwrap().volatility_accumulator, 15000);
+}
+
+#[test]
+fn read_v2() {
+    let blocks = read_fixture("v2.jsonl");
+    assert_eq!(blocks.len(), 3);
+    assert!(blocks.iter().all(|block| block.format_version == WHIRLPOOL_EVENT_FORMAT_VERSION));
+    assert_eq!(events(&blocks).len(), 3);
+}
+
+#[test]
+fn read_v3() {
+    let blocks = read_fixture("v3.jsonl");
+    assert!(blocks.iter().all(|block| block.format_version == WHIRLPOOL_EVENT_FORMAT_VERSION));
+    let WhirlpoolEvent::Traded(traded) = events(&blocks)[2] else {
+        panic!("Traded expected");
+    };
+    assert_eq!(traded.lp_fee, Some(261000));
+    assert_eq!(traded.protocol_fee, Some(39000));
+
+    // exact fees are missing in older files
+    let blocks = read_fixture("v2.jsonl");
+    let WhirlpoolEvent::Traded(traded) = events(&blocks)[2] else {
+        panic!("Traded expected");
+    };
+    assert_eq!(traded.lp_fee, None);
+    assert_eq!(traded.protocol_fee, None);
+}
+
+#[test]
+fn read_v4() {
+    let blocks = read_fixture("v4.jsonl");
+    assert!(blocks.iter().all(|block| block.format_version == WHIRLPOOL_EVENT_FORMAT_VERSION));
+    let WhirlpoolEvent::Traded(traded) = events(&blocks)[2] else {
+        panic!("Traded expected");
+    };
+    assert_eq!(traded.old_liquidity, Some(1000000000));
+    assert_eq!(traded.new_liquidity, Some(1000000000));
+    assert_eq!(traded.crossed_initialized_tick, Some(false));
+
+    // liquidity is missing in older files
+    let blocks = read_fixture("v3.jsonl");
+    let WhirlpoolEvent::Traded(traded) = events(&blocks)[2] else {
+        panic!("Traded expected");
+    };
+    assert_eq!(traded.old_liquidity, None);
+    assert_eq!(traded.new_liquidity, None);
+    assert_eq!(traded.crossed_initialized_tick, None);
+}
+
+#[test]
+fn upgraded_block_is_written_in_current_format() {
+    for name in ["v1-pre-token-2022.jsonl", "v1-adaptive-fee.jsonl", "v2.jsonl", "v3.jsonl", "v4.jsonl"] {
+        for block in read_fixture(name) {
+            let jsonl = serde_json::to_string(&block).unwrap();
+            assert!(jsonl.starts_with(&format!("{{\"v\":{},", WHIRLPOOL_EVENT_FORMAT_VERSION)));
+
+            // current format can be read without upgrade
+            let reread: WhirlpoolEventBlock = serde_json::from_str(&jsonl).unwrap();
+            assert_eq!(reread, block);
+            assert_eq!(parse_whirlpool_event_block(&jsonl).unwrap(), block);
+        }
+    }
+}
+
+#[test]
+fn open_gzip_compressed_file() {
+    let path = std::env::temp_dir().join(format!("whirlpool-event-reader-test-{}.jsonl.gz", std::process::id()));
+    let mut encoder = GzEncoder::new(File::create(&path).unwrap(), flate2::Compression::default());
+    encoder.write_all(&std::fs::read(fixture_path("v1-pre-token-2022.jsonl")).unwrap()).unwrap();
+    encoder.finish().unwrap();
+
+    let blocks = WhirlpoolEventReader::open(path.to_str().unwrap())
+        .unwrap()
+        .collect::<anyhow::Result<Vec<_>>>()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(blocks, read_fixture("v1-pre-token-2022.jsonl"));
+}
+
+#[test]
+fn reject_unsupported_format_version() {
+    let jsonl = format!("{{\"v\":{},\"s\":1,\"h\":1,\"t\":1,\"x\":[]}}", WHIRLPOOL_EVENT_FORMAT_VERSION + 1);
+    assert!(parse_whirlpool_event_block(&jsonl).is_err());
+
+    let jsonl = "{\"v\":\"2\",\"s\":1,\"h\":1,\"t\":1,\"x\":[]}";
+    assert!(parse_whirlpool_event_block(jsonl).is_err());
+}
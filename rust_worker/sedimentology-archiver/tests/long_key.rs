@@ -0,0 +1,167 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+
+use flate2::{read::GzDecoder, write::GzEncoder};
+use sedimentology_archiver::converter;
+
+use sedimentology_archiver::converter::model::event::reader::WhirlpoolEventReader;
+use sedimentology_archiver::converter::model::event::{WhirlpoolEvent, WHIRLPOOL_EVENT_FORMAT_VERSION};
+use sedimentology_archiver::converter::model::long_key::{self, KeyFormat};
+use sedimentology_archiver::converter::model::ohlcv::{WhirlpoolOhlcvDailyData, WhirlpoolOhlcvMinutelyData};
+use serde_json::Value;
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn read_lines(name: &str) -> Vec<String> {
+    let file = File::open(fixture_path(name)).unwrap();
+    BufReader::new(file).lines().map(|line| line.unwrap()).collect()
+}
+
+fn long(value: &impl serde::Serialize) -> Value {
+    serde_json::from_str(&long_key::to_string(value, KeyFormat::Long).unwrap()).unwrap()
+}
+
+// every key of short key output is expanded (keys are in the same order thanks to preserve_order)
+fn assert_expanded(short: &Value, long: &Value) {
+    match (short, long) {
+        (Value::Object(short), Value::Object(long)) => {
+            assert_eq!(short.len(), long.len());
+            for ((short_key, short_value), (long_key, long_value)) in short.iter().zip(long.iter()) {
+                assert_ne!(short_key, long_key, "{} is not expanded", short_key);
+                assert_expanded(short_value, long_value);
+            }
+        }
+        (Value::Array(short), Value::Array(long)) => {
+            assert_eq!(short.len(), long.len());
+            short.iter().zip(long.iter()).for_each(|(short, long)| assert_expanded(short, long));
+        }
+        _ => {}
+    }
+}
+
+#[test]
+fn short_key_format_is_the_archive_format() {
+    let file = File::open(fixture_path("event/v2.jsonl")).unwrap();
+    for block in WhirlpoolEventReader::new(BufReader::new(file)) {
+        let block = block.unwrap();
+        assert_eq!(long_key::to_string(&block, KeyFormat::Short).unwrap(), serde_json::to_string(&block).unwrap());
+    }
+}
+
+#[test]
+fn event_in_long_key_format() {
+    let file = File::open(fixture_path("event/v1-adaptive-fee.jsonl")).unwrap();
+    let blocks = WhirlpoolEventReader::new(BufReader::new(file)).collect::<anyhow::Result<Vec<_>>>().unwrap();
+
+    for block in blocks.iter() {
+        let short: Value = serde_json::to_value(block).unwrap();
+        assert_expanded(&short, &long(block));
+    }
+
+    let block = blocks.iter().find(|block| {
+        block.transactions.iter().any(|transaction| transaction.events.iter().any(|event| matches!(event, WhirlpoolEvent::Traded(_))))
+    }).unwrap();
+    let block = long(block);
+    assert_eq!(block["formatVersion"], WHIRLPOOL_EVENT_FORMAT_VERSION);
+    assert!(block["blockTime"].is_i64());
+
+    let event = &block["transactions"][0]["events"][0];
+    assert_eq!(event["name"], "Traded");
+    let payload = &event["payload"];
+    assert_eq!(payload["origin"], "SwapV2");
+    assert_eq!(payload["tradeMode"], "ExactInput");
+    assert!(payload["tradeDirection"] == "AtoB" || payload["tradeDirection"] == "BtoA");
+    assert!(payload["transferIn"]["amount"].is_string());
+    assert!(payload["newAdaptiveFeeVariables"]["volatilityAccumulator"].is_u64());
+}
+
+#[test]
+fn exact_fees_in_long_key_format() {
+    let file = File::open(fixture_path("event/v3.jsonl")).unwrap();
+    let blocks = WhirlpoolEventReader::new(BufReader::new(file)).collect::<anyhow::Result<Vec<_>>>().unwrap();
+
+    let block = long(&blocks[1]);
+    let payload = &block["transactions"][0]["events"][0]["payload"];
+    assert_eq!(payload["lpFee"], "261000");
+    assert_eq!(payload["protocolFee"], "39000");
+}
+
+#[test]
+fn internally_tagged_enum_in_long_key_format() {
+    let short = r#"{"n":"PL","p":{"o":"lp","w":"HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ","p":"5B3kbaFxpeTDTpTNPmNFkpLGRnmrBiy2q9gkC9r9r4Nk","lt":{"n":"p"},"lc":"3n1wURmNVvktbjfM8yuMLMgLFHmjxj7RsuBHrtJDvQN2","lti":-443584,"uti":443584,"ldp":"1.0e-19","udp":"1.0e19","ll":"1000000","po":"4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T","pm":"8zYrD8TEg6WbGA2yd2H4nNpR2cnJMY1zZPqzxFa8hEzT"}}"#;
+    let event: WhirlpoolEvent = serde_json::from_str(short).unwrap();
+
+    let event = long(&event);
+    assert_eq!(event["name"], "PositionLocked");
+    assert_eq!(event["payload"]["origin"], "LockPosition");
+    assert_eq!(event["payload"]["lockType"]["name"], "Permanent");
+    assert_eq!(event["payload"]["lockedLiquidity"], "1000000");
+}
+
+#[test]
+fn ohlcv_in_long_key_format() {
+    for line in read_lines("ohlcv/minutely.jsonl") {
+        let minutely: WhirlpoolOhlcvMinutelyData = serde_json::from_str(&line).unwrap();
+        // short key output is unchanged by the round trip
+        assert_eq!(long_key::to_string(&minutely, KeyFormat::Short).unwrap(), line);
+        assert_expanded(&serde_json::from_str(&line).unwrap(), &long(&minutely));
+
+        // daily file has the same fields except minutely
+        let mut daily: Value = serde_json::from_str(&line).unwrap();
+        daily.as_object_mut().unwrap().remove("m");
+        let daily: WhirlpoolOhlcvDailyData = serde_json::from_value(daily).unwrap();
+        assert_expanded(&serde_json::to_value(&daily).unwrap(), &long(&daily));
+    }
+
+    let lines = read_lines("ohlcv/minutely.jsonl");
+    let existing = long(&serde_json::from_str::<WhirlpoolOhlcvMinutelyData>(&lines[0]).unwrap());
+    assert_eq!(existing["whirlpool"], "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ");
+    assert_eq!(existing["tokenA"]["decimals"], 9);
+    assert_eq!(existing["initialState"]["type"], "Existing");
+    assert_eq!(existing["initialState"]["payload"]["previousCloseSqrtPrice"], "7145929705339707732");
+    assert_eq!(existing["estimatedFees"]["liquidityProviderFeeA"], "261000");
+    assert_eq!(existing["daily"]["volume"]["aToB"]["totalIn"], "1000000000");
+    assert_eq!(existing["minutely"][0]["ohlc"]["decimalPrice"]["close"], "1.500200000e2");
+
+    let new = long(&serde_json::from_str::<WhirlpoolOhlcvMinutelyData>(&lines[1]).unwrap());
+    assert_eq!(new["initialState"]["type"], "New");
+    assert_eq!(new["initialState"]["payload"]["initializedSlot"], 300);
+    assert_eq!(new["minutely"], Value::Array(vec![]));
+}
+
+#[test]
+fn convert_existing_short_key_files() {
+    let gzip = |name: &str| {
+        let path = std::env::temp_dir().join(format!("long-key-test-{}-{}.gz", std::process::id(), name.replace('/', "-")));
+        let mut encoder = GzEncoder::new(File::create(&path).unwrap(), flate2::Compression::default());
+        encoder.write_all(&std::fs::read(fixture_path(name)).unwrap()).unwrap();
+        encoder.finish().unwrap();
+        path.to_string_lossy().to_string()
+    };
+    let gunzip = |path: &str| {
+        let mut jsonl = String::new();
+        GzDecoder::new(File::open(path).unwrap()).read_to_string(&mut jsonl).unwrap();
+        std::fs::remove_file(path).unwrap();
+        jsonl.lines().map(|line| serde_json::from_str(line).unwrap()).collect::<Vec<Value>>()
+    };
+
+    let event = gzip("event/v1-pre-token-2022.jsonl");
+    let output = format!("{}.long", event);
+    converter::process::long_key::process_event(event.clone(), output.clone()).unwrap();
+    std::fs::remove_file(&event).unwrap();
+    let blocks = gunzip(&output);
+    // empty line is skipped, and v1 file is upgraded
+    assert_eq!(blocks.len(), 3);
+    assert!(blocks.iter().all(|block| block["formatVersion"] == WHIRLPOOL_EVENT_FORMAT_VERSION));
+    assert_eq!(blocks[0]["transactions"][0]["events"][0]["payload"]["tokenProgramA"], "Token");
+
+    let minutely = gzip("ohlcv/minutely.jsonl");
+    let output = format!("{}.long", minutely);
+    converter::process::long_key::process_ohlcv_minutely(minutely.clone(), output.clone()).unwrap();
+    std::fs::remove_file(&minutely).unwrap();
+    let data = gunzip(&output);
+    assert_eq!(data.len(), 2);
+    assert_eq!(data[1]["initialState"]["type"], "New");
+}
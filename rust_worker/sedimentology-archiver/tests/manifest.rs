@@ -0,0 +1,144 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use flate2::write::GzEncoder;
+use sedimentology_archiver::artifact::ArtifactType;
+use sedimentology_archiver::converter::model::event::WHIRLPOOL_EVENT_FORMAT_VERSION;
+use sedimentology_archiver::manifest::{self, BlockInfo, WHIRLPOOL_MANIFEST_FORMAT_VERSION};
+use sedimentology_archiver::schema::{WHIRLPOOL_STATE_FORMAT_VERSION, WHIRLPOOL_TOKEN_FORMAT_VERSION, WHIRLPOOL_TRANSACTION_FORMAT_VERSION};
+use sedimentology_archiver::storage::ObjectSummary;
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn tmpdir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("whirlpool-manifest-test-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_gzip(path: &Path, data: &[u8]) -> String {
+    let mut encoder = GzEncoder::new(File::create(path).unwrap(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn summary(sha256: &str) -> ObjectSummary {
+    ObjectSummary {
+        size: 100,
+        sha256: sha256.to_string(),
+    }
+}
+
+#[test]
+fn inspect_counts_records() {
+    let dir = tmpdir("inspect");
+
+    let token = write_gzip(
+        &dir.join("token.json.gz"),
+        br#"{"tokens":[{"mint":"a","decimals":6},{"mint":"b","decimals":9}]}"#,
+    );
+    let summary = manifest::inspect(ArtifactType::Token, &token).unwrap();
+    assert_eq!(summary.record_count, 2);
+    assert_eq!(summary.first_block, None);
+
+    let state = write_gzip(
+        &dir.join("state.json.gz"),
+        br#"{"slot":1,"blockHeight":1,"blockTime":1,"accounts":[{"pubkey":"a","data":""},{"pubkey":"b","data":""},{"pubkey":"c","data":""}],"programData":""}"#,
+    );
+    assert_eq!(manifest::inspect(ArtifactType::State, &state).unwrap().record_count, 3);
+
+    // empty lines are not records
+    let ohlcv = write_gzip(&dir.join("ohlcv.jsonl.gz"), b"{\"w\":\"a\"}\n\n{\"w\":\"b\"}\n");
+    let summary = manifest::inspect(ArtifactType::OhlcvDaily, &ohlcv).unwrap();
+    assert_eq!(summary.record_count, 2);
+    assert_eq!(summary.first_block, None);
+    assert_eq!(summary.last_block, None);
+
+    let empty = write_gzip(&dir.join("empty.jsonl.gz"), b"");
+    assert_eq!(manifest::inspect(ArtifactType::PositionLedger, &empty).unwrap().record_count, 0);
+
+    let transaction = write_gzip(
+        &dir.join("transaction.jsonl.gz"),
+        &std::fs::read(fixture_path("transaction/transactions.jsonl")).unwrap(),
+    );
+    let summary = manifest::inspect(ArtifactType::Transaction, &transaction).unwrap();
+    assert_eq!(summary.record_count, 2);
+    assert_eq!(summary.first_block, Some(BlockInfo { slot: 300, block_height: 290 }));
+    assert_eq!(summary.last_block, Some(BlockInfo { slot: 301, block_height: 291 }));
+
+    let event = write_gzip(&dir.join("event.jsonl.gz"), &std::fs::read(fixture_path("event/v4.jsonl")).unwrap());
+    let summary = manifest::inspect(ArtifactType::Event, &event).unwrap();
+    assert_eq!(summary.record_count, 3);
+    assert_eq!(summary.first_block, Some(BlockInfo { slot: 300, block_height: 290 }));
+    assert_eq!(summary.last_block, Some(BlockInfo { slot: 302, block_height: 292 }));
+}
+
+#[test]
+fn build_manifest_of_date() {
+    let dir = tmpdir("build");
+
+    let token = write_gzip(&dir.join("token.json.gz"), br#"{"tokens":[{"mint":"a","decimals":6}]}"#);
+    let state = write_gzip(&dir.join("state.json.gz"), br#"{"accounts":[]}"#);
+    let transaction = write_gzip(
+        &dir.join("transaction.jsonl.gz"),
+        &std::fs::read(fixture_path("transaction/transactions.jsonl")).unwrap(),
+    );
+    let event = write_gzip(&dir.join("event.jsonl.gz"), &std::fs::read(fixture_path("event/v4.jsonl")).unwrap());
+
+    let (token_summary, state_summary, transaction_summary, event_summary) =
+        (summary("01"), summary("02"), summary("03"), summary("04"));
+    let manifest = manifest::build_manifest(
+        20240101,
+        &[
+            (ArtifactType::Token, &token, &token_summary),
+            (ArtifactType::State, &state, &state_summary),
+            (ArtifactType::Transaction, &transaction, &transaction_summary),
+            (ArtifactType::Event, &event, &event_summary),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(manifest.format_version, WHIRLPOOL_MANIFEST_FORMAT_VERSION);
+    assert_eq!(manifest.date, 20240101);
+    // block range is taken from the transaction file (not the event file)
+    assert_eq!(manifest.first_slot, 300);
+    assert_eq!(manifest.last_slot, 301);
+    assert_eq!(manifest.first_block_height, 290);
+    assert_eq!(manifest.last_block_height, 291);
+
+    let artifacts = manifest
+        .artifacts
+        .iter()
+        .map(|a| (a.name.as_str(), a.path.as_str(), a.sha256.as_str(), a.record_count, a.format_version))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        artifacts,
+        vec![
+            ("token", "2024/0101/whirlpool-token-20240101.json.gz", "01", 1, WHIRLPOOL_TOKEN_FORMAT_VERSION),
+            ("state", "2024/0101/whirlpool-state-20240101.json.gz", "02", 0, WHIRLPOOL_STATE_FORMAT_VERSION),
+            ("transaction", "2024/0101/whirlpool-transaction-20240101.jsonl.gz", "03", 2, WHIRLPOOL_TRANSACTION_FORMAT_VERSION),
+            ("event", "2024/0101/whirlpool-event-20240101.jsonl.gz", "04", 3, WHIRLPOOL_EVENT_FORMAT_VERSION),
+        ]
+    );
+    assert!(manifest.artifacts.iter().all(|a| a.size == 100));
+
+    // round trip through the manifest file
+    let path = dir.join("manifest.json").to_string_lossy().to_string();
+    manifest::save_to_whirlpool_manifest_file(&path, &manifest).unwrap();
+    let saved: manifest::WhirlpoolManifest = serde_json::from_reader(File::open(&path).unwrap()).unwrap();
+    assert_eq!(saved, manifest);
+    assert_eq!(manifest::manifest_key(20240101), "2024/0101/whirlpool-manifest-20240101.json");
+}
+
+#[test]
+fn build_manifest_requires_blocks() {
+    let dir = tmpdir("no-block");
+
+    let transaction = write_gzip(&dir.join("transaction.jsonl.gz"), b"");
+    let transaction_summary = summary("03");
+    assert!(manifest::build_manifest(20240101, &[(ArtifactType::Transaction, &transaction, &transaction_summary)]).is_err());
+}
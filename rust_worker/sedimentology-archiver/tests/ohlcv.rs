@@ -0,0 +1,516 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use bigdecimal::num_bigint::BigUint;
+use flate2::{read::GzDecoder, write::GzEncoder};
+use sedimentology_archiver::artifact::ArtifactType;
+use sedimentology_archiver::converter;
+use sedimentology_archiver::converter::model::long_key::KeyFormat;
+use sedimentology_archiver::converter::model::ohlcv::interval::OhlcvInterval;
+use sedimentology_archiver::converter::process::ohlcv::{pricing::DEFAULT_ANCHOR_MINTS, OhlcvOptions};
+use serde_json::{json, Value};
+
+// pools initialized in the day of the fixture (20231115)
+const SOL_USDC: &str = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+const ADAPTIVE_FEE_SOL_USDC: &str = "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE";
+const ORCA_SOL: &str = "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1";
+
+const SOL: &str = "So11111111111111111111111111111111111111112";
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xyybapC8G4wEGGkZwyTDt1v";
+const ORCA: &str = "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE";
+
+// token authorities of the trades (all trades of the SOL/USDC pool are paid by TRADER)
+const TRADER: &str = "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T";
+const DELEGATED_TRADER: &str = "9Ebyb3ZMfYbcYz5dDpPgaJjhTnJC2gpL7LXjsG3WM1KB";
+
+const DAILY_TIMESTAMP: i64 = 1700006400;
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn tmpdir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("whirlpool-ohlcv-test-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn read_file(path: &Path) -> Vec<Value> {
+    let mut jsonl = String::new();
+    GzDecoder::new(File::open(path).unwrap()).read_to_string(&mut jsonl).unwrap();
+    jsonl.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+fn write_gzip(path: &Path, data: &[u8]) -> String {
+    let mut encoder = GzEncoder::new(File::create(path).unwrap(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap();
+    path.to_string_lossy().to_string()
+}
+
+// state at the end of 20231114 (no pools yet, all pools are initialized in the day)
+fn write_state_file(dir: &Path) -> String {
+    let state = json!({ "slot": 999, "blockHeight": 989, "blockTime": DAILY_TIMESTAMP - 1, "accounts": [], "programData": "" });
+    write_gzip(&dir.join(ArtifactType::State.file_name(20231114)), state.to_string().as_bytes())
+}
+
+fn write_token_file(dir: &Path) -> String {
+    let token = json!({
+        "slot": 999, "blockHeight": 989, "blockTime": DAILY_TIMESTAMP - 1,
+        "tokens": [{ "mint": SOL, "decimals": 9 }, { "mint": USDC, "decimals": 6 }, { "mint": ORCA, "decimals": 6 }],
+    });
+    write_gzip(&dir.join(ArtifactType::Token.file_name(20231114)), token.to_string().as_bytes())
+}
+
+// event file of 20231115 (the fixture is not compressed)
+fn write_event_file(dir: &Path) -> String {
+    write_gzip(&dir.join(ArtifactType::Event.file_name(20231115)), &std::fs::read(fixture_path("event/ohlcv.jsonl")).unwrap())
+}
+
+struct OhlcvFiles {
+    daily: Vec<Value>,
+    minutely: Vec<Value>,
+    // 5m and 1h
+    intervals: Vec<Vec<Value>>,
+    pair: Vec<Value>,
+    token: Vec<Value>,
+    stats: Vec<Value>,
+    adaptive_fee: Vec<Value>,
+}
+
+fn process(name: &str, options: &OhlcvOptions, key_format: KeyFormat) -> OhlcvFiles {
+    let dir = tmpdir(name);
+    let path = |artifact: ArtifactType| dir.join(artifact.file_name(20231115));
+    let intervals = [OhlcvInterval::Minutes5, OhlcvInterval::Hours1]
+        .map(|interval| (interval, path(ArtifactType::OhlcvInterval(interval)).to_string_lossy().to_string()));
+    converter::process::ohlcv::process(
+        write_state_file(&dir),
+        write_token_file(&dir),
+        write_event_file(&dir),
+        path(ArtifactType::OhlcvDaily).to_string_lossy().to_string(),
+        path(ArtifactType::OhlcvMinutely).to_string_lossy().to_string(),
+        &intervals,
+        path(ArtifactType::OhlcvPair).to_string_lossy().to_string(),
+        path(ArtifactType::OhlcvToken).to_string_lossy().to_string(),
+        path(ArtifactType::OhlcvStats).to_string_lossy().to_string(),
+        path(ArtifactType::OhlcvAdaptiveFee).to_string_lossy().to_string(),
+        options,
+        key_format,
+    ).unwrap();
+
+    let files = OhlcvFiles {
+        daily: read_file(&path(ArtifactType::OhlcvDaily)),
+        minutely: read_file(&path(ArtifactType::OhlcvMinutely)),
+        intervals: intervals.iter().map(|(_, path)| read_file(Path::new(path))).collect(),
+        pair: read_file(&path(ArtifactType::OhlcvPair)),
+        token: read_file(&path(ArtifactType::OhlcvToken)),
+        stats: read_file(&path(ArtifactType::OhlcvStats)),
+        adaptive_fee: read_file(&path(ArtifactType::OhlcvAdaptiveFee)),
+    };
+    std::fs::remove_dir_all(dir).unwrap();
+    files
+}
+
+fn default_options() -> OhlcvOptions {
+    OhlcvOptions {
+        anchor_mints: DEFAULT_ANCHOR_MINTS.map(String::from).to_vec(),
+        dense_minutely: false,
+        top_traders: 10,
+    }
+}
+
+// line of the pool (files are not sorted by pool)
+fn pool<'a>(lines: &'a [Value], whirlpool: &str) -> &'a Value {
+    lines.iter().find(|line| line["w"] == whirlpool || line["whirlpool"] == whirlpool).unwrap()
+}
+
+// f64 written as a decimal string (10 significant digits)
+fn assert_f64(value: &Value, expected: f64) {
+    let value: f64 = value.as_str().unwrap().parse().unwrap();
+    assert!((value - expected).abs() <= expected.abs() * 1e-9, "{} != {}", value, expected);
+}
+
+// every key of short key output is expanded (keys are in the same order thanks to preserve_order)
+fn assert_expanded(short: &Value, long: &Value) {
+    match (short, long) {
+        (Value::Object(short), Value::Object(long)) => {
+            assert_eq!(short.len(), long.len());
+            for ((short_key, short_value), (long_key, long_value)) in short.iter().zip(long.iter()) {
+                assert_ne!(short_key, long_key, "{} is not expanded", short_key);
+                assert_expanded(short_value, long_value);
+            }
+        }
+        (Value::Array(short), Value::Array(long)) => {
+            assert_eq!(short.len(), long.len());
+            short.iter().zip(long.iter()).for_each(|(short, long)| assert_expanded(short, long));
+        }
+        _ => {}
+    }
+}
+
+#[test]
+fn ohlcv_usd() {
+    let files = process("usd", &default_options(), KeyFormat::Short);
+
+    // SOL is priced through the SOL/USDC pool at the close of each minute, USDC is an anchor
+    let sol_usdc = pool(&files.minutely, SOL_USDC);
+    // 1 SOL and 300 USDC in at 150.2
+    assert_eq!(sol_usdc["m"][0]["u"], json!({ "ca": "1.502000000e2", "cb": "1.000000000e0", "v": "4.502000000e2" }));
+    // 0.5 SOL in at 150.1
+    assert_eq!(sol_usdc["m"][1]["u"], json!({ "ca": "1.501000000e2", "cb": "1.000000000e0", "v": "7.505000000e1" }));
+    // daily close is the close of the last minute, daily volume is the sum of the minutes
+    assert_eq!(sol_usdc["d"]["u"], json!({ "ca": "1.501000000e2", "cb": "1.000000000e0", "v": "5.252500000e2" }));
+    assert_eq!(pool(&files.daily, SOL_USDC)["d"]["u"], sol_usdc["d"]["u"]);
+
+    // the adaptive fee pool has no liquidity, so it is priced by the SOL price of the other pool
+    let adaptive_fee = pool(&files.minutely, ADAPTIVE_FEE_SOL_USDC);
+    assert_eq!(adaptive_fee["m"][0]["u"], json!({ "ca": "1.502000000e2", "cb": "1.000000000e0", "v": "1.502000000e2" }));
+
+    // ORCA is priced through SOL (2 hops): 0.0199 SOL * 150.2 USD, 10 ORCA in
+    let orca_sol = pool(&files.minutely, ORCA_SOL);
+    assert_eq!(orca_sol["m"][0]["u"], json!({ "ca": "2.988980000e0", "cb": "1.502000000e2", "v": "2.988980000e1" }));
+    // daily close follows the SOL price at the end of the day
+    assert_eq!(orca_sol["d"]["u"], json!({ "ca": "2.986990000e0", "cb": "1.501000000e2", "v": "2.988980000e1" }));
+
+    // no USD prices without a path from the anchor mints
+    let unpriced = process("usd-unpriced", &OhlcvOptions { anchor_mints: vec![], ..default_options() }, KeyFormat::Short);
+    assert!(unpriced.minutely.iter().all(|line| line["d"].get("u").is_none() && line["m"][0].get("u").is_none()));
+
+    let long = process("usd-long", &default_options(), KeyFormat::Long);
+    let long_sol_usdc = pool(&long.minutely, SOL_USDC);
+    assert_expanded(sol_usdc, long_sol_usdc);
+    assert_eq!(long_sol_usdc["daily"]["usd"]["closeA"], "1.501000000e2");
+    assert_eq!(long_sol_usdc["minutely"][0]["usd"]["volume"], "4.502000000e2");
+}
+
+#[test]
+fn ohlcv_interval() {
+    for interval in OhlcvInterval::ALL {
+        let artifact = ArtifactType::OhlcvInterval(interval);
+        assert_eq!(ArtifactType::from_name(artifact.name()), Some(artifact));
+        assert_eq!(artifact.file_name(20240102), format!("whirlpool-ohlcv-{}-20240102.jsonl.gz", interval.name()));
+        assert_eq!(86400 % interval.seconds(), 0);
+    }
+
+    let files = process("interval", &default_options(), KeyFormat::Short);
+
+    // trades at 00:01 and 00:06 are in the 5m candles of 00:00 and 00:05
+    let minutes5 = pool(&files.intervals[0], SOL_USDC);
+    assert_eq!(minutes5["i"], 300);
+    let candles = minutes5["c"].as_array().unwrap();
+    assert_eq!(candles.iter().map(|candle| candle["t"].as_i64().unwrap()).collect::<Vec<_>>(), vec![DAILY_TIMESTAMP, DAILY_TIMESTAMP + 300]);
+    assert_eq!(candles[0]["v"], json!({ "ab": { "ti": "1000000000", "to": "150000000", "c": 1 }, "ba": { "ti": "300000000", "to": "2000000000", "c": 1 } }));
+    assert_eq!(candles[1]["v"]["ab"], json!({ "ti": "500000000", "to": "75000000", "c": 1 }));
+    // a candle opens at the close of the previous trade
+    assert_eq!(candles[1]["p"]["sp"]["o"], candles[0]["p"]["sp"]["c"]);
+    assert_eq!(candles[1]["p"]["dp"], json!({ "o": "1.502000000e2", "h": "1.502000000e2", "l": "1.501000000e2", "c": "1.501000000e2" }));
+    // USD prices of the last priced minute and the sum of the USD volume of the minutes
+    assert_eq!(candles[0]["u"], pool(&files.minutely, SOL_USDC)["m"][0]["u"]);
+
+    // all trades of the pool are in the 1h candle of 00:00
+    let hours1 = pool(&files.intervals[1], SOL_USDC);
+    assert_eq!(hours1["i"], 3600);
+    assert_eq!(hours1["c"].as_array().unwrap().len(), 1);
+    assert_eq!(hours1["c"][0]["v"], hours1["d"]["v"]);
+    assert_eq!(hours1["c"][0]["u"]["v"], "5.252500000e2");
+    // daily is the same as the daily file
+    assert_eq!(hours1["d"], pool(&files.daily, SOL_USDC)["d"]);
+
+    let long = process("interval-long", &default_options(), KeyFormat::Long);
+    let long_minutes5 = pool(&long.intervals[0], SOL_USDC);
+    assert_expanded(minutes5, long_minutes5);
+    assert_eq!(long_minutes5["interval"], 300);
+    assert_eq!(long_minutes5["candles"][1]["ohlc"]["decimalPrice"]["close"], "1.501000000e2");
+}
+
+#[test]
+fn ohlcv_dense_minutely() {
+    let sparse = process("sparse", &default_options(), KeyFormat::Short);
+    // sparse file has the minutes with trades only and no synthetic flag
+    assert!(sparse.minutely.iter().all(|line| line["m"].as_array().unwrap().iter().all(|minutely| minutely.get("s").is_none())));
+
+    let files = process("dense", &OhlcvOptions { dense_minutely: true, ..default_options() }, KeyFormat::Short);
+
+    // every minute from the initialization of the pool (00:00) to the end of the day
+    let sol_usdc = pool(&files.minutely, SOL_USDC);
+    let minutely = sol_usdc["m"].as_array().unwrap();
+    assert_eq!(minutely.len(), 1440);
+    assert!(minutely.iter().enumerate().all(|(i, minutely)| minutely["t"] == DAILY_TIMESTAMP + i as i64 * 60));
+    // the minute before the first trade carries the initial price
+    assert_eq!(minutely[0]["s"], true);
+    assert_eq!(minutely[0]["p"]["sp"], json!({ "o": "7144393258922745604", "h": "7144393258922745604", "l": "7144393258922745604", "c": "7144393258922745604" }));
+    assert_eq!(minutely[0]["v"]["ab"]["c"], 0);
+    // minutes with trades are the same as the sparse file
+    let sparse_sol_usdc = pool(&sparse.minutely, SOL_USDC);
+    assert_eq!(minutely[1], sparse_sol_usdc["m"][0]);
+    assert_eq!(minutely[6], sparse_sol_usdc["m"][1]);
+    // empty minutes carry the previous close and have no USD prices
+    assert_eq!(minutely[2]["s"], true);
+    assert_eq!(minutely[2]["p"]["sp"]["o"], minutely[1]["p"]["sp"]["c"]);
+    assert_eq!(minutely[2]["p"]["sp"]["l"], minutely[1]["p"]["sp"]["c"]);
+    assert!(minutely[2].get("u").is_none());
+    assert_eq!(minutely[1439]["p"]["sp"]["c"], sparse_sol_usdc["d"]["p"]["sp"]["c"]);
+    // daily is not changed
+    assert_eq!(sol_usdc["d"], sparse_sol_usdc["d"]);
+
+    // the pool initialized at 00:02 starts at 00:02
+    let adaptive_fee = pool(&files.minutely, ADAPTIVE_FEE_SOL_USDC)["m"].as_array().unwrap();
+    assert_eq!(adaptive_fee.len(), 1438);
+    assert_eq!(adaptive_fee[0]["t"], DAILY_TIMESTAMP + 120);
+
+    let long = process("dense-long", &OhlcvOptions { dense_minutely: true, ..default_options() }, KeyFormat::Long);
+    let long_minutely = &pool(&long.minutely, SOL_USDC)["minutely"];
+    assert_eq!(long_minutely[0]["synthetic"], true);
+    assert!(long_minutely[1].get("synthetic").is_none());
+}
+
+// time-weighted average of the prices (square of sqrt price) as sqrt price
+fn twap_sqrt_price(sqrt_price_seconds: &[(u128, u32)]) -> BigUint {
+    let price_seconds = sqrt_price_seconds.iter().map(|(sqrt_price, seconds)| BigUint::from(*sqrt_price).pow(2) * *seconds).sum::<BigUint>();
+    let seconds = sqrt_price_seconds.iter().map(|(_, seconds)| *seconds).sum::<u32>();
+    (price_seconds / seconds).sqrt()
+}
+
+#[test]
+fn ohlcv_average_prices() {
+    // sqrt prices of the SOL/USDC pool: initial (150), after the trades at 00:01:00 (149.9), 00:01:30 (150.2) and 00:06:40 (150.1)
+    let sqrt_prices: [u128; 4] = [7144393258922745604, 7142011397460009351, 7149154601176628437, 7146774326563898391];
+
+    let files = process("average-prices", &default_options(), KeyFormat::Short);
+    let sol_usdc = pool(&files.minutely, SOL_USDC);
+
+    // 1 SOL for 150 USDC and 2 SOL for 300 USDC: 450 USDC / 3 SOL
+    let minutely = &sol_usdc["m"][0];
+    assert_eq!(minutely["vw"], json!({ "sp": "7144393258922745604", "dp": "1.500000000e2" }));
+    // 149.9 for 30 seconds and 150.2 for 30 seconds since the first trade of the minute
+    let twap = twap_sqrt_price(&[(sqrt_prices[1], 30), (sqrt_prices[2], 30)]);
+    assert_eq!(minutely["tw"], json!({ "sp": twap.to_string(), "dp": "1.500500000e2" }));
+
+    // daily TWAP is weighted by the seconds each price stood from the initialization to the end of the day
+    let twap = twap_sqrt_price(&[(sqrt_prices[0], 60), (sqrt_prices[1], 30), (sqrt_prices[2], 310), (sqrt_prices[3], 86400 - 400)]);
+    assert_eq!(sol_usdc["d"]["tw"], json!({ "sp": twap.to_string(), "dp": "1.501002199e2" }));
+    assert_eq!(sol_usdc["d"]["vw"]["dp"], "1.500000000e2");
+
+    // 299 USDC / 2 SOL in the adaptive fee pool
+    assert_eq!(pool(&files.minutely, ADAPTIVE_FEE_SOL_USDC)["d"]["vw"]["dp"], "1.495000000e2");
+
+    let long = process("average-prices-long", &default_options(), KeyFormat::Long);
+    let long_sol_usdc = pool(&long.minutely, SOL_USDC);
+    assert_eq!(long_sol_usdc["minutely"][0]["vwap"]["decimalPrice"], "1.500000000e2");
+    assert_eq!(long_sol_usdc["daily"]["twap"]["sqrtPrice"], twap.to_string());
+}
+
+#[test]
+fn ohlcv_fees() {
+    let files = process("fees", &default_options(), KeyFormat::Short);
+    let sol_usdc = pool(&files.minutely, SOL_USDC);
+
+    // exact fees of the trades in the event
+    assert_eq!(sol_usdc["m"][0]["f"], json!({
+        "lpfa": "2610000", "lpfb": "783000", "pfa": "390000", "pfb": "117000", "mnfr": 3000, "mxfr": 3000, "vwfr": 3000, "etc": 0,
+    }));
+    // estimated from the fee rate (0.3% of 0.5 SOL, 13% of it to the protocol) if the trade has no fees
+    assert_eq!(sol_usdc["m"][1]["f"], json!({
+        "lpfa": "1305000", "lpfb": "0", "pfa": "195000", "pfb": "0", "mnfr": 3000, "mxfr": 3000, "vwfr": 3000, "etc": 1,
+    }));
+    // daily fees are the sum of the trades
+    assert_eq!(sol_usdc["ef"], json!({ "lpfa": "3915000", "lpfb": "783000", "pfa": "585000", "pfb": "117000" }));
+    assert_eq!(sol_usdc["d"]["f"]["etc"], 1);
+
+    // fee rate of the adaptive fee pool is the effective one of each trade:
+    // 3400000 / 1 SOL in and 780000 / 150 USDC in, weighted by 1 SOL of each trade
+    let adaptive_fee = pool(&files.minutely, ADAPTIVE_FEE_SOL_USDC);
+    assert_eq!(adaptive_fee["m"][0]["f"]["mnfr"], 3400);
+    assert_eq!(adaptive_fee["m"][1]["f"]["mnfr"], 5200);
+    assert_eq!(adaptive_fee["d"]["f"], json!({
+        "lpfa": "2958000", "lpfb": "678600", "pfa": "442000", "pfb": "101400", "mnfr": 3400, "mxfr": 5200, "vwfr": 4300, "etc": 0,
+    }));
+
+    let long = process("fees-long", &default_options(), KeyFormat::Long);
+    let long_adaptive_fee = pool(&long.minutely, ADAPTIVE_FEE_SOL_USDC);
+    assert_eq!(long_adaptive_fee["daily"]["fees"]["vwapFeeRate"], 4300);
+    assert_eq!(long_adaptive_fee["daily"]["fees"]["estimatedTradeCount"], 0);
+    assert_eq!(long_adaptive_fee["estimatedFees"]["protocolFeeB"], "101400");
+}
+
+#[test]
+fn ohlcv_liquidity() {
+    let files = process("liquidity", &default_options(), KeyFormat::Short);
+
+    // new pool opens at 0, deposited at 00:00 and the trade at 00:01:30 crossed a tick
+    let sol_usdc = pool(&files.daily, SOL_USDC);
+    assert_eq!(sol_usdc["l"], json!({
+        "p": { "o": "0", "h": "10000000000000", "l": "0", "c": "8000000000000" },
+        "dc": 1, "wc": 0, "rc": 0, "aa": "33000000000", "ab": "5000000000", "ra": "0", "rb": "0", "pc": 1,
+    }));
+    // the minutely file has the same liquidity
+    assert_eq!(pool(&files.minutely, SOL_USDC)["l"], sol_usdc["l"]);
+
+    // no liquidity events
+    let adaptive_fee = pool(&files.daily, ADAPTIVE_FEE_SOL_USDC);
+    assert_eq!(adaptive_fee["l"]["p"], json!({ "o": "0", "h": "0", "l": "0", "c": "0" }));
+    assert_eq!(adaptive_fee["l"]["pc"], 0);
+
+    let long = process("liquidity-long", &default_options(), KeyFormat::Long);
+    let long_sol_usdc = pool(&long.daily, SOL_USDC);
+    assert_expanded(&sol_usdc["l"], &long_sol_usdc["liquidity"]);
+    assert_eq!(long_sol_usdc["liquidity"]["ohlc"]["high"], "10000000000000");
+    assert_eq!(long_sol_usdc["liquidity"]["depositCount"], 1);
+    assert_eq!(long_sol_usdc["liquidity"]["addedA"], "33000000000");
+    assert_eq!(long_sol_usdc["liquidity"]["positionCount"], 1);
+}
+
+#[test]
+fn ohlcv_traders() {
+    let files = process("traders", &default_options(), KeyFormat::Short);
+
+    // 2 token authorities in 3 trades, all paid by the same payer
+    let sol_usdc = pool(&files.daily, SOL_USDC);
+    assert_eq!(sol_usdc["d"]["tr"], json!({ "tac": 2, "pc": 1 }));
+    // tracked for the candles of an hour or longer only
+    assert_eq!(pool(&files.intervals[1], SOL_USDC)["c"][0]["tr"], json!({ "tac": 2, "pc": 1 }));
+    assert!(pool(&files.intervals[0], SOL_USDC)["c"][0].get("tr").is_none());
+    assert!(pool(&files.minutely, SOL_USDC)["m"][0].get("tr").is_none());
+
+    // sorted by the volume of token A
+    assert_eq!(sol_usdc["tt"], json!([
+        { "ta": DELEGATED_TRADER, "c": 1, "va": "2000000000", "vb": "300000000" },
+        { "ta": TRADER, "c": 2, "va": "1500000000", "vb": "225000000" },
+    ]));
+    // the minutely file has no top traders
+    assert!(pool(&files.minutely, SOL_USDC).get("tt").is_none());
+
+    let top = process("traders-top", &OhlcvOptions { top_traders: 1, ..default_options() }, KeyFormat::Short);
+    assert_eq!(pool(&top.daily, SOL_USDC)["tt"].as_array().unwrap().len(), 1);
+    assert_eq!(pool(&top.daily, SOL_USDC)["tt"][0]["ta"], DELEGATED_TRADER);
+
+    let long = process("traders-long", &default_options(), KeyFormat::Long);
+    let long_sol_usdc = pool(&long.daily, SOL_USDC);
+    assert_expanded(sol_usdc, long_sol_usdc);
+    assert_eq!(long_sol_usdc["daily"]["traders"], json!({ "tokenAuthorityCount": 2, "payerCount": 1 }));
+    assert_eq!(long_sol_usdc["topTraders"][1]["tokenAuthority"], TRADER);
+    assert_eq!(long_sol_usdc["topTraders"][1]["volumeB"], "225000000");
+}
+
+#[test]
+fn ohlcv_pair_and_token() {
+    for (artifact, name) in [(ArtifactType::OhlcvPair, "ohlcv-pair"), (ArtifactType::OhlcvToken, "ohlcv-token")] {
+        assert!(ArtifactType::ALL.contains(&artifact));
+        assert_eq!(ArtifactType::from_name(name), Some(artifact));
+        assert_eq!(artifact.file_name(20240102), format!("whirlpool-{}-20240102.jsonl.gz", name));
+    }
+
+    let files = process("pair-and-token", &default_options(), KeyFormat::Short);
+
+    // sorted by (mint A, mint B), the 2 SOL/USDC pools are merged
+    assert_eq!(files.pair.len(), 2);
+    let sol_usdc = &files.pair[0];
+    assert_eq!(sol_usdc["ta"], json!({ "m": SOL, "d": 9 }));
+    assert_eq!(sol_usdc["tb"], json!({ "m": USDC, "d": 6 }));
+    assert_eq!(sol_usdc["t"], DAILY_TIMESTAMP);
+    assert_eq!(sol_usdc["w"], json!([ADAPTIVE_FEE_SOL_USDC, SOL_USDC]));
+    assert_eq!(sol_usdc["v"], json!({ "ab": { "ti": "2500000000", "to": "374000000", "c": 3 }, "ba": { "ti": "450000000", "to": "3000000000", "c": 2 } }));
+    // 824 USDC / 5.5 SOL
+    assert_eq!(sol_usdc["vw"]["dp"], "1.498181818e2");
+    assert_eq!(sol_usdc["f"], json!({ "lpfa": "6873000", "lpfb": "1461600", "pfa": "1027000", "pfb": "218400" }));
+    assert_eq!(files.pair[1]["w"], json!([ORCA_SOL]));
+
+    // sorted by mint, volume in and out of the pools with the mint as token A or token B
+    let mints = files.token.iter().map(|token| token["m"].as_str().unwrap()).collect::<Vec<_>>();
+    assert_eq!(mints, vec![USDC, SOL, ORCA]);
+    assert_eq!(files.token[0], json!({
+        "m": USDC, "d": 6, "t": DAILY_TIMESTAMP, "pc": 2, "c": 5, "vi": "450000000", "vo": "374000000", "lpf": "1461600", "pf": "218400",
+    }));
+    // 2 SOL/USDC pools and the ORCA/SOL pool
+    assert_eq!(files.token[1]["pc"], 3);
+    assert_eq!(files.token[1]["c"], 6);
+    assert_eq!(files.token[1]["vo"], "3199000000");
+    assert_eq!(files.token[2]["vi"], "10000000");
+
+    let long = process("pair-and-token-long", &default_options(), KeyFormat::Long);
+    assert_expanded(&files.pair[0], &long.pair[0]);
+    assert_expanded(&files.token[0], &long.token[0]);
+    assert_eq!(long.pair[0]["whirlpools"][1], SOL_USDC);
+    assert_eq!(long.pair[0]["volume"]["aToB"]["totalIn"], "2500000000");
+    assert_eq!(long.pair[0]["fees"]["liquidityProviderFeeA"], "6873000");
+    assert_eq!(long.token[1]["poolCount"], 3);
+    assert_eq!(long.token[1]["volumeOut"], "3199000000");
+}
+
+#[test]
+fn ohlcv_stats() {
+    let artifact = ArtifactType::OhlcvStats;
+    assert!(ArtifactType::ALL.contains(&artifact));
+    assert_eq!(ArtifactType::from_name("ohlcv-stats"), Some(artifact));
+    assert_eq!(artifact.file_name(20240102), "whirlpool-ohlcv-stats-20240102.jsonl.gz");
+
+    // sqrt prices of the SOL/USDC pool: initial, after the trades at 00:01:00, 00:01:30 and 00:06:40
+    let sqrt_prices: [f64; 4] = [7144393258922745604.0, 7142011397460009351.0, 7149154601176628437.0, 7146774326563898391.0];
+
+    let files = process("stats", &default_options(), KeyFormat::Short);
+    let sol_usdc = pool(&files.stats, SOL_USDC);
+    assert_eq!(sol_usdc["ta"], json!({ "m": SOL, "d": 9 }));
+    let stats = &sol_usdc["s"];
+    assert_eq!(stats["t"], DAILY_TIMESTAMP);
+
+    // log returns of the closes of the 2 minutes with trades (from the initial price)
+    let log_return = |from: f64, to: f64| 2.0 * (to / from).ln();
+    let realized_volatility = (log_return(sqrt_prices[0], sqrt_prices[2]).powi(2) + log_return(sqrt_prices[2], sqrt_prices[3]).powi(2)).sqrt();
+    assert_f64(&stats["rv"], realized_volatility);
+    assert_eq!(stats["rc"], 2);
+    assert_eq!(stats["c"], 3);
+
+    // |new price / old price - 1| of each trade
+    let price_impacts = sqrt_prices.windows(2).map(|pair| ((pair[1] / pair[0]).powi(2) - 1.0).abs()).collect::<Vec<_>>();
+    assert_f64(&stats["pi"]["a"], price_impacts.iter().sum::<f64>() / 3.0);
+    assert_f64(&stats["pi"]["m"], price_impacts.iter().cloned().fold(0.0, f64::max));
+
+    // the trade at 00:01:30 changed the active liquidity, the trade at 00:06:40 crossed ticks whose net liquidity cancels out
+    assert_eq!(stats["itc"], json!({ "c": 2, "s": "6.666666667e-1" }));
+    assert_eq!(pool(&files.stats, ORCA_SOL)["s"]["itc"], json!({ "c": 0, "s": "0e0" }));
+
+    let long = process("stats-long", &default_options(), KeyFormat::Long);
+    let long_sol_usdc = pool(&long.stats, SOL_USDC);
+    assert_expanded(sol_usdc, long_sol_usdc);
+    assert_eq!(long_sol_usdc["stats"]["returnCount"], 2);
+    assert_eq!(long_sol_usdc["stats"]["priceImpact"]["max"], stats["pi"]["m"]);
+    assert_eq!(long_sol_usdc["stats"]["initializedTickCrossingTrades"]["share"], "6.666666667e-1");
+}
+
+#[test]
+fn ohlcv_adaptive_fee() {
+    let artifact = ArtifactType::OhlcvAdaptiveFee;
+    assert!(ArtifactType::ALL.contains(&artifact));
+    assert_eq!(ArtifactType::from_name("ohlcv-adaptive-fee"), Some(artifact));
+    assert_eq!(artifact.file_name(20240102), "whirlpool-ohlcv-adaptive-fee-20240102.jsonl.gz");
+
+    let files = process("adaptive-fee", &default_options(), KeyFormat::Short);
+
+    // adaptive fee pools only
+    assert_eq!(files.adaptive_fee.len(), 1);
+    let adaptive_fee = &files.adaptive_fee[0];
+    assert_eq!(adaptive_fee["w"], ADAPTIVE_FEE_SOL_USDC);
+    // constants of the initialization, updated at 00:05
+    assert_eq!(adaptive_fee["afc"], json!({ "fp": 30, "dp": 600, "rf": 5000, "afcf": 4000, "mva": 450000, "tgs": 64, "mstt": 64 }));
+    assert_eq!(adaptive_fee["cuc"], 1);
+
+    // the first trade is a major swap resetting the reference, effective fee rates are 3400 and 5200
+    assert_eq!(adaptive_fee["d"], json!({
+        "t": DAILY_TIMESTAMP, "c": 2, "va": { "o": 0, "h": 45000, "l": 0, "c": 45000 }, "msc": 1, "rrc": 1,
+        "fr": { "mn": 3400, "p25": 3400, "p50": 3400, "p75": 5200, "p90": 5200, "mx": 5200 },
+    }));
+    let minutely = adaptive_fee["m"].as_array().unwrap();
+    assert_eq!(minutely.iter().map(|minutely| minutely["t"].as_i64().unwrap()).collect::<Vec<_>>(), vec![DAILY_TIMESTAMP + 180, DAILY_TIMESTAMP + 240]);
+    assert_eq!(minutely[1]["va"], json!({ "o": 30000, "h": 45000, "l": 30000, "c": 45000 }));
+    assert_eq!(minutely[1]["msc"], 0);
+    assert_eq!(minutely[1]["fr"]["mn"], 5200);
+
+    let long = process("adaptive-fee-long", &default_options(), KeyFormat::Long);
+    assert_expanded(adaptive_fee, &long.adaptive_fee[0]);
+    assert_eq!(long.adaptive_fee[0]["adaptiveFeeConstants"]["maxVolatilityAccumulator"], 450000);
+    assert_eq!(long.adaptive_fee[0]["constantsUpdateCount"], 1);
+    assert_eq!(long.adaptive_fee[0]["daily"]["volatilityAccumulator"]["high"], 45000);
+    assert_eq!(long.adaptive_fee[0]["minutely"][0]["feeRate"]["percentile75"], 3400);
+    assert_eq!(long.adaptive_fee[0]["minutely"][0]["referenceResetCount"], 1);
+}
@@ -0,0 +1,177 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::{read::GzDecoder, write::GzEncoder};
+use sedimentology_archiver::artifact::ArtifactType;
+use sedimentology_archiver::converter;
+use sedimentology_archiver::converter::model::long_key::KeyFormat;
+use sedimentology_archiver::converter::model::ohlcv::period::OhlcvPeriod;
+use serde_json::{json, Value};
+
+const SECONDS_PER_DAY: i64 = 60 * 60 * 24;
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn tmpdir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("whirlpool-ohlcv-rollup-test-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// daily data of the fixture (minutely file of 20231115 without minutely)
+fn daily_fixture() -> Vec<Value> {
+    std::fs::read_to_string(fixture_path("ohlcv/minutely.jsonl"))
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let mut daily: Value = serde_json::from_str(line).unwrap();
+            daily.as_object_mut().unwrap().remove("m");
+            daily
+        })
+        .collect()
+}
+
+fn write_daily_file(dir: &Path, yyyymmdd_date: u32, lines: &[Value]) -> String {
+    let path = dir.join(ArtifactType::OhlcvDaily.file_name(yyyymmdd_date));
+    let mut encoder = GzEncoder::new(File::create(&path).unwrap(), flate2::Compression::default());
+    for line in lines {
+        encoder.write_all(line.to_string().as_bytes()).unwrap();
+        encoder.write_all(b"\n").unwrap();
+    }
+    encoder.finish().unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn read_file(path: &str) -> Vec<Value> {
+    let mut jsonl = String::new();
+    GzDecoder::new(File::open(path).unwrap()).read_to_string(&mut jsonl).unwrap();
+    jsonl.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+// 20231115: existing pool only, 20231116: existing pool and a new pool
+fn daily_files(dir: &Path) -> Vec<String> {
+    let fixture = daily_fixture();
+    let new = fixture[1].clone();
+
+    let mut existing = fixture[0].clone();
+    existing["d"]["tw"] = json!({ "sp": "7144000000000000000", "dp": "1.499834871e2" });
+
+    let mut existing_next_day = existing.clone();
+    existing_next_day["is"]["p"] = json!({ "pcsp": "7145000000000000000", "pcdp": "1.500200000e2" });
+    existing_next_day["d"]["t"] = json!(1700006400 + SECONDS_PER_DAY);
+    existing_next_day["d"]["p"] = json!({
+        "sp": { "o": "7145000000000000000", "h": "7146000000000000000", "l": "7144000000000000000", "c": "7146000000000000000" },
+        "dp": { "o": "1.500200000e2", "h": "1.500600000e2", "l": "1.499700000e2", "c": "1.500600000e2" },
+    });
+    existing_next_day["d"]["v"] = json!({ "ab": { "ti": "0", "to": "0", "c": 0 }, "ba": { "ti": "150000000", "to": "1000000000", "c": 1 } });
+    existing_next_day["d"]["tw"] = json!({ "sp": "7146000000000000000", "dp": "1.500674762e2" });
+    existing_next_day["ef"] = json!({ "lpfa": "0", "lpfb": "39150", "pfa": "0", "pfb": "5850" });
+
+    let mut new_next_day = new.clone();
+    new_next_day["is"]["p"]["ibt"] = json!(1700006500 + SECONDS_PER_DAY);
+    new_next_day["d"]["t"] = json!(1700006400 + SECONDS_PER_DAY);
+
+    vec![
+        write_daily_file(dir, 20231115, &[existing]),
+        write_daily_file(dir, 20231116, &[existing_next_day, new_next_day]),
+    ]
+}
+
+#[test]
+fn ohlcv_period_dates() {
+    assert_eq!(OhlcvPeriod::Weekly.yyyymmdd_dates(20240103), (20240101..=20240107).collect::<Vec<_>>());
+    // ISO week across the year boundary
+    assert_eq!(OhlcvPeriod::Weekly.first_yyyymmdd_date(20230101), 20221226);
+    assert_eq!(OhlcvPeriod::Monthly.yyyymmdd_dates(20240215).len(), 29);
+    assert_eq!(OhlcvPeriod::Monthly.first_yyyymmdd_date(20240229), 20240201);
+
+    assert_eq!(OhlcvPeriod::closing_on(20240929), vec![OhlcvPeriod::Weekly]);
+    assert_eq!(OhlcvPeriod::closing_on(20240930), vec![OhlcvPeriod::Monthly]);
+    assert_eq!(OhlcvPeriod::closing_on(20240331), vec![OhlcvPeriod::Weekly, OhlcvPeriod::Monthly]);
+    assert!(OhlcvPeriod::closing_on(20240101).is_empty());
+
+    // rollup files are archived on the last date of the period only
+    let artifacts = ArtifactType::of_profile(20240331, &[], &[], false);
+    assert!(artifacts.contains(&ArtifactType::OhlcvRollup(OhlcvPeriod::Weekly)));
+    assert!(artifacts.contains(&ArtifactType::OhlcvRollup(OhlcvPeriod::Monthly)));
+    assert!(!ArtifactType::of_profile(20240101, &[], &[], false).iter().any(|artifact| matches!(artifact, ArtifactType::OhlcvRollup(_))));
+    for period in OhlcvPeriod::ALL {
+        let artifact = ArtifactType::OhlcvRollup(period);
+        assert_eq!(ArtifactType::from_name(artifact.name()), Some(artifact));
+        assert_eq!(artifact.file_name(20240331), format!("whirlpool-ohlcv-{}-20240331.jsonl.gz", period.name()));
+    }
+}
+
+#[test]
+fn weekly_rollup() {
+    let dir = tmpdir("weekly");
+    let output = dir.join("weekly.jsonl.gz").to_string_lossy().to_string();
+    converter::process::ohlcv::rollup::process(OhlcvPeriod::Weekly, &daily_files(&dir), output.clone(), KeyFormat::Short).unwrap();
+
+    // sorted by whirlpool
+    let lines = read_file(&output);
+    assert_eq!(lines.len(), 2);
+    let (new, existing) = (&lines[0], &lines[1]);
+
+    // monday of the week
+    assert_eq!(existing["p"]["t"], 1699833600);
+    assert_eq!(existing["dc"], 2);
+    assert_eq!(existing["is"]["p"]["pcsp"], "7145929705339707732");
+    assert_eq!(existing["p"]["p"]["sp"], json!({ "o": "7145929705339707732", "h": "7146000000000000000", "l": "7144000000000000000", "c": "7146000000000000000" }));
+    assert_eq!(existing["p"]["p"]["dp"]["l"], "1.499700000e2");
+    assert_eq!(existing["p"]["v"], json!({ "ab": { "ti": "1000000000", "to": "150000000", "c": 1 }, "ba": { "ti": "150000000", "to": "1000000000", "c": 1 } }));
+    assert_eq!(existing["ef"], json!({ "lpfa": "261000", "lpfb": "39150", "pfa": "39000", "pfb": "5850" }));
+    // total B / total A
+    assert_eq!(existing["p"]["vw"]["dp"], "1.500000000e2");
+    // average of the prices of the days (not of the sqrt prices) weighted by the seconds priced in the day
+    assert_eq!(existing["p"]["tw"], json!({ "sp": "7145000069979005955", "dp": "1.500254817e2" }));
+    // no twap in the daily file of the new pool
+    assert!(new["p"].get("tw").is_none());
+    // distinct traders are unknown
+    assert!(existing["p"].get("tr").is_none());
+
+    // initialized within the week
+    assert_eq!(new["dc"], 1);
+    assert_eq!(new["p"]["t"], 1699833600);
+    assert_eq!(new["is"]["t"], "n");
+    assert_eq!(new["is"]["p"]["ibt"], 1700006500 + SECONDS_PER_DAY);
+    // no trades
+    assert_eq!(new["p"]["v"]["ab"]["c"], 0);
+    assert!(new["p"].get("vw").is_none());
+    assert_eq!(new["p"]["p"]["sp"]["o"], new["p"]["p"]["sp"]["c"]);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn monthly_rollup() {
+    let dir = tmpdir("monthly");
+    let output = dir.join("monthly.jsonl.gz").to_string_lossy().to_string();
+    converter::process::ohlcv::rollup::process(OhlcvPeriod::Monthly, &daily_files(&dir), output.clone(), KeyFormat::Long).unwrap();
+
+    let lines = read_file(&output);
+    assert_eq!(lines[1]["whirlpool"], "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ");
+    // first day of the month
+    assert_eq!(lines[1]["period"]["timestamp"], 1698796800);
+    assert_eq!(lines[1]["dayCount"], 2);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn rollup_rejects_daily_file_of_another_period() {
+    let dir = tmpdir("another-period");
+    let mut daily = daily_fixture().remove(0);
+    let mut daily_files = daily_files(&dir);
+    // monday of the next week
+    daily["d"]["t"] = json!(1699833600 + 7 * SECONDS_PER_DAY);
+    daily_files.push(write_daily_file(&dir, 20231120, &[daily]));
+
+    let output = dir.join("weekly.jsonl.gz").to_string_lossy().to_string();
+    assert!(converter::process::ohlcv::rollup::process(OhlcvPeriod::Weekly, &daily_files, output, KeyFormat::Short).is_err());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
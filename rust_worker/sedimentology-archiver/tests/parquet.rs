@@ -0,0 +1,208 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use flate2::write::GzEncoder;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::Field;
+use sedimentology_archiver::artifact::ArtifactType;
+use sedimentology_archiver::converter;
+use sedimentology_archiver::converter::model::event::reader::WhirlpoolEventReader;
+use sedimentology_archiver::converter::model::long_key::{self, KeyFormat};
+use sedimentology_archiver::converter::model::parquet::{ParquetTable, EVENT_TABLES};
+use sedimentology_archiver::manifest;
+use serde_json::{json, Value};
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn tmpdir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("whirlpool-parquet-test-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// the converter reads GZIP compressed files
+fn gzip_fixture(name: &str, dir: &Path) -> String {
+    let path = dir.join(format!("{}.gz", name.replace('/', "-")));
+    let mut encoder = GzEncoder::new(File::create(&path).unwrap(), flate2::Compression::default());
+    encoder.write_all(&std::fs::read(fixture_path(name)).unwrap()).unwrap();
+    encoder.finish().unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn path(dir: &Path, table: ParquetTable) -> String {
+    dir.join(ArtifactType::Parquet(table).file_name(20231115)).to_string_lossy().to_string()
+}
+
+// (column names, rows)
+fn read_parquet(path: &str) -> (Vec<String>, Vec<Value>) {
+    let reader = SerializedFileReader::new(File::open(path).unwrap()).unwrap();
+    let columns = reader
+        .metadata()
+        .file_metadata()
+        .schema_descr()
+        .columns()
+        .iter()
+        .map(|column| column.name().to_string())
+        .collect();
+    let rows = reader
+        .get_row_iter(None)
+        .unwrap()
+        .map(|row| {
+            let row = row.unwrap();
+            let fields = row.get_column_iter().map(|(name, field)| (name.clone(), json_value(field)));
+            Value::Object(fields.collect())
+        })
+        .collect();
+    (columns, rows)
+}
+
+fn json_value(field: &Field) -> Value {
+    match field {
+        Field::Null => Value::Null,
+        Field::Bool(value) => json!(value),
+        Field::Long(value) => json!(value),
+        Field::Str(value) => json!(value),
+        field => panic!("unexpected field {:?}", field),
+    }
+}
+
+#[test]
+fn transaction_tables() {
+    let dir = tmpdir("transaction");
+    let transaction = gzip_fixture("transaction/transactions.jsonl", &dir);
+    let instruction = path(&dir, ParquetTable::TransactionInstruction);
+    let balance = path(&dir, ParquetTable::TransactionBalance);
+    converter::process::parquet::process_transaction(transaction, instruction.clone(), balance.clone()).unwrap();
+
+    let (columns, rows) = read_parquet(&instruction);
+    assert_eq!(
+        columns,
+        vec!["slot", "block_height", "block_time", "transaction_index", "signature", "payer", "instruction_index", "name", "payload"]
+    );
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0]["slot"], json!(300));
+    assert_eq!(rows[1]["instruction_index"], json!(1));
+    assert_eq!(rows[1]["name"], json!("updateFeesAndRewards"));
+    assert_eq!(rows[2]["transaction_index"], json!(2));
+    assert_eq!(rows[2]["signature"], json!("5301sig"));
+    // payload is kept as JSON
+    let payload: Value = serde_json::from_str(rows[0]["payload"].as_str().unwrap()).unwrap();
+    assert_eq!(payload["dataAmount"], json!("1000000000"));
+
+    // u64 over i64::MAX is kept
+    let (_, rows) = read_parquet(&balance);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["pre"], json!("18446744073709551615"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn event_tables() {
+    let dir = tmpdir("event");
+    let event = gzip_fixture("event/v2.jsonl", &dir);
+    converter::process::parquet::process_event(event.clone(), |table| path(&dir, table)).unwrap();
+
+    // one file for each event type (empty tables have the base columns only)
+    for table in ParquetTable::event_tables() {
+        assert!(Path::new(&path(&dir, table)).is_file(), "{} is not written", table.name());
+    }
+    let (columns, rows) = read_parquet(&path(&dir, ParquetTable::Event(&EVENT_TABLES[0])));
+    assert_eq!(columns, vec!["slot", "block_height", "block_time", "signature", "payer"]);
+    assert!(rows.is_empty());
+
+    let traded = ParquetTable::from_name("event-traded").unwrap();
+    let (columns, rows) = read_parquet(&path(&dir, traded));
+    assert_eq!(&columns[..5], &["slot", "block_height", "block_time", "signature", "payer"]);
+    assert!(columns.contains(&"transfer_in_amount".to_string()));
+    assert!(columns.contains(&"old_sqrt_price".to_string()));
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["slot"], json!(301));
+    assert_eq!(rows[0]["block_time"], json!(1700000301));
+    assert_eq!(rows[0]["signature"], json!("5301sig"));
+    assert_eq!(rows[0]["payer"], json!("4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T"));
+    assert_eq!(rows[0]["trade_direction"], json!("AtoB"));
+    assert_eq!(rows[0]["transfer_in_amount"], json!("1000000000"));
+
+    // u128 and decimal prices are the same strings as the long key event file
+    let block = WhirlpoolEventReader::open(&event).unwrap().nth(1).unwrap().unwrap();
+    let long: Value = serde_json::from_str(&long_key::to_string(&block, KeyFormat::Long).unwrap()).unwrap();
+    let payload = &long["transactions"][0]["events"][0]["payload"];
+    assert_eq!(rows[0]["old_sqrt_price"], json!("7145929705339707732"));
+    assert_eq!(rows[0]["old_sqrt_price"], payload["oldSqrtPrice"]);
+    assert_eq!(rows[0]["new_decimal_price"], payload["newDecimalPrice"]);
+
+    let pool_initialized = ParquetTable::from_name("event-pool-initialized").unwrap();
+    let (_, rows) = read_parquet(&path(&dir, pool_initialized));
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["tick_spacing"], json!(64));
+    assert_eq!(rows[0]["sqrt_price"], json!("7145929705339707732"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn ohlcv_tables() {
+    let dir = tmpdir("ohlcv");
+    // daily data is a part of minutely data
+    let ohlcv = gzip_fixture("ohlcv/minutely.jsonl", &dir);
+    let daily = path(&dir, ParquetTable::OhlcvDaily);
+    let minutely = path(&dir, ParquetTable::OhlcvMinutely);
+    converter::process::parquet::process_ohlcv_daily(ohlcv.clone(), daily.clone()).unwrap();
+    converter::process::parquet::process_ohlcv_minutely(ohlcv, minutely.clone()).unwrap();
+
+    let (columns, rows) = read_parquet(&daily);
+    assert_eq!(&columns[..3], &["whirlpool", "whirlpools_config", "token_a_mint"]);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["initial_state_type"], json!("Existing"));
+    assert_eq!(rows[0]["initial_state_payload_previous_close_sqrt_price"], json!("7145929705339707732"));
+    assert_eq!(rows[0]["daily_ohlc_decimal_price_open"], json!("1.500600000e2"));
+    // columns of the other initial state are null
+    assert_eq!(rows[0]["initial_state_payload_initialized_slot"], Value::Null);
+    assert_eq!(rows[1]["initial_state_payload_initialized_slot"], json!(300));
+
+    let (columns, rows) = read_parquet(&minutely);
+    assert!(!columns.iter().any(|column| column.starts_with("daily_") || column.starts_with("initial_state_")));
+    let minutes = std::fs::read_to_string(fixture_path("ohlcv/minutely.jsonl"))
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap()["m"].as_array().unwrap().len())
+        .sum::<usize>();
+    assert_eq!(rows.len(), minutes);
+    assert_eq!(rows[0]["tick_spacing"], json!(64));
+    assert_eq!(rows[0]["timestamp"], json!(1700006460));
+    assert_eq!(rows[0]["volume_a_to_b_total_in"], json!("1000000000"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn parquet_artifact_names() {
+    for table in ParquetTable::all() {
+        let artifact = ArtifactType::Parquet(table);
+        assert_eq!(ArtifactType::from_name(artifact.name()), Some(artifact));
+        // admArchiverStage.artifact is varchar(64)
+        assert!(artifact.name().len() <= 64);
+    }
+    let traded = ArtifactType::from_name("parquet-event-traded").unwrap();
+    assert_eq!(traded.file_name(20240101), "whirlpool-event-traded-20240101.parquet");
+    assert_eq!(traded.key(20240101), "2024/0101/whirlpool-event-traded-20240101.parquet");
+}
+
+#[test]
+fn inspect_parquet_record_count() {
+    let dir = tmpdir("inspect");
+    let transaction = gzip_fixture("transaction/transactions.jsonl", &dir);
+    let instruction = path(&dir, ParquetTable::TransactionInstruction);
+    let balance = path(&dir, ParquetTable::TransactionBalance);
+    converter::process::parquet::process_transaction(transaction, instruction.clone(), balance).unwrap();
+
+    let summary = manifest::inspect(ArtifactType::Parquet(ParquetTable::TransactionInstruction), &instruction).unwrap();
+    assert_eq!(summary.record_count, 3);
+    assert_eq!(summary.first_block, None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
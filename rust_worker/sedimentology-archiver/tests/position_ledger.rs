@@ -0,0 +1,207 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::{read::GzDecoder, write::GzEncoder};
+use sedimentology_archiver::archive::{self, ArchiveOptions};
+use sedimentology_archiver::artifact::ArtifactType;
+use sedimentology_archiver::converter;
+use sedimentology_archiver::storage::{ArchiveStorage, LocalFileStorage};
+use sedimentology_archiver::converter::model::long_key::KeyFormat;
+use serde_json::{json, Value};
+
+const POSITION: &str = "5Ye4bNzMv2N2uNVRPJRYv4gWa1RNpv3MqVHBZwmx4nTS";
+// open on the previous date
+const CARRIED_POSITION: &str = "9Ebyb3ZMfYbcYz5dDpPgaJjhTnJC2gpL7LXjsG3WM1KB";
+// opened before the ledger started
+const UNKNOWN_POSITION: &str = "DqAEf9Ya9jeaPJ2XHWzD3bVPpJVxUvzrUfSBKQXGAFu5";
+// closed on the previous date
+const CLOSED_POSITION: &str = "2xQJBqVCnaTTSm2T2XCkDkWBTrE3TgcxwRJEzxGjVUuJ";
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn tmpdir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("whirlpool-position-ledger-test-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn read_file(path: &str) -> Vec<Value> {
+    let mut jsonl = String::new();
+    GzDecoder::new(File::open(path).unwrap()).read_to_string(&mut jsonl).unwrap();
+    jsonl.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+// event file of 20231115 (the fixture is not compressed)
+fn write_event_file(dir: &Path) -> String {
+    let path = dir.join(ArtifactType::Event.file_name(20231115));
+    let mut encoder = GzEncoder::new(File::create(&path).unwrap(), flate2::Compression::default());
+    encoder.write_all(&std::fs::read(fixture_path("event/position.jsonl")).unwrap()).unwrap();
+    encoder.finish().unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn write_previous_ledger_file(dir: &Path) -> String {
+    let ledger = |position: &str, closed: Option<Value>| {
+        let mut ledger = json!({
+            "p": position,
+            "w": "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ",
+            "ta": { "m": "So11111111111111111111111111111111111111112", "d": 9 },
+            "tb": { "m": "EPjFWdd5AufqSSqeM2qN1xyybapC8G4wEGGkZwyTDt1v", "d": 6 },
+            "o": { "s": 100, "bt": 1699900000 },
+            "lti": -18112,
+            "uti": -17984,
+            "l": "300000",
+            "dp": "1.500000000e2",
+            "d": { "a": "300", "b": "45000" },
+            "wd": { "a": "0", "b": "0" },
+            "f": { "a": "1", "b": "100" },
+            "r": [],
+            "e": [{ "s": 100, "bt": 1699900000, "sg": "100sig", "t": "o", "l": "0", "lti": -18112, "uti": -17984 }],
+        });
+        if let Some(closed) = closed {
+            ledger["cl"] = closed;
+        }
+        ledger
+    };
+
+    let path = dir.join(ArtifactType::PositionLedger.file_name(20231114));
+    let mut encoder = GzEncoder::new(File::create(&path).unwrap(), flate2::Compression::default());
+    for line in [ledger(CARRIED_POSITION, None), ledger(CLOSED_POSITION, Some(json!({ "s": 101, "bt": 1699900001 })))] {
+        encoder.write_all(line.to_string().as_bytes()).unwrap();
+        encoder.write_all(b"\n").unwrap();
+    }
+    encoder.finish().unwrap();
+    path.to_string_lossy().to_string()
+}
+
+#[test]
+fn position_ledger() {
+    let artifact = ArtifactType::PositionLedger;
+    assert_eq!(ArtifactType::from_name("position-ledger"), Some(artifact));
+    assert_eq!(artifact.file_name(20231115), "whirlpool-position-ledger-20231115.jsonl.gz");
+    assert!(ArtifactType::of_profile(20231115, &[], &[], false).contains(&artifact));
+
+    let dir = tmpdir("short");
+    let previous = write_previous_ledger_file(&dir);
+    let output = dir.join("ledger.jsonl.gz").to_string_lossy().to_string();
+    converter::process::position::ledger::process(Some(previous), write_event_file(&dir), output.clone(), KeyFormat::Short).unwrap();
+
+    // sorted by position, the position closed on the previous date is dropped
+    let lines = read_file(&output);
+    let positions = lines.iter().map(|line| line["p"].as_str().unwrap()).collect::<Vec<_>>();
+    assert_eq!(positions, vec![POSITION, POSITION, CARRIED_POSITION, UNKNOWN_POSITION]);
+
+    // opened, deposited, harvested, repositioned, withdrawn and closed in the day
+    let closed = &lines[0];
+    assert_eq!(closed["o"], json!({ "s": 400, "bt": 1700000400 }));
+    assert_eq!(closed["cl"], json!({ "s": 403, "bt": 1700000403 }));
+    assert_eq!(closed["lti"], -18176);
+    assert_eq!(closed["l"], "0");
+    assert_eq!(closed["ta"], json!({ "m": "So11111111111111111111111111111111111111112", "d": 9 }));
+    // reposition transfers 200 of token A from the owner and returns 30000 of token B to the owner
+    assert_eq!(closed["d"], json!({ "a": "1200", "b": "150000" }));
+    assert_eq!(closed["wd"], json!({ "a": "1200", "b": "150000" }));
+    assert_eq!(closed["f"], json!({ "a": "10", "b": "1500" }));
+    assert_eq!(closed["r"], json!([{ "i": 0, "m": "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE", "a": "500" }]));
+    // 10 * 10^-9 * 150 + 1500 * 10^-6
+    assert_eq!(closed["rp"], json!({ "a": "10", "b": "1500", "v": "1.501500000e-3" }));
+    let types = closed["e"].as_array().unwrap().iter().map(|entry| entry["t"].as_str().unwrap()).collect::<Vec<_>>();
+    assert_eq!(types, vec!["o", "d", "fh", "rh", "rp", "w", "c"]);
+    assert_eq!(closed["e"][4], json!({
+        "s": 402, "bt": 1700000402, "sg": "402sig", "t": "rp", "l": "1200000", "lti": -18176, "uti": -17920,
+        "d": { "a": "200", "b": "0" }, "wd": { "a": "0", "b": "30000" },
+    }));
+
+    // reopened at the same address
+    let reopened = &lines[1];
+    assert_eq!(reopened["o"]["s"], 404);
+    assert!(reopened.get("cl").is_none());
+    assert!(reopened.get("rp").is_none());
+    assert_eq!(reopened["l"], "500000");
+    assert_eq!(reopened["d"], json!({ "a": "500", "b": "75000" }));
+
+    // running state is carried over, entries are of the day only
+    let carried = &lines[2];
+    assert_eq!(carried["o"]["s"], 100);
+    assert_eq!(carried["l"], "300000");
+    assert_eq!(carried["f"], json!({ "a": "4", "b": "500" }));
+    assert_eq!(carried["e"].as_array().unwrap().len(), 1);
+    assert_eq!(carried["e"][0]["t"], "fh");
+
+    // history before the ledger is unknown
+    let unknown = &lines[3];
+    assert!(unknown.get("o").is_none());
+    assert_eq!(unknown["l"], "200000");
+    assert_eq!(unknown["wd"], json!({ "a": "100", "b": "15000" }));
+    assert_eq!(unknown["d"], json!({ "a": "0", "b": "0" }));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn position_ledger_in_long_key_format() {
+    let dir = tmpdir("long");
+    let output = dir.join("ledger.jsonl.gz").to_string_lossy().to_string();
+    converter::process::position::ledger::process(None, write_event_file(&dir), output.clone(), KeyFormat::Long).unwrap();
+
+    let lines = read_file(&output);
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0]["position"], POSITION);
+    assert_eq!(lines[0]["realizedPnl"]["amountA"], "10");
+    assert_eq!(lines[0]["entries"][0]["type"], "Opened");
+    assert_eq!(lines[0]["entries"][3]["reward"]["mint"], "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE");
+    // a position seen for the first time without the previous ledger
+    assert_eq!(lines[2]["position"], CARRIED_POSITION);
+    assert!(lines[2].get("opened").is_none());
+    assert_eq!(lines[2]["fees"], json!({ "amountA": "3", "amountB": "400" }));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn position_ledger_chain_across_missing_date() {
+    let artifact = ArtifactType::PositionLedger;
+    let dir = tmpdir("chain");
+    let storage = LocalFileStorage::new(dir.join("archive").to_string_lossy().to_string());
+    let options = ArchiveOptions::default();
+
+    // the ledger starts over on the first date archived with the ledger (the other artifacts are archived before it)
+    storage.put(&write_event_file(&dir), &ArtifactType::Event.key(20231112)).unwrap();
+    assert_eq!(archive::previous_position_ledger_date(20231113, &options, &storage).unwrap(), None);
+
+    // ledger of 20231113 is archived, 20231114 is missing
+    storage.put(&write_previous_ledger_file(&dir), &artifact.key(20231113)).unwrap();
+    assert!(archive::previous_position_ledger_date(20231115, &options, &storage).is_err());
+    // the dates before the archived ledger still start over
+    assert_eq!(archive::previous_position_ledger_date(20231112, &options, &storage).unwrap(), None);
+
+    // the ledger starts over on the configured start date even if an older ledger is archived
+    let start = ArchiveOptions { position_ledger_start_date: Some(20231115), ..ArchiveOptions::default() };
+    assert_eq!(archive::previous_position_ledger_date(20231115, &start, &storage).unwrap(), None);
+    assert!(archive::previous_position_ledger_date(20231116, &start, &storage).is_err());
+
+    // archive 20231114 (no events) chained to 20231113
+    assert_eq!(archive::previous_position_ledger_date(20231114, &options, &storage).unwrap(), Some(20231113));
+    let previous = dir.join("previous.jsonl.gz").to_string_lossy().to_string();
+    storage.get(&artifact.key(20231113), &previous).unwrap();
+    let empty_event = dir.join("empty-event.jsonl.gz");
+    GzEncoder::new(File::create(&empty_event).unwrap(), flate2::Compression::default()).finish().unwrap();
+    let ledger_20231114 = dir.join("ledger-20231114.jsonl.gz").to_string_lossy().to_string();
+    converter::process::position::ledger::process(Some(previous.clone()), empty_event.to_string_lossy().to_string(), ledger_20231114.clone(), KeyFormat::Short).unwrap();
+    storage.put(&ledger_20231114, &artifact.key(20231114)).unwrap();
+
+    // 20231115 is chained to 20231114, the position open on 20231113 is carried over the day without events
+    assert_eq!(archive::previous_position_ledger_date(20231115, &options, &storage).unwrap(), Some(20231114));
+    storage.get(&artifact.key(20231114), &previous).unwrap();
+    let output = dir.join("ledger.jsonl.gz").to_string_lossy().to_string();
+    converter::process::position::ledger::process(Some(previous), write_event_file(&dir), output.clone(), KeyFormat::Short).unwrap();
+    let lines = read_file(&output);
+    let carried = lines.iter().find(|line| line["p"] == CARRIED_POSITION).unwrap();
+    assert_eq!(carried["o"]["s"], 100);
+    assert_eq!(carried["l"], "300000");
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,167 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountSerialize;
+use base64::Engine;
+use flate2::{read::GzDecoder, write::GzEncoder};
+use sedimentology_archiver::artifact::ArtifactType;
+use sedimentology_archiver::converter;
+use sedimentology_archiver::converter::model::long_key::KeyFormat;
+use serde_json::{json, Value};
+use whirlpool_base::state::{Position, PositionBundle, PositionRewardInfo};
+
+const SOL_USDC: &str = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+
+// position with its own mint
+const POSITION: &str = "5Ye4bNzMv2N2uNVRPJRYv4gWa1RNpv3MqVHBZwmx4nTS";
+const POSITION_MINT: &str = "9Ebyb3ZMfYbcYz5dDpPgaJjhTnJC2gpL7LXjsG3WM1KB";
+
+const POSITION_BUNDLE: &str = "DqAEf9Ya9jeaPJ2XHWzD3bVPpJVxUvzrUfSBKQXGAFu5";
+const POSITION_BUNDLE_MINT: &str = "2xQJBqVCnaTTSm2T2XCkDkWBTrE3TgcxwRJEzxGjVUuJ";
+
+fn tmpdir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("whirlpool-position-snapshot-test-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn read_file(path: &Path) -> Vec<Value> {
+    let mut jsonl = String::new();
+    GzDecoder::new(File::open(path).unwrap()).read_to_string(&mut jsonl).unwrap();
+    jsonl.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+fn pubkey(address: &str) -> Pubkey {
+    Pubkey::from_str(address).unwrap()
+}
+
+fn bundled_position(bundle_index: u16) -> String {
+    let (address, _bump) = Pubkey::find_program_address(
+        &[b"bundled_position", pubkey(POSITION_BUNDLE_MINT).as_ref(), bundle_index.to_string().as_bytes()],
+        &whirlpool_base::ID,
+    );
+    address.to_string()
+}
+
+fn position(liquidity: u128, position_mint: Pubkey) -> Position {
+    Position {
+        whirlpool: pubkey(SOL_USDC),
+        position_mint,
+        liquidity,
+        tick_lower_index: -19008,
+        tick_upper_index: -18944,
+        fee_growth_checkpoint_a: u128::MAX,
+        fee_owed_a: 10,
+        fee_growth_checkpoint_b: 0,
+        fee_owed_b: 1500,
+        reward_infos: [
+            PositionRewardInfo { growth_inside_checkpoint: 1 << 64, amount_owed: 500 },
+            PositionRewardInfo::default(),
+            PositionRewardInfo::default(),
+        ],
+    }
+}
+
+fn encode(account: &impl AccountSerialize) -> String {
+    let mut data = vec![];
+    account.try_serialize(&mut data).unwrap();
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+// state of 20231115: a position with its own mint, a bundle with the bundled positions of the index 0 and 255
+fn write_state_file(dir: &Path) -> String {
+    let mut position_bitmap = [0u8; 32];
+    position_bitmap[0] = 0b0000_0001;
+    position_bitmap[31] = 0b1000_0000;
+    let position_bundle = PositionBundle { position_bundle_mint: pubkey(POSITION_BUNDLE_MINT), position_bitmap };
+
+    let mut accounts = [
+        (POSITION.to_string(), encode(&position(1000000, pubkey(POSITION_MINT)))),
+        (POSITION_BUNDLE.to_string(), encode(&position_bundle)),
+        // bundled positions have no mint of their own (the position mint is the bundle mint)
+        (bundled_position(0), encode(&position(2000000, pubkey(POSITION_BUNDLE_MINT)))),
+        (bundled_position(255), encode(&position(0, pubkey(POSITION_BUNDLE_MINT)))),
+    ];
+    // accounts are sorted by pubkey in the state file
+    accounts.sort();
+    let state = json!({
+        "slot": 1999, "blockHeight": 1989, "blockTime": 1700092799,
+        "accounts": accounts.iter().map(|(pubkey, data)| json!({ "pubkey": pubkey, "data": data })).collect::<Vec<_>>(),
+        "programData": "",
+    });
+
+    let path = dir.join(ArtifactType::State.file_name(20231115));
+    let mut encoder = GzEncoder::new(File::create(&path).unwrap(), flate2::Compression::default());
+    encoder.write_all(state.to_string().as_bytes()).unwrap();
+    encoder.finish().unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn process(name: &str, key_format: KeyFormat) -> Vec<Value> {
+    let dir = tmpdir(name);
+    let output = dir.join(ArtifactType::Position.file_name(20231115));
+    converter::process::position::snapshot::process(write_state_file(&dir), output.to_string_lossy().to_string(), key_format).unwrap();
+
+    let lines = read_file(&output);
+    std::fs::remove_dir_all(dir).unwrap();
+    lines
+}
+
+#[test]
+fn position_snapshot() {
+    let artifact = ArtifactType::Position;
+    assert!(ArtifactType::ALL.contains(&artifact));
+    assert_eq!(ArtifactType::from_name("position"), Some(artifact));
+    assert_eq!(artifact.file_name(20240102), "whirlpool-position-20240102.jsonl.gz");
+
+    let lines = process("short", KeyFormat::Short);
+
+    // Position accounts only (sorted by pubkey as the state), the bundle itself is not a position
+    let mut expected = vec![POSITION.to_string(), bundled_position(0), bundled_position(255)];
+    expected.sort();
+    let positions = lines.iter().map(|line| line["p"].as_str().unwrap().to_string()).collect::<Vec<_>>();
+    assert_eq!(positions, expected);
+    let line = |address: &str| lines.iter().find(|line| line["p"] == address).unwrap();
+
+    let unbundled = line(POSITION);
+    assert_eq!(unbundled["w"], SOL_USDC);
+    assert_eq!(unbundled["pm"], POSITION_MINT);
+    assert_eq!(unbundled["l"], "1000000");
+    assert_eq!((unbundled["lti"].as_i64(), unbundled["uti"].as_i64()), (Some(-19008), Some(-18944)));
+    assert_eq!(unbundled["fgca"], "340282366920938463463374607431768211455");
+    assert_eq!(unbundled["foa"], "10");
+    assert_eq!(unbundled["fob"], "1500");
+    assert_eq!(unbundled["r"], json!([
+        { "gic": "18446744073709551616", "ao": "500" },
+        { "gic": "0", "ao": "0" },
+        { "gic": "0", "ao": "0" },
+    ]));
+    // bundle fields are omitted for a position with its own mint
+    assert!(unbundled.get("pb").is_none());
+    assert!(unbundled.get("pbm").is_none());
+    assert!(unbundled.get("pbi").is_none());
+
+    // resolved by the PDA of the bundle mint and the index of each bit set in the bitmap
+    for (bundle_index, liquidity) in [(0, "2000000"), (255, "0")] {
+        let bundled = line(&bundled_position(bundle_index));
+        assert_eq!(bundled["l"], liquidity);
+        assert_eq!(bundled["pb"], POSITION_BUNDLE);
+        assert_eq!(bundled["pbm"], POSITION_BUNDLE_MINT);
+        assert_eq!(bundled["pbi"], bundle_index);
+    }
+}
+
+#[test]
+fn position_snapshot_in_long_key_format() {
+    let lines = process("long", KeyFormat::Long);
+
+    let bundled = lines.iter().find(|line| line["position"] == bundled_position(255)).unwrap();
+    assert_eq!(bundled["positionMint"], POSITION_BUNDLE_MINT);
+    assert_eq!(bundled["feeOwedB"], "1500");
+    assert_eq!(bundled["rewards"][0], json!({ "growthInsideCheckpoint": "18446744073709551616", "amountOwed": "500" }));
+    assert_eq!(bundled["positionBundle"], POSITION_BUNDLE);
+    assert_eq!(bundled["positionBundleIndex"], 255);
+}
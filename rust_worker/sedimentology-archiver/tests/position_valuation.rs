@@ -0,0 +1,335 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountSerialize;
+use base64::Engine;
+use flate2::{read::GzDecoder, write::GzEncoder};
+use sedimentology_archiver::artifact::ArtifactType;
+use sedimentology_archiver::converter;
+use sedimentology_archiver::converter::model::long_key::KeyFormat;
+use serde_json::{json, Value};
+use whirlpool_base::math::{get_amount_delta_a, get_amount_delta_b, sqrt_price_from_tick_index};
+use whirlpool_base::state::Position;
+
+// pools initialized in the day of the event fixture (20231115)
+const SOL_USDC: &str = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+const ORCA_SOL: &str = "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1";
+
+// close sqrt prices of the pools (150.1 USDC/SOL at tick -18966, 0.0199 SOL/ORCA at tick 29908)
+const SOL_USDC_CLOSE_SQRT_PRICE: u128 = 7146774326563898391;
+const ORCA_SOL_CLOSE_SQRT_PRICE: u128 = 82289848108540554183;
+
+// opened in the day (in range all day)
+const POSITION: &str = "5Ye4bNzMv2N2uNVRPJRYv4gWa1RNpv3MqVHBZwmx4nTS";
+// opened before the day, repositioned above the price in the day
+const CARRIED_POSITION: &str = "9Ebyb3ZMfYbcYz5dDpPgaJjhTnJC2gpL7LXjsG3WM1KB";
+// closed in the day
+const CLOSED_POSITION: &str = "2xQJBqVCnaTTSm2T2XCkDkWBTrE3TgcxwRJEzxGjVUuJ";
+// no line in the ledger, only the Position account in the state
+const SEEDED_POSITION: &str = "8qhh3Mt8sHYFAJy93c4PGbEHiJkGn8URoMZBFMrcW8g5";
+// opened before the ledger started, harvested in the day (no liquidity and range in the ledger)
+const HARVESTED_POSITION: &str = "DqAEf9Ya9jeaPJ2XHWzD3bVPpJVxUvzrUfSBKQXGAFu5";
+
+const DAILY_TIMESTAMP: i64 = 1700006400;
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn tmpdir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("whirlpool-position-valuation-test-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn read_file(path: &Path) -> Vec<Value> {
+    let mut jsonl = String::new();
+    GzDecoder::new(File::open(path).unwrap()).read_to_string(&mut jsonl).unwrap();
+    jsonl.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+fn write_gzip(path: &Path, data: &[u8]) -> String {
+    let mut encoder = GzEncoder::new(File::create(path).unwrap(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap();
+    path.to_string_lossy().to_string()
+}
+
+// state at the end of 20231114 (no pools yet, Position accounts of the pools to be initialized in the day)
+fn write_state_file(dir: &Path) -> String {
+    let account = |pubkey: &str, whirlpool: &str, liquidity: u128, tick_lower_index: i32, tick_upper_index: i32| {
+        let position = Position {
+            whirlpool: Pubkey::from_str(whirlpool).unwrap(),
+            liquidity,
+            tick_lower_index,
+            tick_upper_index,
+            ..Default::default()
+        };
+        let mut data = vec![];
+        position.try_serialize(&mut data).unwrap();
+        json!({ "pubkey": pubkey, "data": base64::engine::general_purpose::STANDARD.encode(data) })
+    };
+
+    let state = json!({
+        "slot": 999, "blockHeight": 989, "blockTime": DAILY_TIMESTAMP - 1,
+        "accounts": [
+            account(SEEDED_POSITION, ORCA_SOL, 1_000_000_000_000, 29888, 30016),
+            account(HARVESTED_POSITION, SOL_USDC, 2_000_000_000_000, -19008, -18944),
+        ],
+        "programData": "",
+    });
+    write_gzip(&dir.join(ArtifactType::State.file_name(20231114)), state.to_string().as_bytes())
+}
+
+fn write_token_file(dir: &Path) -> String {
+    let token = json!({
+        "slot": 999, "blockHeight": 989, "blockTime": DAILY_TIMESTAMP - 1,
+        "tokens": [
+            { "mint": "So11111111111111111111111111111111111111112", "decimals": 9 },
+            { "mint": "EPjFWdd5AufqSSqeM2qN1xyybapC8G4wEGGkZwyTDt1v", "decimals": 6 },
+            { "mint": "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE", "decimals": 6 },
+        ],
+    });
+    write_gzip(&dir.join(ArtifactType::Token.file_name(20231114)), token.to_string().as_bytes())
+}
+
+// event file of 20231115 (the fixture is not compressed)
+fn write_event_file(dir: &Path) -> String {
+    write_gzip(&dir.join(ArtifactType::Event.file_name(20231115)), &std::fs::read(fixture_path("event/ohlcv.jsonl")).unwrap())
+}
+
+// ledger of 20231115 (positions of the SOL/USDC pool)
+fn write_ledger_file(dir: &Path) -> String {
+    let entry = |dt: i64, entry_type: &str, liquidity: &str, lower_tick_index: i32, upper_tick_index: i32| json!({
+        "s": 1000 + dt, "bt": DAILY_TIMESTAMP + dt, "sg": format!("{}sig0", 1000 + dt), "t": entry_type,
+        "l": liquidity, "lti": lower_tick_index, "uti": upper_tick_index,
+    });
+    let ledger = |position: &str, opened: Value, lower_tick_index: i32, upper_tick_index: i32, liquidity: &str, deposited: Value, withdrawn: Value, entries: Vec<Value>| json!({
+        "p": position,
+        "w": SOL_USDC,
+        "ta": { "m": "So11111111111111111111111111111111111111112", "d": 9 },
+        "tb": { "m": "EPjFWdd5AufqSSqeM2qN1xyybapC8G4wEGGkZwyTDt1v", "d": 6 },
+        "o": opened,
+        "lti": lower_tick_index,
+        "uti": upper_tick_index,
+        "l": liquidity,
+        "dp": "1.500000000e2",
+        "d": deposited,
+        "wd": withdrawn,
+        "f": { "a": "0", "b": "0" },
+        "r": [],
+        "e": entries,
+    });
+
+    let zero = json!({ "a": "0", "b": "0" });
+    let opened_today = json!({ "s": 1000, "bt": DAILY_TIMESTAMP });
+    let opened_before = json!({ "s": 100, "bt": 1699900000 });
+    let lines = [
+        ledger(POSITION, opened_today, -19136, -18944, "10000000000000", json!({ "a": "33000000000", "b": "5000000000" }), zero.clone(), vec![
+            entry(0, "o", "10000000000000", -19136, -18944),
+        ]),
+        ledger(CARRIED_POSITION, opened_before.clone(), -18944, -18880, "1000000000000", json!({ "a": "2000000000", "b": "300000000" }), json!({ "a": "0", "b": "300000000" }), vec![
+            entry(10, "d", "1000000000000", -18976, -18960),
+            entry(300, "rp", "1000000000000", -18944, -18880),
+        ]),
+        {
+            let mut closed = ledger(CLOSED_POSITION, opened_before, -18976, -18960, "0", json!({ "a": "1000000000", "b": "150000000" }), json!({ "a": "1000000000", "b": "150000000" }), vec![
+                entry(100, "w", "0", -18976, -18960),
+                entry(200, "c", "0", -18976, -18960),
+            ]);
+            closed["cl"] = json!({ "s": 1200, "bt": DAILY_TIMESTAMP + 200 });
+            closed
+        },
+        json!({
+            "p": HARVESTED_POSITION,
+            "w": SOL_USDC,
+            "d": zero.clone(),
+            "wd": zero.clone(),
+            "f": { "a": "5", "b": "600" },
+            "r": [],
+            "e": [{ "s": 1050, "bt": DAILY_TIMESTAMP + 50, "sg": "1050sig0", "t": "fh", "f": { "a": "5", "b": "600" } }],
+        }),
+    ];
+
+    let mut jsonl = String::new();
+    for line in lines {
+        jsonl.push_str(&format!("{}\n", line));
+    }
+    write_gzip(&dir.join(ArtifactType::PositionLedger.file_name(20231115)), jsonl.as_bytes())
+}
+
+// (valuation, range)
+fn process(name: &str, key_format: KeyFormat) -> (Vec<Value>, Vec<Value>) {
+    let dir = tmpdir(name);
+    let valuation = dir.join(ArtifactType::PositionValuation.file_name(20231115));
+    let range = dir.join(ArtifactType::PositionRange.file_name(20231115));
+    converter::process::position::valuation::process(
+        write_state_file(&dir),
+        write_token_file(&dir),
+        write_event_file(&dir),
+        write_ledger_file(&dir),
+        valuation.to_string_lossy().to_string(),
+        range.to_string_lossy().to_string(),
+        key_format,
+    ).unwrap();
+
+    let files = (read_file(&valuation), read_file(&range));
+    std::fs::remove_dir_all(dir).unwrap();
+    files
+}
+
+fn assert_f64(value: &Value, expected: f64) {
+    let value: f64 = value.as_str().unwrap().parse().unwrap();
+    assert!((value - expected).abs() <= expected.abs() * 1e-9, "{} != {}", value, expected);
+}
+
+fn decimal_price(sqrt_price: u128, decimals_a: i32, decimals_b: i32) -> f64 {
+    (sqrt_price as f64 / 2f64.powi(64)).powi(2) * 10f64.powi(decimals_a - decimals_b)
+}
+
+#[test]
+fn position_valuation() {
+    let artifact = ArtifactType::PositionValuation;
+    assert!(ArtifactType::ALL.contains(&artifact));
+    assert_eq!(ArtifactType::from_name("position-valuation"), Some(artifact));
+    assert_eq!(artifact.file_name(20240102), "whirlpool-position-valuation-20240102.jsonl.gz");
+
+    let (valuation, _) = process("short", KeyFormat::Short);
+
+    // sorted by position, the closed position is not valued
+    let positions = valuation.iter().map(|line| line["p"].as_str().unwrap()).collect::<Vec<_>>();
+    assert_eq!(positions, vec![POSITION, SEEDED_POSITION, CARRIED_POSITION, HARVESTED_POSITION]);
+    assert!(valuation.iter().all(|line| line["t"] == DAILY_TIMESTAMP));
+
+    // in range at the close price: both tokens
+    let sol_usdc_price = decimal_price(SOL_USDC_CLOSE_SQRT_PRICE, 9, 6);
+    let amount_a = get_amount_delta_a(SOL_USDC_CLOSE_SQRT_PRICE, sqrt_price_from_tick_index(-18944), 10_000_000_000_000, false).unwrap();
+    let amount_b = get_amount_delta_b(sqrt_price_from_tick_index(-19136), SOL_USDC_CLOSE_SQRT_PRICE, 10_000_000_000_000, false).unwrap();
+    let opened = &valuation[0];
+    assert_eq!(opened["w"], SOL_USDC);
+    assert_eq!(opened["ta"], json!({ "m": "So11111111111111111111111111111111111111112", "d": 9 }));
+    assert_eq!(opened["sp"], SOL_USDC_CLOSE_SQRT_PRICE.to_string());
+    assert_f64(&opened["dp"], sol_usdc_price);
+    assert_eq!(opened["ti"], -18966);
+    assert_eq!(opened["ir"], true);
+    assert_eq!(opened["a"], json!({ "a": amount_a.to_string(), "b": amount_b.to_string() }));
+    let value = amount_a as f64 / 1e9 * sol_usdc_price + amount_b as f64 / 1e6;
+    assert_f64(&opened["v"], value);
+    // 33 SOL and 5000 USDC deposited
+    let hold_value = 33.0 * sol_usdc_price + 5000.0;
+    assert_eq!(opened["nd"], json!({ "a": "33000000000", "b": "5000000000" }));
+    assert_f64(&opened["hv"], hold_value);
+    assert_f64(&opened["il"], value - hold_value);
+    assert_f64(&opened["ilr"], (value - hold_value) / hold_value);
+
+    // above the price after the reposition: token A only, withdrawals are netted
+    let amount_a = get_amount_delta_a(sqrt_price_from_tick_index(-18944), sqrt_price_from_tick_index(-18880), 1_000_000_000_000, false).unwrap();
+    let carried = &valuation[2];
+    assert_eq!((carried["lti"].as_i64(), carried["uti"].as_i64()), (Some(-18944), Some(-18880)));
+    assert_eq!(carried["ir"], false);
+    assert_eq!(carried["a"], json!({ "a": amount_a.to_string(), "b": "0" }));
+    assert_f64(&carried["v"], amount_a as f64 / 1e9 * sol_usdc_price);
+    assert_eq!(carried["nd"], json!({ "a": "2000000000", "b": "0" }));
+    assert_f64(&carried["hv"], 2.0 * sol_usdc_price);
+
+    // seeded from the state: valued at the close of the pool initialized in the day, deposits are unknown
+    let orca_sol_price = decimal_price(ORCA_SOL_CLOSE_SQRT_PRICE, 6, 9);
+    let amount_a = get_amount_delta_a(ORCA_SOL_CLOSE_SQRT_PRICE, sqrt_price_from_tick_index(30016), 1_000_000_000_000, false).unwrap();
+    let amount_b = get_amount_delta_b(sqrt_price_from_tick_index(29888), ORCA_SOL_CLOSE_SQRT_PRICE, 1_000_000_000_000, false).unwrap();
+    let seeded = &valuation[1];
+    assert_eq!(seeded["w"], ORCA_SOL);
+    assert_eq!(seeded["tb"], json!({ "m": "So11111111111111111111111111111111111111112", "d": 9 }));
+    assert_eq!(seeded["l"], "1000000000000");
+    assert_eq!(seeded["ti"], 29908);
+    assert_eq!(seeded["ir"], true);
+    assert_eq!(seeded["a"], json!({ "a": amount_a.to_string(), "b": amount_b.to_string() }));
+    assert_f64(&seeded["v"], amount_a as f64 / 1e6 * orca_sol_price + amount_b as f64 / 1e9);
+    assert_eq!(seeded["nd"], json!({ "a": "0", "b": "0" }));
+    assert!(seeded.get("hv").is_none());
+    assert!(seeded.get("il").is_none());
+    assert!(seeded.get("ilr").is_none());
+
+    // harvested only since before the ledger: liquidity and range are filled from the state, deposits are unknown
+    let amount_a = get_amount_delta_a(SOL_USDC_CLOSE_SQRT_PRICE, sqrt_price_from_tick_index(-18944), 2_000_000_000_000, false).unwrap();
+    let amount_b = get_amount_delta_b(sqrt_price_from_tick_index(-19008), SOL_USDC_CLOSE_SQRT_PRICE, 2_000_000_000_000, false).unwrap();
+    let harvested = &valuation[3];
+    assert_eq!(harvested["l"], "2000000000000");
+    assert_eq!((harvested["lti"].as_i64(), harvested["uti"].as_i64()), (Some(-19008), Some(-18944)));
+    assert_eq!(harvested["ir"], true);
+    assert_eq!(harvested["a"], json!({ "a": amount_a.to_string(), "b": amount_b.to_string() }));
+    assert_f64(&harvested["v"], amount_a as f64 / 1e9 * sol_usdc_price + amount_b as f64 / 1e6);
+    assert!(harvested.get("hv").is_none());
+}
+
+#[test]
+fn position_valuation_in_long_key_format() {
+    let (valuation, _) = process("long", KeyFormat::Long);
+
+    let opened = &valuation[0];
+    assert_eq!(opened["position"], POSITION);
+    assert_eq!(opened["tokenA"]["decimals"], 9);
+    assert_eq!(opened["inRange"], true);
+    assert_eq!(opened["netDeposited"], json!({ "amountA": "33000000000", "amountB": "5000000000" }));
+    assert!(opened.get("impermanentLossRatio").is_some());
+    assert!(valuation[1].get("holdValue").is_none());
+}
+
+#[test]
+fn position_range() {
+    let artifact = ArtifactType::PositionRange;
+    assert!(ArtifactType::ALL.contains(&artifact));
+    assert_eq!(ArtifactType::from_name("position-range"), Some(artifact));
+    assert_eq!(artifact.file_name(20240102), "whirlpool-position-range-20240102.jsonl.gz");
+
+    let (_, range) = process("range", KeyFormat::Short);
+    let day_end = DAILY_TIMESTAMP + 86400;
+    let segments = range.iter()
+        .map(|line| (line["p"].as_str().unwrap(), line["st"].as_i64().unwrap(), line["et"].as_i64().unwrap(), line["lti"].as_i64().unwrap(), line["uti"].as_i64().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(segments, vec![
+        // closed in the day: tracked until the close, the withdrawal keeps the range
+        (CLOSED_POSITION, DAILY_TIMESTAMP, DAILY_TIMESTAMP + 200, -18976, -18960),
+        // opened at the beginning of the day
+        (POSITION, DAILY_TIMESTAMP, day_end, -19136, -18944),
+        // seeded from the state: range of the account all day
+        (SEEDED_POSITION, DAILY_TIMESTAMP, day_end, 29888, 30016),
+        // split by the reposition, the deposit keeps the range
+        (CARRIED_POSITION, DAILY_TIMESTAMP, DAILY_TIMESTAMP + 300, -18976, -18960),
+        (CARRIED_POSITION, DAILY_TIMESTAMP + 300, day_end, -18944, -18880),
+        // harvested only: range of the account all day, the harvest keeps the range
+        (HARVESTED_POSITION, DAILY_TIMESTAMP, day_end, -19008, -18944),
+    ]);
+    assert!(range.iter().all(|line| line["w"] == if line["p"] == SEEDED_POSITION { ORCA_SOL } else { SOL_USDC }));
+    assert!(range.iter().all(|line| line["t"] == DAILY_TIMESTAMP));
+
+    // ticks of SOL/USDC: -18973 (0s), -18979 (60s), -18959 (90s), -18966 (400s)
+    // ticks of ORCA/SOL: 29958 (30s, initialized), 29908 (240s)
+    let minutes = range.iter().map(|line| (line["tm"].as_u64().unwrap(), line["irm"].as_u64().unwrap())).collect::<Vec<_>>();
+    assert_eq!(minutes, vec![
+        // 200s tracked, in range for the first 60s only
+        (3, 1),
+        (1440, 1440),
+        // not tracked before the initialization of the pool
+        (1439, 1439),
+        (5, 1),
+        // below the range after the reposition
+        (1435, 0),
+        (1440, 1440),
+    ]);
+}
+
+#[test]
+fn position_range_in_long_key_format() {
+    let (_, range) = process("range-long", KeyFormat::Long);
+
+    let closed = &range[0];
+    assert_eq!(closed["position"], CLOSED_POSITION);
+    assert_eq!(closed["startTime"], DAILY_TIMESTAMP);
+    assert_eq!(closed["endTime"], DAILY_TIMESTAMP + 200);
+    assert_eq!(closed["lowerTickIndex"], -18976);
+    assert_eq!(closed["trackedMinutes"], 3);
+    assert_eq!(closed["inRangeMinutes"], 1);
+}
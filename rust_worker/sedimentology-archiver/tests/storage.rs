@@ -0,0 +1,211 @@
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+use sedimentology_archiver::storage::{
+    self, ArchiveStorage, HashingReader, HashingWriter, LocalFileStorage, ObjectMeta, ObjectSummary, S3Storage,
+};
+
+// sha256 of "hello world"
+const HELLO_WORLD_SHA256: &str = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+fn tmpdir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("whirlpool-storage-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_file(path: &PathBuf, data: &[u8]) -> String {
+    std::fs::write(path, data).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+#[test]
+fn hashing_reader_and_writer() {
+    let data = b"hello world";
+
+    let mut reader = HashingReader::new(&data[..]);
+    let mut read = Vec::new();
+    // small buffer to hash across multiple reads
+    let mut buf = [0u8; 3];
+    loop {
+        let n = reader.read(&mut buf).unwrap();
+        if n == 0 {
+            break;
+        }
+        read.extend_from_slice(&buf[..n]);
+    }
+    assert_eq!(read, data);
+    let expected = ObjectSummary {
+        size: 11,
+        sha256: HELLO_WORLD_SHA256.to_string(),
+    };
+    assert_eq!(reader.finalize(), expected);
+
+    let mut written = Vec::new();
+    let mut writer = HashingWriter::new(&mut written);
+    writer.write_all(b"hello ").unwrap();
+    writer.write_all(b"world").unwrap();
+    assert_eq!(writer.finalize().unwrap(), expected);
+    assert_eq!(written, data);
+}
+
+#[test]
+fn local_file_summary() {
+    let dir = tmpdir("summary");
+    let path = write_file(&dir.join("hello.txt"), b"hello world");
+
+    let summary = storage::local_file_summary(&path).unwrap().unwrap();
+    assert_eq!(summary.size, 11);
+    assert_eq!(summary.sha256, HELLO_WORLD_SHA256);
+
+    let missing = dir.join("missing.txt").to_string_lossy().to_string();
+    assert!(storage::local_file_summary(&missing).unwrap().is_none());
+}
+
+#[test]
+fn local_storage_round_trip() {
+    let dir = tmpdir("round-trip");
+    let root = dir.join("root");
+    let storage = LocalFileStorage::new(root.to_string_lossy().to_string());
+
+    let transaction = write_file(&dir.join("transaction"), b"hello world");
+    let state = write_file(&dir.join("state"), b"state");
+    let transaction_key = storage::date_key(20240101, "whirlpool-transaction-20240101.jsonl.gz");
+    let state_key = storage::date_key(20240101, "whirlpool-state-20240101.json.gz");
+    let next_key = storage::date_key(20240102, "whirlpool-state-20240102.json.gz");
+    assert_eq!(transaction_key, "2024/0101/whirlpool-transaction-20240101.jsonl.gz");
+
+    assert!(storage.head(&transaction_key).unwrap().is_none());
+
+    let summary = storage.put(&transaction, &transaction_key).unwrap();
+    assert_eq!(summary.size, 11);
+    assert_eq!(summary.sha256, HELLO_WORLD_SHA256);
+    storage.put(&state, &state_key).unwrap();
+    storage.put(&state, &next_key).unwrap();
+
+    // the .partial file is renamed to the key
+    assert!(root.join(&transaction_key).is_file());
+    assert!(!root.join(format!("{}.partial", transaction_key)).exists());
+
+    assert_eq!(
+        storage.head(&transaction_key).unwrap(),
+        Some(ObjectMeta {
+            key: transaction_key.clone(),
+            size: 11
+        })
+    );
+    // directories are not objects
+    assert!(storage.head("2024/0101").unwrap().is_none());
+
+    let downloaded = dir.join("downloaded").to_string_lossy().to_string();
+    let summary = storage.get(&transaction_key, &downloaded).unwrap();
+    assert_eq!(summary.sha256, HELLO_WORLD_SHA256);
+    assert_eq!(std::fs::read(&downloaded).unwrap(), b"hello world");
+    assert!(storage.get("2024/0103/missing", &downloaded).is_err());
+
+    // sorted by key, filtered by prefix
+    let keys = |prefix: &str| {
+        storage
+            .list(prefix)
+            .unwrap()
+            .into_iter()
+            .map(|object| object.key)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(keys("2024/0101/"), vec![state_key.clone(), transaction_key.clone()]);
+    assert_eq!(keys("2024/"), vec![state_key.clone(), transaction_key.clone(), next_key.clone()]);
+    assert_eq!(keys("2024/0101/whirlpool-state"), vec![state_key.clone()]);
+    assert!(keys("2025/").is_empty());
+}
+
+#[test]
+fn local_storage_ignores_partial_file() {
+    let dir = tmpdir("partial");
+    let root = dir.join("root");
+    let storage = LocalFileStorage::new(root.to_string_lossy().to_string());
+
+    let key = storage::date_key(20240101, "whirlpool-state-20240101.json.gz");
+    // left behind by an interrupted upload
+    std::fs::create_dir_all(root.join("2024/0101")).unwrap();
+    write_file(&root.join(format!("{}.partial", key)), b"incomplete");
+
+    assert!(storage.head(&key).unwrap().is_none());
+    assert!(storage.list("2024/0101/").unwrap().is_empty());
+
+    // put overwrites the stale .partial file and renames it
+    let state = write_file(&dir.join("state"), b"hello world");
+    storage.put(&state, &key).unwrap();
+    assert!(!root.join(format!("{}.partial", key)).exists());
+    assert_eq!(std::fs::read(root.join(&key)).unwrap(), b"hello world");
+    assert_eq!(storage.list("2024/0101/").unwrap().len(), 1);
+}
+
+// runs against an S3 compatible service only if SEDIMENTOLOGY_TEST_S3_ENDPOINT is set (the bucket must exist), e.g. MinIO:
+//   docker run -p 9000:9000 minio/minio server /data
+//   mc mb local/sedimentology-test
+//   SEDIMENTOLOGY_TEST_S3_ENDPOINT=http://localhost:9000 cargo test --test storage s3_storage_round_trip
+#[test]
+fn s3_storage_round_trip() {
+    let Ok(endpoint) = std::env::var("SEDIMENTOLOGY_TEST_S3_ENDPOINT") else {
+        println!("SEDIMENTOLOGY_TEST_S3_ENDPOINT is not set, skipped");
+        return;
+    };
+    let env = |name: &str, default: &str| std::env::var(name).unwrap_or(default.to_string());
+    // objects of each run are isolated by the prefix
+    let prefix = format!("test-{}-{}", std::process::id(), chrono::Utc::now().timestamp_millis());
+    let storage = S3Storage::new(
+        endpoint,
+        env("SEDIMENTOLOGY_TEST_S3_REGION", "us-east-1"),
+        env("SEDIMENTOLOGY_TEST_S3_BUCKET", "sedimentology-test"),
+        prefix,
+        env("SEDIMENTOLOGY_TEST_S3_ACCESS_KEY_ID", "minioadmin"),
+        env("SEDIMENTOLOGY_TEST_S3_SECRET_ACCESS_KEY", "minioadmin"),
+        true,
+    );
+
+    let dir = tmpdir("s3");
+    let state = write_file(&dir.join("state"), b"hello world");
+    // larger than the part size (64MB), uploaded with multipart upload
+    let large_data = (0..64 * 1024 * 1024 + 10).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+    let transaction = write_file(&dir.join("transaction"), &large_data);
+    let state_key = storage::date_key(20240101, "whirlpool-state-20240101.json.gz");
+    let transaction_key = storage::date_key(20240101, "whirlpool-transaction-20240101.jsonl.gz");
+    let next_key = storage::date_key(20240102, "whirlpool-state-20240102.json.gz");
+
+    assert!(storage.head(&state_key).unwrap().is_none());
+
+    let summary = storage.put(&state, &state_key).unwrap();
+    assert_eq!(summary, ObjectSummary { size: 11, sha256: HELLO_WORLD_SHA256.to_string() });
+    storage.put(&state, &next_key).unwrap();
+    let large_summary = storage.put(&transaction, &transaction_key).unwrap();
+    assert_eq!(large_summary, storage::local_file_summary(&transaction).unwrap().unwrap());
+
+    assert_eq!(
+        storage.head(&state_key).unwrap(),
+        Some(ObjectMeta {
+            key: state_key.clone(),
+            size: 11
+        })
+    );
+    assert_eq!(storage.head(&transaction_key).unwrap().unwrap().size, large_data.len() as u64);
+
+    let downloaded = dir.join("downloaded").to_string_lossy().to_string();
+    assert_eq!(storage.get(&state_key, &downloaded).unwrap(), summary);
+    assert_eq!(std::fs::read(&downloaded).unwrap(), b"hello world");
+    assert_eq!(storage.get(&transaction_key, &downloaded).unwrap(), large_summary);
+
+    // keys are relative to the prefix, sorted by key, filtered by prefix
+    let keys = |prefix: &str| {
+        storage
+            .list(prefix)
+            .unwrap()
+            .into_iter()
+            .map(|object| object.key)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(keys("2024/0101/"), vec![state_key.clone(), transaction_key.clone()]);
+    assert_eq!(keys("2024/"), vec![state_key.clone(), transaction_key.clone(), next_key.clone()]);
+    assert_eq!(keys("2024/0101/whirlpool-state"), vec![state_key.clone()]);
+    assert!(keys("2025/").is_empty());
+}
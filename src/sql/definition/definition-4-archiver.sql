@@ -16,6 +16,17 @@ CREATE TABLE `admArchiverState` (
   PRIMARY KEY (`profile`)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
 
+CREATE TABLE `admArchiverStage` (
+  `profile` varchar(64) NOT NULL,
+  `date` int(11) unsigned NOT NULL,
+  `artifact` varchar(64) NOT NULL,
+  `stage` varchar(16) NOT NULL,
+  `size` bigint(11) unsigned NOT NULL,
+  `sha256` char(64) NOT NULL,
+  `updatedAt` timestamp NOT NULL DEFAULT current_timestamp(),
+  PRIMARY KEY (`profile`,`date`,`artifact`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
 
 /*!40111 SET SQL_NOTES=@OLD_SQL_NOTES */;
 /*!40101 SET SQL_MODE=@OLD_SQL_MODE */;
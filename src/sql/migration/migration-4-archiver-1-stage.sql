@@ -0,0 +1,20 @@
+--
+-- MIGRATION
+--
+-- for deployments created from definition-4-archiver.sql before admArchiverStage was added
+-- (new deployments already have the table)
+--
+-- dates before latestArchivedDate have no stage, they are never resumed.
+-- the date being archived at the time of the upgrade is archived again from the first stage.
+--
+
+CREATE TABLE IF NOT EXISTS `admArchiverStage` (
+  `profile` varchar(64) NOT NULL,
+  `date` int(11) unsigned NOT NULL,
+  `artifact` varchar(64) NOT NULL,
+  `stage` varchar(16) NOT NULL,
+  `size` bigint(11) unsigned NOT NULL,
+  `sha256` char(64) NOT NULL,
+  `updatedAt` timestamp NOT NULL DEFAULT current_timestamp(),
+  PRIMARY KEY (`profile`,`date`,`artifact`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;